@@ -12,7 +12,6 @@
 extern crate js;
 extern crate collections;
 extern crate getopts;
-extern crate jit;
 #[phase(plugin, link)]
 extern crate log;
 /// Interactive mode
@@ -30,13 +29,17 @@ pub fn main() {
         getopts::optflag("h", "help", "Show this message"),
         getopts::optflag("t", "tests", "Run tests"),
         getopts::optflag("i", "interactive", "Run in interactive mode"),
-        getopts::optopt("s", "source-code", "Run some Javascript code", "The path to the source code")
+        getopts::optopt("s", "source-code", "Run some Javascript code", "The path to the source code"),
+        getopts::optopt("k", "tokens", "Print the tokens of some Javascript code as JSON", "The path to the source code")
     ];
     let m = getopts::getopts(std::os::args().as_slice(), opts).ok().expect("Could not parse arguments");
     match m.opt_str("s") {
         Some(path) => {
             Runner::new(path).run()
         },
+        None if m.opt_present("k") => {
+            Runner::new(m.opt_str("k").unwrap()).print_tokens()
+        },
         None if m.opt_present("h") => {
             println!("{}", getopts::usage("Usage: js.rs [OPTIONS] [INPUT]", opts));
         },
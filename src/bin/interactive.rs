@@ -1,28 +1,30 @@
-use js::back::compiler::JitCompiler;
-use js::back::executor::JitExecutor;
-use js::front::run::compiler::Compiler;
 use js::front::run::executor::Executor;
-use js::syntax::lexer::Lexer;
+use js::front::run::interpreter::Interpreter;
+use js::syntax::lexer::{Lexer, LexError, UnexpectedEnd};
 use js::syntax::parser::Parser;
-use jit::Context;
+use js::syntax::hoist::Hoisting;
+use js::syntax::transform::Pipeline;
 use std::default::Default;
 use std::io::stdio::{stdin, StdReader};
-use std::io::{BufReader, BufferedReader};
+use std::io::{BufferedReader, MemReader};
 /// An interactive command-line mode
-pub struct Interactive<'a> {
-    context: Context<'a>,
+pub struct Interactive {
     /// The execution engine to run the expressions on
-    pub executor: JitExecutor,
+    pub executor: Interpreter,
     /// The standard input stream to read from
-    pub input: BufferedReader<StdReader>
+    pub input: BufferedReader<StdReader>,
+    /// The transforms run on each expression between parsing and running it
+    pub pipeline: Pipeline
 }
-impl<'a> Interactive<'a> {
+impl Interactive {
     /// Create a new interactive mode info
-    pub fn new() -> Interactive<'a> {
+    pub fn new() -> Interactive {
+        let mut pipeline = Pipeline::new();
+        pipeline.add(box Hoisting);
         Interactive {
-            context: Context::new(),
             executor: Executor::new(&Default::default()),
-            input: stdin()
+            input: stdin(),
+            pipeline: pipeline
         }
     }
     /// Run the interactive mode
@@ -32,20 +34,45 @@ impl<'a> Interactive<'a> {
         loop {
             let line = self.input.read_line().unwrap();
             debug!("Now parsing line {}", line);
-            let line_bytes = line.as_bytes();
             debug!("Now lexing...");
-            let mut lexer = Lexer::new(BufferedReader::new(BufReader::new(line_bytes)));
-            lexer.lex().unwrap();
+            let mut lexer = Lexer::new(MemReader::new(line.into_bytes()));
+            let mut lexed = lexer.lex();
+            loop {
+                let ended_early = match lexed {
+                    Ok(()) | Err(LexError {kind: UnexpectedEnd, ..}) => lexer.unfinished().is_some(),
+                    Err(_) => false
+                };
+                if !ended_early {
+                    break;
+                }
+                print!("... ");
+                let mut source = lexer.pending().into_string();
+                source.push_str(self.input.read_line().unwrap().as_slice());
+                lexed = lexer.resume(MemReader::new(source.into_bytes()));
+            }
+            match lexed {
+                Ok(()) => (),
+                Err(err) => {
+                    println!("Failed with {}", err);
+                    print!("> ");
+                    continue;
+                }
+            }
             let tokens = lexer.tokens;
             debug!("Lexed into tokens: {}", tokens);
             debug!("Now parsing...");
-            let expr = Parser::new(tokens).parse_all().unwrap();
+            let expr = match Parser::new(tokens).parse_all() {
+                Ok(expr) => expr,
+                Err(err) => {
+                    println!("Failed with {}", err);
+                    print!("> ");
+                    continue;
+                }
+            };
             debug!("Parsed into expression: {}", expr);
-            debug!("Now compiling");
-            let compiler = JitCompiler::new(&self.context);
-            let compiled = compiler.compile(&expr);
+            let expr = self.pipeline.run(expr);
             debug!("Now executing");
-            match self.executor.execute(&compiled) {
+            match self.executor.execute(&expr).and_then(|v| self.executor.run_event_loop().map(|_| v)) {
                 Ok(v) =>
                     println!("{}", v),
                 Err(v) =>
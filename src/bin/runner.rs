@@ -1,27 +1,31 @@
-use js::back::compiler::JitCompiler;
-use js::back::executor::JitExecutor;
-use js::front::run::compiler::Compiler;
 use js::front::run::executor::Executor;
+use js::front::run::interpreter::Interpreter;
 use js::syntax::lexer::Lexer;
 use js::syntax::parser::Parser;
-use jit::Context;
+use js::syntax::hoist::Hoisting;
+use js::syntax::transform::Pipeline;
 use std::default::Default;
 use std::io::{BufferedReader, File};
 use std::path::Path;
 /// An command-line script executor
 pub struct Runner {
     /// The path to the script
-    pub path: Path
+    pub path: Path,
+    /// The transforms run on the script between parsing and running it
+    pub pipeline: Pipeline
 }
 impl Runner {
     /// Create a new interactive mode info
     pub fn new(script: String) -> Runner {
+        let mut pipeline = Pipeline::new();
+        pipeline.add(box Hoisting);
         Runner {
-            path: Path::new(script.as_slice())
+            path: Path::new(script.as_slice()),
+            pipeline: pipeline
         }
     }
     /// Run the script
-    pub fn run(&self) {
+    pub fn run(&mut self) {
         if self.path.exists() {
             let file = File::open(&self.path).unwrap();
             debug!("Now lexing...");
@@ -32,14 +36,10 @@ impl Runner {
             debug!("Now parsing...");
             let expr = Parser::new(tokens).parse_all().unwrap();
             debug!("Parsed as {}", expr);
-            debug!("Creating JIT Context");
-            let context = Context::new();
-            debug!("Compiling");
-            let compiler = JitCompiler::new(&context);
-            let result = compiler.compile(&expr);
-            debug!("Now running on JIT backend...");
-            let executor: JitExecutor = Executor::new(&Default::default());
-            match executor.execute(&result) {
+            let expr = self.pipeline.run(expr);
+            debug!("Now running...");
+            let executor: Interpreter = Executor::new(&Default::default());
+            match executor.execute(&expr).and_then(|v| executor.run_event_loop().map(|_| v)) {
                 Ok(v) =>
                     println!("{}", v),
                 Err(v) =>
@@ -49,4 +49,19 @@ impl Runner {
             fail!("{} does not exist", self.path.display());
         }
     }
+    /// Print the tokens of the script as JSON
+    pub fn print_tokens(&self) {
+        if self.path.exists() {
+            let file = File::open(&self.path).unwrap();
+            let mut lexer = Lexer::new(BufferedReader::new(file));
+            match lexer.lex() {
+                Ok(()) =>
+                    println!("{}", lexer.to_json().to_pretty_str()),
+                Err(err) =>
+                    println!("Failed with {}", err)
+            }
+        } else {
+            fail!("{} does not exist", self.path.display());
+        }
+    }
 }
\ No newline at end of file
@@ -1,14 +1,13 @@
 use collections::treemap::TreeMap;
-use js::back::compiler::JitCompiler;
-use js::back::executor::JitExecutor;
-use js::front::run::compiler::Compiler;
 use js::front::run::executor::Executor;
+use js::front::run::interpreter::Interpreter;
 use js::front::stdlib::function::Function;
 use js::front::stdlib::value::{ResultValue, Value, to_value, from_value};
 use js::syntax::lexer::Lexer;
 use js::syntax::parser::Parser;
+use js::syntax::hoist::Hoisting;
+use js::syntax::transform::Pipeline;
 use js::syntax::ast::token::{Token, TComment};
-use jit::Context;
 use std::default::Default;
 use std::io::{BufferedReader, File};
 use std::io::fs::walk_dir;
@@ -31,14 +30,17 @@ fn find_attrs(tokens: Vec<Token>) -> TreeMap<String, String> {
     map
 }
 /// Test against unit tests
-pub struct Tests<'a> {
-    context: Context<'a>
+pub struct Tests {
+    /// The transforms run on each test between parsing and running it
+    pub pipeline: Pipeline
 }
-impl<'a> Tests<'a> {
+impl Tests {
     /// Create a new unit tester
-    pub fn new() -> Tests<'a> {
+    pub fn new() -> Tests {
+        let mut pipeline = Pipeline::new();
+        pipeline.add(box Hoisting);
         Tests {
-            context: Context::new()
+            pipeline: pipeline
         }
     }
     /// Run a test
@@ -67,13 +69,11 @@ impl<'a> Tests<'a> {
         debug!("Parsing");
         let expr = Parser::new(tokens).parse_all().unwrap();
         debug!("Parsed as {}", expr);
+        let expr = self.pipeline.run(expr);
         debug!("Now running");
-        let env = Value::new_obj(None);
-        env.set_field("assert", Function::make(assert, ["condition"]));
-        let compiler = JitCompiler::new(&self.context);
-        let compiled = compiler.compile(&expr);
-        let executor: JitExecutor = Executor::new(&Default::default());
-        match executor.execute(&compiled) {
+        let executor: Interpreter = Executor::new(&Default::default());
+        executor.set_global("assert", Function::make(assert, ["condition"]));
+        match executor.execute(&expr).and_then(|v| executor.run_event_loop().map(|_| v)) {
             Ok(_) =>
                 println!("{}: {}: All tests passed successfully", file, desc),
             Err(v) =>
@@ -0,0 +1,214 @@
+use front::stdlib::value::*;
+use front::stdlib::function;
+use front::stdlib::symbol::TO_PRIMITIVE;
+use front::stdlib::object::{PropertyKey, StringKey, SymbolKey};
+use std::f64;
+use std::num::{from_str_radix, Zero, ToPrimitive};
+#[deriving(Clone, PartialEq)]
+/// The type that converting an object to a primitive should prefer to give
+pub enum PreferredType {
+    /// No preference, which prefers numbers except for dates
+    HintDefault,
+    /// Prefer a number, so `valueOf` is tried before `toString`
+    HintNumber,
+    /// Prefer a string, so `toString` is tried before `valueOf`
+    HintString
+}
+/// Check if a value is a primitive, which is anything that isn't an object or function
+pub fn is_primitive(value:&Value) -> bool {
+    match **value {
+        VObject(_) | VFunction(_) => false,
+        _ => true
+    }
+}
+/// Convert a value to a primitive, which calls the `Symbol.toPrimitive` method of an object with
+/// the hint if it has one, and otherwise calls its `valueOf` and `toString` methods in the order
+/// the hint prefers until one gives a primitive, and throws if none of them do, running the methods
+/// with the global object that the object or the method was made for
+pub fn to_primitive(value:Value, hint:PreferredType) -> ResultValue {
+    if is_primitive(&value) {
+        return Ok(value);
+    }
+    let global_for = |method:Value| match value.global_of().or(method.global_of()) {
+        Some(global) => Ok(global),
+        None => Err(to_value(format!("Cannot find the global object to convert {} to a primitive in", value)))
+    };
+    let exotic = try!(value.get(TO_PRIMITIVE));
+    if !exotic.is_null_or_undefined() {
+        let hint = match hint {
+            HintDefault => "default",
+            HintNumber => "number",
+            HintString => "string"
+        };
+        let global = try!(global_for(exotic));
+        let result = try!(function::call_value(exotic, vec!(to_value(hint)), global, global, value));
+        return if is_primitive(&result) {
+            Ok(result)
+        } else {
+            Err(to_value("Cannot convert object to primitive value"))
+        };
+    }
+    let methods = match hint {
+        HintString => ["toString", "valueOf"],
+        HintDefault | HintNumber => ["valueOf", "toString"]
+    };
+    for method in methods.iter() {
+        let method = value.get_field(*method);
+        match *method {
+            VFunction(_) => {
+                let global = try!(global_for(method));
+                let result = try!(function::call_value(method, Vec::new(), global, global, value));
+                if is_primitive(&result) {
+                    return Ok(result);
+                }
+            },
+            _ => ()
+        }
+    }
+    Err(to_value("Cannot convert object to primitive value"))
+}
+/// Convert a value to a boolean, where only `false`, `0`, `NaN`, `""`, `null` and `undefined` are false
+pub fn to_boolean(value:&Value) -> bool {
+    match **value {
+        VObject(_) | VFunction(_) | VSymbol(_) => true,
+        VString(ref s) => !s.is_empty(),
+        VNumber(n) => n != 0.0 && !n.is_nan(),
+        VInteger(n) => n != 0,
+        VBigInt(ref n) => !n.is_zero(),
+        VBoolean(v) => v,
+        VNull | VUndefined => false
+    }
+}
+/// Check if a character is whitespace or a line terminator, which is trimmed from strings converted to numbers
+pub fn is_js_whitespace(ch:char) -> bool {
+    ch.is_whitespace() || ch == '\uFEFF'
+}
+/// Convert a string to a number, which is `0` if it is empty or only whitespace, can be
+/// written in hexadecimal, octal or binary with a prefix, and is `NaN` if it isn't a number
+pub fn string_to_number(text:&str) -> f64 {
+    let text = text.trim_chars(is_js_whitespace);
+    if text.is_empty() {
+        return 0.0;
+    }
+    let prefix = if text.len() >= 2 {text.slice_to(2)} else {text};
+    let radix = match prefix {
+        "0x" | "0X" => 16,
+        "0o" | "0O" => 8,
+        "0b" | "0B" => 2,
+        _ => 10
+    };
+    if radix != 10 {
+        return from_str_radix::<f64>(text.slice_from(2), radix).unwrap_or(f64::NAN);
+    }
+    let (sign, digits) = match text.char_at(0) {
+        '-' => (-1.0, text.slice_from(1)),
+        '+' => (1.0, text.slice_from(1)),
+        _ => (1.0, text)
+    };
+    if digits == "Infinity" {
+        return sign * f64::INFINITY;
+    }
+    // Rust accepts names such as `inf` and `NaN` that Javascript doesn't
+    if digits.is_empty() || !digits.chars().all(|ch| ch.is_digit() || ch == '.' || ch == 'e' || ch == 'E' || ch == '+' || ch == '-') {
+        return f64::NAN;
+    }
+    match from_str::<f64>(digits) {
+        Some(num) => sign * num,
+        None => f64::NAN
+    }
+}
+/// Convert a value to a number, converting an object to a primitive first
+pub fn to_number(value:Value) -> Result<f64, Value> {
+    Ok(match *try!(to_primitive(value, HintNumber)) {
+        VString(ref text) => string_to_number(text.as_slice()),
+        VNumber(num) => num,
+        VInteger(num) => num as f64,
+        VBigInt(ref num) => num.to_f64().unwrap_or(f64::NAN),
+        VBoolean(true) => 1.0,
+        VBoolean(false) | VNull => 0.0,
+        VSymbol(_) => return Err(to_value("Cannot convert a Symbol value to a number")),
+        VUndefined | VObject(_) | VFunction(_) => f64::NAN
+    })
+}
+/// Convert a number to a 32-bit integer, wrapping it around, as bitwise operators do
+pub fn to_int32(num:f64) -> i32 {
+    if num.is_nan() || num.is_infinite() {
+        return 0;
+    }
+    let wrapped = num.trunc() % 4294967296.0;
+    (if wrapped < 0.0 {
+        wrapped + 4294967296.0
+    } else {
+        wrapped
+    }) as u32 as i32
+}
+/// Convert a number to an unsigned 32-bit integer, wrapping it around
+pub fn to_uint32(num:f64) -> u32 {
+    to_int32(num) as u32
+}
+/// Get the fewest significant digits that give back the number given when they are parsed, along
+/// with the exponent of the first of them, so `1234.5` gives `("12345", 3)`
+fn shortest_digits(num:f64) -> (String, i32) {
+    for precision in range(1u, 18) {
+        let text = f64::to_str_exp_exact(num, precision - 1, false);
+        if from_str::<f64>(text.as_slice()) != Some(num) && precision < 17 {
+            continue;
+        }
+        let mut parts = text.as_slice().split('e');
+        let mantissa = parts.next().unwrap_or("0");
+        let exponent = parts.next().and_then(|exp| from_str::<i32>(exp)).unwrap_or(0);
+        let digits : String = mantissa.chars().filter(|ch| ch.is_digit()).collect();
+        let digits = digits.as_slice().trim_right_chars('0');
+        return (if digits.is_empty() {"0".into_string()} else {digits.into_string()}, exponent);
+    }
+    unreachable!()
+}
+/// Convert a number to a string like Javascript does, which uses the fewest digits that give
+/// back the number, leaves whole numbers without a fraction, gives `0` for `-0`, and uses an
+/// exponent for numbers from `1e21` or below `1e-6`
+pub fn number_to_string(num:f64) -> String {
+    if num.is_nan() {
+        return "NaN".into_string();
+    } else if num == 0.0 {
+        return "0".into_string();
+    } else if num < 0.0 {
+        return format!("-{}", number_to_string(-num));
+    } else if num.is_infinite() {
+        return "Infinity".into_string();
+    }
+    let (digits, exponent) = shortest_digits(num);
+    let digits = digits.as_slice();
+    let (k, n) = (digits.len() as i32, exponent + 1);
+    if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as uint))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", digits.slice_to(n as uint), digits.slice_from(n as uint))
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat(-n as uint), digits)
+    } else {
+        let sign = if exponent < 0 {"-"} else {"+"};
+        let exponent = if exponent < 0 {-exponent} else {exponent};
+        if k == 1 {
+            format!("{}e{}{}", digits, sign, exponent)
+        } else {
+            format!("{}.{}e{}{}", digits.slice_to(1), digits.slice_from(1), sign, exponent)
+        }
+    }
+}
+/// Convert a value to a string, converting an object to a primitive first
+pub fn to_string(value:Value) -> Result<String, Value> {
+    let value = try!(to_primitive(value, HintString));
+    if value.is_symbol() {
+        return Err(to_value("Cannot convert a Symbol value to a string"));
+    }
+    Ok(value.to_string())
+}
+/// Convert a value to the key of a property, which is keyed by the symbol for symbols,
+/// and by the value converted to a string otherwise
+pub fn to_property_key(value:Value) -> Result<PropertyKey, Value> {
+    let value = try!(to_primitive(value, HintString));
+    match *value {
+        VSymbol(ref sym) => Ok(SymbolKey(sym.id)),
+        _ => Ok(StringKey(try!(to_string(value))))
+    }
+}
@@ -5,7 +5,7 @@ macro_rules! js(
 	}) => ({
 		let value = Value::new_obj(Some($global));
 		$(
-			value.set_field($name, js!($value));
+			value.set_hidden_field($name, js!($value));
 		)+
 		value
 	});
@@ -16,14 +16,14 @@ macro_rules! js(
 #[macro_export]
 macro_rules! js_extend(
 	($object:expr, {$name:expr: $value:expr}) => (
-		$object.set_field($name, $value)
+		$object.set_hidden_field($name, $value)
 	);
 	($object:expr, {
 		$($name:expr: $value:expr),+
 	}) => ({
 		let object = $object;
 		$(
-			object.set_field($name, js!($value));
+			object.set_hidden_field($name, js!($value));
 		)+
 	});
 )
\ No newline at end of file
@@ -0,0 +1,105 @@
+use front::stdlib::value::{Value, ResultValue, to_value};
+use front::stdlib::function::{Function, call_value};
+use front::run::scope::new_scope;
+use front::run::interpreter::{run_in, run_in_scopes};
+use front::stdlib::error::new_error;
+use syntax::ast::expr::{Expr, ScriptExpr, FunctionDeclExpr};
+use syntax::ast::token::Token;
+use syntax::lexer::Lexer;
+use syntax::parser::{Parser, ParserOptions};
+use syntax::hoist::hoist;
+use std::default::Default;
+use std::io::MemReader;
+/// The hidden field of the global object holding the `eval` function it was made with, so a
+/// call to `eval` is only direct if it calls that function
+static EVAL: &'static str = "__eval__";
+
+/// Lex source code given while running, throwing a `SyntaxError` if it can't be lexed
+fn lex(source:&str, global:Value) -> Result<Vec<Token>, Value> {
+    let mut lexer = Lexer::new(MemReader::new(source.as_bytes().to_vec()));
+    match lexer.lex() {
+        Ok(()) => Ok(lexer.tokens),
+        Err(err) => Err(new_error(global, "SyntaxError", err.to_string().as_slice()))
+    }
+}
+/// Lex and parse source code as a script run by `eval`, in strict mode if the code calling it is,
+/// hoisting its declarations, giving `None` if there is no code, and throwing a `SyntaxError`
+/// if it can't be parsed
+pub fn parse(source:&str, strict:bool, global:Value) -> Result<Option<Expr>, Value> {
+    let tokens = try!(lex(source, global));
+    if tokens.is_empty() {
+        return Ok(None);
+    }
+    let options = ParserOptions {
+        strict: strict,
+        ..Default::default()
+    };
+    match Parser::with_options(tokens, options).parse_script() {
+        Ok(expr) => Ok(Some(hoist(expr))),
+        Err(err) => Err(new_error(global, "SyntaxError", err.to_string().as_slice()))
+    }
+}
+/// Run source code as a script in the scope given with the `this` given, giving the value of its last
+/// statement, where values that aren't strings are given back as they are, sloppy code declares its
+/// variables in `vars`, the scope of the function or script it is run in, and strict code declares
+/// them in a scope of its own
+pub fn eval_source(source:Value, global:Value, scope:Value, vars:Value, this:Value, strict:bool) -> ResultValue {
+    if !source.is_string() {
+        return Ok(source);
+    }
+    let expr = match try!(parse(source.to_string().as_slice(), strict, global)) {
+        Some(expr) => expr,
+        None => return Ok(Value::undefined())
+    };
+    let strict = match expr.def {
+        ScriptExpr(_, strict) => strict,
+        _ => strict
+    };
+    if strict {
+        let scope = new_scope(scope);
+        run_in(&expr, global, scope, this)
+    } else {
+        run_in_scopes(&expr, global, scope, vars, this)
+    }
+}
+/// Run a call to a function named `eval`, which is a direct call that runs the source given in the scopes and
+/// with the `this` of the code calling it if the function is the `eval` function of the global object, and a
+/// plain call of the function otherwise
+pub fn direct_eval(func:Value, args:Vec<Value>, global:Value, scope:Value, vars:Value, this:Value, strict:bool) -> ResultValue {
+    if !func.strict_equals(&global.get_field(EVAL)) {
+        return call_value(func, args, global, scope, Value::undefined());
+    }
+    let source = if args.len() >= 1 {args[0]} else {Value::undefined()};
+    eval_source(source, global, scope, vars, this, strict)
+}
+/// Run the source given as a script in the global scope, as a call to `eval` that isn't direct does
+pub fn eval(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    let source = if args.len() >= 1 {args[0]} else {Value::undefined()};
+    eval_source(source, global, global, global, global, false)
+}
+/// Make a function from the source of its parameters and body, as the `Function` constructor does,
+/// which closes over the global scope instead of the scope it is made in, is named `anonymous` without
+/// its body seeing that name, and throws a `SyntaxError` if the source isn't a single function
+pub fn function_from_source(params:&str, body:&str, global:Value) -> ResultValue {
+    // the line breaks stop comments at the end of the parameters or body from hiding the rest
+    let source = format!("function ({}\n) {{\n{}\n}}", params, body);
+    let expr = match Parser::new(try!(lex(source.as_slice(), global))).parse_expr() {
+        Ok(expr) => expr,
+        Err(err) => return Err(new_error(global, "SyntaxError", err.to_string().as_slice()))
+    };
+    match expr.def {
+        FunctionDeclExpr(None, _, _, _) => (),
+        _ => return Err(new_error(global, "SyntaxError", "Invalid function parameters or body"))
+    }
+    let func = try!(run_in(&hoist(expr), global, global, global));
+    func.set_hidden_field("name", to_value("anonymous"));
+    Ok(func)
+}
+/// Initialise the global object with the `eval` function
+pub fn init(global:Value) {
+    let eval = Function::make(eval, ["source"]);
+    global.set_hidden_field(EVAL, eval);
+    js_extend!(global, {
+        "eval": eval
+    });
+}
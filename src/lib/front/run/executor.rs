@@ -2,9 +2,12 @@ use front::stdlib::value::{
     Value, 
     ResultValue
 };
+use front::stdlib::function;
+use front::stdlib::promise;
+use front::stdlib::timer;
 use std::default::Default;
 
-/// An execution engine which runs whatever is generated by the `Compiler`
+/// An execution engine which runs code in the form it takes it in, such as the expressions the `Interpreter` walks
 pub trait Executor<Compiled> {
     /// Create a new execution engine with the given configuration
     fn new(config:&ExecutorConfig) -> Self;
@@ -20,19 +23,171 @@ pub trait Executor<Compiled> {
     fn set_global<'a>(&self, field: &'a str, value:Value) -> Value {
         self.get_global_obj().set_field(field, value)
     }
-    /// Execute a compiled expression
-    fn execute(&self, comp:&Compiled) -> ResultValue;
+    #[inline]
+    /// Execute an expression in the global scope
+    fn execute(&self, comp:&Compiled) -> ResultValue {
+        let global = self.get_global_obj();
+        self.execute_in(comp, global, global)
+    }
+    /// Execute an expression in the scope given, with the `this` given
+    fn execute_in(&self, comp:&Compiled, scope:Value, this:Value) -> ResultValue;
+    #[inline]
+    /// Call a function value from a script, such as a callback it gave, with the `this` and
+    /// arguments given, giving what it returns or throwing what it throws
+    fn call(&self, func:Value, this:Value, args:&[Value]) -> ResultValue {
+        let global = self.get_global_obj();
+        function::call_value(func, args.to_vec(), global, global, this)
+    }
+    #[inline]
+    /// Construct an object with a constructor from a script and the arguments given, as `new` does
+    fn construct(&self, func:Value, args:&[Value]) -> ResultValue {
+        let global = self.get_global_obj();
+        function::construct_value(func, args.to_vec(), global, global)
+    }
+    #[inline]
+    /// Run the jobs queued by promises that were waiting when this was called, giving how many
+    /// were run and leaving the jobs they queue for the next call
+    fn run_jobs(&self) -> Result<uint, Value> {
+        promise::run_jobs(self.get_global_obj())
+    }
+    #[inline]
+    /// Run the jobs queued by promises until none are left, giving how many were run
+    fn run_until_idle(&self) -> Result<uint, Value> {
+        promise::run_until_idle(self.get_global_obj())
+    }
+    #[inline]
+    /// Run the queued jobs and then the timers in the order they are due, along with the jobs
+    /// each of them queue, until no timers are left, giving how many timers were run
+    fn run_event_loop(&self) -> Result<uint, Value> {
+        timer::run_event_loop(self.get_global_obj())
+    }
+    #[inline]
+    /// Move the time of the event loop forward by the number of milliseconds given, running
+    /// the timers due by then, giving how many were run
+    fn advance_time(&self, millis:f64) -> Result<uint, Value> {
+        timer::advance(self.get_global_obj(), millis)
+    }
+}
+#[deriving(Clone)]
+/// How a statement finished running, so that loops and switches can tell when
+/// to stop early and functions can tell when a value was returned, along with the
+/// completion value of the statement, which is `None` for statements that don't
+/// give one, such as declarations, so the value of the statement before them is kept
+pub enum Completion {
+    /// The statement ran to the end, giving a value
+    NormalCompletion(Option<Value>),
+    /// A `break` was run and should exit the innermost loop or switch, or the labelled statement given
+    BreakCompletion(Option<String>, Option<Value>),
+    /// A `continue` was run and should skip to the next iteration of the innermost loop, or the labelled loop given
+    ContinueCompletion(Option<String>, Option<Value>),
+    /// A `return` was run with the value given and should exit the function
+    ReturnCompletion(Value)
+}
+impl Completion {
+    /// Get the value of the completion, which is `undefined` if it doesn't have one
+    pub fn get_value(&self) -> Value {
+        match *self {
+            NormalCompletion(Some(v)) | BreakCompletion(_, Some(v)) | ContinueCompletion(_, Some(v)) | ReturnCompletion(v) => v,
+            _ => Value::undefined()
+        }
+    }
+    /// Returns true if the completion has a value
+    pub fn has_value(&self) -> bool {
+        match *self {
+            NormalCompletion(None) | BreakCompletion(_, None) | ContinueCompletion(_, None) => false,
+            _ => true
+        }
+    }
+    /// Give the completion the value given if it doesn't have one, so a block gives the value of the
+    /// last statement in it that gives one
+    pub fn update_empty(self, value:Option<Value>) -> Completion {
+        match self {
+            NormalCompletion(None) => NormalCompletion(value),
+            BreakCompletion(label, None) => BreakCompletion(label, value),
+            ContinueCompletion(label, None) => ContinueCompletion(label, value),
+            done => done
+        }
+    }
+    /// Returns true if this is a break or continue that targets the loop or switch with
+    /// the labels given
+    pub fn targets(&self, labels:&[String]) -> bool {
+        match *self {
+            BreakCompletion(None, _) | ContinueCompletion(None, _) => true,
+            BreakCompletion(Some(ref label), _) | ContinueCompletion(Some(ref label), _) => labels.contains(label),
+            _ => false
+        }
+    }
+    /// Returns true if the statement ran to the end
+    pub fn is_normal(&self) -> bool {
+        match *self {
+            NormalCompletion(_) => true,
+            _ => false
+        }
+    }
+    /// Get the value that a function gives when its body finishes with this completion,
+    /// which is the value returned, or `undefined` if it ran to the end without returning
+    pub fn return_value(&self) -> Value {
+        match *self {
+            ReturnCompletion(v) => v,
+            _ => Value::undefined()
+        }
+    }
+}
+/// The completion of a statement, or the value it threw
+pub type CompletionResult = Result<Completion, Value>;
+/// Give the completion of a block after one more of its statements ran with the completion given,
+/// where the block stops at the first statement that doesn't run to the end, so that nothing after
+/// a `return`, `break`, `continue` or `throw` is run, and the value of the block is the value of the
+/// last statement run that gives one, so `1; var x = 2;` gives `1`
+pub fn block_step(before:&Completion, completion:Completion) -> Completion {
+    let value = if before.has_value() {Some(before.get_value())} else {None};
+    completion.update_empty(value)
+}
+/// Give the completion of an `if` statement from that of the branch it ran, which is `undefined`
+/// if the branch gives no value or none was run, so `1; if (false) 2;` gives `undefined`
+pub fn end_if(branch:Option<Completion>) -> Completion {
+    match branch {
+        Some(completion) => completion.update_empty(Some(Value::undefined())),
+        None => NormalCompletion(Some(Value::undefined()))
+    }
+}
+/// Give the value of a loop after an iteration of its body ran with the completion given, where `value`
+/// is the value of the last iteration that gave one, or the completion of the loop if the iteration
+/// ends it, which it does on a `break` that targets it or any other abrupt completion
+pub fn loop_step(labels:&[String], value:Value, completion:Completion) -> Result<Value, Completion> {
+    let value = if completion.has_value() {completion.get_value()} else {value};
+    let targeted = completion.targets(labels);
+    match completion {
+        ContinueCompletion(_, _) if targeted => Ok(value),
+        NormalCompletion(_) => Ok(value),
+        BreakCompletion(_, _) if targeted => Err(NormalCompletion(Some(value))),
+        done => Err(done.update_empty(Some(value)))
+    }
+}
+/// Give the completion of a `switch` statement from that of the clauses it ran as a block, where a
+/// `break` that targets it ends it normally, and it gives `undefined` if the clauses give no value
+pub fn end_switch(clauses:Completion, labels:&[String]) -> Completion {
+    let completion = clauses.update_empty(Some(Value::undefined()));
+    let targeted = completion.targets(labels);
+    match completion {
+        BreakCompletion(_, Some(value)) if targeted => NormalCompletion(Some(value)),
+        done => done
+    }
 }
 /// Configuration for the executor
 pub struct ExecutorConfig {
     /// The initial global value
-    pub global: Value
+    pub global: Value,
+    /// How many calls can run inside each other, past which calls throw a `RangeError`
+    /// instead of overflowing the stack
+    pub max_call_depth: uint
 }
 impl Default for ExecutorConfig {
     #[inline(always)]
     fn default() -> ExecutorConfig {
         ExecutorConfig {
-            global: Value::new_global()
+            global: Value::new_global(),
+            max_call_depth: function::DEFAULT_MAX_CALL_DEPTH
         }
     }
 }
\ No newline at end of file
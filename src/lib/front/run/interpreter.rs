@@ -0,0 +1,1890 @@
+use front::run::executor::{Executor, ExecutorConfig, Completion, CompletionResult, NormalCompletion, BreakCompletion,
+                           ContinueCompletion, ReturnCompletion, block_step, end_if, loop_step, end_switch};
+use front::run::scope::{new_scope, find_scope, get_var, declare_var, declare_const, is_const, assign_var};
+use front::run::eval::direct_eval;
+use front::run::module::DEFAULT_EXPORT;
+use front::stdlib::value::{Value, VNull, VFunction, ResultValue, to_value};
+use front::stdlib::function::{Function, HOME, NEW_TARGET, call_value, set_max_call_depth, enter_call, leave_call};
+use front::stdlib::object::{Property, PropertyKey, StringKey, GeneratorObject, PROTOTYPE, INSTANCE_PROTOTYPE};
+use front::stdlib::generator::{make_generator, make_result};
+use front::stdlib::{array, error, iterator, regexp};
+use conversions::{to_number, to_boolean, to_string, to_property_key};
+use syntax::ast::expr::*;
+use syntax::ast::op::*;
+use syntax::ast::constant::*;
+use syntax::ast::pattern::{Pattern, RefPattern, ObjectPattern, ArrayPattern, DefaultPattern};
+use syntax::ast::pos::Position;
+use num::bigint::BigInt;
+use std::cmp::{Less, Greater};
+use std::mem::replace;
+use std::num::One;
+use std::rc::Rc;
+/// The label of the break that ends an optional chain when an optional access short-circuits,
+/// which can't clash with any label in a script
+static CHAIN: &'static str = "?.";
+
+/// An executor that runs expressions by walking them, keeping what is left to do in each
+/// expression being run on a stack of tasks instead of on the native stack
+pub struct Interpreter {
+    global: Value
+}
+impl Executor<Expr> for Interpreter {
+    fn new(config:&ExecutorConfig) -> Interpreter {
+        set_max_call_depth(config.global, config.max_call_depth);
+        Interpreter {
+            global: config.global
+        }
+    }
+    #[inline]
+    fn get_global_obj(&self) -> Value {
+        self.global
+    }
+    fn execute_in(&self, expr:&Expr, scope:Value, this:Value) -> ResultValue {
+        run_in(expr, self.global, scope, this)
+    }
+}
+#[deriving(Clone)]
+/// The state of a generator whose body is run by the interpreter, which holds what is left to do
+/// in its body while it is paused at a `yield`
+pub struct Generator {
+    state: GeneratorState
+}
+#[deriving(Clone)]
+/// How far a generator has run its body
+enum GeneratorState {
+    /// The body hasn't been run yet, and will be run where given
+    NotStarted(Expr, Env),
+    /// The body is paused at a `yield`, with the tasks given left to do
+    Paused(Vec<Task>),
+    /// The body is running, or has finished
+    Closed
+}
+/// Run an expression in the scope given, with the `this` given, giving the completion value of
+/// the statements it runs, as `eval` does
+pub fn run_in(expr:&Expr, global:Value, scope:Value, this:Value) -> ResultValue {
+    run_in_scopes(expr, global, scope, scope, this)
+}
+/// Run an expression inside the scope given, declaring its variables in `vars`, the scope of the
+/// function or script that the scope is in, as sloppy code run by a direct call to `eval` does
+pub fn run_in_scopes(expr:&Expr, global:Value, scope:Value, vars:Value, this:Value) -> ResultValue {
+    let env = Env {
+        scope: scope,
+        vars: vars,
+        this: this,
+        strict: false
+    };
+    let mut machine = Machine::new(global);
+    finish(machine.run(Run(expr.clone(), env)), global).map(|completion| completion.get_value())
+}
+/// Run the body of a function made by the interpreter with the arguments given, in the scope
+/// made for the call, giving what it returns, or the value of its body if it is an arrow function
+/// whose body is an expression
+pub fn run_function(func:&Function, args:Vec<Value>, global:Value, frame:Value, this:Value) -> ResultValue {
+    let body = match func.body {
+        Some(ref body) => (**body).clone(),
+        None => return Ok(Value::undefined())
+    };
+    bind_params(func, &args, frame);
+    let env = Env {
+        scope: frame,
+        vars: frame,
+        this: this,
+        strict: func.strict
+    };
+    // a generator function gives a generator that runs its body as it is resumed
+    if func.generator {
+        let generator = make_generator(global, Function::make(resume_generator, ["mode", "value"]));
+        generator.set_kind(GeneratorObject(Generator {
+            state: NotStarted(body, env)
+        }));
+        return Ok(generator);
+    }
+    let expression = !is_block(&body);
+    let mut machine = Machine::new(global);
+    let next = machine.enter(body, env);
+    let completion = try!(finish(machine.run(next), global));
+    Ok(if expression {completion.get_value()} else {completion.return_value()})
+}
+/// Resume the body of the generator this is called on in the way given, with the value given, running it up
+/// to the next `yield` or the end, which is the function the generators made by `run_function` resume with
+fn resume_generator(args:Vec<Value>, global:Value, _:Value, this:Value) -> ResultValue {
+    let mode = if args.len() >= 1 {args[0].to_string()} else {String::new()};
+    let value = if args.len() >= 2 {args[1]} else {Value::undefined()};
+    // the state is taken out of the generator while it runs, so it is closed if the body throws
+    let state = this.with_object_data(|data| match data.kind {
+        GeneratorObject(ref mut generator) => replace(&mut generator.state, Closed),
+        _ => Closed
+    }).unwrap_or(Closed);
+    let mut machine = Machine::new(global);
+    let next = match (state, mode.as_slice()) {
+        (NotStarted(body, env), "next") => machine.enter(body, env),
+        (NotStarted(_, _), "throw") => return Err(value),
+        (NotStarted(_, _), _) => return Ok(make_result(global, value, true)),
+        (Paused(tasks), mode) => {
+            machine.tasks = tasks;
+            match mode {
+                "throw" => Give(Err(value)),
+                // returning runs the finally blocks the body is paused in
+                "return" => Give(Ok(ReturnCompletion(value))),
+                _ => give(value)
+            }
+        },
+        (Closed, _) => return Ok(make_result(global, Value::undefined(), true))
+    };
+    match machine.run(next) {
+        Suspended(value) => {
+            let mut tasks = Some(replace(&mut machine.tasks, Vec::new()));
+            this.with_object_data(|data| match data.kind {
+                GeneratorObject(ref mut generator) => generator.state = Paused(tasks.take().unwrap()),
+                _ => ()
+            });
+            Ok(make_result(global, value, false))
+        },
+        Finished(result) => {
+            let completion = try!(result);
+            Ok(make_result(global, completion.return_value(), true))
+        }
+    }
+}
+/// Get the completion of code that isn't the body of a generator, which can't pause at a `yield`
+fn finish(outcome:Outcome, global:Value) -> CompletionResult {
+    match outcome {
+        Finished(result) => result,
+        Suspended(_) => Err(error::new_error(global, "SyntaxError", "A yield can only be run in the body of a generator"))
+    }
+}
+/// Declare the parameters of a function in the scope made for a call to it, as the arguments
+/// given, or `undefined` where fewer arguments were given
+fn bind_params(func:&Function, args:&Vec<Value>, frame:Value) {
+    for (i, name) in func.args.iter().enumerate() {
+        declare_var(frame, name.as_slice(), if i < args.len() {args[i]} else {Value::undefined()});
+    }
+}
+/// Returns true if the body of a function is a block rather than the expression of an arrow function
+fn is_block(body:&Expr) -> bool {
+    match body.def {
+        BlockExpr(_) => true,
+        _ => false
+    }
+}
+/// Returns true if the statement declares a binding in the block it is in, so the block needs a scope of its own
+fn declares_lexically(stmt:&Expr) -> bool {
+    match stmt.def {
+        LetDeclExpr(_) | ConstDeclExpr(_) | ClassDeclExpr(Some(_), _, _, _)
+            | FunctionDeclExpr(Some(_), _, _, _) | GeneratorDeclExpr(Some(_), _, _, _) => true,
+        ExportDeclExpr(box ref decl) => declares_lexically(decl),
+        _ => false
+    }
+}
+/// Give an error the position of the code that threw it, as `error::set_position` does
+fn located(thrown:Value, at:&Position) -> Value {
+    error::set_position(thrown, at);
+    thrown
+}
+// get the value of a result, or give its error from the function this is in, located at the position given
+macro_rules! attempt(
+    ($result:expr, $at:expr) => (
+        match $result {
+            Ok(value) => value,
+            Err(thrown) => return Give(Err(located(thrown, &$at)))
+        }
+    )
+)
+/// Give the value of an expression
+fn give(value:Value) -> Next {
+    Give(Ok(NormalCompletion(Some(value))))
+}
+/// Give the completion of a statement that gives no value, such as a declaration
+fn give_empty() -> Next {
+    Give(Ok(NormalCompletion(None)))
+}
+
+#[deriving(Clone)]
+/// Where code is being run
+struct Env {
+    /// The innermost scope, which blocks declare their bindings in
+    scope: Value,
+    /// The scope of the function or script being run, which `var` declares its variables in
+    vars: Value,
+    /// The value of `this`
+    this: Value,
+    /// Whether the code is in strict mode
+    strict: bool
+}
+/// What the machine should do next
+enum Next {
+    /// Run the expression given where given
+    Run(Expr, Env),
+    /// Give the completion given to the task on the top of the stack
+    Give(CompletionResult),
+    /// Pause the generator being run, giving the value given to the code resuming it
+    Suspend(Value)
+}
+/// How a machine stopped running
+enum Outcome {
+    /// Every task finished, with the completion given
+    Finished(CompletionResult),
+    /// The generator being run paused at a `yield` with the value given, leaving its tasks on the stack
+    Suspended(Value)
+}
+#[deriving(Clone)]
+/// Something to finish once the expression being run completes, which is given its completion
+enum Task {
+    /// Evaluating the operands of an action
+    Evaluating(Operands),
+    /// Running the statements of a block
+    InBlock(Block),
+    /// Waiting for the condition of an `if` or conditional expression, holding its branches
+    Branching(Env, Box<Expr>, Option<Box<Expr>>),
+    /// Running the branch of an `if` or conditional expression
+    EndingIf,
+    /// Running a loop
+    Looping(Loop),
+    /// Running a statement with the labels given that isn't a loop or switch
+    Labelled(Vec<String>),
+    /// Finding the case of a switch statement to start at
+    Switching(Switch),
+    /// Running the cases of a switch statement with the labels given
+    EndingSwitch(Vec<String>),
+    /// Running a try statement
+    Trying(Try),
+    /// Waiting for the left side of a logical operation, holding the right side
+    ShortCircuiting(LogOp, Box<Expr>, Env),
+    /// Running an optional chain
+    Chaining,
+    /// Running a declaration of variables
+    Declaring(Declaration),
+    /// Assigning the parts of values to patterns
+    Binding(Bindings),
+    /// Delegating to the iterator given with `yield*` at the position given
+    Delegating(Value, Position),
+    /// Running the body of a function called from the code on the stack, which gives the value returned,
+    /// or the value of the body if it is an expression, or the object given if it is being constructed
+    /// and doesn't return an object
+    Returning(Option<Value>, bool)
+}
+#[deriving(Clone)]
+/// What to do with the values of operands once they have all been evaluated
+enum Action {
+    /// Run a binary operation that isn't logical
+    DoBinary(BinOp),
+    /// Run a unary operation
+    DoUnary(UnaryOp),
+    /// Give the name of the type of the value
+    DoTypeOf,
+    /// Give the value given instead
+    DoGive(Value),
+    /// End the enclosing optional chain if the value is `null` or `undefined`
+    DoOptional,
+    /// Get the field given of the object, or the field that the second value is the key of
+    DoGetField(Option<String>),
+    /// Get the method given of the object, or the method that the second value is the key of, then
+    /// call it with the arguments given, on the object, or the `this` being run with if it is `super`
+    DoMethod(Option<String>, Vec<Expr>, bool),
+    /// Call the function given on the `this` given with the values as arguments
+    DoCall(Value, Value),
+    /// Call the first value with the rest as arguments
+    DoCallValue,
+    /// Call the first value with the rest as arguments as a call to a function named `eval`
+    DoDirectEval,
+    /// Construct an object with the first value and the rest as arguments
+    DoConstruct,
+    /// Construct `this` with the constructor of the class being extended and the values as arguments
+    DoSuperCall,
+    /// Make an array of the values
+    DoArray,
+    /// Make a string of the text given with the values between each piece
+    DoTemplate(Vec<String>),
+    /// Make an object with the fields given, taking one value for each data field and for each
+    /// getter and setter of an accessor
+    DoObject(Vec<(String, FieldKind)>),
+    /// Throw the value
+    DoThrow,
+    /// Return the value from the function being run
+    DoReturn,
+    /// Pause the generator being run, giving the value, or delegating to its iterator if this is a `yield*`
+    DoYield(bool),
+    /// Use the field of the object given, or the field that the second value is the key of, as given
+    DoReference(Option<String>, RefUse),
+    /// Store the value in the reference given
+    DoStore(Reference),
+    /// Store the operation given between the value given and the value in the reference given
+    DoCombine(Reference, BinOp, Value),
+    /// Assign the parts of the value to the pattern given
+    DoDestructure(Pattern),
+    /// Make a class with the name, constructor and methods given, extending the value if there is one
+    DoClass(Option<String>, Box<Expr>, Vec<(bool, MethodKind, String, Expr)>),
+    /// Export the value as the default export of the module being run, also binding it to the name
+    /// given if it is a named function or class declaration
+    DoExportDefault(Option<String>)
+}
+#[deriving(Clone)]
+/// The kind of a field in an object declaration
+enum FieldKind {
+    /// A field with a value
+    DataField,
+    /// A field with a getter, a setter or both
+    AccessorField(bool, bool)
+}
+#[deriving(Clone)]
+/// Something that can be assigned to
+enum Reference {
+    /// A variable
+    VarRef(String),
+    /// A field of an object
+    FieldRef(Value, PropertyKey)
+}
+#[deriving(Clone)]
+/// How a reference is used
+enum RefUse {
+    /// Assign the value of the expression given to it
+    AssignRef(Box<Expr>),
+    /// Increment or decrement it, giving the new value if it is a prefix
+    UpdateRef(bool, UpdateOp),
+    /// Run an operation between it and the expression given, then assign the result to it
+    AssignOpRef(BinOp, Box<Expr>),
+    /// Remove it
+    DeleteRef,
+    /// Assign the value given to it
+    BindRef(Value)
+}
+#[deriving(Clone, PartialEq)]
+/// How a pattern binds its variables
+enum BindKind {
+    /// As variables scoped to the function, which are declared if they haven't been
+    BindVar,
+    /// As variables scoped to the block
+    BindLet,
+    /// As constants scoped to the block
+    BindConst,
+    /// By assigning to them where they are declared
+    BindAssign
+}
+#[deriving(Clone)]
+/// The operands of an action being evaluated
+struct Operands {
+    env: Env,
+    /// The operands left to evaluate, in reverse
+    exprs: Vec<Expr>,
+    /// The values of the operands evaluated so far
+    values: Vec<Value>,
+    /// Whether the operand being evaluated is spread
+    spreading: bool,
+    action: Action,
+    /// Where the expression of the action starts
+    at: Position
+}
+#[deriving(Clone)]
+/// The statements of a block being run
+struct Block {
+    env: Env,
+    /// The statements left to run, in reverse
+    stmts: Vec<Expr>,
+    /// The completion of the statements run so far
+    last: Completion,
+    /// Where the statement being run starts
+    at: Position
+}
+#[deriving(Clone)]
+/// What is being run of a loop
+enum LoopPhase {
+    /// The initial expression of a for loop, or the value of a for-in or for-of loop
+    LoopStarting,
+    /// The condition
+    LoopChecking,
+    /// The binding of the next item of a for-in or for-of loop
+    LoopBinding,
+    /// The body
+    LoopRunning,
+    /// The step of a for loop
+    LoopStepping
+}
+#[deriving(Clone)]
+/// The kind of a loop
+enum LoopKind {
+    /// A while loop with the condition given
+    WhileLoop(Box<Expr>),
+    /// A for loop with the condition and step given, whose body has copies of the variables
+    /// its initial expression declares with `let` or `const` for each iteration
+    ForLoop(Option<Box<Expr>>, Option<Box<Expr>>, Vec<String>),
+    /// A for-in loop binding the pattern given to the property names left, which are in reverse
+    ForInLoop(BindKind, Pattern, Vec<String>),
+    /// A for-of loop binding the pattern given to the items of the iterator given
+    ForOfLoop(BindKind, Pattern, Value)
+}
+#[deriving(Clone)]
+/// A loop being run
+struct Loop {
+    env: Env,
+    /// The scope around the loop, which each iteration's scope is made inside of
+    outer: Value,
+    labels: Vec<String>,
+    /// The value of the last iteration that gave one
+    value: Value,
+    body: Box<Expr>,
+    kind: LoopKind,
+    phase: LoopPhase
+}
+#[deriving(Clone)]
+/// A switch statement whose cases are being checked
+struct Switch {
+    env: Env,
+    labels: Vec<String>,
+    /// The value being switched on, once it has been evaluated
+    value: Option<Value>,
+    cases: Vec<(Expr, Vec<Expr>)>,
+    /// The case being checked
+    index: uint,
+    default: Vec<Expr>
+}
+#[deriving(Clone)]
+/// What is being run of a try statement
+enum TryPhase {
+    /// The block
+    InTry,
+    /// The catch block
+    InCatch,
+    /// The finally block, holding the completion of what was run before it
+    InFinally(CompletionResult)
+}
+#[deriving(Clone)]
+/// A try statement being run
+struct Try {
+    env: Env,
+    name: Option<String>,
+    catch: Option<Box<Expr>>,
+    finally: Option<Box<Expr>>,
+    phase: TryPhase
+}
+#[deriving(Clone)]
+/// A declaration of variables being run
+struct Declaration {
+    env: Env,
+    kind: BindKind,
+    /// The declarations left to run, in reverse
+    decls: Vec<(Pattern, Option<Expr>)>,
+    /// The pattern waiting for its initial value
+    pending: Option<Pattern>
+}
+#[deriving(Clone)]
+/// Patterns being bound to values
+struct Bindings {
+    env: Env,
+    kind: BindKind,
+    /// The patterns left to bind and their values, in reverse
+    stack: Vec<(Pattern, Value)>,
+    /// The pattern waiting for its default value
+    pending: Option<Pattern>,
+    /// The value to give once every pattern is bound
+    result: Option<Value>
+}
+
+/// Runs expressions by keeping what is left to do of each expression on a stack of tasks,
+/// so expressions can be nested as deeply as memory allows
+struct Machine {
+    global: Value,
+    tasks: Vec<Task>
+}
+impl Machine {
+    /// Make a machine with no tasks
+    fn new(global:Value) -> Machine {
+        Machine {
+            global: global,
+            tasks: Vec::new()
+        }
+    }
+    /// Do what is given and everything that follows from it, until there are no tasks left,
+    /// giving the completion given to the bottom of the stack, or until a generator pauses
+    fn run(&mut self, next:Next) -> Outcome {
+        let mut next = next;
+        loop {
+            next = match next {
+                Run(expr, env) => self.eval(expr, env),
+                Give(result) => match self.tasks.pop() {
+                    Some(task) => self.resume(task, result),
+                    None => return Finished(result)
+                },
+                Suspend(value) => return Suspended(value)
+            };
+        }
+    }
+    /// Start running the body of a function
+    fn enter(&mut self, body:Expr, env:Env) -> Next {
+        match body {
+            Expr {def: BlockExpr(stmts), ..} => self.block(stmts, env, false),
+            body => Run(body, env)
+        }
+    }
+    /// Start running an expression
+    fn eval(&mut self, expr:Expr, env:Env) -> Next {
+        let Expr {def: def, start: start, ..} = expr;
+        let global = self.global;
+        match def {
+            BinOpExpr(BinLog(op), left, right) => {
+                self.tasks.push(ShortCircuiting(op, right, env));
+                Run(*left, env)
+            },
+            BinOpExpr(op, box left, box right) =>
+                self.operands(env, vec!(left, right), DoBinary(op), start),
+            UnaryOpExpr(op, box operand) =>
+                self.operands(env, vec!(operand), DoUnary(op), start),
+            UpdateExpr(prefix, op, box target) =>
+                self.reference(target, UpdateRef(prefix, op), env),
+            ConstExpr(value) => give(match value {
+                CString(text) => to_value(text),
+                CRegExp(body, flags) => regexp::make(global, body, &flags),
+                CNum(num) => to_value(num),
+                CInt(num) => to_value(num),
+                CBigInt(num) => to_value(num),
+                CBool(value) => to_value(value),
+                CNull => Value::new(VNull),
+                CUndefined => Value::undefined()
+            }),
+            TemplateExpr(quasis, exprs) =>
+                self.operands(env, exprs, DoTemplate(quasis), start),
+            BlockExpr(stmts) =>
+                self.block(stmts, env, true),
+            LocalExpr(name) => match get_var(env.scope, name.as_slice()) {
+                Some(value) => give(value),
+                None => Give(Err(located(error::new_error(global, "ReferenceError", format!("{} is not defined", name).as_slice()), &start)))
+            },
+            ThisExpr =>
+                give(env.this),
+            GetConstFieldExpr(box obj, field) =>
+                self.operands(env, vec!(obj), DoGetField(Some(field)), start),
+            GetFieldExpr(box obj, box field) =>
+                self.operands(env, vec!(obj, field), DoGetField(None), start),
+            OptionalChainExpr(chain) => {
+                self.tasks.push(Chaining);
+                Run(*chain, env)
+            },
+            OptionalExpr(box obj) =>
+                self.operands(env, vec!(obj), DoOptional, start),
+            CallExpr(box callee, args) => match callee.def {
+                LocalExpr(ref name) if name.as_slice() == "eval" => {
+                    let mut operands = vec!(callee.clone());
+                    operands.push_all_move(args);
+                    self.operands(env, operands, DoDirectEval, start)
+                },
+                SuperExpr =>
+                    self.operands(env, args, DoSuperCall, start),
+                GetConstFieldExpr(box ref obj, ref field) => {
+                    let from_super = obj.def == SuperExpr;
+                    self.operands(env, vec!(obj.clone()), DoMethod(Some(field.clone()), args, from_super), start)
+                },
+                GetFieldExpr(box ref obj, box ref field) => {
+                    let from_super = obj.def == SuperExpr;
+                    self.operands(env, vec!(obj.clone(), field.clone()), DoMethod(None, args, from_super), start)
+                },
+                _ => {
+                    let mut operands = vec!(callee.clone());
+                    operands.push_all_move(args);
+                    self.operands(env, operands, DoCallValue, start)
+                }
+            },
+            WhileLoopExpr(cond, body) =>
+                self.start_loop(WhileLoop(cond), None, body, env, Vec::new()),
+            ForLoopExpr(init, cond, step, body) => {
+                let head = init.map(|init| *init);
+                self.start_loop(ForLoop(cond, step, Vec::new()), head, body, env, Vec::new())
+            },
+            ForInExpr(box head, box obj, body) => {
+                let (kind, pattern) = loop_target(head);
+                self.start_loop(ForInLoop(kind, pattern, Vec::new()), Some(obj), body, env, Vec::new())
+            },
+            ForOfExpr(box head, box iterable, body) => {
+                let (kind, pattern) = loop_target(head);
+                self.start_loop(ForOfLoop(kind, pattern, Value::undefined()), Some(iterable), body, env, Vec::new())
+            },
+            IfExpr(box cond, if_expr, else_expr) => {
+                self.tasks.push(Branching(env, if_expr, else_expr));
+                Run(cond, env)
+            },
+            TryExpr(box block, name, catch_block, finally_block) => {
+                self.tasks.push(Trying(Try {
+                    env: env,
+                    name: name,
+                    catch: catch_block,
+                    finally: finally_block,
+                    phase: InTry
+                }));
+                Run(block, env)
+            },
+            BreakExpr(label) =>
+                Give(Ok(BreakCompletion(label, None))),
+            ContinueExpr(label) =>
+                Give(Ok(ContinueCompletion(label, None))),
+            LabelledExpr(label, box body) => {
+                let mut labels = vec!(label);
+                let mut body = body;
+                // the labels of a statement with more than one all belong to it
+                while is_labelled(&body) {
+                    body = match body {
+                        Expr {def: LabelledExpr(label, box inner), ..} => {
+                            labels.push(label);
+                            inner
+                        },
+                        _ => unreachable!()
+                    };
+                }
+                self.labelled(body, env, labels)
+            },
+            SwitchExpr(box value, cases, default) => {
+                self.tasks.push(Switching(Switch {
+                    env: env,
+                    labels: Vec::new(),
+                    value: None,
+                    cases: cases,
+                    index: 0,
+                    default: match default {
+                        Some(box Expr {def: BlockExpr(stmts), ..}) => stmts,
+                        Some(box default) => vec!(default),
+                        None => Vec::new()
+                    }
+                }));
+                Run(value, env)
+            },
+            ObjectDeclExpr(box fields) => {
+                let mut operands = Vec::new();
+                let mut kinds = Vec::new();
+                for (name, value) in fields.move_iter() {
+                    match value {
+                        Expr {def: AccessorExpr(getter, setter), ..} => {
+                            kinds.push((name, AccessorField(getter.is_some(), setter.is_some())));
+                            operands.extend(getter.move_iter().chain(setter.move_iter()).map(|accessor| *accessor));
+                        },
+                        value => {
+                            kinds.push((name, DataField));
+                            operands.push(value);
+                        }
+                    }
+                }
+                self.operands(env, operands, DoObject(kinds), start)
+            },
+            AccessorExpr(_, _) =>
+                Give(Err(located(error::new_error(global, "SyntaxError", "Unexpected accessor outside of an object declaration"), &start))),
+            ArrayDeclExpr(items) =>
+                self.operands(env, items, DoArray, start),
+            SpreadExpr(_) =>
+                Give(Err(located(error::new_error(global, "SyntaxError", "Unexpected spread outside of a call or array declaration"), &start))),
+            FunctionDeclExpr(name, args, box body, strict) => {
+                let scope = named_scope(env.scope, &name);
+                let func = with_prototype(global, new_function(scope, args, body, strict).into_value(global));
+                bind_name(scope, name, func);
+                give(func)
+            },
+            GeneratorDeclExpr(name, args, box body, strict) => {
+                let scope = named_scope(env.scope, &name);
+                let func = new_generator(scope, args, body, strict).into_value(global);
+                bind_name(scope, name, func);
+                give(func)
+            },
+            YieldExpr(delegate, value) => {
+                let value = match value {
+                    Some(box value) => value,
+                    None => Expr::new(ConstExpr(CUndefined), start, start)
+                };
+                self.operands(env, vec!(value), DoYield(delegate), start)
+            },
+            ArrowFunctionDeclExpr(args, box body, strict) => {
+                let mut func = new_function(env.scope, args, body, strict);
+                func.bound_this = Some(env.this);
+                give(func.into_value(global))
+            },
+            ClassDeclExpr(name, parent, constructor, methods) => match parent {
+                Some(box parent) => self.operands(env, vec!(parent), DoClass(name, constructor, methods), start),
+                None => self.act(DoClass(name, constructor, methods), Vec::new(), env, start)
+            },
+            SuperExpr => {
+                let home = env.scope.get_field(HOME);
+                if home.is_undefined() {
+                    Give(Err(located(error::new_error(global, "SyntaxError", "'super' keyword unexpected here"), &start)))
+                } else {
+                    give(home.get_field(INSTANCE_PROTOTYPE))
+                }
+            },
+            NewTargetExpr =>
+                give(env.scope.get_field(NEW_TARGET)),
+            ConstructExpr(box func, args) => {
+                let mut operands = vec!(func);
+                operands.push_all_move(args);
+                self.operands(env, operands, DoConstruct, start)
+            },
+            ReturnExpr(Some(box value)) =>
+                self.operands(env, vec!(value), DoReturn, start),
+            ReturnExpr(None) =>
+                Give(Ok(ReturnCompletion(Value::undefined()))),
+            ThrowExpr(box value) =>
+                self.operands(env, vec!(value), DoThrow, start),
+            AssignExpr(box target, right) =>
+                self.reference(target, AssignRef(right), env),
+            DestructureExpr(pattern, box value) =>
+                self.operands(env, vec!(value), DoDestructure(pattern), start),
+            AssignOpExpr(op, box target, right) =>
+                self.reference(target, AssignOpRef(op, right), env),
+            VarDeclExpr(vars) =>
+                self.declare(env, BindVar, vars),
+            LetDeclExpr(vars) =>
+                self.declare(env, BindLet, vars),
+            ConstDeclExpr(vars) =>
+                self.declare(env, BindConst, vars),
+            ScriptExpr(stmts, strict) => {
+                let env = Env {strict: env.strict || strict, ..env};
+                self.block(stmts, env, false)
+            },
+            ModuleExpr(stmts) => {
+                let env = Env {strict: true, ..env};
+                self.block(stmts, env, false)
+            },
+            ImportExpr(_, specifier) | ExportNamedExpr(_, Some(specifier)) | ExportAllExpr(specifier) =>
+                Give(Err(located(error::new_error(global, "Error", format!("Cannot find module '{}'", specifier).as_slice()), &start))),
+            ExportNamedExpr(_, None) =>
+                give_empty(),
+            ExportDeclExpr(box decl) =>
+                self.statement(decl, env),
+            ExportDefaultExpr(box value) => {
+                let name = match value.def {
+                    FunctionDeclExpr(ref name, _, _, _) | GeneratorDeclExpr(ref name, _, _, _) | ClassDeclExpr(ref name, _, _, _) => name.clone(),
+                    _ => None
+                };
+                self.operands(env, vec!(value), DoExportDefault(name), start)
+            },
+            TypeOfExpr(box Expr {def: LocalExpr(ref name), ..}) if find_scope(env.scope, name.as_slice()).is_none() =>
+                give(to_value("undefined")),
+            TypeOfExpr(box operand) =>
+                self.operands(env, vec!(operand), DoTypeOf, start),
+            VoidExpr(box operand) =>
+                self.operands(env, vec!(operand), DoGive(Value::undefined()), start),
+            DeleteExpr(box target) => if is_reference(&target) {
+                self.reference(target, DeleteRef, env)
+            } else {
+                self.operands(env, vec!(target), DoGive(to_value(true)), start)
+            }
+        }
+    }
+    /// Start running a statement of a block, where named function declarations are declared in the
+    /// scope of the block, and named class declarations are declared like `let`
+    fn statement(&mut self, stmt:Expr, env:Env) -> Next {
+        let Expr {def: def, start: start, end: end} = stmt;
+        let global = self.global;
+        match def {
+            FunctionDeclExpr(Some(name), args, box body, strict) => {
+                let func = with_prototype(global, new_function(env.scope, args, body, strict).into_value(global));
+                declare_var(env.scope, name.as_slice(), func);
+                give_empty()
+            },
+            GeneratorDeclExpr(Some(name), args, box body, strict) => {
+                let func = new_generator(env.scope, args, body, strict).into_value(global);
+                declare_var(env.scope, name.as_slice(), func);
+                give_empty()
+            },
+            ClassDeclExpr(Some(name), parent, constructor, methods) => {
+                let class = Expr::new(ClassDeclExpr(Some(name.clone()), parent, constructor, methods), start, end);
+                let target = Expr::new(LocalExpr(name), start, start);
+                Run(Expr::new(LetDeclExpr(vec!((RefPattern(box target), Some(class)))), start, end), env)
+            },
+            ExportDeclExpr(box decl) =>
+                self.statement(decl, env),
+            def => Run(Expr::new(def, start, end), env)
+        }
+    }
+    /// Start running the statements of a block, in a scope of its own if it is `scoped` and declares anything
+    fn block(&mut self, stmts:Vec<Expr>, env:Env, scoped:bool) -> Next {
+        let mut env = env;
+        if scoped && stmts.iter().any(|stmt| declares_lexically(stmt)) {
+            env.scope = new_scope(env.scope);
+        }
+        let mut stmts = stmts;
+        stmts.reverse();
+        self.next_stmt(Block {
+            env: env,
+            stmts: stmts,
+            last: NormalCompletion(None),
+            at: Position::new(0, 0, 0)
+        })
+    }
+    /// Run the next statement of a block, or give its completion if there are none left
+    fn next_stmt(&mut self, block:Block) -> Next {
+        let mut block = block;
+        match block.stmts.pop() {
+            Some(stmt) => {
+                let env = block.env;
+                block.at = stmt.start;
+                self.tasks.push(InBlock(block));
+                self.statement(stmt, env)
+            },
+            None => Give(Ok(block.last))
+        }
+    }
+    /// Start evaluating the operands given, then run the action given with their values
+    fn operands(&mut self, env:Env, exprs:Vec<Expr>, action:Action, at:Position) -> Next {
+        let mut exprs = exprs;
+        exprs.reverse();
+        self.next_operand(Operands {
+            env: env,
+            exprs: exprs,
+            values: Vec::new(),
+            spreading: false,
+            action: action,
+            at: at
+        })
+    }
+    /// Evaluate the next operand, or run the action if there are none left
+    fn next_operand(&mut self, operands:Operands) -> Next {
+        let mut operands = operands;
+        match operands.exprs.pop() {
+            Some(Expr {def: SpreadExpr(box iterable), ..}) => {
+                let env = operands.env;
+                operands.spreading = true;
+                self.tasks.push(Evaluating(operands));
+                Run(iterable, env)
+            },
+            Some(expr) => {
+                let env = operands.env;
+                operands.spreading = false;
+                self.tasks.push(Evaluating(operands));
+                Run(expr, env)
+            },
+            None => {
+                let Operands {env: env, values: values, action: action, at: at, ..} = operands;
+                self.act(action, values, env, at)
+            }
+        }
+    }
+    /// Start a loop with the labels given, which runs the head first if it has one
+    fn start_loop(&mut self, kind:LoopKind, head:Option<Expr>, body:Box<Expr>, env:Env, labels:Vec<String>) -> Next {
+        let outer = env.scope;
+        let mut env = env;
+        let mut kind = kind;
+        // the variables a for loop declares with `let` or `const` are copied for each iteration
+        match head {
+            Some(Expr {def: LetDeclExpr(ref vars), ..}) | Some(Expr {def: ConstDeclExpr(ref vars), ..}) => match kind {
+                ForLoop(_, _, ref mut names) => {
+                    for &(ref pattern, _) in vars.iter() {
+                        pattern.add_names(names);
+                    }
+                    env.scope = new_scope(outer);
+                },
+                _ => ()
+            },
+            _ => ()
+        }
+        let mut state = Loop {
+            env: env,
+            outer: outer,
+            labels: labels,
+            value: Value::undefined(),
+            body: body,
+            kind: kind,
+            phase: LoopStarting
+        };
+        match head {
+            Some(head) => {
+                self.tasks.push(Looping(state));
+                Run(head, env)
+            },
+            None => {
+                state.phase = LoopChecking;
+                self.check(state)
+            }
+        }
+    }
+    /// Check the condition of a loop, running the body if there is none
+    fn check(&mut self, state:Loop) -> Next {
+        let mut state = state;
+        let cond = match state.kind {
+            WhileLoop(ref cond) | ForLoop(Some(ref cond), _, _) => Some((**cond).clone()),
+            _ => None
+        };
+        match cond {
+            Some(cond) => {
+                let env = state.env;
+                state.phase = LoopChecking;
+                self.tasks.push(Looping(state));
+                Run(cond, env)
+            },
+            None => self.run_body(state)
+        }
+    }
+    /// Run an iteration of the body of a loop
+    fn run_body(&mut self, state:Loop) -> Next {
+        let mut state = state;
+        let env = state.env;
+        let body = (*state.body).clone();
+        state.phase = LoopRunning;
+        self.tasks.push(Looping(state));
+        Run(body, env)
+    }
+    /// Bind the next item of a for-in or for-of loop and run the body with it,
+    /// or finish the loop if there are none left
+    fn next_item(&mut self, state:Loop) -> Next {
+        let global = self.global;
+        let mut state = state;
+        let item = match state.kind {
+            ForInLoop(_, _, ref mut keys) => keys.pop().map(|key| to_value(key)),
+            ForOfLoop(_, _, iter) => match iterator::step(iter, global) {
+                Ok(item) => item,
+                Err(thrown) => return Give(Err(thrown))
+            },
+            _ => None
+        };
+        let item = match item {
+            Some(item) => item,
+            None => return give(state.value)
+        };
+        let (kind, pattern) = match state.kind {
+            ForInLoop(kind, ref pattern, _) | ForOfLoop(kind, ref pattern, _) => (kind, pattern.clone()),
+            _ => unreachable!()
+        };
+        // each iteration has its own variables if the loop declares them with `let` or `const`
+        if kind == BindLet || kind == BindConst {
+            state.env.scope = new_scope(state.outer);
+        }
+        let env = state.env;
+        state.phase = LoopBinding;
+        self.tasks.push(Looping(state));
+        self.bind(Bindings {
+            env: env,
+            kind: kind,
+            stack: vec!((pattern, item)),
+            pending: None,
+            result: None
+        })
+    }
+    /// Run the next iteration of a loop after its body ran to the end or continued
+    fn next_iteration(&mut self, state:Loop) -> Next {
+        let mut state = state;
+        let (step, names) = match state.kind {
+            ForLoop(_, ref step, ref names) => (step.clone(), names.clone()),
+            WhileLoop(_) => (None, Vec::new()),
+            _ => return self.next_item(state)
+        };
+        // the variables of the next iteration start with the values they have at the end of this one
+        if !names.is_empty() {
+            let copy = new_scope(state.outer);
+            for name in names.iter() {
+                match state.env.scope.get_own_prop(name.as_slice()) {
+                    Some(prop) => {
+                        copy.set_prop(name.as_slice(), prop);
+                    },
+                    None => ()
+                }
+            }
+            state.env.scope = copy;
+        }
+        match step {
+            Some(box step) => {
+                let env = state.env;
+                state.phase = LoopStepping;
+                self.tasks.push(Looping(state));
+                Run(step, env)
+            },
+            None => self.check(state)
+        }
+    }
+    /// Run a statement with the labels given, which loops and switches take as their own
+    fn labelled(&mut self, body:Expr, env:Env, labels:Vec<String>) -> Next {
+        let Expr {def: def, start: start, end: end} = body;
+        match def {
+            WhileLoopExpr(cond, body) =>
+                self.start_loop(WhileLoop(cond), None, body, env, labels),
+            ForLoopExpr(init, cond, step, body) => {
+                let head = init.map(|init| *init);
+                self.start_loop(ForLoop(cond, step, Vec::new()), head, body, env, labels)
+            },
+            ForInExpr(box head, box obj, body) => {
+                let (kind, pattern) = loop_target(head);
+                self.start_loop(ForInLoop(kind, pattern, Vec::new()), Some(obj), body, env, labels)
+            },
+            ForOfExpr(box head, box iterable, body) => {
+                let (kind, pattern) = loop_target(head);
+                self.start_loop(ForOfLoop(kind, pattern, Value::undefined()), Some(iterable), body, env, labels)
+            },
+            SwitchExpr(value, cases, default) => {
+                let next = self.eval(Expr::new(SwitchExpr(value, cases, default), start, end), env);
+                match self.tasks.last_mut() {
+                    Some(&mut Switching(ref mut switch)) => switch.labels = labels,
+                    _ => ()
+                }
+                next
+            },
+            def => {
+                self.tasks.push(Labelled(labels));
+                self.statement(Expr::new(def, start, end), env)
+            }
+        }
+    }
+    /// Check the next case of a switch statement, or run the default clause if none are left
+    fn next_case(&mut self, switch:Switch) -> Next {
+        let mut switch = switch;
+        if switch.index < switch.cases.len() {
+            let env = switch.env;
+            let test = {
+                let &(ref test, _) = &switch.cases[switch.index];
+                test.clone()
+            };
+            self.tasks.push(Switching(switch));
+            Run(test, env)
+        } else {
+            let default = replace(&mut switch.default, Vec::new());
+            self.tasks.push(EndingSwitch(switch.labels));
+            self.block(default, switch.env, true)
+        }
+    }
+    /// Start running a declaration of variables
+    fn declare(&mut self, env:Env, kind:BindKind, vars:Vec<(Pattern, Option<Expr>)>) -> Next {
+        let mut decls = vars;
+        decls.reverse();
+        self.next_decl(Declaration {
+            env: env,
+            kind: kind,
+            decls: decls,
+            pending: None
+        })
+    }
+    /// Run the next declaration, where declarations without values declare their variables as
+    /// `undefined`, except for `var` declarations of variables that have already been declared
+    fn next_decl(&mut self, decl:Declaration) -> Next {
+        let mut decl = decl;
+        loop {
+            match decl.decls.pop() {
+                Some((pattern, Some(value))) => {
+                    let env = decl.env;
+                    decl.pending = Some(pattern);
+                    self.tasks.push(Declaring(decl));
+                    return Run(value, env);
+                },
+                Some((pattern, None)) => {
+                    let mut names = Vec::new();
+                    pattern.add_names(&mut names);
+                    for name in names.iter() {
+                        let name = name.as_slice();
+                        match decl.kind {
+                            BindVar if decl.env.vars.get_own_prop(name).is_none() => {
+                                declare_var(decl.env.vars, name, Value::undefined());
+                            },
+                            BindLet => {
+                                declare_var(decl.env.scope, name, Value::undefined());
+                            },
+                            _ => ()
+                        }
+                    }
+                },
+                None => return give_empty()
+            }
+        }
+    }
+    /// Bind the patterns left to their values, then give the result
+    fn bind(&mut self, bindings:Bindings) -> Next {
+        let global = self.global;
+        let mut bindings = bindings;
+        loop {
+            let (pattern, value) = match bindings.stack.pop() {
+                Some(binding) => binding,
+                None => return Give(Ok(NormalCompletion(bindings.result)))
+            };
+            match pattern {
+                RefPattern(box target) => {
+                    let at = target.start;
+                    match target.def {
+                        LocalExpr(name) => {
+                            attempt!(bind_var(&bindings.env, bindings.kind, global, name.as_slice(), value), at);
+                        },
+                        GetConstFieldExpr(box obj, field) => {
+                            let env = bindings.env;
+                            self.tasks.push(Binding(bindings));
+                            return self.operands(env, vec!(obj), DoReference(Some(field), BindRef(value)), at);
+                        },
+                        GetFieldExpr(box obj, box field) => {
+                            let env = bindings.env;
+                            self.tasks.push(Binding(bindings));
+                            return self.operands(env, vec!(obj, field), DoReference(None, BindRef(value)), at);
+                        },
+                        _ => return Give(Err(located(error::new_error(global, "SyntaxError", "Invalid destructuring target"), &at)))
+                    }
+                },
+                ObjectPattern(fields) => {
+                    if value.is_null_or_undefined() {
+                        return Give(Err(error::new_error(global, "TypeError", format!("Cannot destructure {}", value).as_slice())));
+                    }
+                    let mut parts = Vec::with_capacity(fields.len());
+                    for (name, pattern) in fields.move_iter() {
+                        let part = match value.get_in(name, global) {
+                            Ok(part) => part,
+                            Err(thrown) => return Give(Err(thrown))
+                        };
+                        parts.push((pattern, part));
+                    }
+                    parts.reverse();
+                    bindings.stack.push_all_move(parts);
+                },
+                ArrayPattern(items) => {
+                    let parts = match iterator::take(value, global, items.len()) {
+                        Ok(parts) => parts,
+                        Err(thrown) => return Give(Err(thrown))
+                    };
+                    let mut parts : Vec<(Pattern, Value)> = items.move_iter().zip(parts.move_iter()).filter_map(|(item, part)|
+                        item.map(|pattern| (pattern, part))
+                    ).collect();
+                    parts.reverse();
+                    bindings.stack.push_all_move(parts);
+                },
+                DefaultPattern(box pattern, box default) => {
+                    if value.is_undefined() {
+                        let env = bindings.env;
+                        bindings.pending = Some(pattern);
+                        self.tasks.push(Binding(bindings));
+                        return Run(default, env);
+                    }
+                    bindings.stack.push((pattern, value));
+                }
+            }
+        }
+    }
+    /// Start using the variable or field given as a reference, running the object and field first for fields
+    fn reference(&mut self, target:Expr, use_:RefUse, env:Env) -> Next {
+        let global = self.global;
+        let Expr {def: def, start: start, ..} = target;
+        match def {
+            LocalExpr(name) =>
+                self.use_reference(VarRef(name), use_, env, start),
+            GetConstFieldExpr(box obj, field) =>
+                self.operands(env, vec!(obj), DoReference(Some(field), use_), start),
+            GetFieldExpr(box obj, box field) =>
+                self.operands(env, vec!(obj, field), DoReference(None, use_), start),
+            _ => Give(Err(located(error::new_error(global, "SyntaxError", "Invalid assignment target"), &start)))
+        }
+    }
+    /// Use a reference in the way given
+    fn use_reference(&mut self, reference:Reference, use_:RefUse, env:Env, at:Position) -> Next {
+        let global = self.global;
+        match use_ {
+            AssignRef(box right) =>
+                self.operands(env, vec!(right), DoStore(reference), at),
+            UpdateRef(prefix, op) => {
+                let old = attempt!(read(&reference, &env, global), at);
+                let (old, new) = attempt!(update(old, op), at);
+                attempt!(store(&reference, new, &env, global), at);
+                give(if prefix {new} else {old})
+            },
+            AssignOpRef(BinLog(op), box right) => {
+                let old = attempt!(read(&reference, &env, global), at);
+                let short_circuits = match op {
+                    LogAnd => !to_boolean(&old),
+                    LogOr => to_boolean(&old),
+                    LogCoalesce => !old.is_null_or_undefined()
+                };
+                if short_circuits {
+                    give(old)
+                } else {
+                    self.operands(env, vec!(right), DoStore(reference), at)
+                }
+            },
+            AssignOpRef(op, box right) => {
+                let old = attempt!(read(&reference, &env, global), at);
+                self.operands(env, vec!(right), DoCombine(reference, op, old), at)
+            },
+            DeleteRef => match reference {
+                FieldRef(obj, key) => if obj.remove_field(key.clone()) {
+                    give(to_value(true))
+                } else if env.strict {
+                    let message = format!("Cannot delete property '{}' of {}", key, obj);
+                    Give(Err(located(error::new_error(global, "TypeError", message.as_slice()), &at)))
+                } else {
+                    give(to_value(false))
+                },
+                VarRef(_) => give(to_value(false))
+            },
+            BindRef(value) => {
+                attempt!(store(&reference, value, &env, global), at);
+                give(value)
+            }
+        }
+    }
+    /// Run an action with the values of its operands
+    fn act(&mut self, action:Action, values:Vec<Value>, env:Env, at:Position) -> Next {
+        let global = self.global;
+        let mut values = values;
+        let first = if values.is_empty() {Value::undefined()} else {values[0]};
+        match action {
+            DoBinary(op) =>
+                give(attempt!(binary(op, first, values[1]), at)),
+            DoUnary(op) =>
+                give(attempt!(unary(op, first), at)),
+            DoTypeOf =>
+                give(to_value(first.get_type())),
+            DoGive(value) =>
+                give(value),
+            DoOptional => if first.is_null_or_undefined() {
+                Give(Ok(BreakCompletion(Some(CHAIN.into_string()), None)))
+            } else {
+                give(first)
+            },
+            DoGetField(field) => {
+                let key = attempt!(field_key(field, &values), at);
+                give(attempt!(get_field(first, key, global), at))
+            },
+            DoMethod(field, args, from_super) => {
+                let key = attempt!(field_key(field, &values), at);
+                let method = attempt!(get_field(first, key, global), at);
+                let this = if from_super {env.this} else {first};
+                self.operands(env, args, DoCall(method, this), at)
+            },
+            DoCall(func, this) =>
+                self.call(func, values, this, Value::undefined(), None, at),
+            DoCallValue => {
+                let func = values.remove(0).unwrap();
+                self.call(func, values, Value::undefined(), Value::undefined(), None, at)
+            },
+            DoDirectEval => {
+                let func = values.remove(0).unwrap();
+                give(attempt!(direct_eval(func, values, global, env.scope, env.vars, env.this, env.strict), at))
+            },
+            DoConstruct => {
+                let func = values.remove(0).unwrap();
+                if !func.is_function() {
+                    return Give(Err(located(error::new_error(global, "TypeError", format!("{} is not a constructor", func).as_slice()), &at)));
+                }
+                let this = Value::new_obj(Some(global));
+                this.set_hidden_field(INSTANCE_PROTOTYPE, func.get_field(PROTOTYPE));
+                self.call(func, values, this, func, Some(this), at)
+            },
+            DoSuperCall => {
+                let parent = env.scope.get_field(HOME).get_field(INSTANCE_PROTOTYPE).get_field("constructor");
+                if !parent.is_function() {
+                    return Give(Err(located(error::new_error(global, "TypeError", format!("Super constructor {} is not a constructor", parent).as_slice()), &at)));
+                }
+                let target = env.scope.get_field(NEW_TARGET);
+                self.call(parent, values, env.this, target, Some(env.this), at)
+            },
+            DoArray =>
+                give(array::new_array(global, values)),
+            DoTemplate(quasis) => {
+                let mut text = String::new();
+                for (i, quasi) in quasis.iter().enumerate() {
+                    text.push_str(quasi.as_slice());
+                    if i < values.len() {
+                        text.push_str(attempt!(to_string(values[i]), at).as_slice());
+                    }
+                }
+                give(to_value(text))
+            },
+            DoObject(fields) =>
+                give(make_object(global, fields, values)),
+            DoThrow =>
+                Give(Err(located(first, &at))),
+            DoReturn =>
+                Give(Ok(ReturnCompletion(first))),
+            DoYield(false) =>
+                Suspend(first),
+            DoYield(true) => {
+                let iter = attempt!(iterator::get_iterator(first, global), at);
+                self.delegate(iter, Ok(NormalCompletion(None)), at)
+            },
+            DoReference(field, use_) => {
+                if first.is_null_or_undefined() {
+                    let key = match field {
+                        Some(ref field) => field.clone(),
+                        None => values[1].to_string()
+                    };
+                    return Give(Err(located(error::new_error(global, "TypeError", format!("Cannot use property '{}' of {}", key, first).as_slice()), &at)));
+                }
+                let key = attempt!(field_key(field, &values), at);
+                self.use_reference(FieldRef(first, key), use_, env, at)
+            },
+            DoStore(reference) => {
+                attempt!(store(&reference, first, &env, global), at);
+                give(first)
+            },
+            DoCombine(reference, op, old) => {
+                let value = attempt!(binary(op, old, first), at);
+                attempt!(store(&reference, value, &env, global), at);
+                give(value)
+            },
+            DoDestructure(pattern) => self.bind(Bindings {
+                env: env,
+                kind: BindAssign,
+                stack: vec!((pattern, first)),
+                pending: None,
+                result: Some(first)
+            }),
+            DoClass(name, box constructor, methods) => {
+                let parent = if values.is_empty() {None} else {Some(first)};
+                give(attempt!(make_class(global, &env, name, parent, constructor, methods), at))
+            },
+            DoExportDefault(name) => {
+                declare_var(env.scope, DEFAULT_EXPORT, first);
+                match name {
+                    Some(name) => {
+                        declare_var(env.scope, name.as_slice(), first);
+                    },
+                    None => ()
+                }
+                give_empty()
+            }
+        }
+    }
+    /// Start a call to a function with `new.target` being the target given, where the body of a function made
+    /// from a script is run on this machine, so calls between them can be nested as deeply as the call depth
+    /// limit allows without using the native stack, and any other function is run directly, giving the object
+    /// being constructed instead of what the function gives if it is given and the function doesn't give an object
+    fn call(&mut self, func:Value, args:Vec<Value>, this:Value, target:Value, constructed:Option<Value>, at:Position) -> Next {
+        let global = self.global;
+        let entered = match *func {
+            VFunction(ref inner) => {
+                let inner = inner.borrow();
+                // generator functions are called directly, as they give a generator instead of running their body
+                match inner.body {
+                    Some(ref body) if !inner.generator => {
+                        let (frame, this) = inner.make_frame(&args, global, global, this, target, func);
+                        bind_params(&*inner, &args, frame);
+                        Some(((**body).clone(), Env {
+                            scope: frame,
+                            vars: frame,
+                            this: this,
+                            strict: inner.strict
+                        }))
+                    },
+                    _ => None
+                }
+            },
+            _ => None
+        };
+        match entered {
+            Some((body, env)) => {
+                attempt!(enter_call(global), at);
+                self.tasks.push(Returning(constructed, !is_block(&body)));
+                self.enter(body, env)
+            },
+            None => give(attempt!(match constructed {
+                Some(obj) => construct_with(func, args, global, obj, target),
+                None => call_value(func, args, global, global, this)
+            }, at))
+        }
+    }
+    /// Resume the iterator a `yield*` delegates to in the way the generator running it was resumed,
+    /// pausing the generator with the value the iterator gives until it is done, where returning from the
+    /// generator returns from the iterator too, and throwing into it throws into the iterator
+    fn delegate(&mut self, iter:Value, resumed:CompletionResult, at:Position) -> Next {
+        let global = self.global;
+        let (method, value, returning) = match resumed {
+            Ok(ReturnCompletion(value)) => ("return", value, true),
+            Ok(completion) => ("next", completion.get_value(), false),
+            Err(thrown) => ("throw", thrown, false)
+        };
+        let func = attempt!(iter.get_in(method, global), at);
+        if func.is_null_or_undefined() {
+            return match method {
+                "return" => Give(Ok(ReturnCompletion(value))),
+                "throw" => {
+                    let _ = iterator::close(iter, global);
+                    Give(Err(located(error::new_error(global, "TypeError", "The iterator does not provide a 'throw' method"), &at)))
+                },
+                _ => Give(Err(located(error::new_error(global, "TypeError", format!("{} is not a function", func).as_slice()), &at)))
+            };
+        }
+        let result = attempt!(call_value(func, vec!(value), global, global, iter), at);
+        if !result.is_object() {
+            return Give(Err(located(error::new_error(global, "TypeError", format!("Iterator result {} is not an object", result).as_slice()), &at)));
+        }
+        let done = attempt!(result.get_in("done", global), at);
+        let value = attempt!(result.get_in("value", global), at);
+        if !to_boolean(&done) {
+            self.tasks.push(Delegating(iter, at));
+            Suspend(value)
+        } else if returning {
+            Give(Ok(ReturnCompletion(value)))
+        } else {
+            give(value)
+        }
+    }
+    /// Finish a task with the completion of the expression it was waiting for
+    fn resume(&mut self, task:Task, result:CompletionResult) -> Next {
+        let global = self.global;
+        // only calls, delegations, tries and loops do anything with errors, and only statements with abrupt completions
+        let completion = match (task, result) {
+            (Returning(constructed, expression), result) => {
+                leave_call(global);
+                let completion = match result {
+                    Ok(completion) => completion,
+                    Err(thrown) => return Give(Err(thrown))
+                };
+                let value = if expression {completion.get_value()} else {completion.return_value()};
+                return give(match constructed {
+                    Some(obj) if !value.is_object() && !value.is_function() => obj,
+                    _ => value
+                });
+            },
+            (Delegating(iter, at), result) => return self.delegate(iter, result, at),
+            (Trying(state), result) => return self.resume_try(state, result),
+            (Looping(state), result) => return self.resume_loop(state, result),
+            (Labelled(ref labels), Ok(BreakCompletion(Some(ref label), ref value))) if labels.contains(label) =>
+                return Give(Ok(NormalCompletion(*value))),
+            (Chaining, Ok(BreakCompletion(Some(ref label), _))) if label.as_slice() == CHAIN =>
+                return give(Value::undefined()),
+            (InBlock(block), Err(thrown)) =>
+                return Give(Err(located(thrown, &block.at))),
+            (InBlock(mut block), Ok(completion)) => {
+                block.last = block_step(&block.last, completion);
+                return if block.last.is_normal() {
+                    self.next_stmt(block)
+                } else {
+                    Give(Ok(block.last))
+                };
+            },
+            (EndingIf, Ok(completion)) =>
+                return Give(Ok(end_if(Some(completion)))),
+            (EndingSwitch(labels), Ok(completion)) =>
+                return Give(Ok(end_switch(completion, labels.as_slice()))),
+            (_, Err(thrown)) =>
+                return Give(Err(thrown)),
+            (task, Ok(completion)) => if completion.is_normal() {
+                (task, completion.get_value())
+            } else {
+                return Give(Ok(completion));
+            }
+        };
+        match completion {
+            (Evaluating(mut operands), value) => {
+                if operands.spreading {
+                    let items = attempt!(iterator::iterate(value, global), operands.at);
+                    operands.values.push_all_move(items);
+                } else {
+                    operands.values.push(value);
+                }
+                self.next_operand(operands)
+            },
+            (Branching(env, if_expr, else_expr), value) => {
+                let branch = if to_boolean(&value) {Some(if_expr)} else {else_expr};
+                match branch {
+                    Some(box branch) => {
+                        self.tasks.push(EndingIf);
+                        Run(branch, env)
+                    },
+                    None => Give(Ok(end_if(None)))
+                }
+            },
+            (Switching(mut switch), value) => match switch.value {
+                None => {
+                    switch.value = Some(value);
+                    self.next_case(switch)
+                },
+                Some(discriminant) if discriminant.strict_equals(&value) => {
+                    // the clauses from the matching case onwards are run, including the default clause
+                    let mut stmts = Vec::new();
+                    for (_, body) in switch.cases.move_iter().skip(switch.index) {
+                        stmts.push_all_move(body);
+                    }
+                    stmts.push_all_move(switch.default);
+                    self.tasks.push(EndingSwitch(switch.labels));
+                    self.block(stmts, switch.env, true)
+                },
+                Some(_) => {
+                    switch.index += 1;
+                    self.next_case(switch)
+                }
+            },
+            (ShortCircuiting(op, right, env), value) => {
+                let short_circuits = match op {
+                    LogAnd => !to_boolean(&value),
+                    LogOr => to_boolean(&value),
+                    LogCoalesce => !value.is_null_or_undefined()
+                };
+                if short_circuits {
+                    give(value)
+                } else {
+                    Run(*right, env)
+                }
+            },
+            (Declaring(mut decl), value) => match decl.pending.take() {
+                Some(pattern) => {
+                    let bindings = Bindings {
+                        env: decl.env,
+                        kind: decl.kind,
+                        stack: vec!((pattern, value)),
+                        pending: None,
+                        result: None
+                    };
+                    self.tasks.push(Declaring(decl));
+                    self.bind(bindings)
+                },
+                None => self.next_decl(decl)
+            },
+            (Binding(mut bindings), value) => {
+                match bindings.pending.take() {
+                    Some(pattern) => bindings.stack.push((pattern, value)),
+                    None => ()
+                }
+                self.bind(bindings)
+            },
+            // the tasks that only look at abrupt completions give normal ones as they are
+            (_, value) => give(value)
+        }
+    }
+    /// Finish running part of a try statement
+    fn resume_try(&mut self, state:Try, result:CompletionResult) -> Next {
+        let mut state = state;
+        let result = match (state.phase.clone(), result) {
+            (InTry, Err(thrown)) if state.catch.is_some() => {
+                let env = Env {scope: new_scope(state.env.scope), ..state.env};
+                match state.name {
+                    Some(ref name) => {
+                        declare_var(env.scope, name.as_slice(), thrown);
+                    },
+                    None => ()
+                }
+                let catch = (**state.catch.get_ref()).clone();
+                state.phase = InCatch;
+                self.tasks.push(Trying(state));
+                return Run(catch, env);
+            },
+            (InTry, result) | (InCatch, result) => result,
+            // a finally block that completes normally keeps the completion of what ran before it
+            (InFinally(before), Ok(NormalCompletion(_))) => before,
+            (InFinally(_), result) => result
+        };
+        // the finally block is taken out of the statement once it is run
+        match state.finally.take() {
+            Some(box finally) => {
+                let env = state.env;
+                state.phase = InFinally(result);
+                self.tasks.push(Trying(state));
+                Run(finally, env)
+            },
+            _ => Give(result.map(|completion| completion.update_empty(Some(Value::undefined()))))
+        }
+    }
+    /// Finish running part of a loop
+    fn resume_loop(&mut self, state:Loop, result:CompletionResult) -> Next {
+        let global = self.global;
+        let mut state = state;
+        let completion = match result {
+            Ok(completion) => completion,
+            Err(thrown) => {
+                // a for-of loop closes its iterator if the body or binding throws
+                match (&state.kind, &state.phase) {
+                    (&ForOfLoop(_, _, iter), &LoopBinding) | (&ForOfLoop(_, _, iter), &LoopRunning) => {
+                        let _ = iterator::close(iter, global);
+                    },
+                    _ => ()
+                }
+                return Give(Err(thrown));
+            }
+        };
+        match state.phase {
+            LoopRunning => (),
+            _ if !completion.is_normal() => return Give(Ok(completion)),
+            LoopStarting => {
+                let value = completion.get_value();
+                let iterates = match state.kind {
+                    ForInLoop(_, _, ref mut keys) => {
+                        *keys = if value.is_null_or_undefined() {Vec::new()} else {value.enumerable_keys()};
+                        keys.reverse();
+                        true
+                    },
+                    ForOfLoop(_, _, ref mut iter) => {
+                        *iter = match iterator::get_iterator(value, global) {
+                            Ok(iter) => iter,
+                            Err(thrown) => return Give(Err(thrown))
+                        };
+                        true
+                    },
+                    _ => false
+                };
+                return if iterates {self.next_item(state)} else {self.check(state)};
+            },
+            LoopChecking => return if to_boolean(&completion.get_value()) {
+                self.run_body(state)
+            } else {
+                give(state.value)
+            },
+            LoopBinding => return self.run_body(state),
+            LoopStepping => return self.check(state)
+        }
+        match loop_step(state.labels.as_slice(), state.value, completion) {
+            Ok(value) => {
+                state.value = value;
+                self.next_iteration(state)
+            },
+            Err(done) => match state.kind {
+                // a for-of loop closes its iterator if it is left before it is done
+                ForOfLoop(_, _, iter) => match iterator::close(iter, global) {
+                    Ok(_) => Give(Ok(done)),
+                    Err(thrown) => Give(Err(thrown))
+                },
+                _ => Give(Ok(done))
+            }
+        }
+    }
+}
+/// Returns true if the expression is a labelled statement
+fn is_labelled(expr:&Expr) -> bool {
+    match expr.def {
+        LabelledExpr(_, _) => true,
+        _ => false
+    }
+}
+/// Returns true if the expression is a variable or field, which can be assigned to or deleted
+fn is_reference(expr:&Expr) -> bool {
+    match expr.def {
+        LocalExpr(_) | GetConstFieldExpr(_, _) | GetFieldExpr(_, _) => true,
+        _ => false
+    }
+}
+/// Get how the head of a for-in or for-of loop binds each item, and the pattern it binds them to
+fn loop_target(head:Expr) -> (BindKind, Pattern) {
+    let (kind, vars) = match head.def {
+        VarDeclExpr(vars) => (BindVar, vars),
+        LetDeclExpr(vars) => (BindLet, vars),
+        ConstDeclExpr(vars) => (BindConst, vars),
+        def => return (BindAssign, RefPattern(box Expr::new(def, head.start, head.end)))
+    };
+    match vars.move_iter().next() {
+        Some((pattern, _)) => (kind, pattern),
+        None => (BindAssign, RefPattern(box Expr::new(ConstExpr(CUndefined), head.start, head.end)))
+    }
+}
+/// Get the scope a function expression is made in, which is a scope of its own that binds its name if it has one
+fn named_scope(scope:Value, name:&Option<String>) -> Value {
+    match *name {
+        Some(_) => new_scope(scope),
+        None => scope
+    }
+}
+/// Bind the name of a function or class expression in the scope of its own made for it, if it has one
+fn bind_name(scope:Value, name:Option<String>, value:Value) {
+    match name {
+        Some(name) => {
+            declare_var(scope, name.as_slice(), value);
+        },
+        None => ()
+    }
+}
+/// Make a function that runs the body given in a new scope inside of the scope given when it is called
+fn new_function(scope:Value, args:Vec<String>, body:Expr, strict:bool) -> Function {
+    Function::new_body(args, Rc::new(body), strict, scope)
+}
+/// Make a generator function that runs the body given in a new scope inside of the scope given as its generators are resumed
+fn new_generator(scope:Value, args:Vec<String>, body:Expr, strict:bool) -> Function {
+    let mut func = new_function(scope, args, body, strict);
+    func.generator = true;
+    func
+}
+/// Give a function value the object that the objects it constructs inherit from, as functions
+/// that aren't arrow functions or methods have
+fn with_prototype(global:Value, func:Value) -> Value {
+    let prototype = Value::new_obj(Some(global));
+    prototype.set_hidden_field("constructor", func);
+    func.set_hidden_field(PROTOTYPE, prototype);
+    func
+}
+/// Make a method from its function expression, whose `super` is the object that `home` inherits from
+fn make_method(global:Value, scope:Value, home:Value, method:Expr) -> ResultValue {
+    match method.def {
+        FunctionDeclExpr(_, args, box body, strict) => {
+            let mut func = new_function(scope, args, body, strict);
+            func.home = Some(home);
+            Ok(func.into_value(global))
+        },
+        _ => Err(error::new_error(global, "SyntaxError", "Invalid method"))
+    }
+}
+/// Make a class out of its constructor and methods, extending the parent given if there is one, which is
+/// either a constructor or `null`, binding its name in a scope of its own if it has one
+fn make_class(global:Value, env:&Env, name:Option<String>, parent:Option<Value>, constructor:Expr, methods:Vec<(bool, MethodKind, String, Expr)>) -> ResultValue {
+    let scope = named_scope(env.scope, &name);
+    let prototype = Value::new_obj(Some(global));
+    match parent {
+        Some(parent) if parent.is_null() => {
+            prototype.set_hidden_field(INSTANCE_PROTOTYPE, parent);
+        },
+        Some(parent) if parent.is_function() => {
+            prototype.set_hidden_field(INSTANCE_PROTOTYPE, try!(parent.get(PROTOTYPE)));
+        },
+        Some(parent) => return Err(error::new_error(global, "TypeError", format!("Class extends value {} is not a constructor or null", parent).as_slice())),
+        None => ()
+    }
+    let class = try!(make_method(global, scope, prototype, constructor));
+    class.set_hidden_field(PROTOTYPE, prototype);
+    prototype.set_hidden_field("constructor", class);
+    match parent {
+        Some(parent) if parent.is_function() => {
+            class.set_hidden_field(INSTANCE_PROTOTYPE, parent);
+        },
+        _ => ()
+    }
+    for (is_static, kind, name, method) in methods.move_iter() {
+        let target = if is_static {class} else {prototype};
+        let method = try!(make_method(global, scope, target, method));
+        if kind == PlainMethod {
+            target.set_hidden_field(name, method);
+            continue;
+        }
+        // getters and setters with the same name share a property
+        let mut prop = match target.get_own_prop(name.as_slice()) {
+            Some(prop) if prop.is_accessor() => prop,
+            _ => Property::accessor(Value::undefined(), Value::undefined())
+        };
+        match kind {
+            GetterMethod => prop.get = method,
+            _ => prop.set = method
+        }
+        target.set_prop(name, prop);
+    }
+    bind_name(scope, name, class);
+    Ok(class)
+}
+/// Make an object from the fields of an object declaration and the values of their operands
+fn make_object(global:Value, fields:Vec<(String, FieldKind)>, values:Vec<Value>) -> Value {
+    let object = Value::new_obj(Some(global));
+    let mut values = values.move_iter();
+    for (name, kind) in fields.move_iter() {
+        match kind {
+            DataField => {
+                let value = values.next().unwrap();
+                if name.as_slice() == INSTANCE_PROTOTYPE {
+                    if value.is_object() || value.is_function() || value.is_null() {
+                        object.set_hidden_field(INSTANCE_PROTOTYPE, value);
+                    }
+                } else {
+                    object.set_prop(name, Property::data(value));
+                }
+            },
+            AccessorField(has_getter, has_setter) => {
+                let get = if has_getter {values.next().unwrap()} else {Value::undefined()};
+                let set = if has_setter {values.next().unwrap()} else {Value::undefined()};
+                object.set_prop(name, Property {
+                    enumerable: true,
+                    .. Property::accessor(get, set)
+                });
+            }
+        }
+    }
+    object
+}
+/// Construct the object given with a function that isn't run by the interpreter, with `new.target` being the
+/// target given, giving the object the function returns if it returns one, and the object given otherwise
+fn construct_with(func:Value, args:Vec<Value>, global:Value, obj:Value, target:Value) -> ResultValue {
+    let result = match *func {
+        VFunction(ref inner) => try!(inner.borrow().construct(args, global, global, obj, target)),
+        _ => return Err(error::new_error(global, "TypeError", format!("{} is not a constructor", func).as_slice()))
+    };
+    Ok(if result.is_object() || result.is_function() {result} else {obj})
+}
+/// Get the key of the field of an action, which is the name given, or the second value converted to a key
+fn field_key(field:Option<String>, values:&Vec<Value>) -> Result<PropertyKey, Value> {
+    match field {
+        Some(field) => Ok(StringKey(field)),
+        None => to_property_key(values[1])
+    }
+}
+/// Get a field of a value, throwing if it is `null` or `undefined`
+fn get_field(obj:Value, key:PropertyKey, global:Value) -> ResultValue {
+    if obj.is_null_or_undefined() {
+        return Err(error::new_error(global, "TypeError", format!("Cannot read property '{}' of {}", key, obj).as_slice()));
+    }
+    obj.get_in(key, global)
+}
+/// Get the value in a reference, throwing if it is a variable that isn't declared
+fn read(reference:&Reference, env:&Env, global:Value) -> ResultValue {
+    match *reference {
+        VarRef(ref name) => match get_var(env.scope, name.as_slice()) {
+            Some(value) => Ok(value),
+            None => Err(error::new_error(global, "ReferenceError", format!("{} is not defined", name).as_slice()))
+        },
+        FieldRef(obj, ref key) => obj.get_in(key.clone(), global)
+    }
+}
+/// Store a value in a reference, throwing if it can't be stored there in strict mode
+fn store(reference:&Reference, value:Value, env:&Env, global:Value) -> ResultValue {
+    match *reference {
+        VarRef(ref name) => assign(env, global, name.as_slice(), value),
+        FieldRef(obj, ref key) => obj.set(key.clone(), value, env.strict)
+    }
+}
+/// Assign a value to a variable like `scope::assign_var`, throwing a `TypeError` if the variable is a
+/// constant, or can't be assigned to in strict code, where sloppy code leaves it as it is, as with `NaN`
+fn assign(env:&Env, global:Value, name:&str, value:Value) -> ResultValue {
+    match find_scope(env.scope, name) {
+        Some(scope) if is_const(scope, name) =>
+            Err(error::new_error(global, "TypeError", format!("Assignment to constant variable {}", name).as_slice())),
+        Some(scope) => match scope.get_own_prop(name) {
+            Some(ref prop) if !prop.writable && env.strict =>
+                Err(error::new_error(global, "TypeError", format!("Cannot assign to read only variable {}", name).as_slice())),
+            Some(ref prop) if !prop.writable => Ok(value),
+            _ => scope.set(name, value, env.strict)
+        },
+        None => assign_var(env.scope, global, name, value, env.strict)
+    }
+}
+/// Bind a variable to a value in the way given
+fn bind_var(env:&Env, kind:BindKind, global:Value, name:&str, value:Value) -> ResultValue {
+    match kind {
+        BindVar if find_scope(env.scope, name).is_none() => Ok(declare_var(env.vars, name, value)),
+        BindLet => Ok(declare_var(env.scope, name, value)),
+        BindConst => Ok(declare_const(env.scope, name, value)),
+        BindVar | BindAssign => assign(env, global, name, value)
+    }
+}
+/// Convert the old value of an increment or decrement to a number, or leave it if it is a BigInt,
+/// giving it along with the new value
+fn update(old:Value, op:UpdateOp) -> Result<(Value, Value), Value> {
+    match old.as_bigint() {
+        Some(num) => {
+            let one : BigInt = One::one();
+            Ok((old, to_value(match op {
+                UpdateIncrement => num + one,
+                UpdateDecrement => num - one
+            })))
+        },
+        None => {
+            let num = try!(to_number(old));
+            Ok((to_value(num), to_value(match op {
+                UpdateIncrement => num + 1.0,
+                UpdateDecrement => num - 1.0
+            })))
+        }
+    }
+}
+/// Run a binary operation that isn't logical
+fn binary(op:BinOp, left:Value, right:Value) -> ResultValue {
+    Ok(match op {
+        BinNum(OpAdd) => return left + right,
+        BinNum(OpSub) => return left - right,
+        BinNum(OpMul) => return left * right,
+        BinNum(OpDiv) => return left / right,
+        BinNum(OpMod) => return left % right,
+        BinNum(OpPow) => return left.pow(&right),
+        BinBit(BitAnd) => left & right,
+        BinBit(BitOr) => left | right,
+        BinBit(BitXor) => left ^ right,
+        BinBit(BitShl) => left << right,
+        BinBit(BitShr) => left >> right,
+        BinBit(BitUShr) => left.unsigned_shr(&right),
+        BinComp(CompEqual) => to_value(left.loose_equals(&right)),
+        BinComp(CompNotEqual) => to_value(!left.loose_equals(&right)),
+        BinComp(CompStrictEqual) => to_value(left.strict_equals(&right)),
+        BinComp(CompStrictNotEqual) => to_value(!left.strict_equals(&right)),
+        BinComp(comp) => {
+            let order = try!(left.compare(&right));
+            to_value(match (comp, order) {
+                (_, None) => false,
+                (CompLessThan, Some(order)) => order == Less,
+                (CompLessThanOrEqual, Some(order)) => order != Greater,
+                (CompGreaterThan, Some(order)) => order == Greater,
+                (_, Some(order)) => order != Less
+            })
+        },
+        BinLog(_) => unreachable!()
+    })
+}
+/// Run a unary operation
+fn unary(op:UnaryOp, value:Value) -> ResultValue {
+    Ok(match op {
+        UnaryMinus if value.as_bigint().is_some() => -value,
+        UnaryMinus => to_value(-try!(to_number(value))),
+        UnaryPlus => to_value(try!(to_number(value))),
+        UnaryNot => to_value(!to_boolean(&value)),
+        UnaryBitNot => value.bit_not()
+    })
+}
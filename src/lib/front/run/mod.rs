@@ -1,4 +1,10 @@
-/// For compiling Javascript values
-pub mod compiler;
-/// For executing the compiled Javascript values
-pub mod executor;
\ No newline at end of file
+/// For running source code given to `eval` while running
+pub mod eval;
+/// For executing Javascript values
+pub mod executor;
+/// For running Javascript expressions by walking them, on a stack of tasks instead of the native stack
+pub mod interpreter;
+/// For linking the imports and exports of modules
+pub mod module;
+/// For looking up and declaring variables in the chain of scopes being run in
+pub mod scope;
\ No newline at end of file
@@ -0,0 +1,297 @@
+use syntax::ast::expr::*;
+use collections::treemap::TreeMap;
+/// The local name of the value of a default export that isn't a named declaration
+pub static DEFAULT_EXPORT: &'static str = "*default*";
+#[deriving(Clone, PartialEq, Show)]
+/// How far a module has got in being linked and run
+pub enum ModuleStatus {
+    /// The imports of the module haven't been resolved
+    Unlinked,
+    /// The imports of the module and the modules it imports are being resolved
+    Linking,
+    /// The imports of the module have been resolved to the bindings they refer to
+    Linked,
+    /// The module has been run
+    Evaluated
+}
+#[deriving(Clone, PartialEq, Show)]
+/// A binding that an import or export refers to, as the specifier of the module it is
+/// declared in and its local name there, which is `*` for the object holding all of the
+/// exports of the module
+pub struct Binding {
+    /// The specifier of the module the binding is in
+    pub module: String,
+    /// The local name of the binding in that module
+    pub name: String
+}
+#[deriving(Clone)]
+/// The imports and exports of a module, which are found from its statements and linked
+/// to the bindings of other modules in a `ModuleGraph`
+pub struct ModuleRecord {
+    /// The specifier that the module is imported with
+    pub specifier: String,
+    /// The statements of the module
+    pub body: Expr,
+    /// The specifiers of the modules it imports from, in the order they are first requested
+    pub requested: Vec<String>,
+    /// The imports of the module, as the specifier of the module imported from, the
+    /// name imported and the local name it is bound to
+    pub imports: Vec<(String, String, String)>,
+    /// The local bindings exported, as the local name and the name exported
+    pub local_exports: Vec<(String, String)>,
+    /// The exports of other modules exported again, as the specifier of the module,
+    /// the name imported and the name exported
+    pub indirect_exports: Vec<(String, String, String)>,
+    /// The specifiers of the modules whose exports are all exported again
+    pub star_exports: Vec<String>,
+    /// How far the module has got in being linked and run
+    pub status: ModuleStatus,
+    /// The bindings that the local names of the imports refer to, once the module is linked
+    pub resolved: TreeMap<String, Binding>
+}
+/// Get the names of the bindings declared by an exported declaration
+fn declared_names(decl:&Expr) -> Vec<String> {
+    let mut names = Vec::new();
+    match decl.def {
+        VarDeclExpr(ref vars) | LetDeclExpr(ref vars) | ConstDeclExpr(ref vars) =>
+            for &(ref pattern, _) in vars.iter() {
+                pattern.add_names(&mut names);
+            },
+        FunctionDeclExpr(Some(ref name), _, _, _) | GeneratorDeclExpr(Some(ref name), _, _, _) | ClassDeclExpr(Some(ref name), _, _, _) =>
+            names.push(name.clone()),
+        _ => ()
+    }
+    names
+}
+impl ModuleRecord {
+    /// Make a record of the imports and exports of the module given, which should have been parsed as a module
+    pub fn new(specifier:String, body:Expr) -> ModuleRecord {
+        let mut record = ModuleRecord {
+            specifier: specifier,
+            body: body.clone(),
+            requested: Vec::new(),
+            imports: Vec::new(),
+            local_exports: Vec::new(),
+            indirect_exports: Vec::new(),
+            star_exports: Vec::new(),
+            status: Unlinked,
+            resolved: TreeMap::new()
+        };
+        let stmts = match body.def {
+            ModuleExpr(ref stmts) => stmts.clone(),
+            _ => vec!(body.clone())
+        };
+        let mut exports = Vec::new();
+        for stmt in stmts.iter() {
+            match stmt.def {
+                ImportExpr(ref names, ref from) => {
+                    record.request(from);
+                    for &(ref imported, ref local) in names.iter() {
+                        record.imports.push((from.clone(), imported.clone(), local.clone()));
+                    }
+                },
+                ExportDeclExpr(box ref decl) => for name in declared_names(decl).move_iter() {
+                    exports.push((name.clone(), name));
+                },
+                ExportDefaultExpr(box ref value) => {
+                    let local = match declared_names(value).move_iter().next() {
+                        Some(name) => name,
+                        None => DEFAULT_EXPORT.into_string()
+                    };
+                    exports.push((local, "default".into_string()));
+                },
+                ExportNamedExpr(ref names, None) =>
+                    exports.push_all(names.as_slice()),
+                ExportNamedExpr(ref names, Some(ref from)) => {
+                    record.request(from);
+                    for &(ref imported, ref exported) in names.iter() {
+                        record.indirect_exports.push((from.clone(), imported.clone(), exported.clone()));
+                    }
+                },
+                ExportAllExpr(ref from) => {
+                    record.request(from);
+                    record.star_exports.push(from.clone());
+                },
+                _ => ()
+            }
+        }
+        // exporting a name that was imported by name exports the binding it was imported from
+        for (local, exported) in exports.move_iter() {
+            let import = record.imports.iter().find(|&&(_, ref imported, ref name)| *name == local && imported.as_slice() != "*").map(|i| i.clone());
+            match import {
+                Some((from, imported, _)) => record.indirect_exports.push((from, imported, exported)),
+                None => record.local_exports.push((local, exported))
+            }
+        }
+        record
+    }
+    /// Add the specifier to the modules requested, if it hasn't been already
+    fn request(&mut self, specifier:&String) {
+        if !self.requested.contains(specifier) {
+            self.requested.push(specifier.clone());
+        }
+    }
+}
+/// A set of modules by their specifiers, which are linked together by resolving the
+/// imports of each to the bindings exported by the others
+pub struct ModuleGraph {
+    /// The modules in the graph, by their specifiers
+    pub modules: TreeMap<String, ModuleRecord>
+}
+impl ModuleGraph {
+    /// Make an empty module graph
+    pub fn new() -> ModuleGraph {
+        ModuleGraph {
+            modules: TreeMap::new()
+        }
+    }
+    /// Add a module to the graph
+    pub fn add(&mut self, record:ModuleRecord) {
+        self.modules.insert(record.specifier.clone(), record);
+    }
+    /// Get the specifiers of the modules that are requested by the modules in the graph
+    /// but haven't been added to it, which should be loaded and added before linking
+    pub fn missing(&self) -> Vec<String> {
+        let mut missing = Vec::new();
+        for module in self.modules.values() {
+            for specifier in module.requested.iter() {
+                if !self.modules.contains_key(specifier) && !missing.contains(specifier) {
+                    missing.push(specifier.clone());
+                }
+            }
+        }
+        missing
+    }
+    /// Find the binding that the export of the module with the name given refers to, if there is one
+    pub fn resolve_export(&self, specifier:&str, name:&str) -> Result<Option<Binding>, String> {
+        self.resolve_export_from(specifier, name, &mut Vec::new())
+    }
+    fn resolve_export_from(&self, specifier:&str, name:&str, visited:&mut Vec<(String, String)>) -> Result<Option<Binding>, String> {
+        let key = (specifier.into_string(), name.into_string());
+        // an export that is part of a cycle of exports doesn't resolve to anything
+        if visited.contains(&key) {
+            return Ok(None);
+        }
+        visited.push(key);
+        let module = match self.modules.find(&specifier.into_string()) {
+            Some(module) => module,
+            None => return Err(format!("Cannot find module '{}'", specifier))
+        };
+        for &(ref local, ref exported) in module.local_exports.iter() {
+            if exported.as_slice() == name {
+                return Ok(Some(Binding {module: specifier.into_string(), name: local.clone()}));
+            }
+        }
+        for &(ref from, ref imported, ref exported) in module.indirect_exports.iter() {
+            if exported.as_slice() == name {
+                return if imported.as_slice() == "*" {
+                    Ok(Some(Binding {module: from.clone(), name: imported.clone()}))
+                } else {
+                    self.resolve_export_from(from.as_slice(), imported.as_slice(), visited)
+                };
+            }
+        }
+        // the default export is never exported by `export *`
+        if name == "default" {
+            return Ok(None);
+        }
+        let mut found : Option<Binding> = None;
+        for from in module.star_exports.iter() {
+            match try!(self.resolve_export_from(from.as_slice(), name, visited)) {
+                Some(binding) => match found {
+                    Some(ref other) if *other != binding =>
+                        return Err(format!("The export '{}' of module '{}' is ambiguous", name, specifier)),
+                    _ => found = Some(binding)
+                },
+                None => ()
+            }
+        }
+        Ok(found)
+    }
+    /// Get the names of all the exports of the module, which are the fields of the
+    /// object holding them that it is imported as with `import * as`
+    pub fn export_names(&self, specifier:&str) -> Vec<String> {
+        let mut names = Vec::new();
+        self.add_export_names(specifier, &mut names, &mut Vec::new());
+        names
+    }
+    fn add_export_names(&self, specifier:&str, names:&mut Vec<String>, visited:&mut Vec<String>) {
+        let specifier = specifier.into_string();
+        if visited.contains(&specifier) {
+            return;
+        }
+        visited.push(specifier.clone());
+        let module = match self.modules.find(&specifier) {
+            Some(module) => module,
+            None => return
+        };
+        for &(_, ref exported) in module.local_exports.iter() {
+            names.push(exported.clone());
+        }
+        for &(_, _, ref exported) in module.indirect_exports.iter() {
+            names.push(exported.clone());
+        }
+        for from in module.star_exports.iter() {
+            let mut star_names = Vec::new();
+            self.add_export_names(from.as_slice(), &mut star_names, visited);
+            for name in star_names.move_iter() {
+                if name.as_slice() != "default" && !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+    }
+    /// Link the module with the specifier given and the modules it imports from, resolving
+    /// each of their imports to the binding it refers to
+    pub fn link(&mut self, specifier:&str) -> Result<(), String> {
+        let key = specifier.into_string();
+        let (requested, imports) = match self.modules.find_mut(&key) {
+            // modules that import each other are linked while the first of them is still linking
+            Some(module) if module.status != Unlinked => return Ok(()),
+            Some(module) => {
+                module.status = Linking;
+                (module.requested.clone(), module.imports.clone())
+            },
+            None => return Err(format!("Cannot find module '{}'", specifier))
+        };
+        for from in requested.iter() {
+            try!(self.link(from.as_slice()));
+        }
+        let mut resolved = TreeMap::new();
+        for (from, imported, local) in imports.move_iter() {
+            let binding = if imported.as_slice() == "*" {
+                Binding {module: from, name: imported}
+            } else {
+                match try!(self.resolve_export(from.as_slice(), imported.as_slice())) {
+                    Some(binding) => binding,
+                    None => return Err(format!("The module '{}' doesn't export '{}'", from, imported))
+                }
+            };
+            resolved.insert(local, binding);
+        }
+        let module = self.modules.find_mut(&key).unwrap();
+        module.resolved = resolved;
+        module.status = Linked;
+        Ok(())
+    }
+    /// Get the specifiers of the module given and the modules it imports from in the order
+    /// they should be run in, where each module comes after the modules it imports from
+    pub fn evaluation_order(&self, specifier:&str) -> Vec<String> {
+        let mut order = Vec::new();
+        self.add_to_order(specifier.into_string(), &mut order, &mut Vec::new());
+        order
+    }
+    fn add_to_order(&self, specifier:String, order:&mut Vec<String>, visited:&mut Vec<String>) {
+        if visited.contains(&specifier) {
+            return;
+        }
+        visited.push(specifier.clone());
+        match self.modules.find(&specifier) {
+            Some(module) => for from in module.requested.iter() {
+                self.add_to_order(from.clone(), order, visited);
+            },
+            None => ()
+        }
+        order.push(specifier);
+    }
+}
@@ -0,0 +1,68 @@
+use front::stdlib::object::{INSTANCE_PROTOTYPE, Property};
+use front::stdlib::value::{Value, ResultValue, to_value};
+use front::stdlib::error::new_error;
+/// The hidden field of a scope holding an object whose fields are the names of the constants it declares
+static CONSTANTS: &'static str = "__constants__";
+/// Make a new scope for the variables of a block or function, inside of the scope given,
+/// which is an object whose fields are the variables and whose prototype is its parent
+pub fn new_scope(parent:Value) -> Value {
+    let scope = Value::new_obj(None);
+    scope.set_hidden_field(INSTANCE_PROTOTYPE, parent);
+    scope
+}
+/// Get the scope that a scope is inside of, if it isn't the outermost one
+pub fn parent_of(scope:Value) -> Option<Value> {
+    match scope.get_own_prop(INSTANCE_PROTOTYPE) {
+        Some(prop) if prop.value.is_object() => Some(prop.value),
+        _ => None
+    }
+}
+/// Find the innermost scope from the one given outwards that declares the variable given
+pub fn find_scope(scope:Value, name:&str) -> Option<Value> {
+    let mut scope = scope;
+    loop {
+        if scope.get_own_prop(name).is_some() {
+            return Some(scope);
+        }
+        scope = match parent_of(scope) {
+            Some(parent) => parent,
+            None => return None
+        };
+    }
+}
+/// Get the value of a variable from the innermost scope that declares it, or `None` if none of them do
+pub fn get_var(scope:Value, name:&str) -> Option<Value> {
+    find_scope(scope, name).map(|scope| scope.get_field(name))
+}
+/// Declare a variable in the scope given, which hides any variable with the same name outside of it
+pub fn declare_var(scope:Value, name:&str, value:Value) -> Value {
+    scope.set_field(name, value)
+}
+/// Declare a constant in the scope given, which can't be assigned to
+pub fn declare_const(scope:Value, name:&str, value:Value) -> Value {
+    let mut prop = Property::data(value);
+    prop.writable = false;
+    scope.set_prop(name, prop);
+    let constants = match scope.get_own_prop(CONSTANTS) {
+        Some(prop) => prop.value,
+        None => scope.set_hidden_field(CONSTANTS, Value::new_obj(None))
+    };
+    constants.set_field(name, to_value(true));
+    value
+}
+/// Returns true if the variable given is a constant declared in the scope given
+pub fn is_const(scope:Value, name:&str) -> bool {
+    match scope.get_own_prop(CONSTANTS) {
+        Some(prop) => prop.value.get_own_prop(name).is_some(),
+        None => false
+    }
+}
+/// Assign a value to a variable in the innermost scope that declares it, or if none of them do,
+/// make it a field of the global object, unless the code is in strict mode, where that throws
+pub fn assign_var(scope:Value, global:Value, name:&str, value:Value, strict:bool) -> ResultValue {
+    match find_scope(scope, name) {
+        Some(scope) => Ok(scope.set_field(name, value)),
+        None if strict => Err(new_error(global, "ReferenceError", format!("{} is not defined", name).as_slice())),
+        None => Ok(global.set_field(name, value))
+    }
+}
@@ -1,14 +1,127 @@
 use front::stdlib::value::{Value, ResultValue, to_value};
-use front::stdlib::function::Function;
-
-/// Create a new array
-pub fn make_array(_:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
-    this.set_field("length", to_value(0i32));
-    Ok(Value::undefined())
+use front::stdlib::function::{Function, NEW_TARGET};
+use front::stdlib::object::{PROTOTYPE, INSTANCE_PROTOTYPE, ArrayObject, PropertyKey, StringKey};
+use front::stdlib::iterator::{ITERATOR, values};
+use conversions;
+use std::{i32, u32};
+/// Make an object an array, with the length of an empty array
+fn init_array(array:Value) {
+    array.set_kind(ArrayObject);
+    array.set_hidden_field("length", to_value(0i32));
+}
+/// Create a new array, which has the length given if it is only given a number, and holds
+/// the arguments otherwise, whether it is constructed or called
+pub fn make_array(args:Vec<Value>, global:Value, scope:Value, this:Value) -> ResultValue {
+    let array = if scope.get_field(NEW_TARGET).is_undefined() {
+        new_array(global, Vec::new())
+    } else {
+        init_array(this);
+        this
+    };
+    if args.len() == 1 && args[0].get_type() == "number" {
+        try!(array.set("length", args[0], true));
+    } else {
+        for (i, item) in args.iter().enumerate() {
+            array.set_field(i.to_string().as_slice(), *item);
+        }
+    }
+    Ok(array)
+}
+/// Make an array holding the items given, which inherits from `Array.prototype` on the global object given
+pub fn new_array(global:Value, items:Vec<Value>) -> Value {
+    let array = Value::new_obj(Some(global));
+    array.set_hidden_field(INSTANCE_PROTOTYPE, global.get_field("Array").get_field(PROTOTYPE));
+    init_array(array);
+    for (i, item) in items.iter().enumerate() {
+        array.set_field(i.to_string().as_slice(), *item);
+    }
+    array
+}
+/// Get the index of an array that the key of a property is, which is only the case for the
+/// canonical decimal form of an integer below `2^32 - 1`, so `"01"` and `"1.0"` aren't indexes
+pub fn array_index(key:&str) -> Option<u32> {
+    match from_str::<u32>(key) {
+        Some(index) if index != u32::MAX && index.to_string().as_slice() == key => Some(index),
+        _ => None
+    }
+}
+/// Make the value of the length of an array, which is an integer if it fits in one
+fn length_value(length:u32) -> Value {
+    if length <= i32::MAX as u32 {
+        to_value(length as i32)
+    } else {
+        to_value(length as f64)
+    }
+}
+/// Update the length of an array for a field that is about to be set, where setting an index at or
+/// past the length grows the length past it, and setting the length deletes the items at or past the
+/// new length, throwing if it isn't a valid length, giving the value to store in the field,
+/// where only a shorter length has to look for the items to delete
+pub fn before_set(array:Value, field:&PropertyKey, val:Value) -> ResultValue {
+    let field = match *field {
+        StringKey(ref name) => name.as_slice(),
+        _ => return Ok(val)
+    };
+    if field == "length" {
+        let number = try!(conversions::to_number(val));
+        let length = conversions::to_uint32(number);
+        if length as f64 != number {
+            return Err(to_value("Invalid array length"));
+        }
+        if length >= array.get_field("length").to_uint() {
+            return Ok(length_value(length));
+        }
+        for key in array.own_keys().move_iter() {
+            let index = match key {
+                StringKey(ref name) => array_index(name.as_slice()),
+                _ => None
+            };
+            match index {
+                Some(index) if index >= length => {
+                    array.remove_field(key);
+                },
+                _ => ()
+            }
+        }
+        return Ok(length_value(length));
+    }
+    match array_index(field) {
+        Some(index) if index >= array.get_field("length").to_uint() => {
+            array.set_field("length", length_value(index + 1));
+        },
+        _ => ()
+    }
+    Ok(val)
+}
+/// Check if a value is an array
+pub fn is_array(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
+    Ok(to_value(args.len() >= 1 && args[0].is_array()))
+}
+/// Get the string representation of the array, which is its items converted to strings
+/// and separated by commas, with `null` and `undefined` as empty strings
+pub fn to_string(_:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
+    let length = this.get_field("length").to_int();
+    let mut text = String::new();
+    for i in range(0, length) {
+        if i > 0 {
+            text.push_char(',');
+        }
+        let item = this.get_field(i.to_string().as_slice());
+        if !item.is_null_or_undefined() {
+            text.push_str(try!(conversions::to_string(item)).as_slice());
+        }
+    }
+    Ok(to_value(text))
 }
 /// Create a new `Array` object
-pub fn _create(_: Value) -> Value {
+pub fn _create(global: Value) -> Value {
     let array = Function::make(make_array, []);
+    let prototype = js!(global, {
+        "toString": Function::make(to_string, []),
+        ITERATOR: Function::make(values, [])
+    });
+    array.set_hidden_field(PROTOTYPE, prototype);
+    array.set_hidden_field("isArray", Function::make(is_array, ["value"]));
     array
 }
 /// Initialise the global object with the `Array` object
@@ -0,0 +1,438 @@
+use front::stdlib::value::{Value, ValueData, ResultValue, to_value, VUndefined, VNull, VBoolean, VString,
+                           VNumber, VInteger, VBigInt, VSymbol, VObject, VFunction};
+use front::stdlib::function::{Function, FunctionData, NEW_TARGET, call_value};
+use front::stdlib::object::{PROTOTYPE, Property, CollectionObject, WeakCollectionObject};
+use front::stdlib::symbol::SymbolId;
+use front::stdlib::array::new_array;
+use front::stdlib::iterator::{ITERATOR, iterate, return_this};
+use front::stdlib::generator::make_result;
+use std::collections::HashMap;
+use std::sync::atomics::{AtomicUint, INIT_ATOMIC_UINT, SeqCst};
+use std::mem::transmute;
+use std::cmp::max;
+/// The hidden field of a collection iterator holding the collection it iterates over
+static ITERATED: &'static str = "__iterated__";
+/// The hidden field of a collection iterator holding the position of the next entry
+static INDEX: &'static str = "__index__";
+/// The hidden field of a collection iterator holding whether it gives `"keys"`, `"values"` or `"entries"`
+static KIND: &'static str = "__kind__";
+/// The number of weak collections made so far, which gives each one an ID that no other has
+static WEAK_COLLECTIONS_MADE: AtomicUint = INIT_ATOMIC_UINT;
+
+#[deriving(Clone, PartialEq, Eq, Hash)]
+/// A key of a collection, made so keys that are the same value are equal and hash the same
+enum CollectionKey {
+    UndefinedKey,
+    NullKey,
+    BooleanKey(bool),
+    /// A number that isn't `NaN`, by its bits, where negative zero is made zero
+    NumberKey(u64),
+    /// `NaN`, which is the same key as itself
+    NaNKey,
+    BigIntKey(String),
+    TextKey(String),
+    SymbolIdKey(SymbolId),
+    /// An object or function, by its address, which stays the same while the collection holds it
+    ObjectKey(uint)
+}
+impl CollectionKey {
+    /// Make the key for a value
+    fn new(value:&Value) -> CollectionKey {
+        match **value {
+            VUndefined => UndefinedKey,
+            VNull => NullKey,
+            VBoolean(b) => BooleanKey(b),
+            VNumber(_) | VInteger(_) => {
+                let num = value.to_num();
+                if num.is_nan() {
+                    NaNKey
+                } else {
+                    NumberKey(unsafe {transmute(if num == 0.0 {0.0f64} else {num})})
+                }
+            },
+            VBigInt(ref num) => BigIntKey(num.to_string()),
+            VString(ref string) => TextKey(string.clone()),
+            VSymbol(ref sym) => SymbolIdKey(sym.id),
+            VObject(_) | VFunction(_) => ObjectKey(&**value as *const ValueData as uint)
+        }
+    }
+}
+
+#[deriving(Clone)]
+/// The entries of a map or set, in the order they were added
+pub struct Collection {
+    /// The keys and values of the entries, where deleted entries are left as holes so iterators
+    /// past them don't skip any
+    entries: Vec<Option<(Value, Value)>>,
+    /// The position of the entry with each key
+    positions: HashMap<CollectionKey, uint>,
+    /// How many positions came before the first entry, from the entries removed by clearing,
+    /// so iterators keep their places
+    cleared: uint,
+    /// How many entries there are that haven't been deleted
+    size: uint
+}
+impl Collection {
+    /// Make an empty collection
+    pub fn new() -> Collection {
+        Collection {
+            entries: Vec::new(),
+            positions: HashMap::new(),
+            cleared: 0,
+            size: 0
+        }
+    }
+    /// Get the value of the entry with the key given
+    fn get(&self, key:&Value) -> Option<Value> {
+        match self.positions.find(&CollectionKey::new(key)) {
+            Some(&pos) => self.entries[pos - self.cleared].map(|(_, value)| value),
+            None => None
+        }
+    }
+    /// Check if there is an entry with the key given
+    fn has(&self, key:&Value) -> bool {
+        self.positions.contains_key(&CollectionKey::new(key))
+    }
+    /// Set the value of the entry with the key given, adding an entry to the end if there isn't one
+    fn set(&mut self, key:Value, value:Value) {
+        let hashed = CollectionKey::new(&key);
+        match self.positions.find(&hashed).map(|pos| *pos) {
+            Some(pos) => {
+                let entry = self.entries.get_mut(pos - self.cleared);
+                *entry = entry.map(|(key, _)| (key, value));
+            },
+            None => {
+                // negative zero is stored as zero, so it is given as zero when iterating
+                let key = if key.is_double() && key.to_num() == 0.0 {to_value(0i32)} else {key};
+                self.positions.insert(hashed, self.cleared + self.entries.len());
+                self.entries.push(Some((key, value)));
+                self.size += 1;
+            }
+        }
+    }
+    /// Delete the entry with the key given, giving true if there was one
+    fn delete(&mut self, key:&Value) -> bool {
+        match self.positions.pop(&CollectionKey::new(key)) {
+            Some(pos) => {
+                *self.entries.get_mut(pos - self.cleared) = None;
+                self.size -= 1;
+                true
+            },
+            None => false
+        }
+    }
+    /// Delete every entry, moving on the position of the first entry so iterators start at entries added after
+    fn clear(&mut self) {
+        self.cleared += self.entries.len();
+        self.entries.clear();
+        self.positions.clear();
+        self.size = 0;
+    }
+    /// Get the first entry at or after the position given that hasn't been deleted, as the position after it, its key and its value
+    fn next_entry(&self, position:uint) -> Option<(uint, Value, Value)> {
+        let mut pos = max(position, self.cleared);
+        while pos - self.cleared < self.entries.len() {
+            match self.entries[pos - self.cleared] {
+                Some((key, value)) => return Some((pos + 1, key, value)),
+                None => pos += 1
+            }
+        }
+        None
+    }
+}
+
+/// How a collection holds its entries
+enum Storage {
+    /// In the collection, as maps and sets do
+    Strong,
+    /// On the keys, by the ID of the weak collection, so the entries go when the keys do
+    Weak(uint)
+}
+
+/// Get the argument at the index given, or `undefined` if there are fewer arguments
+fn arg(args:&Vec<Value>, index:uint) -> Value {
+    if args.len() > index {args[index]} else {Value::undefined()}
+}
+/// Get how a collection holds its entries, throwing if the value isn't a collection
+fn storage(this:Value) -> Result<Storage, Value> {
+    let storage = this.with_object_data(|data| match data.kind {
+        CollectionObject(_) => Some(Strong),
+        WeakCollectionObject(id) => Some(Weak(id)),
+        _ => None
+    });
+    match storage {
+        Some(Some(storage)) => Ok(storage),
+        _ => Err(to_value(format!("{} is not a collection", this)))
+    }
+}
+/// Run a function with the entries of a map or set, throwing if the value isn't one
+fn with_collection<T>(this:Value, f:|&mut Collection| -> T) -> Result<T, Value> {
+    let result = this.with_object_data(|data| match data.kind {
+        CollectionObject(ref mut entries) => Some(f(entries)),
+        _ => None
+    });
+    match result {
+        Some(Some(result)) => Ok(result),
+        _ => Err(to_value(format!("{} is not a Map or Set", this)))
+    }
+}
+/// Get the value a weak collection holds for a key, which is `None` if the key isn't an object
+fn weak_entry(id:uint, key:Value) -> Option<Value> {
+    key.with_object_data(|data| data.weak_entries.find(&id).map(|value| *value)).unwrap_or(None)
+}
+/// Set the value a weak collection holds for a key, throwing if the key isn't an object
+fn set_weak_entry(id:uint, key:Value, value:Value) -> Result<(), Value> {
+    match key.with_object_data(|data| {data.weak_entries.insert(id, value);}) {
+        Some(()) => Ok(()),
+        None => Err(to_value(format!("Invalid value used as weak key: {}", key)))
+    }
+}
+/// Initialise a new collection, adding the items of the iterable given with the `adder`
+/// method of the collection, which is given each item, or each key and value for maps
+fn construct(args:Vec<Value>, global:Value, scope:Value, this:Value, name:&'static str, adder:&'static str, weak:bool) -> ResultValue {
+    if scope.get_field(NEW_TARGET).is_undefined() {
+        return Err(to_value(format!("Constructor {} requires 'new'", name)));
+    }
+    this.set_kind(if weak {
+        WeakCollectionObject(WEAK_COLLECTIONS_MADE.fetch_add(1, SeqCst))
+    } else {
+        CollectionObject(Collection::new())
+    });
+    let iterable = arg(&args, 0);
+    if iterable.is_null_or_undefined() {
+        return Ok(Value::undefined());
+    }
+    let add = try!(this.get(adder));
+    for item in try!(iterate(iterable, global)).move_iter() {
+        let args = if adder == "set" {
+            if !item.is_object() {
+                return Err(to_value(format!("Iterator value {} is not an entry object", item)));
+            }
+            vec!(try!(item.get("0")), try!(item.get("1")))
+        } else {
+            vec!(item)
+        };
+        try!(call_value(add, args, global, global, this));
+    }
+    Ok(Value::undefined())
+}
+/// Create a new map from an iterable of key and value pairs
+pub fn make_map(args:Vec<Value>, global:Value, scope:Value, this:Value) -> ResultValue {
+    construct(args, global, scope, this, "Map", "set", false)
+}
+/// Create a new set from an iterable of values
+pub fn make_set(args:Vec<Value>, global:Value, scope:Value, this:Value) -> ResultValue {
+    construct(args, global, scope, this, "Set", "add", false)
+}
+/// Create a new weak map from an iterable of object keys and values
+pub fn make_weak_map(args:Vec<Value>, global:Value, scope:Value, this:Value) -> ResultValue {
+    construct(args, global, scope, this, "WeakMap", "set", true)
+}
+/// Create a new weak set from an iterable of objects
+pub fn make_weak_set(args:Vec<Value>, global:Value, scope:Value, this:Value) -> ResultValue {
+    construct(args, global, scope, this, "WeakSet", "add", true)
+}
+/// Get the value of the entry with the key given, or `undefined` if there isn't one
+pub fn get(args:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
+    let key = arg(&args, 0);
+    let value = match try!(storage(this)) {
+        Strong => try!(with_collection(this, |entries| entries.get(&key))),
+        Weak(id) => weak_entry(id, key)
+    };
+    Ok(value.unwrap_or(Value::undefined()))
+}
+/// Set the value of the entry with the key given, giving the map
+pub fn set(args:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
+    let (key, value) = (arg(&args, 0), arg(&args, 1));
+    match try!(storage(this)) {
+        Strong => try!(with_collection(this, |entries| entries.set(key, value))),
+        Weak(id) => try!(set_weak_entry(id, key, value))
+    }
+    Ok(this)
+}
+/// Add a value to the set if it isn't already in it, giving the set
+pub fn add(args:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
+    let value = arg(&args, 0);
+    match try!(storage(this)) {
+        Strong => try!(with_collection(this, |entries| if !entries.has(&value) {
+            entries.set(value, value);
+        })),
+        Weak(id) => try!(set_weak_entry(id, value, to_value(true)))
+    }
+    Ok(this)
+}
+/// Check if there is an entry with the key given
+pub fn has(args:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
+    let key = arg(&args, 0);
+    Ok(to_value(match try!(storage(this)) {
+        Strong => try!(with_collection(this, |entries| entries.has(&key))),
+        Weak(id) => weak_entry(id, key).is_some()
+    }))
+}
+/// Delete the entry with the key given, giving true if there was one
+pub fn delete(args:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
+    let key = arg(&args, 0);
+    Ok(to_value(match try!(storage(this)) {
+        Strong => try!(with_collection(this, |entries| entries.delete(&key))),
+        Weak(id) => key.with_object_data(|data| data.weak_entries.pop(&id).is_some()).unwrap_or(false)
+    }))
+}
+/// Delete every entry
+pub fn clear(_:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
+    try!(with_collection(this, |entries| entries.clear()));
+    Ok(Value::undefined())
+}
+/// Get how many entries there are
+pub fn get_size(_:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
+    Ok(to_value(try!(with_collection(this, |entries| entries.size)) as i32))
+}
+/// Call a function with the value and key of each entry and the collection, in the order they were
+/// added, including entries added while it is running
+pub fn for_each(args:Vec<Value>, global:Value, _:Value, this:Value) -> ResultValue {
+    try!(with_collection(this, |_| ()));
+    let callback = arg(&args, 0);
+    if !callback.is_function() {
+        return Err(to_value(format!("{} is not a function", callback)));
+    }
+    let mut pos = 0u;
+    // the entries aren't borrowed while the callback runs, so it can change them
+    loop {
+        match try!(with_collection(this, |entries| entries.next_entry(pos))) {
+            Some((next, key, value)) => {
+                pos = next;
+                try!(call_value(callback, vec!(value, key, this), global, global, arg(&args, 1)));
+            },
+            None => return Ok(Value::undefined())
+        }
+    }
+}
+/// Make an iterator over the entries of a collection, giving their keys, values or entries
+fn make_iterator(global:Value, collection:Value, kind:&'static str) -> ResultValue {
+    try!(with_collection(collection, |_| ()));
+    let iterator = js!(global, {
+        "next": Function::make(next, []),
+        ITERATOR: Function::make(return_this, [])
+    });
+    iterator.set_hidden_field(ITERATED, collection);
+    iterator.set_hidden_field(INDEX, to_value(0i32));
+    iterator.set_hidden_field(KIND, to_value(kind));
+    Ok(iterator)
+}
+/// Give the next entry of a collection iterator, skipping entries that were deleted
+pub fn next(_:Vec<Value>, global:Value, _:Value, this:Value) -> ResultValue {
+    let collection = this.get_field(ITERATED);
+    if !collection.is_object() {
+        return Ok(make_result(global, Value::undefined(), true));
+    }
+    let pos = this.get_field(INDEX).to_int() as uint;
+    match try!(with_collection(collection, |entries| entries.next_entry(pos))) {
+        Some((next, key, value)) => {
+            this.set_hidden_field(INDEX, to_value(next as i32));
+            let kind = this.get_field(KIND);
+            let item = if kind.strict_equals(&to_value("keys")) {
+                key
+            } else if kind.strict_equals(&to_value("values")) {
+                value
+            } else {
+                new_array(global, vec!(key, value))
+            };
+            Ok(make_result(global, item, false))
+        },
+        None => {
+            // a finished iterator stays finished, even if entries are added to the collection later
+            this.set_hidden_field(ITERATED, Value::undefined());
+            Ok(make_result(global, Value::undefined(), true))
+        }
+    }
+}
+/// Give an iterator over the keys of the entries
+pub fn keys(_:Vec<Value>, global:Value, _:Value, this:Value) -> ResultValue {
+    make_iterator(global, this, "keys")
+}
+/// Give an iterator over the values of the entries
+pub fn values(_:Vec<Value>, global:Value, _:Value, this:Value) -> ResultValue {
+    make_iterator(global, this, "values")
+}
+/// Give an iterator over the entries, as arrays of their keys and values
+pub fn entries_iterator(_:Vec<Value>, global:Value, _:Value, this:Value) -> ResultValue {
+    make_iterator(global, this, "entries")
+}
+/// Make a collection constructor with the prototype given, which has a `size` if the collection can be iterated over
+fn make_constructor(constructor:FunctionData, prototype:Value, iterable:bool) -> Value {
+    let function = Function::make(constructor, ["iterable"]);
+    if iterable {
+        prototype.set_prop("size", Property {
+            configurable: true,
+            enumerable: false,
+            writable: false,
+            value: Value::undefined(),
+            get: Function::make(get_size, []),
+            set: Value::undefined()
+        });
+    }
+    prototype.set_hidden_field("constructor", function);
+    function.set_hidden_field(PROTOTYPE, prototype);
+    function
+}
+/// Create a new `Map` object
+pub fn _create_map(global:Value) -> Value {
+    let entries = Function::make(entries_iterator, []);
+    let prototype = js!(global, {
+        "get": Function::make(get, ["key"]),
+        "set": Function::make(set, ["key", "value"]),
+        "has": Function::make(has, ["key"]),
+        "delete": Function::make(delete, ["key"]),
+        "clear": Function::make(clear, []),
+        "forEach": Function::make(for_each, ["callback"]),
+        "keys": Function::make(keys, []),
+        "values": Function::make(values, []),
+        "entries": entries,
+        ITERATOR: entries
+    });
+    make_constructor(make_map, prototype, true)
+}
+/// Create a new `Set` object
+pub fn _create_set(global:Value) -> Value {
+    let values = Function::make(values, []);
+    let prototype = js!(global, {
+        "add": Function::make(add, ["value"]),
+        "has": Function::make(has, ["value"]),
+        "delete": Function::make(delete, ["value"]),
+        "clear": Function::make(clear, []),
+        "forEach": Function::make(for_each, ["callback"]),
+        "keys": values,
+        "values": values,
+        "entries": Function::make(entries_iterator, []),
+        ITERATOR: values
+    });
+    make_constructor(make_set, prototype, true)
+}
+/// Create a new `WeakMap` object, which can't be iterated over
+pub fn _create_weak_map(global:Value) -> Value {
+    let prototype = js!(global, {
+        "get": Function::make(get, ["key"]),
+        "set": Function::make(set, ["key", "value"]),
+        "has": Function::make(has, ["key"]),
+        "delete": Function::make(delete, ["key"])
+    });
+    make_constructor(make_weak_map, prototype, false)
+}
+/// Create a new `WeakSet` object, which can't be iterated over
+pub fn _create_weak_set(global:Value) -> Value {
+    let prototype = js!(global, {
+        "add": Function::make(add, ["value"]),
+        "has": Function::make(has, ["value"]),
+        "delete": Function::make(delete, ["value"])
+    });
+    make_constructor(make_weak_set, prototype, false)
+}
+/// Initialise the global object with the `Map`, `Set`, `WeakMap` and `WeakSet` objects, where the
+/// weak collections keep their entries on their keys, so an entry is only kept while its key is
+pub fn init(global:Value) {
+    js_extend!(global, {
+        "Map": _create_map(global),
+        "Set": _create_set(global),
+        "WeakMap": _create_weak_map(global),
+        "WeakSet": _create_weak_set(global)
+    });
+}
@@ -1,11 +1,12 @@
-use front::stdlib::object::PROTOTYPE;
+use front::stdlib::object::{PROTOTYPE, INSTANCE_PROTOTYPE};
 use front::stdlib::value::{Value, ResultValue, to_value};
 use front::stdlib::function::Function;
+use syntax::ast::pos::Position;
 
 /// Create a new error
 pub fn make_error(args:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
     if args.len() >= 1 {
-        this.set_field("message", args[0]);
+        this.set_hidden_field("message", args[0]);
     }
     Ok(Value::undefined())
 }
@@ -15,6 +16,21 @@ pub fn to_string(_:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
     let message = this.get_field("message");
     Ok(to_value(format!("{}: {}", name, message).into_string()))
 }
+/// Make an error of the kind given by the name of its constructor on the global object, such as
+/// `"RangeError"`, with the message given, as the engine throws
+pub fn new_error(global:Value, name:&str, message:&str) -> Value {
+    let error = Value::new_obj(Some(global));
+    error.set_hidden_field(INSTANCE_PROTOTYPE, global.get_field(name).get_field(PROTOTYPE));
+    error.set_hidden_field("message", to_value(message));
+    error
+}
+/// Give an error object the line and column of the code that threw it, unless it already has them
+pub fn set_position(error:Value, pos:&Position) {
+    if error.is_object() && error.get_field("lineNumber").is_undefined() {
+        error.set_hidden_field("lineNumber", to_value(pos.line_number as i32));
+        error.set_hidden_field("columnNumber", to_value(pos.column_number as i32));
+    }
+}
 /// Create a new `Error` object
 pub fn _create(global: Value) -> Value {
     let prototype = js!(global, {
@@ -23,12 +39,28 @@ pub fn _create(global: Value) -> Value {
         "toString": Function::make(to_string, [])
     });
     let error = Function::make(make_error, ["message"]);
-    error.set_field(PROTOTYPE, prototype);
+    error.set_hidden_field(PROTOTYPE, prototype);
     error
 }
-/// Initialise the global object with the `Error` object
+/// Create a new kind of error with the name given, whose prototype inherits from the prototype of `Error`
+pub fn _create_native(global: Value, error: Value, name: &'static str) -> Value {
+    let prototype = js!(global, {
+        "name": name
+    });
+    prototype.set_hidden_field(INSTANCE_PROTOTYPE, error.get_field(PROTOTYPE));
+    let native = Function::make(make_error, ["message"]);
+    native.set_hidden_field(PROTOTYPE, prototype);
+    prototype.set_hidden_field("constructor", native);
+    native
+}
+/// Initialise the global object with the `Error`, `RangeError`, `ReferenceError`, `SyntaxError` and `TypeError` objects
 pub fn init(global:Value) {
+    let error = _create(global);
     js_extend!(global, {
-        "Error": _create(global)
+        "Error": error,
+        "RangeError": _create_native(global, error, "RangeError"),
+        "ReferenceError": _create_native(global, error, "ReferenceError"),
+        "SyntaxError": _create_native(global, error, "SyntaxError"),
+        "TypeError": _create_native(global, error, "TypeError")
     });
 }
\ No newline at end of file
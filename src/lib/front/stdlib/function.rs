@@ -1,25 +1,97 @@
-use front::stdlib::object::{ObjectData, Property};
+use front::stdlib::object::{ObjectData, Property, PROTOTYPE, INSTANCE_PROTOTYPE};
+use front::run::scope::new_scope;
+use front::run::eval::function_from_source;
+use front::run::interpreter::run_function;
+use front::stdlib::realm::with_realm;
+use front::stdlib::error::new_error;
+use front::stdlib::iterator::{ITERATOR, values};
+use conversions;
 use front::stdlib::value::{Value, VFunction, ResultValue, to_value};
-use collections::treemap::TreeMap;
 use std::iter::FromIterator;
 use std::cell::RefCell;
+use std::rc::Rc;
+use syntax::ast::expr::Expr;
 pub type FunctionData = fn(Vec<Value>, Value, Value, Value) -> ResultValue;
+/// The field of the scope of a function being run that holds the value of `new.target`
+pub static NEW_TARGET: &'static str = "new.target";
+/// The field of the scope of a method being run that holds the object it was defined on, which
+/// `super` looks up the object that inherits from
+pub static HOME: &'static str = "__home__";
+/// How many calls can run inside each other unless the executor is configured otherwise
+pub static DEFAULT_MAX_CALL_DEPTH: uint = 1000;
 #[deriving(Clone)]
 /// A Javascript function
 pub struct Function {
     /// The fields associated with the function
     pub object : ObjectData,
-    /// This function's JIT representation
+    /// This function's native representation, which is run if it has no body
     pub repr : FunctionData,
     /// The argument names of the function
-    pub args : Vec<String>
+    pub args : Vec<String>,
+    /// The value of `this` where an arrow function was made, which it always runs with
+    pub bound_this : Option<Value>,
+    /// Whether the function is in strict mode, where it runs with the `this` it is called
+    /// with even if it is `null` or `undefined`
+    pub strict : bool,
+    /// The scope the function was made in, which it runs inside of instead of the scope
+    /// it is called from, so it can still use the variables around it after that has finished
+    pub scope : Option<Value>,
+    /// The function that this function was made from with `bind`, which it runs instead
+    pub target : Option<Value>,
+    /// The arguments given to `bind`, which are given to the target before any others
+    pub bound_args : Vec<Value>,
+    /// The body of the function if it was made from a script, which is run by the interpreter
+    /// instead of the representation
+    pub body : Option<Rc<Expr>>,
+    /// Whether the function is a generator function, which gives a generator when called instead of running its body
+    pub generator : bool,
+    /// The object a method was defined on, whose prototype is where `super` looks up fields
+    pub home : Option<Value>
 }
 impl Function {
     /// Make a new function
     pub fn new(repr : FunctionData, args: Vec<String>) -> Function {
-        let mut obj = TreeMap::new();
-        obj.insert("arguments".into_string(), Property::new(to_value(args.len() as i32)));
-        Function {object: obj, repr: repr, args: args}
+        let mut obj = ObjectData::new();
+        obj.properties.insert("arguments".into_string(), Property::new(to_value(args.len() as i32)));
+        Function {object: obj, repr: repr, args: args, bound_this: None, strict: false, scope: None, target: None, bound_args: Vec::new(),
+                  body: None, generator: false, home: None}
+    }
+    /// Make a new function that closes over the scope it was made in
+    pub fn new_closure(repr : FunctionData, args: Vec<String>, scope: Value) -> Function {
+        let mut func = Function::new(repr, args);
+        func.scope = Some(scope);
+        func
+    }
+    /// Make a new arrow function, which runs with the `this` given instead of the one it is called with
+    pub fn new_arrow(repr : FunctionData, args: Vec<String>, this: Value) -> Function {
+        let mut func = Function::new(repr, args);
+        func.bound_this = Some(this);
+        func
+    }
+    /// Make a new function from a script that runs the body given inside of the scope given
+    pub fn new_body(args: Vec<String>, body: Rc<Expr>, strict: bool, scope: Value) -> Function {
+        let mut func = Function::new_closure(run_body, args, scope);
+        func.body = Some(body);
+        func.strict = strict;
+        func
+    }
+    /// Make a new function that runs the target with the `this` and leading arguments given, which
+    /// constructs the target with those arguments instead when it is constructed
+    pub fn new_bound(target: Value, this: Value, args: Vec<Value>) -> Function {
+        let mut func = Function::new(run_bound, Vec::new());
+        func.bound_this = Some(this);
+        func.target = Some(target);
+        func.bound_args = args;
+        func
+    }
+    /// Make a function value from the function, which inherits from `Function.prototype`
+    /// on the global object given, so it has methods such as `call`
+    pub fn into_value(self, global: Value) -> Value {
+        let mut func = self;
+        func.object.global = Some(global);
+        let func = Value::new(VFunction(RefCell::new(func)));
+        func.set_hidden_field(INSTANCE_PROTOTYPE, global.get_field("Function").get_field(PROTOTYPE));
+        func
     }
     /// Create a function from function data and arguments
     pub fn make(repr: FunctionData, args:&[&'static str]) -> Value {
@@ -27,13 +99,191 @@ impl Function {
     }
     /// Call with some args
     pub fn call(&self, args: Vec<Value>, global:Value, scope:Value, this:Value) -> ResultValue {
-        (self.repr)(args, global, scope, this)
+        self.run(args, global, scope, this, Value::undefined(), Value::undefined())
+    }
+    /// Call as a constructor with some args, where `new.target` is the constructor given
+    pub fn construct(&self, args: Vec<Value>, global:Value, scope:Value, this:Value, target:Value) -> ResultValue {
+        self.run(args, global, scope, this, target, Value::undefined())
+    }
+    /// Make the scope a call runs in and the `this` it runs with, where the scope is inside
+    /// the one the function was made in, or the one given if it doesn't close over one,
+    /// `new.target` is the value given and `arguments` holds the arguments and the function
+    /// value they were given to, unless this is an arrow function, which uses the `this`,
+    /// `new.target` and `arguments` of where it was made
+    pub fn make_frame(&self, args:&Vec<Value>, global:Value, scope:Value, this:Value, target:Value, callee:Value) -> (Value, Value) {
+        let scope = self.scope.unwrap_or(scope);
+        let (frame, this) = match self.bound_this {
+            Some(bound) => (new_scope(scope), bound),
+            None => {
+                let frame = new_scope(scope);
+                frame.set_hidden_field(NEW_TARGET, target);
+                frame.set_field("arguments", make_arguments(args, global, if self.strict {Value::undefined()} else {callee}));
+                // functions that aren't strict run plain calls on the global object
+                let this = if !self.strict && this.is_null_or_undefined() {
+                    global
+                } else {
+                    this
+                };
+                (frame, this)
+            }
+        };
+        match self.home {
+            Some(home) => {
+                frame.set_hidden_field(HOME, home);
+            },
+            None => ()
+        }
+        (frame, this)
+    }
+    /// Run with the scope and `this` made by `make_frame`, running the body with the interpreter
+    /// if the function was made from a script
+    fn run(&self, args: Vec<Value>, global:Value, scope:Value, this:Value, target:Value, callee:Value) -> ResultValue {
+        match self.target {
+            Some(bound_target) => {
+                let mut all_args = self.bound_args.clone();
+                all_args.push_all_move(args);
+                return if target.is_undefined() {
+                    call_value(bound_target, all_args, global, scope, self.bound_this.unwrap())
+                } else {
+                    construct_value(bound_target, all_args, global, scope)
+                };
+            },
+            None => ()
+        }
+        let (frame, this) = self.make_frame(&args, global, scope, this, target, callee);
+        try!(enter_call(global));
+        let result = match self.body {
+            Some(_) => run_function(self, args, global, frame, this),
+            None => (self.repr)(args, global, frame, this)
+        };
+        leave_call(global);
+        result
     }
 }
+/// Set how many calls can run inside each other on the global object given, past which calls throw
+pub fn set_max_call_depth(global:Value, depth:uint) {
+    with_realm(global, |realm| realm.max_call_depth = depth);
+}
+/// Count a call as running, throwing a `RangeError` instead if as many calls as the global
+/// object allows are running inside each other, so deep recursion throws instead of overflowing the stack
+pub fn enter_call(global:Value) -> Result<(), Value> {
+    let entered = with_realm(global, |realm| {
+        if realm.call_depth >= realm.max_call_depth {
+            return false;
+        }
+        realm.call_depth += 1;
+        true
+    });
+    if entered {
+        Ok(())
+    } else {
+        Err(new_error(global, "RangeError", "Maximum call stack size exceeded"))
+    }
+}
+/// Count a call as finished, whether it returned or threw
+pub fn leave_call(global:Value) {
+    with_realm(global, |realm| realm.call_depth -= 1);
+}
+/// Make the array-like `arguments` object of a function call, holding the arguments given
+/// and the function they were given to as `callee`, unless it is `undefined`
+fn make_arguments(args:&Vec<Value>, global:Value, callee:Value) -> Value {
+    let arguments = Value::new_obj(Some(global));
+    for (i, arg) in args.iter().enumerate() {
+        arguments.set_field(i.to_string().as_slice(), *arg);
+    }
+    arguments.set_hidden_field("length", to_value(args.len() as i32));
+    arguments.set_hidden_field(ITERATOR, Function::make(values, []));
+    if !callee.is_undefined() {
+        arguments.set_hidden_field("callee", callee);
+    }
+    arguments
+}
+/// Call a function value on the value given as `this`, which is the object a method was
+/// found on, or `undefined` for plain calls, which functions that aren't strict replace
+/// with the global object, and throw if the value isn't a function
+pub fn call_value(func:Value, args:Vec<Value>, global:Value, scope:Value, this:Value) -> ResultValue {
+    match *func {
+        VFunction(ref inner) => inner.borrow().run(args, global, scope, this, Value::undefined(), func),
+        _ => Err(new_error(global, "TypeError", format!("{} is not a function", func).as_slice()))
+    }
+}
+/// Construct an object with a constructor, running it with `this` being a new object that
+/// inherits from its prototype, and `new.target` being the constructor, giving the object the
+/// constructor returns if it returns one, and `this` otherwise, and throwing what it throws
+pub fn construct_value(func:Value, args:Vec<Value>, global:Value, scope:Value) -> ResultValue {
+    match *func {
+        // a bound function constructs its target with the arguments bound to it
+        VFunction(ref inner) if inner.borrow().target.is_some() => {
+            let inner = inner.borrow();
+            let mut all_args = inner.bound_args.clone();
+            all_args.push_all_move(args);
+            construct_value(inner.target.unwrap(), all_args, global, scope)
+        },
+        VFunction(ref inner) => {
+            let this = Value::new_obj(Some(global));
+            this.set_hidden_field(INSTANCE_PROTOTYPE, func.get_field(PROTOTYPE));
+            let result = try!(inner.borrow().run(args, global, scope, this, func, func));
+            Ok(if result.is_object() || result.is_function() {result} else {this})
+        },
+        _ => Err(new_error(global, "TypeError", format!("{} is not a constructor", func).as_slice()))
+    }
+}
+/// The function data of a function made from a script, which is never run as its body is run instead
+fn run_body(_:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
+    Ok(Value::undefined())
+}
+/// The function data of a bound function, which is never run as its target is run instead
+fn run_bound(_:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
+    Ok(Value::undefined())
+}
+/// Call the function with the `this` and arguments given after the first argument
+pub fn call(args:Vec<Value>, global:Value, scope:Value, this:Value) -> ResultValue {
+    let mut args = args;
+    let this_arg = if args.is_empty() {Value::undefined()} else {args.remove(0).unwrap()};
+    call_value(this, args, global, scope, this_arg)
+}
+/// Call the function with the `this` given and the items of an array as its arguments
+pub fn apply(args:Vec<Value>, global:Value, scope:Value, this:Value) -> ResultValue {
+    let this_arg = if args.len() > 0 {args[0]} else {Value::undefined()};
+    let call_args = if args.len() > 1 && !args[1].is_null_or_undefined() {
+        try!(args[1].spread())
+    } else {
+        Vec::new()
+    };
+    call_value(this, call_args, global, scope, this_arg)
+}
+/// Make a function that calls this one with the `this` given and the rest of the arguments before its own
+pub fn bind(args:Vec<Value>, global:Value, _:Value, this:Value) -> ResultValue {
+    if !this.is_function() {
+        return Err(new_error(global, "TypeError", format!("{} is not a function", this).as_slice()));
+    }
+    let mut args = args;
+    let this_arg = if args.is_empty() {Value::undefined()} else {args.remove(0).unwrap()};
+    Ok(Function::new_bound(this, this_arg, args).into_value(global))
+}
+/// Make a function from the source of its parameters, given as the arguments before the last,
+/// and its body, given as the last argument, which closes over the global scope
+pub fn make_function(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    let mut params = Vec::new();
+    for arg in args.iter().take(if args.len() >= 1 {args.len() - 1} else {0}) {
+        params.push(try!(conversions::to_string(*arg)));
+    }
+    let body = match args.last() {
+        Some(body) => try!(conversions::to_string(*body)),
+        None => String::new()
+    };
+    function_from_source(params.connect(",").as_slice(), body.as_slice(), global)
+}
 /// Create a new `Function` object
-pub fn _create(_ : Value) -> Value {
-    let function : ObjectData = TreeMap::new();
-    to_value(function)
+pub fn _create(global : Value) -> Value {
+    let function = Function::make(make_function, ["args"]);
+    let prototype = js!(global, {
+        "call": Function::make(call, ["thisArg"]),
+        "apply": Function::make(apply, ["thisArg", "args"]),
+        "bind": Function::make(bind, ["thisArg"])
+    });
+    function.set_hidden_field(PROTOTYPE, prototype);
+    function
 }
 /// Initialise the global object with the `Function` object
 pub fn init(global:Value) {
@@ -0,0 +1,94 @@
+use front::stdlib::object::{PROTOTYPE, INSTANCE_PROTOTYPE};
+use front::stdlib::value::{Value, VFunction, ResultValue, to_value};
+use front::stdlib::function::Function;
+use front::stdlib::iterator::{ITERATOR, return_this};
+/// The hidden field of a generator holding the function that resumes running its body
+pub static RESUME: &'static str = "__resume__";
+/// The hidden field of a generator that is true while its body is running
+static RUNNING: &'static str = "__running__";
+/// The hidden field of a generator that is true once its body has finished
+static DONE: &'static str = "__done__";
+
+/// Make a generator that runs its body by calling `resume`, which is called on the generator
+/// with how it is being resumed (`"next"`, `"return"` or `"throw"`) and the value it is resumed
+/// with, and should give the result of running the body up to the next `yield` or the end
+pub fn make_generator(global:Value, resume:Value) -> Value {
+    let generator = Value::new_obj(Some(global));
+    generator.set_hidden_field(INSTANCE_PROTOTYPE, global.get_field("Generator").get_field(PROTOTYPE));
+    generator.set_hidden_field(RESUME, resume);
+    generator.set_hidden_field(RUNNING, to_value(false));
+    generator.set_hidden_field(DONE, to_value(false));
+    generator
+}
+/// Make the object given when a generator is resumed, holding the value given and whether it finished
+pub fn make_result(global:Value, value:Value, done:bool) -> Value {
+    let result = Value::new_obj(Some(global));
+    result.set_field("value", value);
+    result.set_field("done", to_value(done));
+    result
+}
+/// Resume the generator in the way given, unless it has already finished
+fn resume(global:Value, this:Value, mode:&'static str, value:Value) -> ResultValue {
+    let resume = this.get_field(RESUME);
+    if !resume.is_function() {
+        return Err(to_value(format!("{} is not a generator", this)));
+    }
+    if this.get_field(RUNNING).is_true() {
+        return Err(to_value("Generator is already running"));
+    }
+    if this.get_field(DONE).is_true() {
+        return match mode {
+            "throw" => Err(value),
+            "return" => Ok(make_result(global, value, true)),
+            _ => Ok(make_result(global, Value::undefined(), true))
+        };
+    }
+    this.set_hidden_field(RUNNING, to_value(true));
+    let result = match *resume {
+        VFunction(ref func) => func.borrow().call(vec!(to_value(mode), value), global, Value::undefined(), this),
+        _ => unreachable!()
+    };
+    this.set_hidden_field(RUNNING, to_value(false));
+    // a generator that throws can't be resumed again
+    let done = match result {
+        Ok(result) => result.get_field("done").is_true(),
+        Err(_) => true
+    };
+    if done {
+        this.set_hidden_field(DONE, to_value(true));
+    }
+    result
+}
+/// Run the generator up to the next `yield`, which gives the value passed in
+pub fn next(args:Vec<Value>, global:Value, _:Value, this:Value) -> ResultValue {
+    let value = if args.len() >= 1 {args[0]} else {Value::undefined()};
+    resume(global, this, "next", value)
+}
+/// Finish the generator as if it returned the value given, running any `finally` blocks it is paused in
+pub fn return_(args:Vec<Value>, global:Value, _:Value, this:Value) -> ResultValue {
+    let value = if args.len() >= 1 {args[0]} else {Value::undefined()};
+    resume(global, this, "return", value)
+}
+/// Throw the value given from the `yield` the generator is paused at
+pub fn throw(args:Vec<Value>, global:Value, _:Value, this:Value) -> ResultValue {
+    let value = if args.len() >= 1 {args[0]} else {Value::undefined()};
+    resume(global, this, "throw", value)
+}
+/// Create the object holding the prototype of generators
+pub fn _create(global: Value) -> Value {
+    let prototype = js!(global, {
+        "next": Function::make(next, ["value"]),
+        "return": Function::make(return_, ["value"]),
+        "throw": Function::make(throw, ["exception"]),
+        ITERATOR: Function::make(return_this, [])
+    });
+    let generator = Value::new_obj(Some(global));
+    generator.set_hidden_field(PROTOTYPE, prototype);
+    generator
+}
+/// Initialise the global object with the prototype of generators
+pub fn init(global:Value) {
+    js_extend!(global, {
+        "Generator": _create(global)
+    });
+}
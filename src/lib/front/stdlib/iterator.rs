@@ -0,0 +1,127 @@
+use front::stdlib::value::{Value, VString, ResultValue, to_value};
+use front::stdlib::function::{Function, call_value};
+use front::stdlib::generator::make_result;
+use front::stdlib::symbol::SymbolId;
+/// The well-known symbol `Symbol.iterator`, which keys the method that gives an iterator over a value
+pub static ITERATOR: SymbolId = SymbolId(0);
+/// The hidden field of a list iterator holding the value being iterated over
+static ITERATED: &'static str = "__iterated__";
+/// The hidden field of a list iterator holding the index of the next item, which is a byte offset for strings
+static INDEX: &'static str = "__index__";
+
+/// Make an iterator over the items of an array-like value, or the characters of a string
+pub fn make_list_iterator(global:Value, iterated:Value) -> Value {
+    let iterator = js!(global, {
+        "next": Function::make(list_next, []),
+        ITERATOR: Function::make(return_this, [])
+    });
+    iterator.set_hidden_field(ITERATED, iterated);
+    iterator.set_hidden_field(INDEX, to_value(0i32));
+    iterator
+}
+/// Give the next item of a list iterator
+pub fn list_next(_:Vec<Value>, global:Value, _:Value, this:Value) -> ResultValue {
+    let iterated = this.get_field(ITERATED);
+    let index = this.get_field(INDEX).to_int() as uint;
+    let item = match *iterated {
+        VString(ref s) if index < s.len() => {
+            let range = s.as_slice().char_range_at(index);
+            this.set_hidden_field(INDEX, to_value(range.next as i32));
+            Some(to_value(range.ch))
+        },
+        VString(_) => None,
+        // the length is read on every step, so items added during iteration are included
+        _ if iterated.is_undefined() => None,
+        _ if (index as i32) < try!(iterated.get("length")).to_int() => {
+            this.set_hidden_field(INDEX, to_value(index as i32 + 1));
+            Some(try!(iterated.get(index.to_string().as_slice())))
+        },
+        _ => None
+    };
+    match item {
+        Some(item) => Ok(make_result(global, item, false)),
+        None => {
+            // a finished iterator stays finished, even if items are added to the list later
+            this.set_hidden_field(ITERATED, Value::undefined());
+            Ok(make_result(global, Value::undefined(), true))
+        }
+    }
+}
+/// Give an iterator over the items of `this`, which is the iterator method of arrays and strings
+pub fn values(_:Vec<Value>, global:Value, _:Value, this:Value) -> ResultValue {
+    Ok(make_list_iterator(global, this))
+}
+/// Give `this`, which is the iterator method of iterators, so they can be iterated over themselves
+pub fn return_this(_:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
+    Ok(this)
+}
+/// Get an iterator over a value by calling its iterator method, throwing if it isn't iterable
+pub fn get_iterator(value:Value, global:Value) -> ResultValue {
+    let method = if value.is_null_or_undefined() {
+        Value::undefined()
+    } else {
+        try!(value.get_in(ITERATOR, global))
+    };
+    if !method.is_function() {
+        return Err(to_value(format!("{} is not iterable", value)));
+    }
+    let iterator = try!(call_value(method, Vec::new(), global, global, value));
+    if !iterator.is_object() {
+        return Err(to_value(format!("{} is not an iterator", iterator)));
+    }
+    Ok(iterator)
+}
+/// Step an iterator by calling its `next` method, giving the next value, or `None` once it is done
+pub fn step(iterator:Value, global:Value) -> Result<Option<Value>, Value> {
+    let next = try!(iterator.get("next"));
+    let result = try!(call_value(next, Vec::new(), global, global, iterator));
+    if !result.is_object() {
+        return Err(to_value(format!("Iterator result {} is not an object", result)));
+    }
+    if try!(result.get("done")).is_true() {
+        Ok(None)
+    } else {
+        Ok(Some(try!(result.get("value"))))
+    }
+}
+/// Close an iterator that isn't finished by calling its `return` method if it has one, as is
+/// done when a for-of loop is left early or destructuring doesn't use every item
+pub fn close(iterator:Value, global:Value) -> ResultValue {
+    let method = try!(iterator.get("return"));
+    if method.is_undefined() || method.is_null() {
+        return Ok(Value::undefined());
+    }
+    let result = try!(call_value(method, Vec::new(), global, global, iterator));
+    if !result.is_object() {
+        return Err(to_value(format!("Iterator result {} is not an object", result)));
+    }
+    Ok(Value::undefined())
+}
+/// Get every item of an iterable value, as is done when spreading it
+pub fn iterate(value:Value, global:Value) -> Result<Vec<Value>, Value> {
+    let iterator = try!(get_iterator(value, global));
+    let mut items = Vec::new();
+    loop {
+        match try!(step(iterator, global)) {
+            Some(item) => items.push(item),
+            None => return Ok(items)
+        }
+    }
+}
+/// Get the first `count` items of an iterable value, as is done when destructuring it into an array
+/// pattern, closing the iterator if it has more, and padding the items with `undefined` if it has fewer
+pub fn take(value:Value, global:Value, count:uint) -> Result<Vec<Value>, Value> {
+    let iterator = try!(get_iterator(value, global));
+    let mut items = Vec::with_capacity(count);
+    while items.len() < count {
+        match try!(step(iterator, global)) {
+            Some(item) => items.push(item),
+            None => {
+                items.grow(count - items.len(), &Value::undefined());
+                return Ok(items);
+            }
+        }
+    }
+    try!(close(iterator, global));
+    Ok(items)
+}
@@ -1,6 +1,9 @@
-use front::stdlib::value::{Value, ResultValue, to_value};
+use front::stdlib::value::{Value, VNull, VUndefined, VBoolean, VString, VNumber, VInteger, VBigInt, VSymbol,
+                           VObject, VFunction, ResultValue, to_value};
 use front::stdlib::function::Function;
-use serialize::json::{ToJson, from_str};
+use front::stdlib::object::StringKey;
+use serialize::json;
+use serialize::json::from_str;
 /// Parse a JSON string into a Javascript object
 pub fn parse(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
     let arg = args[0];
@@ -13,11 +16,59 @@ pub fn parse(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
         }
     }
 }
-/// Process a Javascript object into a JSON string
+/// Serialize a value as JSON, giving `None` for values that JSON can't hold, which are left out
+/// of objects and are `null` in arrays, and throwing for BigInts, if a getter throws, or if the
+/// value is one of the objects in `parents` that it is nested in
+fn serialize(value:Value, parents:&mut Vec<Value>) -> Result<Option<String>, Value> {
+    if value.is_object() {
+        if parents.iter().any(|parent| parent.strict_equals(&value)) {
+            return Err(to_value("Converting circular structure to JSON"));
+        }
+        parents.push(value);
+    }
+    let json = match *value {
+        VNull => "null".into_string(),
+        VBoolean(v) => v.to_string(),
+        VNumber(num) if num.is_nan() || num.is_infinite() => "null".into_string(),
+        VNumber(_) | VInteger(_) => value.to_string(),
+        VString(ref text) => json::String(text.clone()).to_string(),
+        VBigInt(_) => return Err(to_value("Do not know how to serialize a BigInt")),
+        VUndefined | VSymbol(_) | VFunction(_) => return Ok(None),
+        VObject(_) if value.is_array() => {
+            let mut items = Vec::new();
+            for i in range(0, value.get_field("length").to_uint()) {
+                let item = try!(serialize(try!(value.get(i.to_string().as_slice())), parents));
+                items.push(item.unwrap_or("null".into_string()));
+            }
+            format!("[{}]", items.connect(","))
+        },
+        VObject(_) => {
+            let mut fields = Vec::new();
+            for key in value.own_keys().move_iter() {
+                let name = match key {
+                    StringKey(ref name) if value.get_own_prop(key.clone()).unwrap().enumerable => name.clone(),
+                    _ => continue
+                };
+                match try!(serialize(try!(value.get(key)), parents)) {
+                    Some(field) => fields.push(format!("{}:{}", json::String(name), field)),
+                    None => ()
+                }
+            }
+            format!("{{{}}}", fields.connect(","))
+        }
+    };
+    if value.is_object() {
+        parents.pop();
+    }
+    Ok(Some(json))
+}
+/// Process a Javascript object into a JSON string, which is `undefined` if it can't be serialized
 pub fn stringify(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
-    let obj = args[0];
-    let json = obj.to_json();
-    Ok(to_value(json.to_pretty_str()))
+    let obj = if args.len() >= 1 {args[0]} else {Value::undefined()};
+    Ok(match try!(serialize(obj, &mut Vec::new())) {
+        Some(json) => to_value(json),
+        None => Value::undefined()
+    })
 }
 /// Create a new `JSON` object
 pub fn _create(global:Value) -> Value {
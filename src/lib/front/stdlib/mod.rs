@@ -2,12 +2,18 @@
 pub mod array;
 /// The `Boolean` global object
 pub mod boolean;
+/// The `Map`, `Set`, `WeakMap` and `WeakSet` global objects
+pub mod collection;
 /// The `console` global object
 pub mod console;
 /// The `Error` global objects
 pub mod error;
 /// The `Function` global object
 pub mod function;
+/// The prototype of generators
+pub mod generator;
+/// The iteration protocol, and the iterators of arrays and strings
+pub mod iterator;
 /// The `JSON` global object
 pub mod json;
 /// The `Math` global object
@@ -16,8 +22,18 @@ pub mod math;
 pub mod number;
 /// The `Object` global object
 pub mod object;
+/// The `Promise` global object and the queue of jobs it runs
+pub mod promise;
+/// The state the engine keeps for each global object
+pub mod realm;
+/// The `RegExp` global object
+pub mod regexp;
 /// The `String` global object
 pub mod string;
+/// The `Symbol` global object and the well-known symbols
+pub mod symbol;
+/// The global timer methods and the event loop that runs them
+pub mod timer;
 /// The global URI methods
 pub mod uri;
 /// An arbritary Javascript value
@@ -1,29 +1,80 @@
-use front::stdlib::value::{Value, ResultValue, VNumber, VInteger, to_value, from_value};
+use front::stdlib::value::{Value, ResultValue, VNumber, VInteger, to_value};
 use front::stdlib::function::Function;
+use front::stdlib::object::Property;
+use conversions;
 use std::f64::{NAN, MAX_VALUE, MIN_VALUE, INFINITY, NEG_INFINITY, EPSILON};
-/// Parse a float into a value
+/// Get the length of the longest start of some text made of digits in the radix given
+fn digits_len(text:&str, radix:uint) -> uint {
+    text.chars().take_while(|ch| ch.to_digit(radix).is_some()).count()
+}
+/// Parse the longest start of a string that is a decimal number into a value, after skipping whitespace,
+/// which can be `Infinity` and is `NaN` if there isn't one
 pub fn parse_float(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
-    let parsed = from_str::<f64>(from_value::<String>(args[0]).unwrap().as_slice());
-    return Ok(to_value(match parsed {
-        Some(v) => v,
-        None => NAN
-    }));
+    let string = try!(conversions::to_string(if args.len() >= 1 {args[0]} else {Value::undefined()}));
+    let text = string.as_slice().trim_left_chars(conversions::is_js_whitespace);
+    let (sign, unsigned) = match text.chars().next() {
+        Some('-') => (-1.0, text.slice_from(1)),
+        Some('+') => (1.0, text.slice_from(1)),
+        _ => (1.0, text)
+    };
+    if unsigned.starts_with("Infinity") {
+        return Ok(to_value(sign * INFINITY));
+    }
+    let whole = digits_len(unsigned, 10);
+    let mut end = whole;
+    if unsigned.slice_from(end).starts_with(".") {
+        let fraction = digits_len(unsigned.slice_from(end + 1), 10);
+        // a point needs a digit on one side of it
+        if whole > 0 || fraction > 0 {
+            end += 1 + fraction;
+        }
+    }
+    if end == 0 {
+        return Ok(to_value(NAN));
+    }
+    let rest = unsigned.slice_from(end);
+    if rest.starts_with("e") || rest.starts_with("E") {
+        let signed = if rest.slice_from(1).starts_with("-") || rest.slice_from(1).starts_with("+") {2} else {1};
+        let exponent = digits_len(rest.slice_from(signed), 10);
+        // an exponent without digits isn't part of the number
+        if exponent > 0 {
+            end += signed + exponent;
+        }
+    }
+    Ok(to_value(sign * from_str::<f64>(unsigned.slice_to(end)).unwrap_or(NAN)))
 }
-/// Parse an int into a value
+/// Parse the longest start of a string that is an integer in the radix given into a value, after skipping
+/// whitespace, where the radix is 10 if it is `0` or missing, or 16 if the integer starts with `0x`, and
+/// the value is `NaN` if there isn't an integer or the radix isn't from 2 to 36
 pub fn parse_int(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
-    let parsed = from_str::<i32>(from_value::<String>(args[0]).unwrap().as_slice());
-    return Ok(match parsed {
-        Some(v) => to_value(v),
-        None => to_value(NAN)
-    });
+    let string = try!(conversions::to_string(if args.len() >= 1 {args[0]} else {Value::undefined()}));
+    let radix = conversions::to_int32(try!(conversions::to_number(if args.len() >= 2 {args[1]} else {Value::undefined()})));
+    let text = string.as_slice().trim_left_chars(conversions::is_js_whitespace);
+    let (sign, text) = match text.chars().next() {
+        Some('-') => (-1.0, text.slice_from(1)),
+        Some('+') => (1.0, text.slice_from(1)),
+        _ => (1.0, text)
+    };
+    if radix != 0 && (radix < 2 || radix > 36) {
+        return Ok(to_value(NAN));
+    }
+    let hex = text.starts_with("0x") || text.starts_with("0X");
+    let (radix, text) = match radix {
+        0 | 16 if hex => (16, text.slice_from(2)),
+        0 => (10, text),
+        _ => (radix as uint, text)
+    };
+    let digits = text.slice_to(digits_len(text, radix));
+    if digits.is_empty() {
+        return Ok(to_value(NAN));
+    }
+    let num = digits.chars().fold(0.0, |num, ch| num * radix as f64 + ch.to_digit(radix).unwrap() as f64);
+    Ok(to_value(sign * num))
 }
-/// Check if a value when converted to a number is finite
+/// Check if a value when converted to a number is finite, where no value is `undefined`, which isn't
 pub fn is_finite(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
-    Ok(to_value(if args.len() == 0 {
-        false
-    } else {
-        from_value::<f64>(args[0]).unwrap().is_finite()
-    }))
+    let num = try!(conversions::to_number(if args.len() >= 1 {args[0]} else {Value::undefined()}));
+    Ok(to_value(num.is_finite()))
 }
 /// Check if a number is finite
 pub fn strict_is_finite(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
@@ -37,13 +88,10 @@ pub fn strict_is_finite(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultVal
         }
     }))
 }
-/// Check if a value when converted to a number is equal to NaN
+/// Check if a value when converted to a number is equal to NaN, where no value is `undefined`, which is
 pub fn is_nan(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
-    Ok(to_value(if args.len() == 0 {
-        false
-    } else {
-        from_value::<f64>(args[0]).unwrap().is_nan()
-    }))
+    let num = try!(conversions::to_number(if args.len() >= 1 {args[0]} else {Value::undefined()}));
+    Ok(to_value(num.is_nan()))
 }
 /// Check if a number is equal to NaN
 pub fn strict_is_nan(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
@@ -66,20 +114,24 @@ pub fn _create(global:Value) -> Value {
         "NEGATIVE_INFINITY": NEG_INFINITY,
         "EPSILON": EPSILON,
         "parseFloat": Function::make(parse_float, ["string"]),
-        "parseInt": Function::make(parse_int, ["string"]),
+        "parseInt": Function::make(parse_int, ["string", "radix"]),
         "isFinite": Function::make(strict_is_finite, ["num"]),
         "isNaN": Function::make(strict_is_nan, ["num"])
     })
 }
-/// Initialise the parse functions and `Number` on the global object
+/// Initialise the global values, the parse and check functions, and `Number` on the global object
 pub fn init(global:Value) {
+    // the global values can't be changed, deleted or enumerated
+    for &(name, value) in [("NaN", to_value(NAN)), ("Infinity", to_value(INFINITY)), ("undefined", Value::undefined())].iter() {
+        global.set_prop(name, Property::new(value));
+    }
+    // the parse functions on the global object are the same ones as on `Number`
+    let number = _create(global);
     js_extend!(global, {
-        "NaN": NAN,
-        "Infinity": INFINITY,
-        "parseFloat": Function::make(parse_float, ["string"]),
-        "parseInt": Function::make(parse_int, ["string"]),
+        "parseFloat": number.get_field("parseFloat"),
+        "parseInt": number.get_field("parseInt"),
         "isFinite": Function::make(is_finite, ["number"]),
         "isNaN": Function::make(is_nan, ["num"]),
-        "Number": _create(global)
+        "Number": number
     });
 }
\ No newline at end of file
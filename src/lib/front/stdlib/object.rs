@@ -1,10 +1,113 @@
 use front::stdlib::value::{Value, ResultValue, ToValue, FromValue, to_value, from_value};
 use front::stdlib::function::Function;
+use front::stdlib::symbol::{SymbolId, TO_STRING_TAG};
+use front::stdlib::collection::Collection;
+use front::stdlib::realm::Realm;
+use front::run::interpreter::Generator;
+use conversions;
 use collections::treemap::TreeMap;
+use std::iter::FromIterator;
+use std::fmt;
 pub static PROTOTYPE: &'static str = "prototype";
 pub static INSTANCE_PROTOTYPE: &'static str = "__proto__";
+
+#[deriving(Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// The key of a property, which is a string or a symbol
+pub enum PropertyKey {
+    /// The key of a property named by a string
+    StringKey(String),
+    /// The key of a property keyed by the symbol with the ID given, which is never enumerated
+    SymbolKey(SymbolId)
+}
+impl fmt::Show for PropertyKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StringKey(ref name) => write!(f, "{}", name),
+            SymbolKey(SymbolId(id)) => write!(f, "Symbol({})", id)
+        }
+    }
+}
+/// Conversion to the keys of properties from Rust values
+pub trait ToPropertyKey {
+    /// Convert this value to the key of a property
+    fn to_key(&self) -> PropertyKey;
+}
+impl<'a> ToPropertyKey for &'a str {
+    fn to_key(&self) -> PropertyKey {
+        StringKey(self.into_string())
+    }
+}
+impl ToPropertyKey for String {
+    fn to_key(&self) -> PropertyKey {
+        StringKey(self.clone())
+    }
+}
+impl ToPropertyKey for SymbolId {
+    fn to_key(&self) -> PropertyKey {
+        SymbolKey(*self)
+    }
+}
+impl ToPropertyKey for PropertyKey {
+    fn to_key(&self) -> PropertyKey {
+        self.clone()
+    }
+}
+
 #[deriving(Clone)]
-pub type ObjectData = TreeMap<String, Property>;
+/// The data of an object, which is its properties along with the state the engine keeps for it
+pub struct ObjectData {
+    /// The properties of the object, by their keys
+    pub properties: TreeMap<PropertyKey, Property>,
+    /// If properties can be added to the object, which is stopped by `Object.preventExtensions` and the like
+    pub extensible: bool,
+    /// The kind of object it is, for objects the engine treats differently to ordinary ones
+    pub kind: ObjectKind,
+    /// The values weak collections hold for the object as a key, by the IDs of the collections,
+    /// so they are only kept for as long as the object is
+    pub weak_entries: TreeMap<uint, Value>,
+    /// The global object the object was made for, which functions it runs without being
+    /// given a global object, such as its getters, setters and conversions, are run with
+    pub global: Option<Value>
+}
+#[deriving(Clone)]
+/// The kinds of objects that the engine treats differently to ordinary objects
+pub enum ObjectKind {
+    /// An ordinary object
+    OrdinaryObject,
+    /// An array, whose length follows the indexes set in it
+    ArrayObject,
+    /// A `Map` or `Set`, whose entries are kept by the engine
+    CollectionObject(Collection),
+    /// A `WeakMap` or `WeakSet` with the ID given, whose entries are kept on their keys
+    WeakCollectionObject(uint),
+    /// A global object, along with the state the engine keeps for it
+    GlobalObject(Realm),
+    /// A generator made by the interpreter, along with what is left to do in its body
+    GeneratorObject(Generator)
+}
+impl ObjectData {
+    /// Make the data of an object with no properties, which can be extended
+    pub fn new() -> ObjectData {
+        ObjectData {
+            properties: TreeMap::new(),
+            extensible: true,
+            kind: OrdinaryObject,
+            weak_entries: TreeMap::new(),
+            global: None
+        }
+    }
+}
+impl FromIterator<(String, Property)> for ObjectData {
+    fn from_iter<T:Iterator<(String, Property)>>(iter:T) -> ObjectData {
+        ObjectData {
+            properties: iter.map(|(name, prop)| (StringKey(name), prop)).collect(),
+            extensible: true,
+            kind: OrdinaryObject,
+            weak_entries: TreeMap::new(),
+            global: None
+        }
+    }
+}
 
 #[deriving(Clone)]
 /// A Javascript property
@@ -34,17 +137,64 @@ impl Property {
             set: Value::undefined()
         }
     }
+    /// Make a new writable, enumerable and configurable property, as assigning to a new field does
+    pub fn data(value : Value) -> Property {
+        Property {
+            configurable: true,
+            enumerable: true,
+            writable: true,
+            value: value,
+            get: Value::undefined(),
+            set: Value::undefined()
+        }
+    }
+    /// Make a new writable and configurable property that isn't enumerated, as built-in properties are
+    pub fn hidden(value : Value) -> Property {
+        Property {
+            enumerable: false,
+            .. Property::data(value)
+        }
+    }
+    /// Make a new configurable property that isn't enumerated with the getter and setter given, which
+    /// are `undefined` if it doesn't have them, as the accessors of classes are
+    pub fn accessor(get : Value, set : Value) -> Property {
+        Property {
+            configurable: true,
+            get: get,
+            set: set,
+            .. Property::new(Value::undefined())
+        }
+    }
+    /// Returns true if the property has a getter or a setter instead of a value
+    pub fn is_accessor(&self) -> bool {
+        self.get.is_function() || self.set.is_function()
+    }
 }
 
+#[deriving(Clone, PartialEq, PartialOrd, Show)]
+/// How locked an object can be made, where each level includes the ones before it
+pub enum Integrity {
+    /// Properties can't be added to the object, as with `Object.preventExtensions`
+    NotExtensible,
+    /// Properties can't be added to or removed from the object, as with `Object.seal`
+    Sealed,
+    /// Properties can't be added to, removed from or assigned to in the object, as with `Object.freeze`
+    Frozen
+}
 impl ToValue for Property {
+    /// Make the descriptor of the property, which has a getter and setter if it is an accessor,
+    /// and a value and whether it is writable otherwise
     fn to_value(&self) -> Value {
         let prop = Value::new_obj(None);
         prop.set_field("configurable", to_value(self.configurable));
         prop.set_field("enumerable", to_value(self.enumerable));
-        prop.set_field("writable", to_value(self.writable));
-        prop.set_field("value", self.value);
-        prop.set_field("get", self.get);
-        prop.set_field("set", self.set);
+        if self.is_accessor() {
+            prop.set_field("get", self.get);
+            prop.set_field("set", self.set);
+        } else {
+            prop.set_field("writable", to_value(self.writable));
+            prop.set_field("value", self.value);
+        }
         prop
     }
 }
@@ -76,26 +226,115 @@ pub fn set_proto_of(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
     obj.set_field(INSTANCE_PROTOTYPE, proto);
     Ok(obj)
 }
-/// Define a property in an object
+/// Define a property in an object, throwing if it is a new property of an object that can't be
+/// extended, or changes an existing property that can't be configured in a way other than making
+/// it read only or giving it a new value while it is writable
 pub fn define_prop(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
     let obj = args[0];
-    let prop = from_value::<String>(args[1]).unwrap();
+    let prop = try!(conversions::to_property_key(args[1]));
     let desc = from_value::<Property>(args[2]).unwrap();
-    obj.set_prop(prop.as_slice(), desc);
+    match obj.get_own_prop(prop.clone()) {
+        None if !obj.is_extensible() =>
+            return Err(to_value(format!("Cannot define property {}, object is not extensible", prop))),
+        Some(ref old) if !old.configurable && !can_redefine(old, &desc) =>
+            return Err(to_value(format!("Cannot redefine property: {}", prop))),
+        _ => ()
+    }
+    obj.set_prop(prop, desc);
     Ok(Value::undefined())
 }
-/// To string
+/// Check if a property that can't be configured can be given the attributes of another
+fn can_redefine(old:&Property, new:&Property) -> bool {
+    if new.configurable || new.enumerable != old.enumerable || old.is_accessor() || new.is_accessor() {
+        return false;
+    }
+    old.writable || !new.writable && new.value.strict_equals(&old.value)
+}
+/// Get the string representation of the object, which is `[object ` followed by its kind and `]`,
+/// where the kind is its `Symbol.toStringTag` property if that is a string
 pub fn to_string(_:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
-    Ok(to_value(this.to_string()))
+    let tag = if this.is_null_or_undefined() {Value::undefined()} else {try!(this.get(TO_STRING_TAG))};
+    if tag.is_string() {
+        return Ok(to_value(format!("[object {}]", tag)));
+    }
+    let kind = if this.is_undefined() {
+        "Undefined"
+    } else if this.is_null() {
+        "Null"
+    } else if this.is_function() {
+        "Function"
+    } else {
+        "Object"
+    };
+    Ok(to_value(format!("[object {}]", kind)))
 }
 /// Check if it has a property
 pub fn has_own_prop(args:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
     let prop = if args.len() == 0 {
         None
     } else {
-        from_value::<String>(args[0]).ok()
+        Some(try!(conversions::to_property_key(args[0])))
     };
-    Ok(to_value(prop.is_some() && this.get_prop(prop.unwrap().as_slice()).is_some()))
+    Ok(to_value(prop.is_some() && this.get_own_prop(prop.unwrap()).is_some()))
+}
+/// Raise the integrity level of an object to the one given, making its own properties unable to be
+/// removed if it is sealed, and unable to be assigned to if it is frozen, giving back the object,
+/// where values that aren't objects are given back as they are
+fn lock(obj:Value, level:Integrity) -> Value {
+    if !obj.is_object() && !obj.is_function() {
+        return obj;
+    }
+    if level >= Sealed {
+        for key in obj.own_keys().iter() {
+            let mut prop = obj.get_own_prop(key.clone()).unwrap();
+            prop.configurable = false;
+            if level == Frozen && !prop.get.is_function() && !prop.set.is_function() {
+                prop.writable = false;
+            }
+            obj.set_prop(key.clone(), prop);
+        }
+    }
+    obj.prevent_extensions();
+    obj
+}
+/// Stop properties from being added to an object
+pub fn prevent_extensions(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
+    Ok(lock(if args.len() >= 1 {args[0]} else {Value::undefined()}, NotExtensible))
+}
+/// Stop properties from being added to or removed from an object
+pub fn seal(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
+    Ok(lock(if args.len() >= 1 {args[0]} else {Value::undefined()}, Sealed))
+}
+/// Stop properties from being added to, removed from or assigned to in an object
+pub fn freeze(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
+    Ok(lock(if args.len() >= 1 {args[0]} else {Value::undefined()}, Frozen))
+}
+/// Check if an object is at least at the integrity level given, which values that aren't objects always
+/// are, and objects that can't be extended are if none of their own properties can be configured, and
+/// for frozen objects, none of them that hold values can be assigned to
+fn is_locked(args:Vec<Value>, level:Integrity) -> bool {
+    let obj = if args.len() >= 1 {args[0]} else {Value::undefined()};
+    if !obj.is_object() && !obj.is_function() {
+        return true;
+    }
+    !obj.is_extensible() && obj.own_keys().iter().all(|key| {
+        let prop = obj.get_own_prop(key.clone()).unwrap();
+        let is_accessor = prop.get.is_function() || prop.set.is_function();
+        *key == INSTANCE_PROTOTYPE.to_key() || level == NotExtensible
+            || !prop.configurable && (level == Sealed || is_accessor || !prop.writable)
+    })
+}
+/// Check if properties can be added to an object
+pub fn is_extensible(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
+    Ok(to_value(!is_locked(args, NotExtensible)))
+}
+/// Check if properties can't be added to or removed from an object
+pub fn is_sealed(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
+    Ok(to_value(is_locked(args, Sealed)))
+}
+/// Check if properties can't be added to, removed from or assigned to in an object
+pub fn is_frozen(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
+    Ok(to_value(is_locked(args, Frozen)))
 }
 /// Create a new `Object` object
 pub fn _create(global:Value) -> Value {
@@ -109,7 +348,13 @@ pub fn _create(global:Value) -> Value {
         PROTOTYPE: prototype,
         "setPrototypeOf": Function::make(get_proto_of, ["object", "prototype"]),
         "getPrototypeOf": Function::make(get_proto_of, ["object"]),
-        "defineProperty": Function::make(define_prop, ["object", "property"])
+        "defineProperty": Function::make(define_prop, ["object", "property"]),
+        "preventExtensions": Function::make(prevent_extensions, ["object"]),
+        "seal": Function::make(seal, ["object"]),
+        "freeze": Function::make(freeze, ["object"]),
+        "isExtensible": Function::make(is_extensible, ["object"]),
+        "isSealed": Function::make(is_sealed, ["object"]),
+        "isFrozen": Function::make(is_frozen, ["object"])
     });
     object
 }
@@ -0,0 +1,442 @@
+use front::stdlib::value::{Value, ResultValue, to_value};
+use front::stdlib::function::{Function, FunctionData, NEW_TARGET, call_value};
+use front::stdlib::object::{PROTOTYPE, INSTANCE_PROTOTYPE};
+use front::stdlib::array::new_array;
+use front::stdlib::iterator;
+/// The hidden field of a promise holding whether it is `"pending"`, `"fulfilled"` or `"rejected"`
+static STATE: &'static str = "__state__";
+/// The hidden field of a promise holding the value it was fulfilled or rejected with
+static RESULT: &'static str = "__result__";
+/// The hidden field of a pending promise holding the reactions to run once it is settled
+static REACTIONS: &'static str = "__reactions__";
+/// The hidden field of the global object holding the queue of jobs waiting to be run
+static JOBS: &'static str = "__jobs__";
+
+/// Get the argument at the index given, or `undefined` if there are fewer arguments
+fn arg(args:&Vec<Value>, index:uint) -> Value {
+    if args.len() > index {args[index]} else {Value::undefined()}
+}
+/// Make a function that runs the native function given with the arguments given before
+/// the ones it is called with, so it can hold on to the values it needs
+fn native_closure(global:Value, repr:FunctionData, args:Vec<Value>) -> Value {
+    Function::new_bound(Function::make(repr, []), Value::undefined(), args).into_value(global)
+}
+/// Add an item to the end of an array-like object
+fn push(list:Value, item:Value) {
+    let length = list.get_field("length").to_int();
+    list.set_field(length.to_string().as_slice(), item);
+    list.set_field("length", to_value(length + 1));
+}
+/// Queue a job, which is a function that is called with no arguments once the code
+/// running now and the jobs queued before it have finished
+pub fn enqueue_job(global:Value, job:Value) {
+    let mut queue = global.get_field(JOBS);
+    if !queue.is_object() {
+        queue = Value::new_obj(None);
+        queue.set_field("next", to_value(0i32));
+        queue.set_field("length", to_value(0i32));
+        global.set_hidden_field(JOBS, queue);
+    }
+    push(queue, job);
+}
+/// Get how many jobs are waiting to be run
+pub fn pending_jobs(global:Value) -> uint {
+    let queue = global.get_field(JOBS);
+    if !queue.is_object() {
+        return 0;
+    }
+    (queue.get_field("length").to_int() - queue.get_field("next").to_int()) as uint
+}
+/// Take the job at the front of the queue, if there is one
+fn dequeue_job(global:Value) -> Option<Value> {
+    if pending_jobs(global) == 0 {
+        return None;
+    }
+    let queue = global.get_field(JOBS);
+    let next = queue.get_field("next").to_int();
+    let index = next.to_string();
+    let job = queue.get_field(index.as_slice());
+    queue.remove_field(index.as_slice());
+    if next + 1 == queue.get_field("length").to_int() {
+        // start the queue over once it is empty, so the indexes don't keep growing
+        queue.set_field("next", to_value(0i32));
+        queue.set_field("length", to_value(0i32));
+    } else {
+        queue.set_field("next", to_value(next + 1));
+    }
+    Some(job)
+}
+/// Run the jobs that were waiting when this was called in the order they were queued, giving
+/// how many were run, and leaving the jobs they queue for the next call, so an embedder can
+/// step through them; a job that throws stops the ones after it, which are left on the queue
+pub fn run_jobs(global:Value) -> Result<uint, Value> {
+    let count = pending_jobs(global);
+    for ran in range(0, count) {
+        match dequeue_job(global) {
+            Some(job) => {
+                try!(call_value(job, Vec::new(), global, global, Value::undefined()));
+            },
+            None => return Ok(ran)
+        }
+    }
+    Ok(count)
+}
+/// Run jobs until none are left, including the ones queued by the jobs run, giving how many were run
+pub fn run_until_idle(global:Value) -> Result<uint, Value> {
+    let mut total = 0;
+    loop {
+        let ran = try!(run_jobs(global));
+        if ran == 0 {
+            return Ok(total);
+        }
+        total += ran;
+    }
+}
+/// Give a value the hidden fields of a pending promise
+fn init_promise(promise:Value) {
+    let reactions = Value::new_obj(None);
+    reactions.set_field("length", to_value(0i32));
+    promise.set_hidden_field(STATE, to_value("pending"));
+    promise.set_hidden_field(RESULT, Value::undefined());
+    promise.set_hidden_field(REACTIONS, reactions);
+}
+/// Make a pending promise, which inherits from `Promise.prototype` on the global object given
+pub fn new_promise(global:Value) -> Value {
+    let promise = Value::new_obj(Some(global));
+    promise.set_hidden_field(INSTANCE_PROTOTYPE, global.get_field("Promise").get_field(PROTOTYPE));
+    init_promise(promise);
+    promise
+}
+/// Returns true if the value is a promise
+pub fn is_promise(value:&Value) -> bool {
+    value.is_object() && value.get_own_prop(STATE).is_some()
+}
+/// Make the functions that resolve and reject a promise, which do nothing once either has been called
+fn make_resolving_functions(global:Value, promise:Value) -> (Value, Value) {
+    let called = Value::new_obj(None);
+    called.set_field("done", to_value(false));
+    (native_closure(global, resolve_function, vec!(promise, called)),
+     native_closure(global, reject_function, vec!(promise, called)))
+}
+/// Make a pending promise along with the functions that resolve and reject it
+fn new_capability(global:Value) -> (Value, Value, Value) {
+    let promise = new_promise(global);
+    let (resolve, reject) = make_resolving_functions(global, promise);
+    (promise, resolve, reject)
+}
+/// Resolve the promise the function was made for, unless it has already been resolved or rejected
+fn resolve_function(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    let (promise, called) = (args[0], args[1]);
+    if called.get_field("done").is_true() {
+        return Ok(Value::undefined());
+    }
+    called.set_field("done", to_value(true));
+    resolve_promise(global, promise, arg(&args, 2));
+    Ok(Value::undefined())
+}
+/// Reject the promise the function was made for, unless it has already been resolved or rejected
+fn reject_function(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    let (promise, called) = (args[0], args[1]);
+    if called.get_field("done").is_true() {
+        return Ok(Value::undefined());
+    }
+    called.set_field("done", to_value(true));
+    reject_promise(global, promise, arg(&args, 2));
+    Ok(Value::undefined())
+}
+/// Resolve a promise with a value, which fulfills it with the value unless it has a `then`
+/// method, in which case a job is queued to make the promise follow it
+pub fn resolve_promise(global:Value, promise:Value, resolution:Value) {
+    if resolution.strict_equals(&promise) {
+        return reject_promise(global, promise, to_value("Chaining cycle detected for promise"));
+    }
+    if !resolution.is_object() && !resolution.is_function() {
+        return fulfill_promise(global, promise, resolution);
+    }
+    let then = match resolution.get("then") {
+        Ok(then) => then,
+        Err(err) => return reject_promise(global, promise, err)
+    };
+    if !then.is_function() {
+        return fulfill_promise(global, promise, resolution);
+    }
+    enqueue_job(global, native_closure(global, resolve_thenable_job, vec!(promise, resolution, then)));
+}
+/// Call the `then` method of a value a promise was resolved with, so the promise settles the same way
+fn resolve_thenable_job(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    let (promise, thenable, then) = (args[0], args[1], args[2]);
+    let (resolve, reject) = make_resolving_functions(global, promise);
+    match call_value(then, vec!(resolve, reject), global, global, thenable) {
+        Ok(_) => Ok(Value::undefined()),
+        Err(err) => call_value(reject, vec!(err), global, global, Value::undefined())
+    }
+}
+/// Fulfill a pending promise with a value
+pub fn fulfill_promise(global:Value, promise:Value, value:Value) {
+    settle(global, promise, false, value)
+}
+/// Reject a pending promise with a reason
+pub fn reject_promise(global:Value, promise:Value, reason:Value) {
+    settle(global, promise, true, reason)
+}
+/// Settle a pending promise with a value, queueing a job for each of its reactions
+fn settle(global:Value, promise:Value, rejected:bool, value:Value) {
+    let reactions = promise.get_field(REACTIONS).spread().unwrap_or(Vec::new());
+    promise.set_hidden_field(STATE, to_value(if rejected {"rejected"} else {"fulfilled"}));
+    promise.set_hidden_field(RESULT, value);
+    promise.set_hidden_field(REACTIONS, Value::undefined());
+    for reaction in reactions.move_iter() {
+        queue_reaction(global, reaction, rejected, value);
+    }
+}
+/// Queue a job running a reaction to a promise being settled with a value
+fn queue_reaction(global:Value, reaction:Value, rejected:bool, value:Value) {
+    enqueue_job(global, native_closure(global, reaction_job, vec!(reaction, to_value(rejected), value)));
+}
+/// Run the handler of a reaction for how its promise was settled, resolving the promise
+/// given by `then` with what it returns, or rejecting it with what it throws
+fn reaction_job(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    let (reaction, rejected, value) = (args[0], args[1].is_true(), args[2]);
+    let handler = reaction.get_field(if rejected {"rejected"} else {"fulfilled"});
+    // without a handler, the value is passed on to the next promise in the chain
+    let result = if handler.is_function() {
+        call_value(handler, vec!(value), global, global, Value::undefined())
+    } else if rejected {
+        Err(value)
+    } else {
+        Ok(value)
+    };
+    let (callback, value) = match result {
+        Ok(value) => (reaction.get_field("resolve"), value),
+        Err(err) => (reaction.get_field("reject"), err)
+    };
+    call_value(callback, vec!(value), global, global, Value::undefined())
+}
+/// Add a reaction to a promise that runs one of the handlers given once it is settled, giving a new promise
+/// that is resolved with what the handler returns, or rejected with what it throws
+fn perform_then(global:Value, promise:Value, on_fulfilled:Value, on_rejected:Value) -> Value {
+    let (derived, resolve, reject) = new_capability(global);
+    let reaction = Value::new_obj(None);
+    reaction.set_field("fulfilled", on_fulfilled);
+    reaction.set_field("rejected", on_rejected);
+    reaction.set_field("resolve", resolve);
+    reaction.set_field("reject", reject);
+    let state = promise.get_field(STATE);
+    if state.strict_equals(&to_value("pending")) {
+        push(promise.get_field(REACTIONS), reaction);
+    } else {
+        queue_reaction(global, reaction, state.strict_equals(&to_value("rejected")), promise.get_field(RESULT));
+    }
+    derived
+}
+/// Give a promise resolved with the value given, or the value itself if it is already a promise
+pub fn promise_resolve(global:Value, value:Value) -> Value {
+    if is_promise(&value) {
+        return value;
+    }
+    let promise = new_promise(global);
+    resolve_promise(global, promise, value);
+    promise
+}
+/// Create a new promise, running the executor given with the functions that resolve and reject it
+pub fn make_promise(args:Vec<Value>, global:Value, scope:Value, this:Value) -> ResultValue {
+    if scope.get_field(NEW_TARGET).is_undefined() {
+        return Err(to_value("Promise constructor cannot be invoked without 'new'"));
+    }
+    let executor = arg(&args, 0);
+    if !executor.is_function() {
+        return Err(to_value(format!("Promise resolver {} is not a function", executor)));
+    }
+    init_promise(this);
+    let (resolve, reject) = make_resolving_functions(global, this);
+    match call_value(executor, vec!(resolve, reject), global, global, Value::undefined()) {
+        Ok(_) => Ok(Value::undefined()),
+        Err(err) => call_value(reject, vec!(err), global, global, Value::undefined())
+    }
+}
+/// Run one of the handlers given once the promise is settled, giving a promise of what it returns
+pub fn then(args:Vec<Value>, global:Value, _:Value, this:Value) -> ResultValue {
+    if !is_promise(&this) {
+        return Err(to_value(format!("{} is not a promise", this)));
+    }
+    Ok(perform_then(global, this, arg(&args, 0), arg(&args, 1)))
+}
+/// Run the handler given if the promise is rejected, giving a promise of what it returns
+pub fn catch_(args:Vec<Value>, global:Value, _:Value, this:Value) -> ResultValue {
+    let then = try!(this.get("then"));
+    call_value(then, vec!(Value::undefined(), arg(&args, 0)), global, global, this)
+}
+/// Run the handler given once the promise is settled, giving a promise that settles the same
+/// way once the handler has finished, unless the handler throws or gives a rejected promise
+pub fn finally_(args:Vec<Value>, global:Value, _:Value, this:Value) -> ResultValue {
+    let on_finally = arg(&args, 0);
+    let then = try!(this.get("then"));
+    if !on_finally.is_function() {
+        return call_value(then, vec!(on_finally, on_finally), global, global, this);
+    }
+    let then_finally = native_closure(global, then_finally, vec!(on_finally));
+    let catch_finally = native_closure(global, catch_finally, vec!(on_finally));
+    call_value(then, vec!(then_finally, catch_finally), global, global, this)
+}
+/// Run the handler of `finally` after its promise was fulfilled, then give the value it was fulfilled with
+fn then_finally(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    after_finally(global, args[0], native_closure(global, give_value, vec!(arg(&args, 1))))
+}
+/// Run the handler of `finally` after its promise was rejected, then throw the reason it was rejected with
+fn catch_finally(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    after_finally(global, args[0], native_closure(global, throw_value, vec!(arg(&args, 1))))
+}
+/// Run the handler of `finally`, giving a promise that runs `next` once what the handler gives is fulfilled
+fn after_finally(global:Value, on_finally:Value, next:Value) -> ResultValue {
+    let result = try!(call_value(on_finally, Vec::new(), global, global, Value::undefined()));
+    let promise = promise_resolve(global, result);
+    let then = try!(promise.get("then"));
+    call_value(then, vec!(next), global, global, promise)
+}
+/// Give the value the function was made with
+fn give_value(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
+    Ok(args[0])
+}
+/// Throw the value the function was made with
+fn throw_value(args:Vec<Value>, _:Value, _:Value, _:Value) -> ResultValue {
+    Err(args[0])
+}
+/// Give a promise resolved with the value given
+pub fn resolve(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    Ok(promise_resolve(global, arg(&args, 0)))
+}
+/// Give a promise rejected with the reason given
+pub fn reject(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    let promise = new_promise(global);
+    reject_promise(global, promise, arg(&args, 0));
+    Ok(promise)
+}
+/// Call `then` on the promise an item of an iterable resolves to, with the handlers given
+fn subscribe(global:Value, item:Value, on_fulfilled:Value, on_rejected:Value) -> ResultValue {
+    let promise = promise_resolve(global, item);
+    let then = try!(promise.get("then"));
+    call_value(then, vec!(on_fulfilled, on_rejected), global, global, promise)
+}
+/// Give the promise made by a combinator such as `Promise.all`, rejecting it with the
+/// error thrown if getting or subscribing to the items of the iterable given failed
+fn finish_combined(global:Value, promise:Value, reject:Value, result:Result<(), Value>) -> ResultValue {
+    match result {
+        Ok(()) => Ok(promise),
+        Err(err) => {
+            try!(call_value(reject, vec!(err), global, global, Value::undefined()));
+            Ok(promise)
+        }
+    }
+}
+/// Subscribe to the items of an iterable with element functions that store what each settles to
+/// in an array, which the combined promise is resolved with once every item has settled, where
+/// an item being rejected rejects the combined promise if there is no element function for it
+fn subscribe_all(global:Value, iterable:Value, on_fulfilled:FunctionData, on_rejected:Option<FunctionData>,
+                 resolve:Value, reject:Value) -> Result<(), Value> {
+    let items = try!(iterator::iterate(iterable, global));
+    let values = new_array(global, Vec::from_elem(items.len(), Value::undefined()));
+    // one more than the number of items, so the array isn't given before every item is subscribed to
+    let remaining = Value::new_obj(None);
+    remaining.set_field("count", to_value(items.len() as i32 + 1));
+    for (i, item) in items.move_iter().enumerate() {
+        let called = Value::new_obj(None);
+        called.set_field("done", to_value(false));
+        let bound = vec!(values, to_value(i as i32), remaining, resolve, called);
+        let fulfilled = native_closure(global, on_fulfilled, bound.clone());
+        let rejected = match on_rejected {
+            Some(repr) => native_closure(global, repr, bound),
+            None => reject
+        };
+        try!(subscribe(global, item, fulfilled, rejected));
+    }
+    count_down(global, remaining, values, resolve)
+}
+/// Count down the items of a combined promise that haven't settled, resolving it with the
+/// values they settled to once none are left
+fn count_down(global:Value, remaining:Value, values:Value, resolve:Value) -> Result<(), Value> {
+    let count = remaining.get_field("count").to_int() - 1;
+    remaining.set_field("count", to_value(count));
+    if count == 0 {
+        try!(call_value(resolve, vec!(values), global, global, Value::undefined()));
+    }
+    Ok(())
+}
+/// Store what an item of a combined promise settled to, unless the element function was already called
+fn store_element(global:Value, args:&Vec<Value>, value:Value) -> ResultValue {
+    let (values, index, remaining, resolve, called) = (args[0], args[1], args[2], args[3], args[4]);
+    if called.get_field("done").is_true() {
+        return Ok(Value::undefined());
+    }
+    called.set_field("done", to_value(true));
+    values.set_field(index.to_int().to_string().as_slice(), value);
+    try!(count_down(global, remaining, values, resolve));
+    Ok(Value::undefined())
+}
+/// Store the value an item of `Promise.all` was fulfilled with
+fn all_element(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    let value = arg(&args, 5);
+    store_element(global, &args, value)
+}
+/// Store the value an item of `Promise.allSettled` was fulfilled with
+fn settled_fulfilled_element(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    let outcome = Value::new_obj(Some(global));
+    outcome.set_field("status", to_value("fulfilled"));
+    outcome.set_field("value", arg(&args, 5));
+    store_element(global, &args, outcome)
+}
+/// Store the reason an item of `Promise.allSettled` was rejected with
+fn settled_rejected_element(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    let outcome = Value::new_obj(Some(global));
+    outcome.set_field("status", to_value("rejected"));
+    outcome.set_field("reason", arg(&args, 5));
+    store_element(global, &args, outcome)
+}
+/// Give a promise fulfilled with an array of the values the items of an iterable are
+/// fulfilled with, or rejected with the reason the first of them to be rejected is
+pub fn all(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    let (promise, resolve, reject) = new_capability(global);
+    let result = subscribe_all(global, arg(&args, 0), all_element, None, resolve, reject);
+    finish_combined(global, promise, reject, result)
+}
+/// Give a promise fulfilled with an array describing how each item of an iterable settled,
+/// once all of them have
+pub fn all_settled(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    let (promise, resolve, reject) = new_capability(global);
+    let result = subscribe_all(global, arg(&args, 0), settled_fulfilled_element, Some(settled_rejected_element), resolve, reject);
+    finish_combined(global, promise, reject, result)
+}
+/// Give a promise that settles the same way as the first item of an iterable to settle
+pub fn race(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    let (promise, resolve, reject) = new_capability(global);
+    let result = iterator::iterate(arg(&args, 0), global).and_then(|items| {
+        for item in items.move_iter() {
+            try!(subscribe(global, item, resolve, reject));
+        }
+        Ok(())
+    });
+    finish_combined(global, promise, reject, result)
+}
+/// Create a new `Promise` object
+pub fn _create(global: Value) -> Value {
+    let prototype = js!(global, {
+        "then": Function::make(then, ["onFulfilled", "onRejected"]),
+        "catch": Function::make(catch_, ["onRejected"]),
+        "finally": Function::make(finally_, ["onFinally"])
+    });
+    let promise = Function::make(make_promise, ["executor"]);
+    promise.set_hidden_field(PROTOTYPE, prototype);
+    js_extend!(promise, {
+        "resolve": Function::make(resolve, ["value"]),
+        "reject": Function::make(reject, ["reason"]),
+        "all": Function::make(all, ["iterable"]),
+        "allSettled": Function::make(all_settled, ["iterable"]),
+        "race": Function::make(race, ["iterable"])
+    });
+    promise
+}
+/// Initialise the global object with the `Promise` object
+pub fn init(global:Value) {
+    js_extend!(global, {
+        "Promise": _create(global)
+    });
+}
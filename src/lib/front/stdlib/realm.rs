@@ -0,0 +1,43 @@
+use front::stdlib::value::Value;
+use front::stdlib::object::GlobalObject;
+use front::stdlib::timer::Timers;
+use front::stdlib::function::DEFAULT_MAX_CALL_DEPTH;
+
+#[deriving(Clone)]
+/// The state the engine keeps for a global object, which isn't visible to scripts
+pub struct Realm {
+    /// The timers that haven't run or been cleared, and the time of the event loop
+    pub timers: Timers,
+    /// How many calls are running inside each other
+    pub call_depth: uint,
+    /// How many calls can run inside each other, past which calls throw
+    pub max_call_depth: uint
+}
+impl Realm {
+    /// Make the state of a new global object
+    pub fn new() -> Realm {
+        Realm {
+            timers: Timers::new(),
+            call_depth: 0,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH
+        }
+    }
+}
+/// Run a function with the state of the global object given, giving it that state first if it
+/// doesn't have it yet
+pub fn with_realm<T>(global:Value, f:|&mut Realm| -> T) -> T {
+    let result = global.with_object_data(|data| {
+        match data.kind {
+            GlobalObject(_) => (),
+            _ => data.kind = GlobalObject(Realm::new())
+        }
+        match data.kind {
+            GlobalObject(ref mut realm) => f(realm),
+            _ => unreachable!()
+        }
+    });
+    match result {
+        Some(result) => result,
+        None => fail!("{} is not a global object", global)
+    }
+}
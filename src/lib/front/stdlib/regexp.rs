@@ -0,0 +1,63 @@
+use front::stdlib::value::{Value, ResultValue, to_value};
+use front::stdlib::function::Function;
+use front::stdlib::object::{PROTOTYPE, INSTANCE_PROTOTYPE};
+use syntax::ast::constant::RegExpFlags;
+
+/// Set up the fields of a regular expression with the pattern and flags given
+fn init_regexp(this:Value, source:String, flags:&RegExpFlags) {
+    this.set_hidden_field("source", to_value(source));
+    this.set_hidden_field("flags", to_value(flags.to_string()));
+    this.set_hidden_field("global", to_value(flags.global));
+    this.set_hidden_field("ignoreCase", to_value(flags.ignore_case));
+    this.set_hidden_field("multiline", to_value(flags.multiline));
+    this.set_hidden_field("dotAll", to_value(flags.dot_all));
+    this.set_hidden_field("unicode", to_value(flags.unicode));
+    this.set_hidden_field("sticky", to_value(flags.sticky));
+    this.set_hidden_field("lastIndex", to_value(0i32));
+}
+/// Make a regular expression object out of the body and flags of a regular expression literal
+pub fn make(global:Value, body:String, flags:&RegExpFlags) -> Value {
+    let regexp = Value::new_obj(Some(global));
+    regexp.set_hidden_field(INSTANCE_PROTOTYPE, global.get_field("RegExp").get_field(PROTOTYPE));
+    init_regexp(regexp, body, flags);
+    regexp
+}
+/// Create a new regular expression
+pub fn make_regexp(args:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
+    let source = if args.len() >= 1 && !args[0].is_undefined() {
+        args[0].to_string()
+    } else {
+        "(?:)".into_string()
+    };
+    let flags = if args.len() >= 2 && !args[1].is_undefined() {
+        args[1].to_string()
+    } else {
+        String::new()
+    };
+    match from_str::<RegExpFlags>(flags.as_slice()) {
+        Some(parsed) => {
+            init_regexp(this, source, &parsed);
+            Ok(Value::undefined())
+        },
+        None => Err(to_value(format!("Invalid regular expression flags '{}'", flags)))
+    }
+}
+/// Get the string representation of the regular expression
+pub fn to_string(_:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
+    Ok(to_value(format!("/{}/{}", this.get_field("source"), this.get_field("flags"))))
+}
+/// Create a new `RegExp` object
+pub fn _create(global: Value) -> Value {
+    let prototype = js!(global, {
+        "toString": Function::make(to_string, [])
+    });
+    let regexp = Function::make(make_regexp, ["pattern", "flags"]);
+    regexp.set_hidden_field(PROTOTYPE, prototype);
+    regexp
+}
+/// Initialise the global object with the `RegExp` object
+pub fn init(global:Value) {
+    js_extend!(global, {
+        "RegExp": _create(global)
+    });
+}
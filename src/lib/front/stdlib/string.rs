@@ -1,16 +1,79 @@
-use front::stdlib::value::{Value, ResultValue, to_value, from_value};
-use front::stdlib::function::Function;
+use front::stdlib::value::{Value, VString, ResultValue, to_value};
+use front::stdlib::function::{Function, NEW_TARGET};
 use front::stdlib::object::{PROTOTYPE, Property};
+use front::stdlib::iterator::{ITERATOR, values};
+use front::stdlib::array::array_index;
+use conversions;
+/// The hidden field of a `String` object holding the primitive string it wraps
+pub static STRING_DATA: &'static str = "__string_data__";
 
-/// Create new string
-pub fn make_string(_:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
-    this.set_field("length", to_value(0i32));
-    Ok(Value::undefined())
+/// Convert a value to a string, or make a `String` object wrapping it if it is constructed
+pub fn make_string(args:Vec<Value>, _:Value, scope:Value, this:Value) -> ResultValue {
+    let constructed = !scope.get_field(NEW_TARGET).is_undefined();
+    let string = if args.len() == 0 {
+        String::new()
+    } else if args[0].is_symbol() && !constructed {
+        // symbols can only be converted to strings explicitly
+        args[0].to_string()
+    } else {
+        try!(conversions::to_string(args[0]))
+    };
+    if !constructed {
+        return Ok(to_value(string));
+    }
+    this.set_hidden_field(STRING_DATA, to_value(string));
+    Ok(this)
+}
+/// Get the string a value holds, which is either a primitive string or a `String` object, throwing if it is neither
+fn this_string(this:Value) -> Result<String, Value> {
+    match *this {
+        VString(ref string) => return Ok(string.clone()),
+        _ => ()
+    }
+    match this.get_own_prop(STRING_DATA) {
+        Some(prop) if prop.value.is_string() => Ok(prop.value.to_string()),
+        _ => Err(to_value(format!("{} is not a string", this)))
+    }
+}
+/// Get a property that a primitive string has itself, which are its characters at their indexes
+/// and its length, where the rest of its properties are inherited from `String.prototype`
+pub fn get_own_field(string:&str, field:&str) -> Option<Value> {
+    match array_index(field) {
+        Some(index) => string.chars().nth(index as uint).map(|ch| to_value(ch)),
+        None if field == "length" => Some(to_value(string.char_len() as i32)),
+        None => None
+    }
 }
 /// Get a string's length
 pub fn get_string_length(_:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
-    let this_str: String = from_value(this).unwrap();
-    Ok(to_value::<i32>(this_str.len() as i32))
+    let this_str = try!(this_string(this));
+    Ok(to_value::<i32>(this_str.as_slice().char_len() as i32))
+}
+/// Get the character at the index given, or an empty string if there isn't one
+pub fn char_at(args:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
+    let this_str = try!(this_string(this));
+    let index = if args.len() >= 1 {try!(conversions::to_number(args[0]))} else {0.0};
+    if index < 0.0 {
+        return Ok(to_value(""));
+    }
+    Ok(match this_str.as_slice().chars().nth(index as uint) {
+        Some(ch) => to_value(ch),
+        None => to_value("")
+    })
+}
+/// Get the string with its letters in upper case
+pub fn to_upper_case(_:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
+    let this_str = try!(this_string(this));
+    Ok(to_value(this_str.as_slice().chars().map(|ch| ch.to_uppercase()).collect::<String>()))
+}
+/// Get the string with its letters in lower case
+pub fn to_lower_case(_:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
+    let this_str = try!(this_string(this));
+    Ok(to_value(this_str.as_slice().chars().map(|ch| ch.to_lowercase()).collect::<String>()))
+}
+/// Get the primitive string a string holds
+pub fn value_of(_:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
+    Ok(to_value(try!(this_string(this))))
 }
 /// Create a new `String` object
 pub fn _create(global: Value) -> Value {
@@ -25,7 +88,15 @@ pub fn _create(global: Value) -> Value {
         set: Value::undefined()
     };
     proto.set_prop("length", prop);
-    string.set_field(PROTOTYPE, proto);
+    js_extend!(proto, {
+        "charAt": Function::make(char_at, ["index"]),
+        "toUpperCase": Function::make(to_upper_case, []),
+        "toLowerCase": Function::make(to_lower_case, []),
+        "toString": Function::make(value_of, []),
+        "valueOf": Function::make(value_of, [])
+    });
+    proto.set_hidden_field(ITERATOR, Function::make(values, []));
+    string.set_hidden_field(PROTOTYPE, proto);
     string
 }
 /// Initialise the `String` object on the global object
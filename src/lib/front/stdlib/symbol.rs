@@ -0,0 +1,138 @@
+use front::stdlib::value::{Value, VSymbol, ResultValue, ToValue, to_value};
+use front::stdlib::function::{Function, NEW_TARGET};
+use front::stdlib::object::{PROTOTYPE, Property};
+use front::stdlib::iterator::ITERATOR;
+use conversions;
+use std::sync::atomics::{AtomicUint, INIT_ATOMIC_UINT, SeqCst};
+/// The well-known symbol `Symbol.toPrimitive`, which keys the method that converts an object to a primitive
+pub static TO_PRIMITIVE: SymbolId = SymbolId(1);
+/// The well-known symbol `Symbol.toStringTag`, which keys the kind of object given by `Object.prototype.toString`
+pub static TO_STRING_TAG: SymbolId = SymbolId(2);
+/// How many IDs are kept for the well-known symbols, which the IDs of other symbols come after
+static WELL_KNOWN_IDS: uint = 16;
+/// The number of symbols made so far, which is shared by every global object so symbols from
+/// different ones are never the same
+static SYMBOLS_MADE: AtomicUint = INIT_ATOMIC_UINT;
+/// The hidden field of the global object holding the symbols made by `Symbol.for`, by their keys
+static REGISTRY: &'static str = "__symbols__";
+
+#[deriving(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Show)]
+/// The ID of a symbol, which no other symbol has, and which keys the properties keyed by it
+pub struct SymbolId(pub uint);
+
+#[deriving(Clone, PartialEq)]
+/// A unique value that can be used as the key of a property
+pub struct Symbol {
+    /// The ID of the symbol
+    pub id: SymbolId,
+    /// The description the symbol was made with
+    pub description: Option<String>
+}
+impl ToValue for Symbol {
+    fn to_value(&self) -> Value {
+        Value::new(VSymbol(self.clone()))
+    }
+}
+/// Make a new symbol, whose ID is unique to it
+pub fn new_symbol(description:Option<String>) -> Value {
+    to_value(Symbol {
+        id: SymbolId(WELL_KNOWN_IDS + SYMBOLS_MADE.fetch_add(1, SeqCst)),
+        description: description
+    })
+}
+/// Make a well-known symbol, which is shared by every global object
+fn well_known(id:SymbolId, name:&'static str) -> Value {
+    to_value(Symbol {
+        id: id,
+        description: Some(format!("Symbol.{}", name))
+    })
+}
+/// Get the symbol in a value, throwing if it isn't a symbol
+fn this_symbol(value:Value) -> Result<Symbol, Value> {
+    match *value {
+        VSymbol(ref sym) => Ok(sym.clone()),
+        _ => Err(to_value(format!("{} is not a symbol", value)))
+    }
+}
+/// Make a new symbol with the description given, which throws if it is constructed
+pub fn make_symbol(args:Vec<Value>, _:Value, scope:Value, _:Value) -> ResultValue {
+    if !scope.get_field(NEW_TARGET).is_undefined() {
+        return Err(to_value("Symbol is not a constructor"));
+    }
+    let description = if args.len() >= 1 && !args[0].is_undefined() {
+        Some(try!(conversions::to_string(args[0])))
+    } else {
+        None
+    };
+    Ok(new_symbol(description))
+}
+/// Get the symbol registered with the key given, registering a new one if there isn't one
+pub fn for_(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    let key = try!(conversions::to_string(if args.len() >= 1 {args[0]} else {Value::undefined()}));
+    let mut registry = global.get_field(REGISTRY);
+    if !registry.is_object() {
+        registry = Value::new_obj(None);
+        global.set_hidden_field(REGISTRY, registry);
+    }
+    match registry.get_own_prop(key.as_slice()) {
+        Some(prop) => Ok(prop.value),
+        None => {
+            let sym = new_symbol(Some(key.clone()));
+            registry.set_field(key.as_slice(), sym);
+            Ok(sym)
+        }
+    }
+}
+/// Get the key a symbol was registered with by `Symbol.for`, or `undefined` if it wasn't
+pub fn key_for(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    let value = if args.len() >= 1 {args[0]} else {Value::undefined()};
+    let sym = try!(this_symbol(value));
+    let registry = global.get_field(REGISTRY);
+    Ok(match sym.description {
+        Some(ref key) if registry.get_field(key.as_slice()).strict_equals(&value) => to_value(key.clone()),
+        _ => Value::undefined()
+    })
+}
+/// Get the string representation of the symbol, which is `Symbol(` followed by its description and `)`
+pub fn to_string(_:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
+    try!(this_symbol(this));
+    Ok(to_value(this.to_string()))
+}
+/// Get the description the symbol was made with
+pub fn get_description(_:Vec<Value>, _:Value, _:Value, this:Value) -> ResultValue {
+    Ok(match try!(this_symbol(this)).description {
+        Some(desc) => to_value(desc),
+        None => Value::undefined()
+    })
+}
+/// Create a new `Symbol` object
+pub fn _create(global: Value) -> Value {
+    let symbol = Function::make(make_symbol, ["description"]);
+    let prototype = js!(global, {
+        "toString": Function::make(to_string, [])
+    });
+    prototype.set_prop("description", Property {
+        configurable: true,
+        enumerable: false,
+        writable: false,
+        value: Value::undefined(),
+        get: Function::make(get_description, []),
+        set: Value::undefined()
+    });
+    prototype.set_hidden_field(TO_STRING_TAG, to_value("Symbol"));
+    symbol.set_hidden_field(PROTOTYPE, prototype);
+    js_extend!(symbol, {
+        "for": Function::make(for_, ["key"]),
+        "keyFor": Function::make(key_for, ["symbol"]),
+        "iterator": well_known(ITERATOR, "iterator"),
+        "toPrimitive": well_known(TO_PRIMITIVE, "toPrimitive"),
+        "toStringTag": well_known(TO_STRING_TAG, "toStringTag")
+    });
+    symbol
+}
+/// Initialise the global object with the `Symbol` object
+pub fn init(global:Value) {
+    js_extend!(global, {
+        "Symbol": _create(global)
+    });
+}
@@ -0,0 +1,171 @@
+use front::stdlib::value::{Value, ResultValue, to_value};
+use front::stdlib::function::{Function, call_value};
+use front::stdlib::promise;
+use front::stdlib::realm::with_realm;
+use collections::treemap::TreeMap;
+
+#[deriving(Clone)]
+/// A function waiting to be called by the event loop
+struct Timer {
+    /// The function to call
+    callback: Value,
+    /// The arguments to call it with
+    args: Vec<Value>,
+    /// How many milliseconds it waits between each call
+    delay: f64,
+    /// The time it is next due at
+    due: f64,
+    /// If it is called again each time the delay passes
+    repeat: bool
+}
+
+#[deriving(Clone)]
+/// The timers of a global object, along with the time of its event loop
+pub struct Timers {
+    /// The timers that haven't run or been cleared, by their IDs
+    timers: TreeMap<uint, Timer>,
+    /// The ID the next timer is given
+    next_id: uint,
+    /// The time of the event loop, in milliseconds
+    now: f64
+}
+impl Timers {
+    /// Make an empty set of timers, starting at time zero
+    pub fn new() -> Timers {
+        Timers {
+            timers: TreeMap::new(),
+            next_id: 1,
+            now: 0.0
+        }
+    }
+    /// Find the ID of the timer that is due first, where timers due at the same time run in the
+    /// order they were set, if there is one due at or before the time given
+    fn next_due(&self, until:Option<f64>) -> Option<uint> {
+        let mut next : Option<(f64, uint)> = None;
+        // the timers are in the order of their IDs, so only an earlier time replaces the one found
+        for (id, timer) in self.timers.iter() {
+            if until.map_or(false, |until| timer.due > until) {
+                continue;
+            }
+            if next.map_or(true, |(due, _)| timer.due < due) {
+                next = Some((timer.due, *id));
+            }
+        }
+        next.map(|(_, id)| id)
+    }
+    /// Take the timer with the ID given to be run, moving the time forward to when it is due,
+    /// and keeping it to run again if it repeats
+    fn take(&mut self, id:uint) -> Timer {
+        let timer = self.timers.pop(&id).unwrap();
+        if timer.due > self.now {
+            self.now = timer.due;
+        }
+        if timer.repeat {
+            // an interval waits at least a millisecond, so it can't stop time from moving forward
+            let delay = if timer.delay > 1.0 {timer.delay} else {1.0};
+            self.timers.insert(id, Timer {due: timer.due + delay, .. timer.clone()});
+        }
+        timer
+    }
+}
+
+/// Get the time of the event loop in milliseconds, which only moves forward when timers are
+/// run, so code using timers runs the same way every time
+pub fn now(global:Value) -> f64 {
+    with_realm(global, |realm| realm.timers.now)
+}
+/// Add a timer that calls the function given with the arguments after it once the delay
+/// given has passed, and again each time it passes after that if it repeats, giving its ID
+fn add_timer(args:Vec<Value>, global:Value, repeat:bool) -> ResultValue {
+    let callback = if args.len() >= 1 {args[0]} else {Value::undefined()};
+    if !callback.is_function() {
+        return Err(to_value(format!("{} is not a function", callback)));
+    }
+    let delay = if args.len() >= 2 {args[1].to_num()} else {0.0};
+    // a delay that isn't a positive number runs the timer as soon as possible
+    let delay = if delay > 0.0 {delay} else {0.0};
+    let id = with_realm(global, |realm| {
+        let timers = &mut realm.timers;
+        let id = timers.next_id;
+        timers.next_id += 1;
+        timers.timers.insert(id, Timer {
+            callback: callback,
+            args: args.iter().skip(2).map(|arg| *arg).collect(),
+            delay: delay,
+            due: timers.now + delay,
+            repeat: repeat
+        });
+        id
+    });
+    Ok(to_value(id as i32))
+}
+/// Call a function once the delay given in milliseconds has passed, giving the ID of the timer
+pub fn set_timeout(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    add_timer(args, global, false)
+}
+/// Call a function each time the delay given in milliseconds passes, giving the ID of the timer
+pub fn set_interval(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    add_timer(args, global, true)
+}
+/// Stop the timer with the ID given from running again, which does nothing if there isn't one
+pub fn clear_timer(args:Vec<Value>, global:Value, _:Value, _:Value) -> ResultValue {
+    if args.len() >= 1 && !args[0].is_null_or_undefined() {
+        let id = args[0].to_num();
+        if id >= 1.0 {
+            with_realm(global, |realm| {realm.timers.timers.pop(&(id as uint));});
+        }
+    }
+    Ok(Value::undefined())
+}
+/// Find the ID of the timer that is due first, if there is one due at or before the time given
+fn next_due(global:Value, until:Option<f64>) -> Option<uint> {
+    with_realm(global, |realm| realm.timers.next_due(until))
+}
+/// Run the timer with the ID given, moving the time forward to when it is due, and setting
+/// it to run again if it repeats, then run the jobs its callback queued
+fn run_timer(global:Value, id:uint) -> Result<(), Value> {
+    // the timers aren't borrowed while the callback runs, so it can add and clear them
+    let timer = with_realm(global, |realm| realm.timers.take(id));
+    try!(call_value(timer.callback, timer.args, global, global, Value::undefined()));
+    try!(promise::run_until_idle(global));
+    Ok(())
+}
+/// Move the time forward by the number of milliseconds given, running the timers that are
+/// due by then in order, along with the jobs each of them queue, giving how many were run
+pub fn advance(global:Value, millis:f64) -> Result<uint, Value> {
+    try!(promise::run_until_idle(global));
+    let until = now(global) + millis;
+    let mut ran = 0;
+    loop {
+        match next_due(global, Some(until)) {
+            Some(id) => try!(run_timer(global, id)),
+            None => break
+        }
+        ran += 1;
+    }
+    with_realm(global, |realm| realm.timers.now = until);
+    Ok(ran)
+}
+/// Run the queued jobs, then each timer in the order they are due, moving the time forward to
+/// when each is due and running the jobs it queues before the next, until no timers are left,
+/// giving how many timers were run; an interval that is never cleared keeps this running forever
+pub fn run_event_loop(global:Value) -> Result<uint, Value> {
+    try!(promise::run_until_idle(global));
+    let mut ran = 0;
+    loop {
+        match next_due(global, None) {
+            Some(id) => try!(run_timer(global, id)),
+            None => return Ok(ran)
+        }
+        ran += 1;
+    }
+}
+/// Initialise the global object with the timer functions
+pub fn init(global:Value) {
+    js_extend!(global, {
+        "setTimeout": Function::make(set_timeout, ["callback", "delay"]),
+        "setInterval": Function::make(set_interval, ["callback", "delay"]),
+        "clearTimeout": Function::make(clear_timer, ["id"]),
+        "clearInterval": Function::make(clear_timer, ["id"])
+    });
+}
@@ -1,16 +1,23 @@
-use front::stdlib::object::{PROTOTYPE, INSTANCE_PROTOTYPE, ObjectData, Property};
+use front::stdlib::object::{PROTOTYPE, INSTANCE_PROTOTYPE, ObjectData, ObjectKind, ArrayObject, GlobalObject, Property,
+                            PropertyKey, StringKey, ToPropertyKey};
 use front::stdlib::function::Function;
+use front::stdlib::realm::Realm;
+use front::stdlib::symbol::Symbol;
 use collections::TreeMap;
 use serialize::json::{ToJson, Json, Number, String, Boolean, List, Object, Null};
 use std::fmt;
 use std::ops::{Add, Sub, Mul, Div, Rem, BitAnd, BitOr, BitXor};
 use std::f64;
+use std::num::{pow, Zero, ToPrimitive};
 use std::gc::{Gc, GC};
 use std::c_str::CString;
 use std::cell::RefCell;
 use std::iter::FromIterator;
 use std::cmp::PartialOrd;
+use num::bigint::BigInt;
 use front::stdlib::*;
+use front::run::eval;
+use conversions;
 #[must_use]
 /// The result of a Javascript expression is represented like this so it can succeed (`Ok`) or fail (`Err`)
 pub type ResultValue = Result<Value, Value>;
@@ -41,7 +48,11 @@ pub enum ValueData {
     VNumber(f64),
     /// `Number` - A 32-bit integer, such as `42`
     VInteger(i32),
-    /// `Object` - An object, such as `Math`, represented by a binary tree of string keys to Javascript values
+    /// `BigInt` - An arbitrary-precision integer, such as `9007199254740993n`
+    VBigInt(BigInt),
+    /// `Symbol` - A unique value that can be the key of a property, such as `Symbol.iterator`
+    VSymbol(Symbol),
+    /// `Object` - An object, such as `Math`, represented by a binary tree of string and symbol keys to Javascript values
     VObject(RefCell<ObjectData>),
     /// `Function` - A runnable block of code, such as `Math.sqrt`, which can take some variables and return a useful value or act upon an object
     VFunction(RefCell<Function>)
@@ -57,28 +68,46 @@ impl Value {
     /// Create a new global object
     pub fn new_global() -> Value {
         let global = Value::new_obj(None);
+        global.set_kind(GlobalObject(Realm::new()));
         array::init(global);
         boolean::init(global);
+        collection::init(global);
         console::init(global);
         error::init(global);
+        eval::init(global);
         function::init(global);
+        generator::init(global);
         json::init(global);
         math::init(global);
         number::init(global);
         object::init(global);
+        promise::init(global);
+        regexp::init(global);
         string::init(global);
+        symbol::init(global);
+        timer::init(global);
         uri::init(global);
         global
     }
     /// Returns a new empty object
     pub fn new_obj(global: Option<Value>) -> Value {
-        let mut obj : ObjectData = TreeMap::new();
+        let mut obj = ObjectData::new();
         if global.is_some() {
             let obj_proto = global.unwrap().get_field("Object").get_field(PROTOTYPE);
-            obj.insert(INSTANCE_PROTOTYPE.into_string(), Property::new(obj_proto));
+            obj.properties.insert(INSTANCE_PROTOTYPE.to_key(), Property::hidden(obj_proto));
         }
+        obj.global = global;
         Value::new(VObject(RefCell::new(obj)))
     }
+    /// Get the global object the object was made for, which is the object itself for a global
+    /// object, or `None` if it wasn't made for one or isn't an object
+    pub fn global_of(&self) -> Option<Value> {
+        let this = *self;
+        self.with_object_data(|data| match data.kind {
+            GlobalObject(_) => Some(this),
+            _ => data.global
+        }).unwrap_or(None)
+    }
     /// Returns true if the value is an object
     pub fn is_object(&self) -> bool {
         match **self {
@@ -107,6 +136,13 @@ impl Value {
             _ => false
         }
     }
+    /// Returns true if the value is a function
+    pub fn is_function(&self) -> bool {
+        match **self {
+            VFunction(_) => true,
+            _ => false
+        }
+    }
     /// Returns true if the value is a 64-bit floating-point number
     pub fn is_double(&self) -> bool {
         match **self {
@@ -121,93 +157,325 @@ impl Value {
             _ => false
         }
     }
-    /// Returns true if the value is true
-    pub fn is_true(&self) -> bool {
+    /// Returns true if the value is a symbol
+    pub fn is_symbol(&self) -> bool {
         match **self {
-            VObject(_) => true,
-            VString(ref s) if s.as_slice() == "1" => true,
-            VNumber(n) if n >= 1.0 && n % 1.0 == 0.0 => true,
-            VInteger(n) if n > 1 => true,
-            VBoolean(v) => v,
+            VSymbol(_) => true,
             _ => false
         }
     }
-    /// Converts the value into a 64-bit floating point number
-    pub fn to_num(&self) -> f64 {
+    /// Returns the arbitrary-precision integer in the value if it is a BigInt
+    pub fn as_bigint(&self) -> Option<BigInt> {
         match **self {
-            VObject(_) | VUndefined | VFunction(_) => f64::NAN,
-            VString(ref str) => match from_str(str.as_slice()) {
-                Some(num) => num,
-                None => f64::NAN
-            },
-            VNumber(num) => num,
-            VBoolean(true) => 1.0,
-            VBoolean(false) | VNull => 0.0,
-            VInteger(num) => num as f64
+            VBigInt(ref num) => Some(num.clone()),
+            _ => None
         }
     }
+    /// Returns true if the value is true, like `conversions::to_boolean`
+    pub fn is_true(&self) -> bool {
+        conversions::to_boolean(self)
+    }
+    /// Converts the value into a 64-bit floating point number, like `conversions::to_number`,
+    /// which is `NaN` if converting an object to a primitive throws
+    pub fn to_num(&self) -> f64 {
+        conversions::to_number(*self).unwrap_or(f64::NAN)
+    }
     /// Converts the value into a 32-bit integer
     pub fn to_int(&self) -> i32 {
         match **self {
-            VObject(_) | VUndefined | VNull | VBoolean(false) | VFunction(_) => 0,
-            VString(ref str) => match from_str(str.as_slice()) {
-                Some(num) => num,
-                None => 0
+            VInteger(num) => num,
+            _ => conversions::to_int32(self.to_num())
+        }
+    }
+    /// Converts the value into an unsigned 32-bit integer
+    pub fn to_uint(&self) -> u32 {
+        self.to_int() as u32
+    }
+    /// Invert the bits of this value as a 32-bit integer
+    pub fn bit_not(&self) -> Value {
+        to_value(!self.to_int())
+    }
+    /// Raise this value to the power of another value
+    pub fn pow(&self, other:&Value) -> ResultValue {
+        match (self.as_bigint(), other.as_bigint()) {
+            (Some(base), Some(exponent)) => return match exponent.to_uint() {
+                Some(exponent) => Ok(to_value(pow(base, exponent))),
+                None => Err(to_value("Exponent must be non-negative"))
             },
-            VNumber(num) => num as i32,
-            VBoolean(true) => 1,
-            VInteger(num) => num
+            (None, None) => (),
+            _ => return Err(to_value("Cannot mix BigInt and other types, use explicit conversions"))
         }
+        let base = self.to_num();
+        let exponent = other.to_num();
+        Ok(to_value(if exponent.is_nan() || (base.abs() == 1.0 && exponent.is_infinite()) {
+            f64::NAN
+        } else {
+            base.powf(exponent)
+        }))
     }
-    /// Resolve the property in the object
-    pub fn get_prop<'a>(&self, field:&'a str) -> Option<Property> {
-        let obj : ObjectData = match **self {
-            VObject(ref obj) => obj.borrow().clone(),
-            VFunction(ref func) =>func.borrow().object.clone(),
-            _ => return None
-        };
-        match obj.find(&field.into_string()) {
-            Some(val) => Some(*val),
-            None => match obj.find(&PROTOTYPE.into_string()) {
-                Some(prop) => 
-                    prop.value.get_prop(field),
-                None => None
+    /// Shift the bits of this value rightwards, filling in zeroes from the left
+    pub fn unsigned_shr(&self, other:&Value) -> Value {
+        to_value((self.to_uint() >> (other.to_uint() & 0x1f) as uint) as f64)
+    }
+    /// Get the property that the object has itself, without looking at the objects it inherits from
+    pub fn get_own_prop<K:ToPropertyKey>(&self, field:K) -> Option<Property> {
+        match **self {
+            VObject(ref obj) => obj.borrow().properties.find(&field.to_key()).map(|prop| *prop),
+            VFunction(ref func) => func.borrow().object.properties.find(&field.to_key()).map(|prop| *prop),
+            _ => None
+        }
+    }
+    /// Get the keys of the properties the object has itself, including those that aren't enumerable
+    pub fn own_keys(&self) -> Vec<PropertyKey> {
+        match **self {
+            VObject(ref obj) => obj.borrow().properties.keys().map(|key| key.clone()).collect(),
+            VFunction(ref func) => func.borrow().object.properties.keys().map(|key| key.clone()).collect(),
+            _ => Vec::new()
+        }
+    }
+    /// Returns true if the value is an array, whose length follows the indexes set in it
+    pub fn is_array(&self) -> bool {
+        match **self {
+            VObject(ref obj) => match obj.borrow().kind {
+                ArrayObject => true,
+                _ => false
+            },
+            _ => false
+        }
+    }
+    /// Make the object the kind of object given, which the engine treats differently to ordinary objects
+    pub fn set_kind(&self, kind:ObjectKind) {
+        match **self {
+            VObject(ref obj) => obj.borrow_mut().kind = kind,
+            VFunction(ref func) => func.borrow_mut().object.kind = kind,
+            _ => ()
+        }
+    }
+    /// Run a function with the data of the object, giving `None` without running it if the value isn't an object
+    pub fn with_object_data<T>(&self, f:|&mut ObjectData| -> T) -> Option<T> {
+        match **self {
+            VObject(ref obj) => Some(f(&mut *obj.borrow_mut())),
+            VFunction(ref func) => Some(f(&mut func.borrow_mut().object)),
+            _ => None
+        }
+    }
+    /// Resolve the property in the object, or in the objects it inherits from by following
+    /// their `__proto__` links until one has the property or the chain ends
+    pub fn get_prop<K:ToPropertyKey>(&self, field:K) -> Option<Property> {
+        let field = field.to_key();
+        let mut obj = *self;
+        loop {
+            match obj.get_own_prop(field.clone()) {
+                Some(prop) => return Some(prop),
+                None => ()
             }
+            obj = match obj.get_own_prop(INSTANCE_PROTOTYPE) {
+                Some(prop) if prop.value.is_object() || prop.value.is_function() => prop.value,
+                _ => return None
+            };
         }
     }
-    /// Resolve the property in the object and get its value, or undefined if this is not an object or the field doesn't exist
-    pub fn get_field<'a>(&self, field:&'a str) -> Value {
+    /// Resolve the property in the object and get its value, or undefined if this is not an object or the field doesn't exist,
+    /// running its getter with this value as `this` if it has one, and throwing if the getter throws
+    pub fn get<K:ToPropertyKey>(&self, field:K) -> ResultValue {
+        self.get_with(field, None)
+    }
+    /// Get the field of the object like `get`, running its getter with the global object given if there is one
+    fn get_with<K:ToPropertyKey>(&self, field:K, global:Option<Value>) -> ResultValue {
         match self.get_prop(field) {
-            Some(prop) => prop.value,
-            None => Value::new(VUndefined)
+            Some(ref prop) if prop.get.is_function() || prop.set.is_function() =>
+                self.call_accessor(prop.get, Vec::new(), global),
+            Some(prop) => Ok(prop.value),
+            None => Ok(Value::new(VUndefined))
         }
     }
-    /// Set the field in the value
-    pub fn set_field<'a>(&self, field:&'a str, val:Value) -> Value {
+    /// Get the field of the value like `get`, where primitives act like the objects that wrap them
+    /// without being wrapped, so strings have their own characters and length, and inherit from
+    /// the prototype of their constructor on the global object, whose getters get the primitive as `this`
+    pub fn get_in<K:ToPropertyKey>(&self, field:K, global:Value) -> ResultValue {
+        let field = field.to_key();
+        let constructor = match (&**self, &field) {
+            (&VString(ref string), &StringKey(ref name)) => match string::get_own_field(string.as_slice(), name.as_slice()) {
+                Some(value) => return Ok(value),
+                None => "String"
+            },
+            (&VString(_), _) => "String",
+            (&VBoolean(_), _) => "Boolean",
+            (&VNumber(_), _) | (&VInteger(_), _) => "Number",
+            (&VBigInt(_), _) => "BigInt",
+            (&VSymbol(_), _) => "Symbol",
+            _ => return self.get_with(field, Some(global))
+        };
+        match global.get_field(constructor).get_field(PROTOTYPE).get_prop(field) {
+            Some(ref prop) if prop.get.is_function() || prop.set.is_function() =>
+                self.call_accessor(prop.get, Vec::new(), Some(global)),
+            Some(prop) => Ok(prop.value),
+            None => Ok(Value::undefined())
+        }
+    }
+    /// Returns true if properties can be added to the object, which values that aren't objects can't have
+    pub fn is_extensible(&self) -> bool {
+        match **self {
+            VObject(ref obj) => obj.borrow().extensible,
+            VFunction(ref func) => func.borrow().object.extensible,
+            _ => false
+        }
+    }
+    /// Stop properties from being added to the object, as `Object.preventExtensions` does
+    pub fn prevent_extensions(&self) {
+        match **self {
+            VObject(ref obj) => obj.borrow_mut().extensible = false,
+            VFunction(ref func) => func.borrow_mut().object.extensible = false,
+            _ => ()
+        }
+    }
+    /// Set the field in the value, running the setter of the property with this value as `this`
+    /// instead if it has a getter or setter, and throwing if the setter throws, where setting
+    /// the indexes and length of an array updates the other like `array::before_set`; a property
+    /// that can't be assigned to, or a new one in an object that can't be extended, is left as
+    /// it is, throwing a `TypeError` in strict code
+    pub fn set<K:ToPropertyKey>(&self, field:K, val:Value, strict:bool) -> ResultValue {
+        let field = field.to_key();
+        let refused = match self.get_prop(field.clone()) {
+            Some(ref prop) if prop.get.is_function() || prop.set.is_function() => {
+                if !prop.set.is_function() {
+                    format!("Cannot set property {} which has only a getter", field)
+                } else {
+                    try!(self.call_accessor(prop.set, vec!(val), None));
+                    return Ok(val);
+                }
+            },
+            Some(ref prop) if !prop.writable =>
+                format!("Cannot assign to read only property '{}'", field),
+            _ if !self.is_extensible() && self.get_own_prop(field.clone()).is_none() =>
+                format!("Cannot add property {}, object is not extensible", field),
+            _ => {
+                let val = if self.is_array() {try!(array::before_set(*self, &field, val))} else {val};
+                // an existing property keeps its attributes, while a new one is enumerable
+                let prop = match self.get_own_prop(field.clone()) {
+                    Some(prop) => Property {value: val, .. prop},
+                    None => Property::data(val)
+                };
+                self.set_prop(field, prop);
+                return Ok(val);
+            }
+        };
+        match self.global_of() {
+            _ if !strict => Ok(val),
+            Some(global) => Err(error::new_error(global, "TypeError", refused.as_slice())),
+            None => Err(to_value(refused))
+        }
+    }
+    /// Get the value of the field like `get`, giving `undefined` if its getter throws
+    pub fn get_field<K:ToPropertyKey>(&self, field:K) -> Value {
+        self.get(field).unwrap_or(Value::undefined())
+    }
+    /// Set the field in the value like `set` in sloppy code, ignoring it if its setter throws
+    pub fn set_field<K:ToPropertyKey>(&self, field:K, val:Value) -> Value {
+        let _ = self.set(field, val, false);
+        val
+    }
+    /// Set a field that isn't enumerated in the value, as built-in properties and the fields
+    /// the engine keeps in objects are, without running setters or checking if it is locked
+    pub fn set_hidden_field<K:ToPropertyKey>(&self, field:K, val:Value) -> Value {
+        self.set_prop(field, Property::hidden(val));
+        val
+    }
+    /// Remove the field from the value, returning true unless it is a
+    /// property of the value that can't be configured
+    pub fn remove_field<K:ToPropertyKey>(&self, field:K) -> bool {
+        let field = field.to_key();
+        match self.get_own_prop(field.clone()) {
+            Some(ref prop) if !prop.configurable => return false,
+            _ => ()
+        }
         match **self {
             VObject(ref obj) => {
-                obj.borrow_mut().insert(field.into_string(), Property::new(val));
+                obj.borrow_mut().properties.remove(&field);
             },
             VFunction(ref func) => {
-                func.borrow_mut().object.insert(field.into_string(), Property::new(val));
+                func.borrow_mut().object.properties.remove(&field);
             },
             _ => ()
         }
-        val
+        true
+    }
+    /// Run the getter or setter of a property of this value with this value as `this`, giving
+    /// `undefined` if it isn't a function, with the global object given, or else the one that
+    /// this value or the accessor was made for
+    fn call_accessor(&self, accessor:Value, args:Vec<Value>, global:Option<Value>) -> ResultValue {
+        if !accessor.is_function() {
+            return Ok(Value::undefined());
+        }
+        match global.or(self.global_of()).or(accessor.global_of()) {
+            Some(global) => function::call_value(accessor, args, global, global, *self),
+            None => Err(to_value(format!("Cannot find the global object to run an accessor of {} in", self)))
+        }
     }
     /// Set the property in the value
-    pub fn set_prop<'a>(&self, field:&'a str, prop:Property) -> Property {
+    pub fn set_prop<K:ToPropertyKey>(&self, field:K, prop:Property) -> Property {
         match **self {
             VObject(ref obj) => {
-                obj.borrow_mut().insert(field.into_string(), prop);
+                obj.borrow_mut().properties.insert(field.to_key(), prop);
             },
             VFunction(ref func) => {
-                func.borrow_mut().object.insert(field.into_string(), prop);
+                func.borrow_mut().object.properties.insert(field.to_key(), prop);
             },
             _ => ()
         }
         prop
     }
+    /// Get the names of the enumerable properties of the object, followed by
+    /// those it inherits that aren't shadowed, as enumerated by a for-in loop,
+    /// which skips properties keyed by symbols
+    pub fn enumerable_keys(&self) -> Vec<String> {
+        let mut keys = Vec::new();
+        let mut shadowed = Vec::new();
+        let mut obj = *self;
+        loop {
+            let data = match *obj {
+                VObject(ref data) => data.borrow().properties.clone(),
+                VFunction(ref func) => func.borrow().object.properties.clone(),
+                _ => break
+            };
+            for (key, prop) in data.iter() {
+                let name = match *key {
+                    StringKey(ref name) if name.as_slice() != INSTANCE_PROTOTYPE && !shadowed.contains(name) => name,
+                    _ => continue
+                };
+                if prop.enumerable {
+                    keys.push(name.clone());
+                }
+                shadowed.push(name.clone());
+            }
+            obj = match data.find(&INSTANCE_PROTOTYPE.to_key()) {
+                Some(prop) => prop.value,
+                None => break
+            };
+        }
+        keys
+    }
+    /// Get an iterator over the items of the value if it is iterable, which
+    /// is the case for strings and array-like objects with a length
+    pub fn iter(&self) -> Option<ValueIterator> {
+        match **self {
+            VString(_) => (),
+            VObject(_) if self.get_prop("length").is_some() => (),
+            _ => return None
+        }
+        Some(ValueIterator {
+            iterable: *self,
+            index: 0
+        })
+    }
+    /// Get the items of the value as they are spread into the arguments of a call
+    /// or the items of an array, throwing if it isn't iterable
+    pub fn spread(&self) -> Result<Vec<Value>, Value> {
+        match self.iter() {
+            Some(items) => Ok(items.collect()),
+            None => Err(to_value(format!("{} is not iterable", self)))
+        }
+    }
     /// Convert from a JSON value to a JS value
     pub fn from_json(json:Json) -> ValueData {
         match json {
@@ -218,29 +486,32 @@ impl Value {
                 let mut i = 0u;
                 let mut data : ObjectData = FromIterator::from_iter(vs.iter().map(|json| {
                     i += 1u;
-                    ((i - 1).to_string(), Property::new(to_value(json.clone())))
+                    ((i - 1).to_string(), Property::data(to_value(json.clone())))
                 }));
-                data.insert("length".into_string(), Property::new(to_value(vs.len() as i32)));
+                data.properties.insert("length".to_key(), Property::hidden(to_value(vs.len() as i32)));
+                data.kind = ArrayObject;
                 VObject(RefCell::new(data))
             },
             Object(obj) => {
                 let data : ObjectData = FromIterator::from_iter(obj.iter().map(|(key, json)| {
-                    (key.clone(), Property::new(to_value(json.clone())))
+                    (key.clone(), Property::data(to_value(json.clone())))
                 }));
                 VObject(RefCell::new(data))
             },
             Null => VNull
         }
     }
-    /// Get the type of the value
+    /// Get the type of the value, as given by `typeof`
     pub fn get_type(&self) -> &'static str {
         match **self {
             VNumber(_) | VInteger(_) => "number",
+            VBigInt(_) => "bigint",
+            VSymbol(_) => "symbol",
             VString(_) => "string",
             VBoolean(_) => "boolean",
-            VNull => "null",
             VUndefined => "undefined",
-            _ => "object"
+            VFunction(_) => "function",
+            VNull | VObject(_) => "object"
         }
     }
     /// Get the value for undefined
@@ -255,17 +526,12 @@ impl fmt::Show for Value {
             VUndefined => write!(f, "undefined"),
             VBoolean(v) => write!(f, "{}", v),
             VString(ref v) => write!(f, "{}", v),
-            VNumber(v) => write!(f, "{}", match v {
-                _ if v.is_nan() => "NaN".into_string(),
-                f64::INFINITY => "Infinity".into_string(),
-                f64::NEG_INFINITY => "-Infinity".into_string(),
-                _ => f64::to_str_digits(v, 15)
-            }),
+            VNumber(v) => write!(f, "{}", conversions::number_to_string(v)),
             VObject(ref v) => {
                 try!(write!(f, "{}", "{"));
-                match v.borrow().iter().last() {
+                match v.borrow().properties.iter().last() {
                     Some((last_key, _)) => {
-                        for (key, val) in v.borrow().iter() {
+                        for (key, val) in v.borrow().properties.iter() {
                             try!(write!(f, "{}: {}", key, val.value));
                             if key != last_key {
                                 try!(write!(f, "{}", ", "));
@@ -277,6 +543,8 @@ impl fmt::Show for Value {
                 write!(f, "{}", "}")
             },
             VInteger(v) => write!(f, "{}", v),
+            VBigInt(ref v) => write!(f, "{}", v),
+            VSymbol(ref sym) => write!(f, "Symbol({})", sym.description.as_ref().map_or("", |desc| desc.as_slice())),
             VFunction(ref v) => {
                 let args = v.borrow().args.connect(", ");
                 write!(f, "function({}){{...}}", args)
@@ -286,14 +554,56 @@ impl fmt::Show for Value {
 }
 impl PartialEq for Value {
     fn eq(&self, other:&Value) -> bool {
-        match ((**self).clone(), (*other.ptr).clone()) {
-            _ if self.is_null_or_undefined() && other.is_null_or_undefined() => true,
-            (VString(_), _) | (_, VString(_)) => self.to_string() == other.to_string(),
-            (VBoolean(a), VBoolean(b)) if a == b => true,
-            (VNumber(a), VNumber(b)) if a == b && !a.is_nan() && !b.is_nan() => true,
-            (VNumber(a), _) if a == other.to_num() => true,
-            (_, VNumber(a)) if a == self.to_num() => true,
-            (VInteger(a), VInteger(b)) if a == b => true,
+        self.loose_equals(other)
+    }
+}
+impl Value {
+    /// Convert an object to a primitive value by calling its `valueOf` method, then its
+    /// `toString` method if that doesn't give a primitive, giving any other value as it is,
+    /// like `conversions::to_primitive`, but giving the object as a string if that throws
+    pub fn to_primitive(&self) -> Value {
+        match conversions::to_primitive(*self, conversions::HintDefault) {
+            Ok(value) => value,
+            Err(_) => to_value(self.to_string())
+        }
+    }
+    /// Check if this value is loosely equal to another value, as `==` does, which converts
+    /// booleans to numbers, objects to primitives and strings to numbers when comparing them
+    /// with a value of another type, and makes `null` and `undefined` only equal each other
+    pub fn loose_equals(&self, other:&Value) -> bool {
+        match (&**self, &**other) {
+            (&VNull, _) | (&VUndefined, _) => other.is_null_or_undefined(),
+            (_, &VNull) | (_, &VUndefined) => false,
+            (&VBoolean(a), _) => to_value(if a {1i32} else {0}).loose_equals(other),
+            (_, &VBoolean(b)) => self.loose_equals(&to_value(if b {1i32} else {0})),
+            (&VObject(_), &VObject(_)) | (&VFunction(_), &VFunction(_))
+                | (&VObject(_), &VFunction(_)) | (&VFunction(_), &VObject(_)) => self.strict_equals(other),
+            (&VObject(_), _) | (&VFunction(_), _) => self.to_primitive().loose_equals(other),
+            (_, &VObject(_)) | (_, &VFunction(_)) => self.loose_equals(&other.to_primitive()),
+            (&VSymbol(ref a), &VSymbol(ref b)) => a == b,
+            (&VSymbol(_), _) | (_, &VSymbol(_)) => false,
+            (&VString(ref a), &VString(ref b)) => a == b,
+            (&VBigInt(ref a), &VBigInt(ref b)) => a == b,
+            (&VBigInt(ref a), &VString(ref b)) | (&VString(ref b), &VBigInt(ref a)) =>
+                from_str::<BigInt>(b.as_slice().trim()).map_or(false, |b| *a == b),
+            // numbers and strings are compared as numbers, including with BigInts
+            _ => self.to_num() == other.to_num()
+        }
+    }
+    /// Check if this value is strictly equal to another value, which unlike `==`
+    /// never coerces the values to the same type
+    pub fn strict_equals(&self, other:&Value) -> bool {
+        match (&**self, &**other) {
+            (&VNull, &VNull) | (&VUndefined, &VUndefined) => true,
+            (&VBoolean(a), &VBoolean(b)) => a == b,
+            (&VString(ref a), &VString(ref b)) => a == b,
+            (&VNumber(a), &VNumber(b)) => a == b,
+            (&VNumber(a), &VInteger(b)) => a == b as f64,
+            (&VInteger(a), &VNumber(b)) => a as f64 == b,
+            (&VInteger(a), &VInteger(b)) => a == b,
+            (&VBigInt(ref a), &VBigInt(ref b)) => a == b,
+            (&VSymbol(ref a), &VSymbol(ref b)) => a == b,
+            (&VObject(_), &VObject(_)) | (&VFunction(_), &VFunction(_)) => self.ptr.ptr_eq(&other.ptr),
             _ => false
         }
     }
@@ -305,9 +615,12 @@ impl ToJson for Value {
             VBoolean(b) => Boolean(b),
             VObject(ref obj) => {
                 let mut nobj = TreeMap::new();
-                for (k, v) in obj.borrow().iter() {
-                    if k.as_slice() != INSTANCE_PROTOTYPE.as_slice() {
-                        nobj.insert(k.clone(), v.value.to_json());
+                for (k, v) in obj.borrow().properties.iter() {
+                    match *k {
+                        StringKey(ref name) if name.as_slice() != INSTANCE_PROTOTYPE => {
+                            nobj.insert(name.clone(), v.value.to_json());
+                        },
+                        _ => ()
                     }
                 }
                 Object(nobj)
@@ -315,38 +628,60 @@ impl ToJson for Value {
             VString(ref str) => String(str.clone()),
             VNumber(num) => Number(num),
             VInteger(val) => Number(val as f64),
-            VFunction(_) => Null
+            VBigInt(_) => Number(self.to_num()),
+            VSymbol(_) | VFunction(_) => Null
         }
     }
 }
-impl Add<Value, Value> for Value {
-    fn add(&self, other:&Value) -> Value {
-        if self.is_string() || other.is_string() {
-            let text = self.to_string().append(other.to_string().as_slice());
-            to_value(text)
+/// Apply an arithmetic operator to two values, using `big` if they are both
+/// BigInts and `num` if they are both numbers
+fn arith_op(a:&Value, b:&Value, big:|BigInt, BigInt| -> ResultValue, num:|f64, f64| -> f64) -> ResultValue {
+    match (a.as_bigint(), b.as_bigint()) {
+        (Some(a), Some(b)) => big(a, b),
+        (None, None) => Ok(to_value(num(a.to_num(), b.to_num()))),
+        _ => Err(to_value("Cannot mix BigInt and other types, use explicit conversions"))
+    }
+}
+impl Add<Value, ResultValue> for Value {
+    /// Add two values, which converts objects to primitives first, then concatenates
+    /// them if either is a string or adds them as numbers otherwise
+    fn add(&self, other:&Value) -> ResultValue {
+        let left = try!(conversions::to_primitive(*self, conversions::HintDefault));
+        let right = try!(conversions::to_primitive(*other, conversions::HintDefault));
+        if left.is_string() || right.is_string() {
+            let text = try!(conversions::to_string(left)).append(try!(conversions::to_string(right)).as_slice());
+            Ok(to_value(text))
         } else {
-            to_value(self.to_num() + other.to_num())
+            arith_op(&left, &right, |a, b| Ok(to_value(a + b)), |a, b| a + b)
         }
     }
 }
-impl Sub<Value, Value> for Value {
-    fn sub(&self, other:&Value) -> Value {
-        to_value(self.to_num() - other.to_num())
+impl Sub<Value, ResultValue> for Value {
+    fn sub(&self, other:&Value) -> ResultValue {
+        arith_op(self, other, |a, b| Ok(to_value(a - b)), |a, b| a - b)
     }
 }
-impl Mul<Value, Value> for Value {
-    fn mul(&self, other:&Value) -> Value {
-        to_value(self.to_num() * other.to_num())
+impl Mul<Value, ResultValue> for Value {
+    fn mul(&self, other:&Value) -> ResultValue {
+        arith_op(self, other, |a, b| Ok(to_value(a * b)), |a, b| a * b)
     }
 }
-impl Div<Value, Value> for Value {
-    fn div(&self, other:&Value) -> Value {
-        to_value(self.to_num() / other.to_num())
+impl Div<Value, ResultValue> for Value {
+    fn div(&self, other:&Value) -> ResultValue {
+        arith_op(self, other, |a, b| if b.is_zero() {
+            Err(to_value("Division by zero"))
+        } else {
+            Ok(to_value(a / b))
+        }, |a, b| a / b)
     }
 }
-impl Rem<Value, Value> for Value {
-    fn rem(&self, other:&Value) -> Value {
-        to_value(self.to_num() % other.to_num())
+impl Rem<Value, ResultValue> for Value {
+    fn rem(&self, other:&Value) -> ResultValue {
+        arith_op(self, other, |a, b| if b.is_zero() {
+            Err(to_value("Division by zero"))
+        } else {
+            Ok(to_value(a % b))
+        }, |a, b| a % b)
     }
 }
 impl BitAnd<Value, Value> for Value {
@@ -366,12 +701,12 @@ impl BitXor<Value, Value> for Value {
 }
 impl Shl<Value, Value> for Value {
     fn shl(&self, other:&Value) -> Value {
-        to_value(self.to_int() << other.to_int() as uint)
+        to_value(self.to_int() << (other.to_uint() & 0x1f) as uint)
     }
 }
 impl Shr<Value, Value> for Value {
     fn shr(&self, other:&Value) -> Value {
-        to_value(self.to_int() >> other.to_int() as uint)
+        to_value(self.to_int() >> (other.to_uint() & 0x1f) as uint)
     }
 }
 impl Not<Value> for Value {
@@ -381,12 +716,36 @@ impl Not<Value> for Value {
 }
 impl Neg<Value> for Value {
     fn neg(&self) -> Value {
-        to_value(-self.to_num())
+        match **self {
+            VBigInt(ref num) => to_value(-*num),
+            _ => to_value(-self.to_num())
+        }
+    }
+}
+impl Value {
+    /// Compare this value with another, as `<`, `<=`, `>` and `>=` do, which converts objects
+    /// to primitives preferring numbers, then compares strings by their UTF-16 code units and
+    /// anything else as numbers, giving `None` if either is `NaN`
+    pub fn compare(&self, other:&Value) -> Result<Option<Ordering>, Value> {
+        let left = try!(conversions::to_primitive(*self, conversions::HintNumber));
+        let right = try!(conversions::to_primitive(*other, conversions::HintNumber));
+        Ok(match (&*left, &*right) {
+            (&VString(ref a), &VString(ref b)) => {
+                let a : Vec<u16> = a.as_slice().utf16_units().collect();
+                let b : Vec<u16> = b.as_slice().utf16_units().collect();
+                Some(a.cmp(&b))
+            },
+            (&VBigInt(ref a), &VBigInt(ref b)) => Some(a.cmp(b)),
+            (&VBigInt(ref a), &VString(ref b)) => from_str::<BigInt>(b.as_slice().trim()).map(|b| a.cmp(&b)),
+            (&VString(ref a), &VBigInt(ref b)) => from_str::<BigInt>(a.as_slice().trim()).map(|a| a.cmp(b)),
+            _ => left.to_num().partial_cmp(&right.to_num())
+        })
     }
 }
 impl PartialOrd for Value {
+    /// Compare two values like `Value::compare`, where they can't be compared if converting either throws
     fn partial_cmp(&self, other: &Value) -> Option<Ordering> {
-        self.to_num().partial_cmp(&other.to_num())
+        self.compare(other).unwrap_or(None)
     }
 }
 /// Conversion to Javascript values from Rust values
@@ -465,6 +824,19 @@ impl FromValue for i32 {
         Ok(v.to_int())
     }
 }
+impl ToValue for BigInt {
+    fn to_value(&self) -> Value {
+        Value::new(VBigInt(self.clone()))
+    }
+}
+impl FromValue for BigInt {
+    fn from_value(v:Value) -> Result<BigInt, &'static str> {
+        match v.as_bigint() {
+            Some(num) => Ok(num),
+            None => Err("Value is not a BigInt")
+        }
+    }
+}
 impl ToValue for bool {
     fn to_value(&self) -> Value {
         Value::new(VBoolean(*self))
@@ -478,14 +850,14 @@ impl FromValue for bool {
 impl<'s, T:ToValue> ToValue for &'s [T] {
     fn to_value(&self) -> Value {
         to_value::<ObjectData>(self.iter().enumerate().map(|(i, elem)| {
-           (i.to_string(), Property::new(elem.to_value()))
+           (i.to_string(), Property::data(elem.to_value()))
         }).collect())
     }
 }
 impl<T:ToValue> ToValue for Vec<T> {
     fn to_value(&self) -> Value {
         to_value::<ObjectData>(self.iter().enumerate().map(|(i, elem)| {
-           (i.to_string(), Property::new(elem.to_value()))
+           (i.to_string(), Property::data(elem.to_value()))
         }).collect())
     }
 }
@@ -550,6 +922,35 @@ impl<T:FromValue> FromValue for Option<T> {
         })
     }
 }
+/// An iterator over the items of an iterable value, as used by a for-of loop
+pub struct ValueIterator {
+    /// The value being iterated over
+    iterable: Value,
+    /// The index of the next item, which is a byte offset for strings
+    index: uint
+}
+impl Iterator<Value> for ValueIterator {
+    fn next(&mut self) -> Option<Value> {
+        match *self.iterable {
+            VString(ref s) => {
+                if self.index >= s.len() {
+                    return None;
+                }
+                let range = s.as_slice().char_range_at(self.index);
+                self.index = range.next;
+                Some(to_value(range.ch))
+            },
+            // the length is read on every step, so items added during iteration are included
+            _ => {
+                if self.index as i32 >= self.iterable.get_field("length").to_int() {
+                    return None;
+                }
+                self.index += 1;
+                Some(self.iterable.get_field((self.index - 1).to_string().as_slice()))
+            }
+        }
+    }
+}
 /// A utility function that just calls FromValue::from_value
 pub fn from_value<A: FromValue>(v: Value) -> Result<A, &'static str> {
     FromValue::from_value(v)
@@ -1,5 +1,5 @@
 #![crate_name = "js"]
-#![comment = "Javascript parsing, compilation and execution using LibJIT"]
+#![comment = "Javascript parsing and execution"]
 #![license = "MIT"]
 #![crate_type = "lib"]
 #![doc(
@@ -11,17 +11,17 @@
 	unreachable_code, unnecessary_allocation, unnecessary_typecast, unnecessary_allocation,
 	uppercase_variables, non_camel_case_types, unused_must_use)]
 //! This is a library with seperate modules for Javascript parsing, the Javascript
-//! standard library, and Javascript execution through LibJIT
+//! standard library, and Javascript execution through an interpreter
 extern crate collections;
-extern crate jit;
 #[phase(plugin, link)]
 extern crate log;
+extern crate num;
 extern crate serialize;
 extern crate time;
 extern crate url;
 /// The backend-defining traits and the Javascript standard library
 pub mod front;
-/// The default backend implemented on top of LibJIT
-pub mod back;
 /// Javascript parsing and syntax
-pub mod syntax;
\ No newline at end of file
+pub mod syntax;
+/// The abstract operations that convert values from one type to another
+pub mod conversions;
\ No newline at end of file
@@ -0,0 +1,118 @@
+use syntax::ast::expr::Expr;
+use syntax::ast::pos::Position;
+use syntax::ast::token::{Token, TComment};
+use syntax::ast::visitor::{Visitor, walk};
+use collections::treemap::TreeMap;
+#[deriving(Clone, PartialEq, Show)]
+/// A comment in the source code, including its position
+pub struct Comment {
+    /// The text of the comment, without the `//` or `/*` and `*/` around it
+    pub text : String,
+    /// The position of the comment's first character
+    pub start : Position,
+    /// The position just after the comment's last character
+    pub end : Position
+}
+#[deriving(Clone, PartialEq, Show)]
+/// The comments attached to an expression
+pub struct Comments {
+    /// The comments just before the expression
+    pub leading : Vec<Comment>,
+    /// The comments after the expression on the line that it ends on
+    pub trailing : Vec<Comment>
+}
+impl Comments {
+    /// Create an empty set of comments
+    pub fn new() -> Comments {
+        Comments {
+            leading: Vec::new(),
+            trailing: Vec::new()
+        }
+    }
+}
+/// The comments attached to the expressions in a tree, looked up by the
+/// expressions' positions
+pub struct CommentMap {
+    /// The comments, by the start and end offsets of the expression they are attached to
+    pub comments : TreeMap<(uint, uint), Comments>
+}
+impl CommentMap {
+    /// Attach the comments in the tokens to the nearest expressions in the tree parsed from them.
+    /// A comment that follows an expression on the line it ends on trails that expression,
+    /// and any other comment leads the outermost expression starting after it
+    pub fn attach(expr:&Expr, tokens:&Vec<Token>) -> CommentMap {
+        let mut spans = SpanCollector {spans: Vec::new()};
+        spans.visit(expr);
+        let spans = spans.spans;
+        let mut map = CommentMap {comments: TreeMap::new()};
+        for tk in tokens.iter() {
+            let comment = match tk.data {
+                TComment(ref text) => Comment {text: text.clone(), start: tk.pos, end: tk.end},
+                _ => continue
+            };
+            // the expression that ends last before the comment, taking the outermost one
+            let mut before : Option<&(Position, Position)> = None;
+            // the expression that starts first after the comment, taking the outermost one
+            let mut after : Option<&(Position, Position)> = None;
+            for span in spans.iter() {
+                let &(ref start, ref end) = span;
+                if end.offset <= comment.start.offset && before.map_or(true, |&(ref b_start, ref b_end)|
+                        end.offset > b_end.offset || (end.offset == b_end.offset && start.offset < b_start.offset)) {
+                    before = Some(span);
+                }
+                if start.offset >= comment.end.offset && after.map_or(true, |&(ref a_start, ref a_end)|
+                        start.offset < a_start.offset || (start.offset == a_start.offset && end.offset > a_end.offset)) {
+                    after = Some(span);
+                }
+            }
+            match (before, after) {
+                (Some(&(ref start, ref end)), _) if end.line_number == comment.start.line_number =>
+                    map.entry(start, end).trailing.push(comment),
+                (_, Some(&(ref start, ref end))) =>
+                    map.entry(start, end).leading.push(comment),
+                // a comment at the end of the source trails the last expression
+                (Some(&(ref start, ref end)), None) =>
+                    map.entry(start, end).trailing.push(comment),
+                (None, None) => ()
+            }
+        }
+        map
+    }
+    /// Get the comments attached to the expression with the positions given, adding
+    /// an empty set of comments for it if it doesn't have any yet
+    fn entry<'a>(&'a mut self, start:&Position, end:&Position) -> &'a mut Comments {
+        let key = (start.offset, end.offset);
+        if !self.comments.contains_key(&key) {
+            self.comments.insert(key, Comments::new());
+        }
+        self.comments.find_mut(&key).unwrap()
+    }
+    /// Get the comments attached to an expression, if there are any
+    pub fn find<'a>(&'a self, expr:&Expr) -> Option<&'a Comments> {
+        self.comments.find(&(expr.start.offset, expr.end.offset))
+    }
+    /// Get the comments before an expression
+    pub fn leading<'a>(&'a self, expr:&Expr) -> &'a [Comment] {
+        match self.find(expr) {
+            Some(comments) => comments.leading.as_slice(),
+            None => &[]
+        }
+    }
+    /// Get the comments after an expression on the line it ends on
+    pub fn trailing<'a>(&'a self, expr:&Expr) -> &'a [Comment] {
+        match self.find(expr) {
+            Some(comments) => comments.trailing.as_slice(),
+            None => &[]
+        }
+    }
+}
+/// Collects the positions of every expression in a tree, outermost first
+struct SpanCollector {
+    spans: Vec<(Position, Position)>
+}
+impl Visitor for SpanCollector {
+    fn visit(&mut self, expr:&Expr) {
+        self.spans.push((expr.start, expr.end));
+        walk(self, expr)
+    }
+}
@@ -1,16 +1,69 @@
 use std::fmt::{Formatter, Result, Show};
+use std::from_str::FromStr;
+use num::bigint::BigInt;
+
+#[deriving(Clone, PartialEq)]
+/// The flags of a regular expression, which change how it matches
+pub struct RegExpFlags {
+    /// The `g` flag, which makes it find all matches rather than just the first
+    pub global: bool,
+    /// The `i` flag, which makes it ignore the case of letters
+    pub ignore_case: bool,
+    /// The `m` flag, which makes `^` and `$` match at the start and end of lines
+    pub multiline: bool,
+    /// The `s` flag, which makes `.` match line terminators
+    pub dot_all: bool,
+    /// The `u` flag, which makes it match code points rather than UTF-16 code units
+    pub unicode: bool,
+    /// The `y` flag, which makes it only match starting at its last index
+    pub sticky: bool
+}
+impl FromStr for RegExpFlags {
+    /// Parse the flags after a regular expression, which can't be repeated or unknown
+    fn from_str(s: &str) -> Option<RegExpFlags> {
+        let mut flags = RegExpFlags {global: false, ignore_case: false, multiline: false, dot_all: false, unicode: false, sticky: false};
+        for ch in s.chars() {
+            let flag = match ch {
+                'g' => &mut flags.global,
+                'i' => &mut flags.ignore_case,
+                'm' => &mut flags.multiline,
+                's' => &mut flags.dot_all,
+                'u' => &mut flags.unicode,
+                'y' => &mut flags.sticky,
+                _ => return None
+            };
+            if *flag {
+                return None;
+            }
+            *flag = true;
+        }
+        Some(flags)
+    }
+}
+impl Show for RegExpFlags {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        for &(set, ch) in [(self.global, 'g'), (self.ignore_case, 'i'), (self.multiline, 'm'), (self.dot_all, 's'), (self.unicode, 'u'), (self.sticky, 'y')].iter() {
+            if set {
+                try!(write!(f, "{}", ch));
+            }
+        }
+        Ok(())
+    }
+}
 
 #[deriving(Clone, PartialEq)]
 /// A Javascript constant
 pub enum Const {
     /// A UTF-8 string, such as `"Hello, world"`
     CString(String),
-    /// A regular expression, such as `/where('s| is) [wW]ally/`
-    CRegExp(String, bool, bool),
+    /// A regular expression, such as `/where('s| is) [wW]ally/g`
+    CRegExp(String, RegExpFlags),
     /// A 64-bit floating-point number, such as `3.1415`
     CNum(f64),
     /// A 32-bit integer, such as `42`
     CInt(i32),
+    /// An arbitrary-precision integer, such as `9007199254740993n`
+    CBigInt(BigInt),
     /// A boolean, which is either `true` or `false` and is used to check if criteria are met
     CBool(bool),
     /// The `null` value, which represents a non-existant value
@@ -22,9 +75,10 @@ impl Show for Const {
     fn fmt(&self, f: &mut Formatter) -> Result {
         return match *self {
             CString(ref st) => write!(f, "\"{}\"", st),
-            CRegExp(ref reg, _, _) => write!(f, "~/{}/", reg),
+            CRegExp(ref reg, ref flags) => write!(f, "/{}/{}", reg, flags),
             CNum(num) => write!(f, "{}", num),
             CInt(num) => write!(f, "{}", num),
+            CBigInt(ref num) => write!(f, "{}n", num),
             CBool(v) => write!(f, "{}", v),
             CNull => write!(f, "null"),
             CUndefined => write!(f, "undefined")
@@ -0,0 +1,1011 @@
+use collections::TreeMap;
+use serialize::json;
+use serialize::json::{ToJson, Json};
+use std::f64;
+use syntax::ast::constant::*;
+use syntax::ast::expr::*;
+use syntax::ast::op::*;
+use syntax::ast::pattern::{Pattern, RefPattern, ObjectPattern, ArrayPattern, DefaultPattern};
+use syntax::ast::pos::Position;
+/// The result of reading an expression from an ESTree node, which gives an
+/// error message if the node can't be read
+pub type ESTreeResult<T> = Result<T, String>;
+
+impl Expr {
+    /// Serialize the expression into an [ESTree](https://github.com/estree/estree) node,
+    /// which is written as a statement unless it is a script or module
+    pub fn to_estree_json(&self) -> Json {
+        stmt_json(self)
+    }
+    /// Read an expression from an ESTree node, such as one given by esprima or babel
+    pub fn from_estree_json(json:&Json) -> ESTreeResult<Expr> {
+        from_node(json)
+    }
+}
+/// Serialize the position of a node as ESTree gives it
+fn loc_json(pos:&Position) -> Json {
+    let mut obj = TreeMap::new();
+    obj.insert("line".into_string(), pos.line_number.to_json());
+    obj.insert("column".into_string(), pos.column_number.to_json());
+    json::Object(obj)
+}
+/// Make an ESTree node with the type and fields given, at the position of the expression
+fn node(kind:&str, at:&Expr, fields:Vec<(&'static str, Json)>) -> Json {
+    let mut obj = TreeMap::new();
+    obj.insert("type".into_string(), kind.to_json());
+    for (key, value) in fields.move_iter() {
+        obj.insert(key.into_string(), value);
+    }
+    let mut loc = TreeMap::new();
+    loc.insert("start".into_string(), loc_json(&at.start));
+    loc.insert("end".into_string(), loc_json(&at.end));
+    obj.insert("loc".into_string(), json::Object(loc));
+    obj.insert("range".into_string(), json::List(vec!(at.start.offset.to_json(), at.end.offset.to_json())));
+    json::Object(obj)
+}
+/// Make an identifier node
+fn ident_json(name:&str, at:&Expr) -> Json {
+    node("Identifier", at, vec!(("name", name.to_json())))
+}
+/// Make an identifier node if there is a name, otherwise `null`
+fn opt_ident_json(name:&Option<String>, at:&Expr) -> Json {
+    match *name {
+        Some(ref name) => ident_json(name.as_slice(), at),
+        None => json::Null
+    }
+}
+/// Make a string literal node
+fn string_json(text:&str, at:&Expr) -> Json {
+    let value = text.to_json();
+    node("Literal", at, vec!(("raw", value.to_string().to_json()), ("value", value)))
+}
+/// Serialize the name of a property, which is an identifier unless it isn't a valid one
+fn key_json(name:&str, at:&Expr) -> Json {
+    let mut chars = name.chars();
+    let is_identifier = match chars.next() {
+        Some(ch) if ch.is_alphabetic() || ch == '_' || ch == '$' =>
+            chars.all(|ch| ch.is_alphanumeric() || ch == '_' || ch == '$'),
+        _ => false
+    };
+    if is_identifier {
+        ident_json(name, at)
+    } else {
+        string_json(name, at)
+    }
+}
+fn stmts_json(stmts:&Vec<Expr>) -> Json {
+    json::List(stmts.iter().map(|stmt| stmt_json(stmt)).collect())
+}
+fn exprs_json(exprs:&Vec<Expr>) -> Json {
+    json::List(exprs.iter().map(|expr| expr_json(expr)).collect())
+}
+fn opt_json(expr:&Option<Box<Expr>>, to_json:|&Expr| -> Json) -> Json {
+    match *expr {
+        Some(box ref expr) => to_json(expr),
+        None => json::Null
+    }
+}
+/// Serialize a constant into a literal, or the identifier it is written as
+fn const_json(c:&Const, at:&Expr) -> Json {
+    let (value, raw) = match *c {
+        CString(ref text) => return string_json(text.as_slice(), at),
+        CNum(num) if num.is_nan() => return ident_json("NaN", at),
+        CNum(num) if num == f64::INFINITY => return ident_json("Infinity", at),
+        CNum(num) if num == f64::NEG_INFINITY => return node("UnaryExpression", at, vec!(
+            ("operator", "-".to_json()),
+            ("prefix", true.to_json()),
+            ("argument", ident_json("Infinity", at))
+        )),
+        CUndefined => return ident_json("undefined", at),
+        CRegExp(ref body, ref flags) => {
+            let mut regex = TreeMap::new();
+            regex.insert("pattern".into_string(), body.to_json());
+            regex.insert("flags".into_string(), flags.to_string().to_json());
+            return node("Literal", at, vec!(
+                ("value", json::Null),
+                ("raw", c.to_string().to_json()),
+                ("regex", json::Object(regex))
+            ));
+        },
+        CBigInt(ref num) => return node("Literal", at, vec!(
+            ("value", json::Null),
+            ("raw", c.to_string().to_json()),
+            ("bigint", num.to_string().to_json())
+        )),
+        CNum(num) => (json::Number(num), c.to_string()),
+        CInt(num) => (json::Number(num as f64), c.to_string()),
+        CBool(value) => (json::Boolean(value), c.to_string()),
+        CNull => (json::Null, c.to_string())
+    };
+    node("Literal", at, vec!(("value", value), ("raw", raw.to_json())))
+}
+/// Serialize a pattern, giving the parts without positions of their own the position of `at`
+fn pattern_json(pattern:&Pattern, at:&Expr) -> Json {
+    match *pattern {
+        RefPattern(box ref target) => expr_json(target),
+        ObjectPattern(ref fields) => node("ObjectPattern", at, vec!(
+            ("properties", json::List(fields.iter().map(|&(ref name, ref field)| node("Property", at, vec!(
+                ("key", key_json(name.as_slice(), at)),
+                ("value", pattern_json(field, at)),
+                ("kind", "init".to_json()),
+                ("method", false.to_json()),
+                ("shorthand", false.to_json()),
+                ("computed", false.to_json())
+            ))).collect()))
+        )),
+        ArrayPattern(ref items) => node("ArrayPattern", at, vec!(
+            ("elements", json::List(items.iter().map(|item| match *item {
+                Some(ref item) => pattern_json(item, at),
+                None => json::Null
+            }).collect()))
+        )),
+        DefaultPattern(box ref pattern, box ref default) => node("AssignmentPattern", at, vec!(
+            ("left", pattern_json(pattern, at)),
+            ("right", expr_json(default))
+        ))
+    }
+}
+/// Serialize a variable declaration
+fn decls_json(kind:&str, vars:&Vec<(Pattern, Option<Expr>)>, at:&Expr) -> Json {
+    node("VariableDeclaration", at, vec!(
+        ("kind", kind.to_json()),
+        ("declarations", json::List(vars.iter().map(|&(ref pattern, ref value)| node("VariableDeclarator", at, vec!(
+            ("id", pattern_json(pattern, at)),
+            ("init", match *value {
+                Some(ref value) => expr_json(value),
+                None => json::Null
+            })
+        ))).collect()))
+    ))
+}
+/// Serialize a function, which is a declaration if `kind` is `FunctionDeclaration`
+fn function_json(kind:&str, name:&Option<String>, args:&Vec<String>, body:&Expr, generator:bool, at:&Expr) -> Json {
+    node(kind, at, vec!(
+        ("id", opt_ident_json(name, at)),
+        ("params", json::List(args.iter().map(|arg| ident_json(arg.as_slice(), at)).collect())),
+        ("body", stmt_json(body)),
+        ("generator", generator.to_json()),
+        ("async", false.to_json()),
+        ("expression", false.to_json())
+    ))
+}
+/// Serialize a class, which is a declaration if `kind` is `ClassDeclaration`
+fn class_json(kind:&str, name:&Option<String>, parent:&Option<Box<Expr>>, constructor:&Expr, methods:&Vec<(bool, MethodKind, String, Expr)>, at:&Expr) -> Json {
+    let mut members = vec!(method_json("constructor", false, "constructor", constructor));
+    for &(is_static, ref kind, ref name, ref method) in methods.iter() {
+        members.push(method_json(match *kind {
+            PlainMethod => "method",
+            GetterMethod => "get",
+            SetterMethod => "set"
+        }, is_static, name.as_slice(), method));
+    }
+    node(kind, at, vec!(
+        ("id", opt_ident_json(name, at)),
+        ("superClass", opt_json(parent, expr_json)),
+        ("body", node("ClassBody", at, vec!(("body", json::List(members)))))
+    ))
+}
+/// Serialize a method of a class
+fn method_json(kind:&str, is_static:bool, name:&str, method:&Expr) -> Json {
+    node("MethodDefinition", method, vec!(
+        ("key", key_json(name, method)),
+        ("value", expr_json(method)),
+        ("kind", kind.to_json()),
+        ("static", is_static.to_json()),
+        ("computed", false.to_json())
+    ))
+}
+/// Serialize a property of an object
+fn property_json(kind:&str, name:&str, value:&Expr, at:&Expr) -> Json {
+    node("Property", at, vec!(
+        ("key", key_json(name, at)),
+        ("value", expr_json(value)),
+        ("kind", kind.to_json()),
+        ("method", false.to_json()),
+        ("shorthand", false.to_json()),
+        ("computed", false.to_json())
+    ))
+}
+/// Serialize the properties of an object with the name and value given, which is a
+/// getter and a setter if it is an accessor
+fn push_properties(properties:&mut Vec<Json>, name:&str, value:&Expr) {
+    match value.def {
+        AccessorExpr(ref getter, ref setter) => {
+            match *getter {
+                Some(box ref getter) => properties.push(property_json("get", name, getter, value)),
+                None => ()
+            }
+            match *setter {
+                Some(box ref setter) => properties.push(property_json("set", name, setter, value)),
+                None => ()
+            }
+        },
+        _ => properties.push(property_json("init", name, value, value))
+    }
+}
+/// Serialize the object of a member access or the function of a call, and whether it is optional
+fn optional_json(obj:&Expr) -> (Json, bool) {
+    match obj.def {
+        OptionalExpr(box ref obj) => (expr_json(obj), true),
+        _ => (expr_json(obj), false)
+    }
+}
+/// Serialize an expression as a statement
+fn stmt_json(expr:&Expr) -> Json {
+    match expr.def {
+        ScriptExpr(ref stmts, _) => node("Program", expr, vec!(("sourceType", "script".to_json()), ("body", stmts_json(stmts)))),
+        ModuleExpr(ref stmts) => node("Program", expr, vec!(("sourceType", "module".to_json()), ("body", stmts_json(stmts)))),
+        BlockExpr(ref stmts) => node("BlockStatement", expr, vec!(("body", stmts_json(stmts)))),
+        IfExpr(box ref cond, box ref body, ref else_body) => node("IfStatement", expr, vec!(
+            ("test", expr_json(cond)),
+            ("consequent", stmt_json(body)),
+            ("alternate", opt_json(else_body, stmt_json))
+        )),
+        WhileLoopExpr(box ref cond, box ref body) => node("WhileStatement", expr, vec!(
+            ("test", expr_json(cond)),
+            ("body", stmt_json(body))
+        )),
+        ForLoopExpr(ref init, ref cond, ref step, box ref body) => node("ForStatement", expr, vec!(
+            ("init", opt_json(init, expr_json)),
+            ("test", opt_json(cond, expr_json)),
+            ("update", opt_json(step, expr_json)),
+            ("body", stmt_json(body))
+        )),
+        ForInExpr(box ref name, box ref obj, box ref body) => node("ForInStatement", expr, vec!(
+            ("left", expr_json(name)),
+            ("right", expr_json(obj)),
+            ("body", stmt_json(body))
+        )),
+        ForOfExpr(box ref name, box ref iterable, box ref body) => node("ForOfStatement", expr, vec!(
+            ("left", expr_json(name)),
+            ("right", expr_json(iterable)),
+            ("body", stmt_json(body)),
+            ("await", false.to_json())
+        )),
+        TryExpr(box ref block, ref name, ref catch_block, ref finally_block) => node("TryStatement", expr, vec!(
+            ("block", stmt_json(block)),
+            ("handler", match *catch_block {
+                Some(box ref catch_block) => node("CatchClause", catch_block, vec!(
+                    ("param", opt_ident_json(name, catch_block)),
+                    ("body", stmt_json(catch_block))
+                )),
+                None => json::Null
+            }),
+            ("finalizer", opt_json(finally_block, stmt_json))
+        )),
+        BreakExpr(ref label) => node("BreakStatement", expr, vec!(("label", opt_ident_json(label, expr)))),
+        ContinueExpr(ref label) => node("ContinueStatement", expr, vec!(("label", opt_ident_json(label, expr)))),
+        LabelledExpr(ref label, box ref body) => node("LabeledStatement", expr, vec!(
+            ("label", ident_json(label.as_slice(), expr)),
+            ("body", stmt_json(body))
+        )),
+        SwitchExpr(box ref value, ref cases, ref default) => {
+            let mut case_nodes : Vec<Json> = cases.iter().map(|&(ref cond, ref block)| node("SwitchCase", cond, vec!(
+                ("test", expr_json(cond)),
+                ("consequent", stmts_json(block))
+            ))).collect();
+            match *default {
+                Some(box ref default) => case_nodes.push(node("SwitchCase", default, vec!(
+                    ("test", json::Null),
+                    ("consequent", match default.def {
+                        BlockExpr(ref stmts) => stmts_json(stmts),
+                        _ => json::List(vec!(stmt_json(default)))
+                    })
+                ))),
+                None => ()
+            }
+            node("SwitchStatement", expr, vec!(
+                ("discriminant", expr_json(value)),
+                ("cases", json::List(case_nodes))
+            ))
+        },
+        ReturnExpr(ref value) => node("ReturnStatement", expr, vec!(("argument", opt_json(value, expr_json)))),
+        ThrowExpr(box ref value) => node("ThrowStatement", expr, vec!(("argument", expr_json(value)))),
+        VarDeclExpr(_) | LetDeclExpr(_) | ConstDeclExpr(_) => expr_json(expr),
+        FunctionDeclExpr(ref name, ref args, box ref body, _) if name.is_some() =>
+            function_json("FunctionDeclaration", name, args, body, false, expr),
+        GeneratorDeclExpr(ref name, ref args, box ref body, _) if name.is_some() =>
+            function_json("FunctionDeclaration", name, args, body, true, expr),
+        ClassDeclExpr(ref name, ref parent, box ref constructor, ref methods) if name.is_some() =>
+            class_json("ClassDeclaration", name, parent, constructor, methods, expr),
+        ImportExpr(ref names, ref specifier) => node("ImportDeclaration", expr, vec!(
+            ("specifiers", json::List(names.iter().map(|&(ref imported, ref local)| match imported.as_slice() {
+                "default" => node("ImportDefaultSpecifier", expr, vec!(("local", ident_json(local.as_slice(), expr)))),
+                "*" => node("ImportNamespaceSpecifier", expr, vec!(("local", ident_json(local.as_slice(), expr)))),
+                _ => node("ImportSpecifier", expr, vec!(
+                    ("imported", ident_json(imported.as_slice(), expr)),
+                    ("local", ident_json(local.as_slice(), expr))
+                ))
+            }).collect())),
+            ("source", string_json(specifier.as_slice(), expr))
+        )),
+        ExportDeclExpr(box ref decl) => node("ExportNamedDeclaration", expr, vec!(
+            ("declaration", stmt_json(decl)),
+            ("specifiers", json::List(Vec::new())),
+            ("source", json::Null)
+        )),
+        ExportDefaultExpr(box ref value) => node("ExportDefaultDeclaration", expr, vec!(
+            ("declaration", match value.def {
+                FunctionDeclExpr(Some(_), _, _, _) | GeneratorDeclExpr(Some(_), _, _, _) | ClassDeclExpr(Some(_), _, _, _) => stmt_json(value),
+                _ => expr_json(value)
+            })
+        )),
+        ExportNamedExpr(ref names, ref specifier) => node("ExportNamedDeclaration", expr, vec!(
+            ("declaration", json::Null),
+            ("specifiers", json::List(names.iter().map(|&(ref local, ref exported)| node("ExportSpecifier", expr, vec!(
+                ("local", ident_json(local.as_slice(), expr)),
+                ("exported", ident_json(exported.as_slice(), expr))
+            ))).collect())),
+            ("source", match *specifier {
+                Some(ref specifier) => string_json(specifier.as_slice(), expr),
+                None => json::Null
+            })
+        )),
+        ExportAllExpr(ref specifier) => node("ExportAllDeclaration", expr, vec!(
+            ("exported", json::Null),
+            ("source", string_json(specifier.as_slice(), expr))
+        )),
+        _ => node("ExpressionStatement", expr, vec!(("expression", expr_json(expr))))
+    }
+}
+/// Serialize an expression
+fn expr_json(expr:&Expr) -> Json {
+    match expr.def {
+        ConstExpr(ref c) => const_json(c, expr),
+        TemplateExpr(ref quasis, ref exprs) => node("TemplateLiteral", expr, vec!(
+            ("quasis", json::List(quasis.iter().enumerate().map(|(i, quasi)| {
+                let mut value = TreeMap::new();
+                value.insert("raw".into_string(), quasi.to_json());
+                value.insert("cooked".into_string(), quasi.to_json());
+                node("TemplateElement", expr, vec!(
+                    ("value", json::Object(value)),
+                    ("tail", (i + 1 == quasis.len()).to_json())
+                ))
+            }).collect())),
+            ("expressions", exprs_json(exprs))
+        )),
+        LocalExpr(ref name) => ident_json(name.as_slice(), expr),
+        ThisExpr => node("ThisExpression", expr, Vec::new()),
+        SuperExpr => node("Super", expr, Vec::new()),
+        NewTargetExpr => node("MetaProperty", expr, vec!(
+            ("meta", ident_json("new", expr)),
+            ("property", ident_json("target", expr))
+        )),
+        GetConstFieldExpr(box ref obj, ref field) => {
+            let (obj, optional) = optional_json(obj);
+            node("MemberExpression", expr, vec!(
+                ("object", obj),
+                ("property", ident_json(field.as_slice(), expr)),
+                ("computed", false.to_json()),
+                ("optional", optional.to_json())
+            ))
+        },
+        GetFieldExpr(box ref obj, box ref field) => {
+            let (obj, optional) = optional_json(obj);
+            node("MemberExpression", expr, vec!(
+                ("object", obj),
+                ("property", expr_json(field)),
+                ("computed", true.to_json()),
+                ("optional", optional.to_json())
+            ))
+        },
+        OptionalChainExpr(box ref chain) => node("ChainExpression", expr, vec!(("expression", expr_json(chain)))),
+        OptionalExpr(box ref obj) => expr_json(obj),
+        CallExpr(box ref func, ref args) => {
+            let (func, optional) = optional_json(func);
+            node("CallExpression", expr, vec!(
+                ("callee", func),
+                ("arguments", exprs_json(args)),
+                ("optional", optional.to_json())
+            ))
+        },
+        ConstructExpr(box ref func, ref args) => node("NewExpression", expr, vec!(
+            ("callee", expr_json(func)),
+            ("arguments", exprs_json(args))
+        )),
+        IfExpr(box ref cond, box ref if_expr, ref else_expr) => node("ConditionalExpression", expr, vec!(
+            ("test", expr_json(cond)),
+            ("consequent", expr_json(if_expr)),
+            ("alternate", match *else_expr {
+                Some(box ref else_expr) => expr_json(else_expr),
+                None => ident_json("undefined", expr)
+            })
+        )),
+        ObjectDeclExpr(box ref fields) => {
+            let mut properties = Vec::new();
+            for (name, value) in fields.iter() {
+                push_properties(&mut properties, name.as_slice(), value);
+            }
+            node("ObjectExpression", expr, vec!(("properties", json::List(properties))))
+        },
+        AccessorExpr(_, _) => {
+            // an accessor outside of an object is written as an object with just it
+            let mut properties = Vec::new();
+            push_properties(&mut properties, "value", expr);
+            node("ObjectExpression", expr, vec!(("properties", json::List(properties))))
+        },
+        ArrayDeclExpr(ref items) => node("ArrayExpression", expr, vec!(("elements", exprs_json(items)))),
+        SpreadExpr(box ref iterable) => node("SpreadElement", expr, vec!(("argument", expr_json(iterable)))),
+        FunctionDeclExpr(ref name, ref args, box ref body, _) =>
+            function_json("FunctionExpression", name, args, body, false, expr),
+        GeneratorDeclExpr(ref name, ref args, box ref body, _) =>
+            function_json("FunctionExpression", name, args, body, true, expr),
+        ArrowFunctionDeclExpr(ref args, box ref body, _) => {
+            let is_block = match body.def {
+                BlockExpr(_) => true,
+                _ => false
+            };
+            node("ArrowFunctionExpression", expr, vec!(
+                ("id", json::Null),
+                ("params", json::List(args.iter().map(|arg| ident_json(arg.as_slice(), expr)).collect())),
+                ("body", if is_block {stmt_json(body)} else {expr_json(body)}),
+                ("generator", false.to_json()),
+                ("async", false.to_json()),
+                ("expression", (!is_block).to_json())
+            ))
+        },
+        ClassDeclExpr(ref name, ref parent, box ref constructor, ref methods) =>
+            class_json("ClassExpression", name, parent, constructor, methods, expr),
+        YieldExpr(delegate, ref value) => node("YieldExpression", expr, vec!(
+            ("argument", opt_json(value, expr_json)),
+            ("delegate", delegate.to_json())
+        )),
+        BinOpExpr(op, box ref left, box ref right) => node(match op {
+            BinLog(_) => "LogicalExpression",
+            _ => "BinaryExpression"
+        }, expr, vec!(
+            ("operator", op.to_string().to_json()),
+            ("left", expr_json(left)),
+            ("right", expr_json(right))
+        )),
+        UnaryOpExpr(op, box ref operand) => node("UnaryExpression", expr, vec!(
+            ("operator", op.to_string().to_json()),
+            ("prefix", true.to_json()),
+            ("argument", expr_json(operand))
+        )),
+        TypeOfExpr(box ref operand) | VoidExpr(box ref operand) | DeleteExpr(box ref operand) => node("UnaryExpression", expr, vec!(
+            ("operator", match expr.def {
+                TypeOfExpr(_) => "typeof",
+                VoidExpr(_) => "void",
+                _ => "delete"
+            }.to_json()),
+            ("prefix", true.to_json()),
+            ("argument", expr_json(operand))
+        )),
+        UpdateExpr(prefix, op, box ref target) => node("UpdateExpression", expr, vec!(
+            ("operator", op.to_string().to_json()),
+            ("prefix", prefix.to_json()),
+            ("argument", expr_json(target))
+        )),
+        AssignExpr(box ref target, box ref value) => node("AssignmentExpression", expr, vec!(
+            ("operator", "=".to_json()),
+            ("left", expr_json(target)),
+            ("right", expr_json(value))
+        )),
+        DestructureExpr(ref pattern, box ref value) => node("AssignmentExpression", expr, vec!(
+            ("operator", "=".to_json()),
+            ("left", pattern_json(pattern, expr)),
+            ("right", expr_json(value))
+        )),
+        AssignOpExpr(op, box ref target, box ref value) => node("AssignmentExpression", expr, vec!(
+            ("operator", format!("{}=", op).to_json()),
+            ("left", expr_json(target)),
+            ("right", expr_json(value))
+        )),
+        VarDeclExpr(ref vars) => decls_json("var", vars, expr),
+        LetDeclExpr(ref vars) => decls_json("let", vars, expr),
+        ConstDeclExpr(ref vars) => decls_json("const", vars, expr),
+        _ => stmt_json(expr)
+    }
+}
+
+/// Get the field of a node with the key given
+fn field<'a>(json:&'a Json, key:&str) -> ESTreeResult<&'a Json> {
+    match *json {
+        json::Object(ref obj) => match obj.find(&key.into_string()) {
+            Some(value) => Ok(value),
+            None => Err(format!("Expected the field '{}' in {}", key, json))
+        },
+        _ => Err(format!("Expected an object but found {}", json))
+    }
+}
+/// Get the field of a node with the key given, if it is there and isn't `null`
+fn opt_field<'a>(json:&'a Json, key:&str) -> Option<&'a Json> {
+    match field(json, key) {
+        Ok(&json::Null) | Err(_) => None,
+        Ok(value) => Some(value)
+    }
+}
+/// Get a string field of a node
+fn str_field(json:&Json, key:&str) -> ESTreeResult<String> {
+    match *try!(field(json, key)) {
+        json::String(ref value) => Ok(value.clone()),
+        ref value => Err(format!("Expected the field '{}' to be a string but found {}", key, value))
+    }
+}
+/// Get a boolean field of a node, which is false if it isn't there
+fn bool_field(json:&Json, key:&str) -> bool {
+    match opt_field(json, key) {
+        Some(&json::Boolean(value)) => value,
+        _ => false
+    }
+}
+/// Get a list field of a node
+fn list_field<'a>(json:&'a Json, key:&str) -> ESTreeResult<&'a Vec<Json>> {
+    match *try!(field(json, key)) {
+        json::List(ref list) => Ok(list),
+        ref value => Err(format!("Expected the field '{}' to be a list but found {}", key, value))
+    }
+}
+/// Get the number in a field of a node, if it is there
+fn uint_field(json:&Json, key:&str) -> Option<uint> {
+    match opt_field(json, key) {
+        Some(&json::Number(num)) if num >= 0.0 => Some(num as uint),
+        _ => None
+    }
+}
+/// Read the name of an identifier, or the value of a string literal used as a name
+fn name_of(json:&Json) -> ESTreeResult<String> {
+    match try!(str_field(json, "type")).as_slice() {
+        "Identifier" => str_field(json, "name"),
+        "Literal" => match *try!(field(json, "value")) {
+            json::String(ref value) => Ok(value.clone()),
+            json::Number(num) => Ok(num.to_string()),
+            ref value => Err(format!("Expected a name but found {}", value))
+        },
+        kind => Err(format!("Expected a name but found a node of type '{}'", kind))
+    }
+}
+/// Read a field holding the name of an identifier, if it is there
+fn opt_name(json:&Json, key:&str) -> ESTreeResult<Option<String>> {
+    match opt_field(json, key) {
+        Some(name) => Ok(Some(try!(name_of(name)))),
+        None => Ok(None)
+    }
+}
+/// Read the start and end positions of a node, from `loc` and `range` if they are there
+fn positions(json:&Json) -> (Position, Position) {
+    let range = match opt_field(json, "range") {
+        Some(&json::List(ref range)) if range.len() == 2 => match (&range[0], &range[1]) {
+            (&json::Number(start), &json::Number(end)) => (start as uint, end as uint),
+            _ => (0, 0)
+        },
+        _ => (0, 0)
+    };
+    let read = |key:&str, offset:uint| match opt_field(json, "loc").and_then(|loc| opt_field(loc, key)) {
+        Some(pos) => Position::new(uint_field(pos, "line").unwrap_or(0), uint_field(pos, "column").unwrap_or(0), offset),
+        None => Position::new(0, 0, offset)
+    };
+    (read("start", range.val0()), read("end", range.val1()))
+}
+fn from_list(list:&Vec<Json>) -> ESTreeResult<Vec<Expr>> {
+    let mut exprs = Vec::with_capacity(list.len());
+    for json in list.iter() {
+        exprs.push(try!(from_node(json)));
+    }
+    Ok(exprs)
+}
+fn from_opt(json:Option<&Json>) -> ESTreeResult<Option<Box<Expr>>> {
+    match json {
+        Some(json) => Ok(Some(box try!(from_node(json)))),
+        None => Ok(None)
+    }
+}
+/// Check if a list of statements starts with a `"use strict"` directive
+fn has_use_strict(stmts:&Vec<Expr>) -> bool {
+    match stmts.head() {
+        Some(&Expr{def: ConstExpr(CString(ref text)), ..}) => text.as_slice() == "use strict",
+        _ => false
+    }
+}
+/// Read the names of the parameters of a function, which have to be identifiers
+fn from_params(json:&Json) -> ESTreeResult<Vec<String>> {
+    let mut args = Vec::new();
+    for param in try!(list_field(json, "params")).iter() {
+        match try!(str_field(param, "type")).as_slice() {
+            "Identifier" => args.push(try!(str_field(param, "name"))),
+            kind => return Err(format!("Parameters of type '{}' aren't supported", kind))
+        }
+    }
+    Ok(args)
+}
+/// Read a binary operator
+fn from_bin_op(op:&str) -> ESTreeResult<BinOp> {
+    Ok(match op {
+        "+" => BinNum(OpAdd),
+        "-" => BinNum(OpSub),
+        "*" => BinNum(OpMul),
+        "/" => BinNum(OpDiv),
+        "%" => BinNum(OpMod),
+        "**" => BinNum(OpPow),
+        "&" => BinBit(BitAnd),
+        "|" => BinBit(BitOr),
+        "^" => BinBit(BitXor),
+        "<<" => BinBit(BitShl),
+        ">>" => BinBit(BitShr),
+        ">>>" => BinBit(BitUShr),
+        "==" => BinComp(CompEqual),
+        "!=" => BinComp(CompNotEqual),
+        "===" => BinComp(CompStrictEqual),
+        "!==" => BinComp(CompStrictNotEqual),
+        ">" => BinComp(CompGreaterThan),
+        ">=" => BinComp(CompGreaterThanOrEqual),
+        "<" => BinComp(CompLessThan),
+        "<=" => BinComp(CompLessThanOrEqual),
+        "&&" => BinLog(LogAnd),
+        "||" => BinLog(LogOr),
+        "??" => BinLog(LogCoalesce),
+        _ => return Err(format!("Unknown binary operator '{}'", op))
+    })
+}
+/// Read a pattern that a value is bound or assigned to
+fn from_pattern(json:&Json) -> ESTreeResult<Pattern> {
+    Ok(match try!(str_field(json, "type")).as_slice() {
+        "ObjectPattern" => {
+            let mut fields = Vec::new();
+            for property in try!(list_field(json, "properties")).iter() {
+                if bool_field(property, "computed") {
+                    return Err("Computed keys in patterns aren't supported".into_string());
+                }
+                fields.push((try!(name_of(try!(field(property, "key")))), try!(from_pattern(try!(field(property, "value"))))));
+            }
+            ObjectPattern(fields)
+        },
+        "ArrayPattern" => {
+            let mut items = Vec::new();
+            for item in try!(list_field(json, "elements")).iter() {
+                items.push(match *item {
+                    json::Null => None,
+                    _ => Some(try!(from_pattern(item)))
+                });
+            }
+            ArrayPattern(items)
+        },
+        "AssignmentPattern" => DefaultPattern(box try!(from_pattern(try!(field(json, "left")))), box try!(from_node(try!(field(json, "right"))))),
+        _ => RefPattern(box try!(from_node(json)))
+    })
+}
+/// Read the object of a member access or the function of a call, which is optional if `optional` is set
+fn from_optional(json:&Json, optional:bool) -> ESTreeResult<Box<Expr>> {
+    let expr = try!(from_node(json));
+    Ok(if optional {
+        box Expr::new(OptionalExpr(box expr.clone()), expr.start, expr.end)
+    } else {
+        box expr
+    })
+}
+/// Read a function, which is a generator if `generator` is set
+fn from_function(json:&Json) -> ESTreeResult<ExprDef> {
+    let name = try!(opt_name(json, "id"));
+    let args = try!(from_params(json));
+    let body = try!(from_node(try!(field(json, "body"))));
+    let strict = match body.def {
+        BlockExpr(ref stmts) => has_use_strict(stmts),
+        _ => false
+    };
+    Ok(if bool_field(json, "generator") {
+        GeneratorDeclExpr(name, args, box body, strict)
+    } else {
+        FunctionDeclExpr(name, args, box body, strict)
+    })
+}
+/// Read a class
+fn from_class(json:&Json, start:Position, end:Position) -> ESTreeResult<ExprDef> {
+    let name = try!(opt_name(json, "id"));
+    let parent = try!(from_opt(opt_field(json, "superClass")));
+    let mut constructor = None;
+    let mut methods = Vec::new();
+    for member in try!(list_field(try!(field(json, "body")), "body")).iter() {
+        if bool_field(member, "computed") {
+            return Err("Computed method names aren't supported".into_string());
+        }
+        let method = try!(from_node(try!(field(member, "value"))));
+        let kind = match try!(str_field(member, "kind")).as_slice() {
+            "constructor" => {
+                constructor = Some(box method);
+                continue;
+            },
+            "get" => GetterMethod,
+            "set" => SetterMethod,
+            _ => PlainMethod
+        };
+        methods.push((bool_field(member, "static"), kind, try!(name_of(try!(field(member, "key")))), method));
+    }
+    let constructor = match constructor {
+        Some(constructor) => constructor,
+        None => {
+            // the default constructor of a class that extends another passes its arguments on
+            let body = match parent {
+                Some(_) => {
+                    let args = Expr::new(LocalExpr("arguments".into_string()), start, end);
+                    let super_call = CallExpr(box Expr::new(SuperExpr, start, end), vec!(Expr::new(SpreadExpr(box args), start, end)));
+                    vec!(Expr::new(super_call, start, end))
+                },
+                None => Vec::new()
+            };
+            let body = Expr::new(BlockExpr(body), start, end);
+            box Expr::new(FunctionDeclExpr(None, Vec::new(), box body, true), start, end)
+        }
+    };
+    Ok(ClassDeclExpr(name, parent, constructor, methods))
+}
+/// Read a literal
+fn from_literal(json:&Json) -> ESTreeResult<Const> {
+    match opt_field(json, "regex") {
+        Some(regex) => {
+            let flags = try!(str_field(regex, "flags"));
+            return match from_str::<RegExpFlags>(flags.as_slice()) {
+                Some(flags) => Ok(CRegExp(try!(str_field(regex, "pattern")), flags)),
+                None => Err(format!("Invalid regular expression flags '{}'", flags))
+            };
+        },
+        None => ()
+    }
+    match opt_field(json, "bigint") {
+        Some(&json::String(ref digits)) => return match from_str(digits.as_slice()) {
+            Some(num) => Ok(CBigInt(num)),
+            None => Err(format!("Invalid big integer '{}'", digits))
+        },
+        _ => ()
+    }
+    Ok(match *try!(field(json, "value")) {
+        json::String(ref text) => CString(text.clone()),
+        json::Number(num) if num == (num as i32) as f64 => CInt(num as i32),
+        json::Number(num) => CNum(num),
+        json::Boolean(value) => CBool(value),
+        json::Null => CNull,
+        ref value => return Err(format!("Invalid literal value {}", value))
+    })
+}
+/// Read an expression from an ESTree node
+fn from_node(json:&Json) -> ESTreeResult<Expr> {
+    let (start, end) = positions(json);
+    let kind = try!(str_field(json, "type"));
+    let def = match kind.as_slice() {
+        "Program" => {
+            let body = try!(from_list(try!(list_field(json, "body"))));
+            match str_field(json, "sourceType") {
+                Ok(ref source_type) if source_type.as_slice() == "module" => ModuleExpr(body),
+                _ => {
+                    let strict = has_use_strict(&body);
+                    ScriptExpr(body, strict)
+                }
+            }
+        },
+        "ExpressionStatement" => return from_node(try!(field(json, "expression"))),
+        "BlockStatement" => BlockExpr(try!(from_list(try!(list_field(json, "body"))))),
+        "EmptyStatement" => BlockExpr(Vec::new()),
+        "IfStatement" | "ConditionalExpression" => IfExpr(
+            box try!(from_node(try!(field(json, "test")))),
+            box try!(from_node(try!(field(json, "consequent")))),
+            try!(from_opt(opt_field(json, "alternate")))
+        ),
+        "WhileStatement" => WhileLoopExpr(
+            box try!(from_node(try!(field(json, "test")))),
+            box try!(from_node(try!(field(json, "body"))))
+        ),
+        "ForStatement" => ForLoopExpr(
+            try!(from_opt(opt_field(json, "init"))),
+            try!(from_opt(opt_field(json, "test"))),
+            try!(from_opt(opt_field(json, "update"))),
+            box try!(from_node(try!(field(json, "body"))))
+        ),
+        "ForInStatement" | "ForOfStatement" => {
+            let left = box try!(from_node(try!(field(json, "left"))));
+            let right = box try!(from_node(try!(field(json, "right"))));
+            let body = box try!(from_node(try!(field(json, "body"))));
+            if kind.as_slice() == "ForInStatement" {
+                ForInExpr(left, right, body)
+            } else {
+                ForOfExpr(left, right, body)
+            }
+        },
+        "TryStatement" => {
+            let (name, catch_block) = match opt_field(json, "handler") {
+                Some(handler) => (try!(opt_name(handler, "param")), Some(box try!(from_node(try!(field(handler, "body")))))),
+                None => (None, None)
+            };
+            TryExpr(box try!(from_node(try!(field(json, "block")))), name, catch_block, try!(from_opt(opt_field(json, "finalizer"))))
+        },
+        "BreakStatement" => BreakExpr(try!(opt_name(json, "label"))),
+        "ContinueStatement" => ContinueExpr(try!(opt_name(json, "label"))),
+        "LabeledStatement" => LabelledExpr(try!(name_of(try!(field(json, "label")))), box try!(from_node(try!(field(json, "body"))))),
+        "SwitchStatement" => {
+            let mut cases = Vec::new();
+            let mut default = None;
+            for case in try!(list_field(json, "cases")).iter() {
+                let block = try!(from_list(try!(list_field(case, "consequent"))));
+                match opt_field(case, "test") {
+                    Some(test) => cases.push((try!(from_node(test)), block)),
+                    None => {
+                        let (start, end) = positions(case);
+                        default = Some(box Expr::new(BlockExpr(block), start, end));
+                    }
+                }
+            }
+            SwitchExpr(box try!(from_node(try!(field(json, "discriminant")))), cases, default)
+        },
+        "ReturnStatement" => ReturnExpr(try!(from_opt(opt_field(json, "argument")))),
+        "ThrowStatement" => ThrowExpr(box try!(from_node(try!(field(json, "argument"))))),
+        "VariableDeclaration" => {
+            let mut vars = Vec::new();
+            for decl in try!(list_field(json, "declarations")).iter() {
+                let value = match opt_field(decl, "init") {
+                    Some(value) => Some(try!(from_node(value))),
+                    None => None
+                };
+                vars.push((try!(from_pattern(try!(field(decl, "id")))), value));
+            }
+            match try!(str_field(json, "kind")).as_slice() {
+                "let" => LetDeclExpr(vars),
+                "const" => ConstDeclExpr(vars),
+                _ => VarDeclExpr(vars)
+            }
+        },
+        "FunctionDeclaration" | "FunctionExpression" => try!(from_function(json)),
+        "ArrowFunctionExpression" => {
+            let body = try!(from_node(try!(field(json, "body"))));
+            let strict = match body.def {
+                BlockExpr(ref stmts) => has_use_strict(stmts),
+                _ => false
+            };
+            ArrowFunctionDeclExpr(try!(from_params(json)), box body, strict)
+        },
+        "ClassDeclaration" | "ClassExpression" => try!(from_class(json, start, end)),
+        "Literal" => ConstExpr(try!(from_literal(json))),
+        "TemplateLiteral" => {
+            let mut quasis = Vec::new();
+            for quasi in try!(list_field(json, "quasis")).iter() {
+                let value = try!(field(quasi, "value"));
+                quasis.push(match str_field(value, "cooked") {
+                    Ok(cooked) => cooked,
+                    Err(_) => try!(str_field(value, "raw"))
+                });
+            }
+            TemplateExpr(quasis, try!(from_list(try!(list_field(json, "expressions")))))
+        },
+        "Identifier" => match try!(str_field(json, "name")) {
+            ref name if name.as_slice() == "undefined" => ConstExpr(CUndefined),
+            name => LocalExpr(name)
+        },
+        "ThisExpression" => ThisExpr,
+        "Super" => SuperExpr,
+        "MetaProperty" => match (try!(name_of(try!(field(json, "meta")))).as_slice(), try!(name_of(try!(field(json, "property")))).as_slice()) {
+            ("new", "target") => NewTargetExpr,
+            (meta, property) => return Err(format!("Unsupported meta property '{}.{}'", meta, property))
+        },
+        "MemberExpression" => {
+            let obj = try!(from_optional(try!(field(json, "object")), bool_field(json, "optional")));
+            let property = try!(field(json, "property"));
+            if bool_field(json, "computed") {
+                GetFieldExpr(obj, box try!(from_node(property)))
+            } else {
+                GetConstFieldExpr(obj, try!(name_of(property)))
+            }
+        },
+        "ChainExpression" => OptionalChainExpr(box try!(from_node(try!(field(json, "expression"))))),
+        "CallExpression" => CallExpr(
+            try!(from_optional(try!(field(json, "callee")), bool_field(json, "optional"))),
+            try!(from_list(try!(list_field(json, "arguments"))))
+        ),
+        "NewExpression" => ConstructExpr(
+            box try!(from_node(try!(field(json, "callee")))),
+            try!(from_list(try!(list_field(json, "arguments"))))
+        ),
+        "ObjectExpression" => {
+            let mut map = TreeMap::new();
+            for property in try!(list_field(json, "properties")).iter() {
+                if bool_field(property, "computed") {
+                    return Err("Computed property names aren't supported".into_string());
+                }
+                let name = try!(name_of(try!(field(property, "key"))));
+                let value = try!(from_node(try!(field(property, "value"))));
+                let kind = try!(str_field(property, "kind"));
+                if kind.as_slice() == "init" {
+                    map.insert(name, value);
+                    continue;
+                }
+                // a getter and a setter with the same name make up a single property
+                let (getter, setter) = match map.pop(&name) {
+                    Some(Expr {def: AccessorExpr(getter, setter), ..}) => (getter, setter),
+                    _ => (None, None)
+                };
+                let (start, end) = positions(property);
+                let accessor = if kind.as_slice() == "get" {
+                    AccessorExpr(Some(box value), setter)
+                } else {
+                    AccessorExpr(getter, Some(box value))
+                };
+                map.insert(name, Expr::new(accessor, start, end));
+            }
+            ObjectDeclExpr(box map)
+        },
+        "ArrayExpression" => {
+            let mut items = Vec::new();
+            for item in try!(list_field(json, "elements")).iter() {
+                items.push(match *item {
+                    // holes are read as `null`, just like they are parsed
+                    json::Null => Expr::new(ConstExpr(CNull), start, end),
+                    _ => try!(from_node(item))
+                });
+            }
+            ArrayDeclExpr(items)
+        },
+        "SpreadElement" => SpreadExpr(box try!(from_node(try!(field(json, "argument"))))),
+        "YieldExpression" => YieldExpr(bool_field(json, "delegate"), try!(from_opt(opt_field(json, "argument")))),
+        "BinaryExpression" | "LogicalExpression" => BinOpExpr(
+            try!(from_bin_op(try!(str_field(json, "operator")).as_slice())),
+            box try!(from_node(try!(field(json, "left")))),
+            box try!(from_node(try!(field(json, "right"))))
+        ),
+        "UnaryExpression" => {
+            let operand = box try!(from_node(try!(field(json, "argument"))));
+            match try!(str_field(json, "operator")).as_slice() {
+                "-" => UnaryOpExpr(UnaryMinus, operand),
+                "+" => UnaryOpExpr(UnaryPlus, operand),
+                "!" => UnaryOpExpr(UnaryNot, operand),
+                "~" => UnaryOpExpr(UnaryBitNot, operand),
+                "typeof" => TypeOfExpr(operand),
+                "void" => VoidExpr(operand),
+                "delete" => DeleteExpr(operand),
+                op => return Err(format!("Unknown unary operator '{}'", op))
+            }
+        },
+        "UpdateExpression" => {
+            let op = match try!(str_field(json, "operator")).as_slice() {
+                "++" => UpdateIncrement,
+                "--" => UpdateDecrement,
+                op => return Err(format!("Unknown update operator '{}'", op))
+            };
+            UpdateExpr(bool_field(json, "prefix"), op, box try!(from_node(try!(field(json, "argument")))))
+        },
+        "AssignmentExpression" => {
+            let left = try!(field(json, "left"));
+            let right = box try!(from_node(try!(field(json, "right"))));
+            let op = try!(str_field(json, "operator"));
+            if op.as_slice() != "=" {
+                AssignOpExpr(try!(from_bin_op(op.as_slice().slice_to(op.len() - 1))), box try!(from_node(left)), right)
+            } else {
+                match try!(str_field(left, "type")).as_slice() {
+                    "ObjectPattern" | "ArrayPattern" => DestructureExpr(try!(from_pattern(left)), right),
+                    _ => AssignExpr(box try!(from_node(left)), right)
+                }
+            }
+        },
+        "ImportDeclaration" => {
+            let mut names = Vec::new();
+            for specifier in try!(list_field(json, "specifiers")).iter() {
+                let local = try!(name_of(try!(field(specifier, "local"))));
+                names.push((match try!(str_field(specifier, "type")).as_slice() {
+                    "ImportDefaultSpecifier" => "default".into_string(),
+                    "ImportNamespaceSpecifier" => "*".into_string(),
+                    _ => try!(name_of(try!(field(specifier, "imported"))))
+                }, local));
+            }
+            ImportExpr(names, try!(name_of(try!(field(json, "source")))))
+        },
+        "ExportNamedDeclaration" => match opt_field(json, "declaration") {
+            Some(decl) => ExportDeclExpr(box try!(from_node(decl))),
+            None => {
+                let mut names = Vec::new();
+                for specifier in try!(list_field(json, "specifiers")).iter() {
+                    names.push((try!(name_of(try!(field(specifier, "local")))), try!(name_of(try!(field(specifier, "exported"))))));
+                }
+                let source = match opt_field(json, "source") {
+                    Some(source) => Some(try!(name_of(source))),
+                    None => None
+                };
+                ExportNamedExpr(names, source)
+            }
+        },
+        "ExportDefaultDeclaration" => ExportDefaultExpr(box try!(from_node(try!(field(json, "declaration"))))),
+        "ExportAllDeclaration" => {
+            if opt_field(json, "exported").is_some() {
+                return Err("Exporting all the exports of a module under a name isn't supported".into_string());
+            }
+            ExportAllExpr(try!(name_of(try!(field(json, "source")))))
+        },
+        _ => return Err(format!("Nodes of type '{}' aren't supported", kind))
+    };
+    Ok(Expr::new(def, start, end))
+}
@@ -1,6 +1,7 @@
 use std::fmt::{Formatter, Result, Show};
 use syntax::ast::op::*;
 use syntax::ast::constant::Const;
+use syntax::ast::pattern::Pattern;
 use syntax::ast::pos::Position;
 use collections::treemap::TreeMap;
 #[deriving(Clone, PartialEq)]
@@ -25,38 +26,100 @@ impl Show for Expr {
     }
 }
 #[deriving(Clone, PartialEq)]
+/// The kind of a method in a class or object declaration
+pub enum MethodKind {
+    /// A method that is called like a normal function
+    PlainMethod,
+    /// A getter, which is called to get the value of the property
+    GetterMethod,
+    /// A setter, which is called with the value assigned to the property
+    SetterMethod
+}
+#[deriving(Clone, PartialEq)]
 /// A Javascript expression
 pub enum ExprDef {
     /// Run a operation between 2 expressions
     BinOpExpr(BinOp, Box<Expr>, Box<Expr>),
     /// Run an operation on a value
     UnaryOpExpr(UnaryOp, Box<Expr>),
+    /// Convert the value referenced to a number and store it after incrementing or decrementing it,
+    /// giving the new value if the operator is a prefix or the old value if it isn't
+    UpdateExpr(bool, UpdateOp, Box<Expr>),
     /// Make a constant value
     ConstExpr(Const),
+    /// Make a string out of the text of a template literal, with the values of the expressions
+    /// between each piece of text substituted in
+    TemplateExpr(Vec<String>, Vec<Expr>),
     /// Run several expressions from top-to-bottom
     BlockExpr(Vec<Expr>),
     /// Load a reference to a value
     LocalExpr(String),
+    /// Load the value that the function being run was called on
+    ThisExpr,
     /// Gets the constant field of a value
     GetConstFieldExpr(Box<Expr>, String),
     /// Gets the field of a value
     GetFieldExpr(Box<Expr>, Box<Expr>),
+    /// Run a chain of field accesses and calls that contains optional accesses, giving
+    /// `undefined` if any of them short-circuit
+    OptionalChainExpr(Box<Expr>),
+    /// The object of an optional access or call such as `a?.b`, which ends the enclosing
+    /// optional chain if it is `null` or `undefined`
+    OptionalExpr(Box<Expr>),
     /// Call a function with some values
     CallExpr(Box<Expr>, Vec<Expr>),
     /// Repeatedly run an expression while the conditional expression resolves to true
     WhileLoopExpr(Box<Expr>, Box<Expr>),
+    /// Run an initial expression, then repeatedly run an expression and a step expression
+    /// while the conditional expression resolves to true, where any of the clauses can be empty
+    ForLoopExpr(Option<Box<Expr>>, Option<Box<Expr>>, Option<Box<Expr>>, Box<Expr>),
+    /// Run an expression for each enumerable property name of an object, including inherited ones,
+    /// after assigning it to the variable or reference given
+    ForInExpr(Box<Expr>, Box<Expr>, Box<Expr>),
+    /// Run an expression for each item of an iterable value such as an array or string,
+    /// after assigning it to the variable or reference given
+    ForOfExpr(Box<Expr>, Box<Expr>, Box<Expr>),
     /// Check if a conditional expression is true and run an expression if it is and another expression if it isn't
     IfExpr(Box<Expr>, Box<Expr>, Option<Box<Expr>>),
+    /// Run a block, and if it throws run the catch block with the thrown value bound to the
+    /// optional name given, then always run the finally block
+    TryExpr(Box<Expr>, Option<String>, Option<Box<Expr>>, Option<Box<Expr>>),
+    /// Exit the innermost loop or switch statement, or the labelled statement given
+    BreakExpr(Option<String>),
+    /// Skip the rest of the current iteration of the innermost loop, or the labelled loop given
+    ContinueExpr(Option<String>),
+    /// Run an expression with a label that breaks and continues inside it can target
+    LabelledExpr(String, Box<Expr>),
     /// Run blocks whose cases match the expression
     SwitchExpr(Box<Expr>, Vec<(Expr, Vec<Expr>)>, Option<Box<Expr>>),
     /// Create an object out of the binary tree given
     ObjectDeclExpr(Box<TreeMap<String, Expr>>),
+    /// A property with a getter, a setter or both, which is only found as a value in an object declaration
+    AccessorExpr(Option<Box<Expr>>, Option<Box<Expr>>),
     /// Create an array with items inside
     ArrayDeclExpr(Vec<Expr>),
-    /// Create a function with the given name, arguments, and expression
-    FunctionDeclExpr(Option<String>, Vec<String>, Box<Expr>),
-    /// Create an arrow function with the given arguments and expression
-    ArrowFunctionDeclExpr(Vec<String>, Box<Expr>),
+    /// Spread the items of an iterable value into the arguments of a call or the items of an array
+    SpreadExpr(Box<Expr>),
+    /// Create a function with the given name, arguments, and expression, and whether it is in strict mode,
+    /// where the name of a function expression can only be used inside of it
+    FunctionDeclExpr(Option<String>, Vec<String>, Box<Expr>, bool),
+    /// Create a generator function with the given name, arguments, and expression, and whether it is in
+    /// strict mode, which gives a generator that runs the expression up to each `yield` as it is resumed
+    GeneratorDeclExpr(Option<String>, Vec<String>, Box<Expr>, bool),
+    /// Pause the generator being run, giving the value of the optional expression to whatever resumed it,
+    /// or give each value of an iterable value if the expression is delegated to with `yield*`
+    YieldExpr(bool, Option<Box<Expr>>),
+    /// Create an arrow function with the given arguments and expression, and whether it is in strict mode
+    ArrowFunctionDeclExpr(Vec<String>, Box<Expr>, bool),
+    /// Create a class with the given name, the class it extends, its constructor function, and
+    /// its methods along with whether they are static
+    ClassDeclExpr(Option<String>, Option<Box<Expr>>, Box<Expr>, Vec<(bool, MethodKind, String, Expr)>),
+    /// The class being extended, which is either called in a constructor or has its methods
+    /// accessed in a method
+    SuperExpr,
+    /// The function that the function being run was called on with `new`, which is `undefined`
+    /// if it was called normally
+    NewTargetExpr,
     /// Construct an object from the function and arguments given
     ConstructExpr(Box<Expr>, Vec<Expr>),
     /// Return the expression from a function
@@ -65,29 +128,60 @@ pub enum ExprDef {
     ThrowExpr(Box<Expr>),
     /// Assign an expression to a value
     AssignExpr(Box<Expr>, Box<Expr>),
-    /// A variable declaration
-    VarDeclExpr(Vec<(String, Option<Expr>)>),
+    /// Assign the parts of the value of an expression to the variables and fields in a pattern
+    DestructureExpr(Pattern, Box<Expr>),
+    /// Run an operation between a value and an expression, then assign the result to the value
+    AssignOpExpr(BinOp, Box<Expr>, Box<Expr>),
+    /// A variable declaration, which is scoped to the function and hoisted to the top of it
+    VarDeclExpr(Vec<(Pattern, Option<Expr>)>),
+    /// A variable declaration that is scoped to the block it is in
+    LetDeclExpr(Vec<(Pattern, Option<Expr>)>),
+    /// A declaration of constants that are scoped to the block they are in and can't be
+    /// reassigned, which only lack values in the head of a for-in or for-of loop
+    ConstDeclExpr(Vec<(Pattern, Option<Expr>)>),
+    /// Run the statements of a script, and whether it is in strict mode
+    ScriptExpr(Vec<Expr>, bool),
+    /// Run the statements of a module, which is always in strict mode and can import and export bindings
+    ModuleExpr(Vec<Expr>),
+    /// Bind the exports of the module with the specifier given to local names, as pairs of the name
+    /// exported and the local name, where the name exported is `default` for the default export and
+    /// `*` for an object holding all of them
+    ImportExpr(Vec<(String, String)>, String),
+    /// Run a declaration and export the bindings it declares under their own names
+    ExportDeclExpr(Box<Expr>),
+    /// Export the value of the expression as the default export of the module
+    ExportDefaultExpr(Box<Expr>),
+    /// Export bindings as pairs of the local name and the name exported, where the bindings are
+    /// exports of the module with the specifier given instead of local bindings if there is one
+    ExportNamedExpr(Vec<(String, String)>, Option<String>),
+    /// Export all the exports of the module with the specifier given, except its default export
+    ExportAllExpr(String),
     /// Return a string representing the type of the given expression
-    TypeOfExpr(Box<Expr>)
+    TypeOfExpr(Box<Expr>),
+    /// Run an expression and give `undefined` instead of its value
+    VoidExpr(Box<Expr>),
+    /// Remove the property referenced by the expression from its object, giving a boolean
+    /// for whether it succeeded
+    DeleteExpr(Box<Expr>)
 }
 impl Operator for ExprDef {
     fn get_assoc(&self) -> bool {
         match *self {
-            ConstructExpr(_, _) | UnaryOpExpr(_, _) | TypeOfExpr(_) | IfExpr(_, _, _) | AssignExpr(_, _) => false,
+            ConstructExpr(_, _) | UnaryOpExpr(_, _) | UpdateExpr(true, _, _) | TypeOfExpr(_) | VoidExpr(_) | DeleteExpr(_) | IfExpr(_, _, _) | YieldExpr(_, _) | AssignExpr(_, _) | DestructureExpr(_, _) | AssignOpExpr(_, _, _) => false,
             _ => true
         }
     }
     fn get_precedence(&self) -> uint {
         match *self {
-            GetFieldExpr(_, _) | GetConstFieldExpr(_, _) => 1,
+            GetFieldExpr(_, _) | GetConstFieldExpr(_, _) | OptionalChainExpr(_) | OptionalExpr(_) => 1,
             CallExpr(_, _) | ConstructExpr(_, _) => 2,
-            UnaryOpExpr(UnaryIncrementPost, _) | UnaryOpExpr(UnaryIncrementPre, _) | UnaryOpExpr(UnaryDecrementPost, _) | UnaryOpExpr(UnaryDecrementPre, _) => 3,
-            UnaryOpExpr(UnaryNot, _) | UnaryOpExpr(UnaryMinus, _) | TypeOfExpr(_) => 4,
+            UpdateExpr(false, _, _) => 3,
+            UpdateExpr(true, _, _) | UnaryOpExpr(UnaryNot, _) | UnaryOpExpr(UnaryBitNot, _) | UnaryOpExpr(UnaryMinus, _) | UnaryOpExpr(UnaryPlus, _) | TypeOfExpr(_) | VoidExpr(_) | DeleteExpr(_) => 4,
             BinOpExpr(op, _, _) => op.get_precedence(),
-            IfExpr(_, _, _) => 15,
-            // 16 should be yield
-            AssignExpr(_, _) => 17,
-            _ => 19
+            IfExpr(_, _, _) => 16,
+            YieldExpr(_, _) => 17,
+            AssignExpr(_, _) | DestructureExpr(_, _) | AssignOpExpr(_, _, _) => 18,
+            _ => 20
         }
     }
 }
@@ -95,6 +189,16 @@ impl Show for ExprDef {
     fn fmt(&self, f: &mut Formatter) -> Result {
         return match *self {
             ConstExpr(ref c) => write!(f, "{}", c),
+            TemplateExpr(ref quasis, ref exprs) => {
+                try!(write!(f, "`"));
+                for (i, quasi) in quasis.iter().enumerate() {
+                    try!(write!(f, "{}", quasi));
+                    if i < exprs.len() {
+                        try!(write!(f, "${}{}{}", "{", exprs[i], "}"));
+                    }
+                }
+                write!(f, "`")
+            },
             BlockExpr(ref block) => {
                 try!(write!(f, "{}", "{"));
                 for expr in block.iter() {
@@ -103,31 +207,150 @@ impl Show for ExprDef {
                 write!(f, "{}", "}")
             },
             LocalExpr(ref s) => write!(f, "{}", s),
+            ThisExpr => write!(f, "{}", "this"),
             GetConstFieldExpr(ref ex, ref field) => write!(f, "{}.{}", ex, field),
+            GetFieldExpr(box Expr{def: OptionalExpr(ref ex), ..}, ref field) => write!(f, "{}?.[{}]", ex, field),
             GetFieldExpr(ref ex, ref field) => write!(f, "{}[{}]", ex, field),
+            OptionalChainExpr(ref ex) => write!(f, "{}", ex),
+            OptionalExpr(ref ex) => write!(f, "{}?", ex),
             CallExpr(ref ex, ref args) => {
-                try!(write!(f, "{}(", ex));
+                try!(match ex.def {
+                    OptionalExpr(ref ex) => write!(f, "{}?.(", ex),
+                    _ => write!(f, "{}(", ex)
+                });
                 let arg_strs:Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
                 write!(f, "{})", arg_strs.connect(","))
             },
             ConstructExpr(ref func, ref args) => write!(f, "new {}({})", func, args),
             WhileLoopExpr(ref cond, ref expr) => write!(f, "while({}) {}", cond, expr),
+            ForLoopExpr(ref init, ref cond, ref step, ref expr) => {
+                try!(write!(f, "for("));
+                for (i, clause) in [init, cond, step].iter().enumerate() {
+                    match **clause {
+                        Some(ref e) => try!(write!(f, "{}", e)),
+                        None => ()
+                    }
+                    if i < 2 {
+                        try!(write!(f, ";"));
+                    }
+                }
+                write!(f, ") {}", expr)
+            },
+            ForInExpr(ref name, ref obj, ref expr) => write!(f, "for({} in {}) {}", name, obj, expr),
+            ForOfExpr(ref name, ref iterable, ref expr) => write!(f, "for({} of {}) {}", name, iterable, expr),
             IfExpr(ref cond, ref expr, None) => write!(f, "if({}) {}", cond, expr),
             IfExpr(ref cond, ref expr, Some(ref else_e)) => write!(f, "if({}) {} else {}", cond, expr, else_e),
+            TryExpr(ref block, ref name, ref catch_block, ref finally_block) => {
+                try!(write!(f, "try {}", block));
+                match *catch_block {
+                    Some(ref catch_block) => try!(match *name {
+                        Some(ref name) => write!(f, " catch({}) {}", name, catch_block),
+                        None => write!(f, " catch {}", catch_block)
+                    }),
+                    None => ()
+                }
+                match *finally_block {
+                    Some(ref finally_block) => write!(f, " finally {}", finally_block),
+                    None => Ok(())
+                }
+            },
             SwitchExpr(ref val, ref vals, None) => write!(f, "switch({}){}", val, vals),
             SwitchExpr(ref val, ref vals, Some(ref def)) => write!(f, "switch({}){}default:{}", val, vals, def),
             ObjectDeclExpr(ref map) => write!(f, "{}", map),
+            AccessorExpr(ref getter, ref setter) => {
+                match *getter {
+                    Some(ref getter) => try!(write!(f, "get {}", getter)),
+                    None => ()
+                }
+                match *setter {
+                    Some(ref setter) => write!(f, "{}set {}", if getter.is_some() {" "} else {""}, setter),
+                    None => Ok(())
+                }
+            },
             ArrayDeclExpr(ref arr) => write!(f, "{}", arr),
-            FunctionDeclExpr(ref name, ref args, ref expr) => write!(f, "function {}({}){}", name, args.connect(", "), expr),
-            ArrowFunctionDeclExpr(ref args, ref expr) => write!(f, "({}) => {}", args.connect(", "), expr),
+            SpreadExpr(ref iterable) => write!(f, "...{}", iterable),
+            FunctionDeclExpr(Some(ref name), ref args, ref expr, _) => write!(f, "function {}({}){}", name, args.connect(", "), expr),
+            FunctionDeclExpr(None, ref args, ref expr, _) => write!(f, "function({}){}", args.connect(", "), expr),
+            GeneratorDeclExpr(Some(ref name), ref args, ref expr, _) => write!(f, "function* {}({}){}", name, args.connect(", "), expr),
+            GeneratorDeclExpr(None, ref args, ref expr, _) => write!(f, "function*({}){}", args.connect(", "), expr),
+            YieldExpr(delegate, ref value) => {
+                try!(write!(f, "{}", if delegate {"yield*"} else {"yield"}));
+                match *value {
+                    Some(ref value) => write!(f, " {}", value),
+                    None => Ok(())
+                }
+            },
+            ArrowFunctionDeclExpr(ref args, ref expr, _) => write!(f, "({}) => {}", args.connect(", "), expr),
+            ClassDeclExpr(ref name, ref parent, ref constructor, ref methods) => {
+                try!(write!(f, "class"));
+                match *name {
+                    Some(ref name) => try!(write!(f, " {}", name)),
+                    None => ()
+                }
+                match *parent {
+                    Some(ref parent) => try!(write!(f, " extends {}", parent)),
+                    None => ()
+                }
+                try!(write!(f, " {}constructor: {};", "{", constructor));
+                for &(is_static, ref kind, ref name, ref method) in methods.iter() {
+                    try!(write!(f, "{}{}{}: {};", if is_static {"static "} else {""}, match *kind {
+                        PlainMethod => "",
+                        GetterMethod => "get ",
+                        SetterMethod => "set "
+                    }, name, method));
+                }
+                write!(f, "{}", "}")
+            },
+            SuperExpr => write!(f, "{}", "super"),
+            NewTargetExpr => write!(f, "{}", "new.target"),
             BinOpExpr(ref op, ref a, ref b) => write!(f, "{} {} {}", a, op, b),
             UnaryOpExpr(ref op, ref a) => write!(f, "{}{}", op, a),
+            UpdateExpr(true, ref op, ref a) => write!(f, "{}{}", op, a),
+            UpdateExpr(false, ref op, ref a) => write!(f, "{}{}", a, op),
+            BreakExpr(Some(ref label)) => write!(f, "break {}", label),
+            BreakExpr(None) => write!(f, "{}", "break"),
+            ContinueExpr(Some(ref label)) => write!(f, "continue {}", label),
+            ContinueExpr(None) => write!(f, "{}", "continue"),
+            LabelledExpr(ref label, ref expr) => write!(f, "{}: {}", label, expr),
             ReturnExpr(Some(ref ex)) => write!(f, "return {}", ex),
             ReturnExpr(None) => write!(f, "{}", "return"),
             ThrowExpr(ref ex) => write!(f, "throw {}", ex),
             AssignExpr(ref ref_e, ref val) => write!(f, "{} = {}", ref_e, val),
+            DestructureExpr(ref pattern, ref val) => write!(f, "{} = {}", pattern, val),
+            AssignOpExpr(ref op, ref ref_e, ref val) => write!(f, "{} {}= {}", ref_e, op, val),
             VarDeclExpr(ref vars) => write!(f, "var {}", vars),
+            LetDeclExpr(ref vars) => write!(f, "let {}", vars),
+            ConstDeclExpr(ref vars) => write!(f, "const {}", vars),
+            ScriptExpr(ref stmts, _) | ModuleExpr(ref stmts) => {
+                for stmt in stmts.iter() {
+                    try!(write!(f, "{};", stmt));
+                }
+                Ok(())
+            },
+            ImportExpr(ref names, ref specifier) => {
+                try!(write!(f, "import {}", "{"));
+                for (i, &(ref imported, ref local)) in names.iter().enumerate() {
+                    try!(write!(f, "{}{} as {}", if i == 0 {""} else {", "}, imported, local));
+                }
+                write!(f, "{} from \"{}\"", "}", specifier)
+            },
+            ExportDeclExpr(ref decl) => write!(f, "export {}", decl),
+            ExportDefaultExpr(ref value) => write!(f, "export default {}", value),
+            ExportNamedExpr(ref names, ref specifier) => {
+                try!(write!(f, "export {}", "{"));
+                for (i, &(ref local, ref exported)) in names.iter().enumerate() {
+                    try!(write!(f, "{}{} as {}", if i == 0 {""} else {", "}, local, exported));
+                }
+                try!(write!(f, "{}", "}"));
+                match *specifier {
+                    Some(ref specifier) => write!(f, " from \"{}\"", specifier),
+                    None => Ok(())
+                }
+            },
+            ExportAllExpr(ref specifier) => write!(f, "export * from \"{}\"", specifier),
             TypeOfExpr(ref e) => write!(f, "typeof {}", e),
+            VoidExpr(ref e) => write!(f, "void {}", e),
+            DeleteExpr(ref e) => write!(f, "delete {}", e),
         }
     }
 }
\ No newline at end of file
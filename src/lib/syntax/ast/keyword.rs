@@ -11,6 +11,8 @@ pub enum Keyword {
     KCatch,
     /// The `class` keyword, which is reserved for future use
     KClass,
+    /// The `const` keyword
+    KConst,
     /// The `continue` keyword
     KContinue,
     /// The `debugger` keyword
@@ -25,6 +27,8 @@ pub enum Keyword {
     KElse,
     /// The `enum` keyword
     KEnum,
+    /// The `export` keyword
+    KExport,
     /// The `extends` keyword
     KExtends,
     /// The `finally` keyword
@@ -41,6 +45,8 @@ pub enum Keyword {
     KInstanceOf,
     /// The `import` keyword
     KImport,
+    /// The `let` keyword
+    KLet,
     /// The `new` keyword
     KNew,
     /// The `return` keyword
@@ -64,7 +70,9 @@ pub enum Keyword {
     /// The `while` keyword
     KWhile,
     /// The `with` keyword
-    KWith
+    KWith,
+    /// The `yield` keyword
+    KYield
 }
 impl FromStr for Keyword {
     fn from_str(s: &str) -> Option<Keyword> {
@@ -73,6 +81,7 @@ impl FromStr for Keyword {
             "case" => Some(KCase),
             "catch" => Some(KCatch),
             "class" => Some(KClass),
+            "const" => Some(KConst),
             "continue" => Some(KContinue),
             "debugger" => Some(KDebugger),
             "default" => Some(KDefault),
@@ -80,6 +89,7 @@ impl FromStr for Keyword {
             "do" => Some(KDo),
             "else" => Some(KElse),
             "enum" => Some(KEnum),
+            "export" => Some(KExport),
             "extends" => Some(KExtends),
             "finally" => Some(KFinally),
             "for" => Some(KFor),
@@ -88,6 +98,7 @@ impl FromStr for Keyword {
             "in" => Some(KIn),
             "instanceof" => Some(KInstanceOf),
             "import" => Some(KImport),
+            "let" => Some(KLet),
             "new" => Some(KNew),
             "return" => Some(KReturn),
             "super" => Some(KSuper),
@@ -100,6 +111,7 @@ impl FromStr for Keyword {
             "void" => Some(KVoid),
             "while" => Some(KWhile),
             "with" => Some(KWith),
+            "yield" => Some(KYield),
             _ => None
         }
     }
@@ -111,6 +123,7 @@ impl Show for Keyword {
             KCase => "case",
             KCatch => "catch",
             KClass => "class",
+            KConst => "const",
             KContinue => "continue",
             KDebugger => "debugger",
             KDefault => "default",
@@ -118,6 +131,7 @@ impl Show for Keyword {
             KDo => "do",
             KElse => "else",
             KEnum => "enum",
+            KExport => "export",
             KExtends => "extends",
             KFinally => "finally",
             KFor => "for",
@@ -126,6 +140,7 @@ impl Show for Keyword {
             KIn => "in",
             KInstanceOf => "instanceof",
             KImport => "import",
+            KLet => "let",
             KNew => "new",
             KReturn => "return",
             KSuper => "super",
@@ -137,7 +152,8 @@ impl Show for Keyword {
             KVar => "var",
             KVoid => "void",
             KWhile => "while",
-            KWith => "with"
+            KWith => "with",
+            KYield => "yield"
         })
     }
 }
\ No newline at end of file
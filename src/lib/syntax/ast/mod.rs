@@ -1,11 +1,17 @@
+/// Comments attached to expressions
+pub mod comment;
 /// Constants
 pub mod constant;
+/// Serialization to and from ESTree JSON
+pub mod estree;
 /// Expressions
 pub mod expr;
 /// Keywords
 pub mod keyword;
 /// Operators
 pub mod op;
+/// Destructuring patterns
+pub mod pattern;
 /// Positions
 pub mod pos;
 /// Punctuators
@@ -15,4 +21,6 @@ pub mod token;
 /// An expression typer
 pub mod typer;
 /// Types
-pub mod types;
\ No newline at end of file
+pub mod types;
+/// A visitor over expressions
+pub mod visitor;
\ No newline at end of file
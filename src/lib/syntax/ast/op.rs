@@ -23,7 +23,9 @@ pub enum NumOp {
     /// `a * b` - Multiplication
     OpMul,
     /// `a % b` - Modulus
-    OpMod
+    OpMod,
+    /// `a ** b` - Exponentiation
+    OpPow
 }
 impl Show for NumOp {
     fn fmt(&self, f: &mut Formatter) -> Result {
@@ -32,36 +34,46 @@ impl Show for NumOp {
             OpSub => "-",
             OpDiv => "/",
             OpMul => "*",
-            OpMod => "%"
+            OpMod => "%",
+            OpPow => "**"
+        })
+    }
+}
+#[deriving(Clone, PartialEq)]
+/// An update of the value stored in a variable or field
+pub enum UpdateOp {
+    /// `++a` / `a++` - increment the value
+    UpdateIncrement,
+    /// `--a` / `a--` - decrement the value
+    UpdateDecrement
+}
+impl Show for UpdateOp {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "{}", match *self {
+            UpdateIncrement => "++",
+            UpdateDecrement => "--"
         })
     }
 }
 #[deriving(Clone, PartialEq)]
 /// A unary operation on a single value
 pub enum UnaryOp {
-    /// `a++` - increment the value
-    UnaryIncrementPost,
-    /// `++a` - increment the value
-    UnaryIncrementPre,
-    /// `a--` - decrement the value
-    UnaryDecrementPost,
-    /// `--a` - decrement the value
-    UnaryDecrementPre,
     /// `-a` - negate the value
     UnaryMinus,
     /// `+a` - convert to a number
     UnaryPlus,
     /// `!a` - get the opposite of the boolean value
-    UnaryNot
+    UnaryNot,
+    /// `~a` - invert the bits of the value as a 32-bit integer
+    UnaryBitNot
 }
 impl Show for UnaryOp {
     fn fmt(&self, f: &mut Formatter) -> Result {
         write!(f, "{}", match *self {
-            UnaryIncrementPost | UnaryIncrementPre => "++",
-            UnaryDecrementPost | UnaryDecrementPre => "--",
             UnaryPlus => "+",
             UnaryMinus => "-",
-            UnaryNot => "!"
+            UnaryNot => "!",
+            UnaryBitNot => "~"
         })
     }
 }
@@ -77,7 +89,9 @@ pub enum BitOp {
     /// `a << b` - Bit-shift leftwards
     BitShl,
     /// `a >> b` - Bit-shift rightrights
-    BitShr
+    BitShr,
+    /// `a >>> b` - Bit-shift rightwards, filling in zeroes from the left
+    BitUShr
 }
 impl Show for BitOp {
     fn fmt(&self, f: &mut Formatter) -> Result {
@@ -86,7 +100,8 @@ impl Show for BitOp {
             BitOr => "|",
             BitXor => "^",
             BitShl => "<<",
-            BitShr => ">>"
+            BitShr => ">>",
+            BitUShr => ">>>"
         })
     }
 }
@@ -130,13 +145,16 @@ pub enum LogOp {
     /// `a && b` - Logical and
     LogAnd,
     /// `a || b` - Logical or
-    LogOr
+    LogOr,
+    /// `a ?? b` - Nullish coalescing, which gives `b` only if `a` is `null` or `undefined`
+    LogCoalesce
 }
 impl Show for LogOp {
     fn fmt(&self, f: &mut Formatter) -> Result {
         write!(f, "{}", match *self {
             LogAnd => "&&",
-            LogOr => "||"
+            LogOr => "||",
+            LogCoalesce => "??"
         })
     }
 }
@@ -154,20 +172,24 @@ pub enum BinOp {
 }
 impl Operator for BinOp {
     fn get_assoc(&self) -> bool {
-        true
+        match *self {
+            BinNum(OpPow) => true,
+            _ => false
+        }
     }
     fn get_precedence(&self) -> uint {
         match *self {
-            BinNum(OpMul) | BinNum(OpDiv) | BinNum(OpMod) => 5,
-            BinNum(OpAdd) | BinNum(OpSub) => 6,
-            BinBit(BitShl) | BinBit(BitShr) => 7,
-            BinComp(CompLessThan) | BinComp(CompLessThanOrEqual) | BinComp(CompGreaterThan) | BinComp(CompGreaterThanOrEqual) => 8,
-            BinComp(CompEqual) | BinComp(CompNotEqual) | BinComp(CompStrictEqual) | BinComp(CompStrictNotEqual) => 9,
-            BinBit(BitAnd) => 10,
-            BinBit(BitXor) => 11,
-            BinBit(BitOr) => 12,
-            BinLog(LogAnd) => 13,
-            BinLog(LogOr) => 14,
+            BinNum(OpPow) => 5,
+            BinNum(OpMul) | BinNum(OpDiv) | BinNum(OpMod) => 6,
+            BinNum(OpAdd) | BinNum(OpSub) => 7,
+            BinBit(BitShl) | BinBit(BitShr) | BinBit(BitUShr) => 8,
+            BinComp(CompLessThan) | BinComp(CompLessThanOrEqual) | BinComp(CompGreaterThan) | BinComp(CompGreaterThanOrEqual) => 9,
+            BinComp(CompEqual) | BinComp(CompNotEqual) | BinComp(CompStrictEqual) | BinComp(CompStrictNotEqual) => 10,
+            BinBit(BitAnd) => 11,
+            BinBit(BitXor) => 12,
+            BinBit(BitOr) => 13,
+            BinLog(LogAnd) => 14,
+            BinLog(LogOr) | BinLog(LogCoalesce) => 15,
             
         }
     }
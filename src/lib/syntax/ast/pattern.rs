@@ -0,0 +1,59 @@
+use std::fmt::{Formatter, Result, Show};
+use syntax::ast::expr::{Expr, LocalExpr};
+#[deriving(Clone, PartialEq)]
+/// A pattern that a value is bound to in a declaration, or assigned to in a destructuring assignment
+pub enum Pattern {
+    /// Store the value in a variable or field, which is always a variable in a declaration
+    RefPattern(Box<Expr>),
+    /// Match the fields of an object with the names given against patterns
+    ObjectPattern(Vec<(String, Pattern)>),
+    /// Match the items of an iterable value against patterns, skipping the holes
+    ArrayPattern(Vec<Option<Pattern>>),
+    /// Match the value against the pattern, using the default value given if it is `undefined`
+    DefaultPattern(Box<Pattern>, Box<Expr>)
+}
+impl Pattern {
+    /// Add the names of the variables bound by the pattern to `names`
+    pub fn add_names(&self, names:&mut Vec<String>) {
+        match *self {
+            RefPattern(box Expr{def: LocalExpr(ref name), ..}) => names.push(name.clone()),
+            RefPattern(_) => (),
+            ObjectPattern(ref fields) => for &(_, ref field) in fields.iter() {
+                field.add_names(names);
+            },
+            ArrayPattern(ref items) => for item in items.iter() {
+                match *item {
+                    Some(ref item) => item.add_names(names),
+                    None => ()
+                }
+            },
+            DefaultPattern(box ref pattern, _) => pattern.add_names(names)
+        }
+    }
+}
+impl Show for Pattern {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match *self {
+            RefPattern(ref target) => write!(f, "{}", target),
+            ObjectPattern(ref fields) => {
+                try!(write!(f, "{}", "{"));
+                for (i, &(ref name, ref pattern)) in fields.iter().enumerate() {
+                    try!(write!(f, "{}{}: {}", if i == 0 {""} else {", "}, name, pattern));
+                }
+                write!(f, "{}", "}")
+            },
+            ArrayPattern(ref items) => {
+                try!(write!(f, "["));
+                for (i, item) in items.iter().enumerate() {
+                    try!(write!(f, "{}", if i == 0 {""} else {", "}));
+                    match *item {
+                        Some(ref pattern) => try!(write!(f, "{}", pattern)),
+                        None => ()
+                    }
+                }
+                write!(f, "]")
+            },
+            DefaultPattern(ref pattern, ref default) => write!(f, "{} = {}", pattern, default)
+        }
+    }
+}
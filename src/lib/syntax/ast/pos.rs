@@ -1,17 +1,31 @@
+use collections::TreeMap;
+use serialize::json::{ToJson, Json, Object};
 #[deriving(Clone, PartialEq)]
 /// A position in Javascript source code
 pub struct Position {
     /// The column number
     pub column_number : uint,
     /// The line number
-    pub line_number : uint
+    pub line_number : uint,
+    /// The number of bytes from the start of the source
+    pub offset : uint
 }
 impl Position {
     /// Create a new position
-    pub fn new(line_number: uint, column_number: uint) -> Position {
+    pub fn new(line_number: uint, column_number: uint, offset: uint) -> Position {
         Position {
             line_number: line_number,
-            column_number: column_number
+            column_number: column_number,
+            offset: offset
         }
     }
-}
\ No newline at end of file
+}
+impl ToJson for Position {
+    fn to_json(&self) -> Json {
+        let mut obj = TreeMap::new();
+        obj.insert("line".into_string(), self.line_number.to_json());
+        obj.insert("column".into_string(), self.column_number.to_json());
+        obj.insert("offset".into_string(), self.offset.to_json());
+        Object(obj)
+    }
+}
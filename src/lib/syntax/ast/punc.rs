@@ -1,4 +1,5 @@
 use std::fmt::{Formatter, Result, Show};
+use syntax::ast::op::*;
 #[deriving(PartialEq, Clone)]
 /// Punctuation
 pub enum Punctuator {
@@ -16,6 +17,10 @@ pub enum Punctuator {
     PCloseBracket,
     /// `.`
     PDot,
+    /// `?.`
+    POptionalChain,
+    /// `...`
+    PSpread,
     /// `;`
     PSemicolon,
     /// `,`
@@ -46,6 +51,8 @@ pub enum Punctuator {
     PDiv,
     /// `%`
     PMod,
+    /// `**`
+    PPow,
     /// `++`
     PInc,
     /// `--`
@@ -70,6 +77,8 @@ pub enum Punctuator {
     PBoolAnd,
     /// `||`
     PBoolOr,
+    /// `??`
+    PCoalesce,
     /// `?`
     PQuestion,
     /// `:`
@@ -86,6 +95,8 @@ pub enum Punctuator {
     PAssignDiv,
     /// `%=`
     PAssignMod,
+    /// `**=`
+    PAssignPow,
     /// `<<=`
     PAssignLeftSh,
     /// `>>=`
@@ -98,9 +109,67 @@ pub enum Punctuator {
     PAssignOr,
     /// `^=`
     PAssignXor,
+    /// `&&=`
+    PAssignBoolAnd,
+    /// `||=`
+    PAssignBoolOr,
+    /// `??=`
+    PAssignCoalesce,
     /// `=>`
     PArrow
 }
+impl Punctuator {
+    /// Get the binary operator that this punctuator stands for, if it is one
+    pub fn as_binop(&self) -> Option<BinOp> {
+        Some(match *self {
+            PAdd => BinNum(OpAdd),
+            PSub => BinNum(OpSub),
+            PMul => BinNum(OpMul),
+            PDiv => BinNum(OpDiv),
+            PMod => BinNum(OpMod),
+            PPow => BinNum(OpPow),
+            PBoolAnd => BinLog(LogAnd),
+            PBoolOr => BinLog(LogOr),
+            PCoalesce => BinLog(LogCoalesce),
+            PAnd => BinBit(BitAnd),
+            POr => BinBit(BitOr),
+            PXor => BinBit(BitXor),
+            PLeftSh => BinBit(BitShl),
+            PRightSh => BinBit(BitShr),
+            PURightSh => BinBit(BitUShr),
+            PEq => BinComp(CompEqual),
+            PNotEq => BinComp(CompNotEqual),
+            PStrictEq => BinComp(CompStrictEqual),
+            PStrictNotEq => BinComp(CompStrictNotEqual),
+            PLessThan => BinComp(CompLessThan),
+            PLessThanOrEq => BinComp(CompLessThanOrEqual),
+            PGreaterThan => BinComp(CompGreaterThan),
+            PGreaterThanOrEq => BinComp(CompGreaterThanOrEqual),
+            _ => return None
+        })
+    }
+    /// Get the binary operator that this compound assignment runs before assigning, if it is one
+    pub fn as_assign_op(&self) -> Option<BinOp> {
+        Some(match *self {
+            PAssignAdd => BinNum(OpAdd),
+            PAssignSub => BinNum(OpSub),
+            PAssignMul => BinNum(OpMul),
+            PAssignDiv => BinNum(OpDiv),
+            PAssignMod => BinNum(OpMod),
+            PAssignPow => BinNum(OpPow),
+            PAssignAnd => BinBit(BitAnd),
+            PAssignOr => BinBit(BitOr),
+            PAssignXor => BinBit(BitXor),
+            PAssignLeftSh => BinBit(BitShl),
+            PAssignRightSh => BinBit(BitShr),
+            PAssignURightSh => BinBit(BitUShr),
+            PAssignBoolAnd => BinLog(LogAnd),
+            PAssignBoolOr => BinLog(LogOr),
+            PAssignCoalesce => BinLog(LogCoalesce),
+            _ => return None
+        })
+    }
+}
 impl Show for Punctuator {
     fn fmt(&self, f: &mut Formatter) -> Result {
         write!(f, "{}", match *self {
@@ -111,6 +180,8 @@ impl Show for Punctuator {
             POpenBracket => "[",
             PCloseBracket => "]",
             PDot => ".",
+            POptionalChain => "?.",
+            PSpread => "...",
             PSemicolon => ";",
             PComma => ",",
             PLessThan => "<",
@@ -126,6 +197,7 @@ impl Show for Punctuator {
             PMul => "*",
             PDiv => "/",
             PMod => "%",
+            PPow => "**",
             PInc => "++",
             PDec => "--",
             PLeftSh => "<<",
@@ -138,6 +210,7 @@ impl Show for Punctuator {
             PNeg => "~",
             PBoolAnd => "&&",
             PBoolOr => "||",
+            PCoalesce => "??",
             PQuestion => "?",
             PColon => ":",
             PAssign => "=",
@@ -146,12 +219,16 @@ impl Show for Punctuator {
             PAssignMul => "*=",
             PAssignDiv => "/=",
             PAssignMod => "%=",
+            PAssignPow => "**=",
             PAssignLeftSh => "<<=",
             PAssignRightSh => ">>=",
             PAssignURightSh => ">>>=",
             PAssignAnd => "&=",
             PAssignOr => "|=",
             PAssignXor => "^=",
+            PAssignBoolAnd => "&&=",
+            PAssignBoolOr => "||=",
+            PAssignCoalesce => "??=",
             PArrow => "=>"
         })
     }
@@ -1,21 +1,46 @@
 use std::fmt::{Formatter, Result, Show};
+use collections::TreeMap;
+use serialize::json::{ToJson, Json, Object};
 use syntax::ast::pos::Position;
 use syntax::ast::punc::Punctuator;
 use syntax::ast::keyword::Keyword;
+use num::bigint::BigInt;
 #[deriving(Clone, PartialEq)]
 /// A single of token of Javascript code including its position
 pub struct Token {
     /// The token
     pub data : TokenData,
-    /// The token's position
-    pub pos : Position
+    /// The position of the token's first character
+    pub pos : Position,
+    /// The position just after the token's last character
+    pub end : Position,
+    /// The source text of the token, exactly as it was written
+    pub raw : String,
+    /// Whether the token uses a legacy octal number or escape, such as `012`
+    /// or `'\12'`, which aren't allowed in strict mode
+    pub legacy_octal : bool,
+    /// Whether there was a line terminator between the token and the one
+    /// before it, which matters for automatic semicolon insertion
+    pub newline_before : bool,
+    /// The whitespace and comments before the token, if the lexer is keeping trivia
+    pub leading : Vec<Trivia>,
+    /// The whitespace and comments after the token on the same line, if the
+    /// lexer is keeping trivia
+    pub trailing : Vec<Trivia>
 }
 impl Token {
-    /// Create a new detailed token from the token data, line number and column number
-    pub fn new(data: TokenData, line_number: uint, column_number: uint) -> Token {
+    /// Create a new detailed token from the token data and the positions it
+    /// starts and ends at
+    pub fn new(data: TokenData, pos: Position, end: Position) -> Token {
         Token {
             data: data,
-            pos: Position::new(line_number, column_number)
+            pos: pos,
+            end: end,
+            raw: String::new(),
+            legacy_octal: false,
+            newline_before: false,
+            leading: Vec::new(),
+            trailing: Vec::new()
         }
     }
 }
@@ -24,6 +49,17 @@ impl Show for Token {
         write!(f, "{}", self.data)
     }
 }
+impl ToJson for Token {
+    fn to_json(&self) -> Json {
+        let mut obj = TreeMap::new();
+        obj.insert("type".into_string(), self.data.kind().to_string().to_json());
+        obj.insert("value".into_string(), self.data.to_string().to_json());
+        obj.insert("raw".into_string(), self.raw.to_json());
+        obj.insert("start".into_string(), self.pos.to_json());
+        obj.insert("end".into_string(), self.end.to_json());
+        Object(obj)
+    }
+}
 #[deriving(Clone, PartialEq)]
 /// A single token of Javacript code - a single word, symbol or constant
 pub enum TokenData {
@@ -39,15 +75,68 @@ pub enum TokenData {
     TNullLiteral,
     /// A numeric literal
     TNumericLiteral(f64),
+    /// An integer literal with an `n` after it, which can be as large as needed
+    TBigInt(BigInt),
     /// A piece of punctuation
     TPunctuator(Punctuator),
     /// A string literal
     TStringLiteral(String),
-    /// A regular expression
-    TRegularExpression(String),
+    /// A regular expression, consisting of the body and the flags
+    TRegularExpression(String, String),
+    /// A template literal without any substitutions, such as `` `hello` ``
+    TTemplate(String),
+    /// The start of a template literal up to its first substitution, such as `` `hello ${ ``
+    TTemplateHead(String),
+    /// The part of a template literal between two substitutions, such as `} and ${`
+    TTemplateMiddle(String),
+    /// The end of a template literal after its last substitution, such as `` }!` ``
+    TTemplateTail(String),
+    /// The `<` that starts a JSX opening tag, such as `<div>`
+    TJSXTagStart,
+    /// The `</` that starts a JSX closing tag, such as `</div>`
+    TJSXClosingTagStart,
+    /// The `>` that ends a JSX tag
+    TJSXTagEnd,
+    /// The `/>` that ends a self-closing JSX tag, such as `<br/>`
+    TJSXSelfClosingTagEnd,
+    /// The name of a JSX element or attribute, such as `data-id`
+    TJSXIdentifier(String),
+    /// The value of a JSX attribute written as a string, which can't have escapes
+    TJSXString(String),
+    /// The text between JSX tags
+    TJSXText(String),
     /// A comment
     TComment(String)
 }
+impl TokenData {
+    /// Get the name of the kind of token this is
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            TBooleanLiteral(_) => "BooleanLiteral",
+            TEOF => "EOF",
+            TIdentifier(_) => "Identifier",
+            TKeyword(_) => "Keyword",
+            TNullLiteral => "NullLiteral",
+            TNumericLiteral(_) => "NumericLiteral",
+            TBigInt(_) => "BigInt",
+            TPunctuator(_) => "Punctuator",
+            TStringLiteral(_) => "StringLiteral",
+            TRegularExpression(_, _) => "RegularExpression",
+            TTemplate(_) => "Template",
+            TTemplateHead(_) => "TemplateHead",
+            TTemplateMiddle(_) => "TemplateMiddle",
+            TTemplateTail(_) => "TemplateTail",
+            TJSXTagStart => "JSXTagStart",
+            TJSXClosingTagStart => "JSXClosingTagStart",
+            TJSXTagEnd => "JSXTagEnd",
+            TJSXSelfClosingTagEnd => "JSXSelfClosingTagEnd",
+            TJSXIdentifier(_) => "JSXIdentifier",
+            TJSXString(_) => "JSXString",
+            TJSXText(_) => "JSXText",
+            TComment(_) => "Comment"
+        }
+    }
+}
 impl Show for TokenData {
     fn fmt(&self, f: &mut Formatter) -> Result {
         match self.clone() {
@@ -57,10 +146,43 @@ impl Show for TokenData {
             TKeyword(word) => write!(f, "{}", word),
             TNullLiteral => write!(f, "null"),
             TNumericLiteral(num) => write!(f, "{}", num),
+            TBigInt(num) => write!(f, "{}n", num),
             TPunctuator(punc) => write!(f, "{}", punc),
             TStringLiteral(lit) => write!(f, "{}", lit),
-            TRegularExpression(reg) => write!(f, "{}", reg),
+            TRegularExpression(body, flags) => write!(f, "/{}/{}", body, flags),
+            TTemplate(text) => write!(f, "`{}`", text),
+            TTemplateHead(text) => write!(f, "`{}${}", text, "{"),
+            TTemplateMiddle(text) => write!(f, "{}{}${}", "}", text, "{"),
+            TTemplateTail(text) => write!(f, "{}{}`", "}", text),
+            TJSXTagStart => write!(f, "<"),
+            TJSXClosingTagStart => write!(f, "</"),
+            TJSXTagEnd => write!(f, ">"),
+            TJSXSelfClosingTagEnd => write!(f, "/>"),
+            TJSXIdentifier(name) => write!(f, "{}", name),
+            TJSXString(text) => write!(f, "\"{}\"", text),
+            TJSXText(text) => write!(f, "{}", text),
             TComment(comm) => write!(f, "/*{}*/", comm)
         }
     }
-}
\ No newline at end of file
+}#[deriving(Clone, PartialEq)]
+/// Source text between tokens that doesn't change the meaning of the script
+pub enum Trivia {
+    /// A run of whitespace, including line terminators
+    WhitespaceTrivia(String),
+    /// A `//` comment, not including the line terminator after it
+    LineCommentTrivia(String),
+    /// A `/* */` comment
+    BlockCommentTrivia(String),
+    /// A `<!--` or `-->` comment, including the characters that start it
+    HtmlCommentTrivia(String)
+}
+impl Show for Trivia {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match *self {
+            WhitespaceTrivia(ref text) => write!(f, "{}", text),
+            LineCommentTrivia(ref comm) => write!(f, "//{}", comm),
+            BlockCommentTrivia(ref comm) => write!(f, "/*{}*/", comm),
+            HtmlCommentTrivia(ref comm) => write!(f, "{}", comm)
+        }
+    }
+}
@@ -10,7 +10,9 @@ pub fn resolve_type(expr:&Expr) -> Type {
             IntegerType,
         ConstExpr(CNum(_)) =>
             NumberType,
-        ConstExpr(CRegExp(_, _, _)) =>
+        ConstExpr(CBigInt(_)) =>
+            BigIntType,
+        ConstExpr(CRegExp(_, _)) =>
             NativeObjectType,
         ConstExpr(CBool(_)) =>
             BooleanType,
@@ -18,7 +20,7 @@ pub fn resolve_type(expr:&Expr) -> Type {
             NullType,
         ConstExpr(CUndefined) =>
             UndefinedType,
-        ConstExpr(CString(_)) =>
+        ConstExpr(CString(_)) | TemplateExpr(_, _) =>
             StringType,
         ObjectDeclExpr(_) =>
             ObjectType,
@@ -30,34 +32,55 @@ pub fn resolve_type(expr:&Expr) -> Type {
                     StringType,
                 (IntegerType, IntegerType) =>
                     IntegerType,
+                (BigIntType, BigIntType) =>
+                    BigIntType,
+                _ => NumberType
+            }
+        },
+        BinOpExpr(BinNum(_), box ref a, box ref b) => {
+            match (resolve_type(a), resolve_type(b)) {
+                (BigIntType, BigIntType) =>
+                    BigIntType,
                 _ => NumberType
             }
         },
-        BinOpExpr(BinNum(_), _, _) =>
-            NumberType,
         BinOpExpr(BinBit(_), _, _) =>
             IntegerType,
         BinOpExpr(BinComp(_), _, _) =>
             BooleanType,
+        BinOpExpr(BinLog(LogCoalesce), box ref a, box ref b) => {
+            match resolve_type(a) {
+                NullType | UndefinedType => resolve_type(b),
+                AnyType => AnyType,
+                AnyOfType(_) => AnyOfType(vec!(resolve_type(a), resolve_type(b))),
+                a_type => a_type
+            }
+        },
         BinOpExpr(BinLog(_), _, _) =>
             BooleanType,
         UnaryOpExpr(UnaryNot, _) =>
             BooleanType,
+        UnaryOpExpr(UnaryBitNot, _) =>
+            IntegerType,
         UnaryOpExpr(UnaryPlus, box ref inner) | UnaryOpExpr(UnaryMinus, box ref inner) =>
             resolve_type(inner),
-        UnaryOpExpr(_, _) =>
+        UnaryOpExpr(_, _) | UpdateExpr(_, _, _) =>
             NumberType,
         BlockExpr(ref exprs) =>
             resolve_type(&exprs[exprs.len() - 1]),
-        LocalExpr(_) =>
+        LocalExpr(_) | ThisExpr | SuperExpr | SpreadExpr(_) | AccessorExpr(_, _) | YieldExpr(_, _) | NewTargetExpr =>
             AnyType,
         GetConstFieldExpr(_, _) =>
             AnyType,
         GetFieldExpr(_, _) =>
             AnyType,
+        OptionalChainExpr(_) =>
+            AnyType,
+        OptionalExpr(box ref ex) =>
+            resolve_type(ex),
         CallExpr(_, _) =>
             AnyType,
-        WhileLoopExpr(_, _) =>
+        WhileLoopExpr(_, _) | ForLoopExpr(_, _, _, _) | ForInExpr(_, _, _) | ForOfExpr(_, _, _) =>
             UndefinedType,
         IfExpr(_, box ref if_expr, Some(box ref else_expr)) => {
             let if_type = resolve_type(if_expr);
@@ -77,6 +100,17 @@ pub fn resolve_type(expr:&Expr) -> Type {
                 AnyOfType(vec!(if_type, else_type))
             }
         },
+        TryExpr(box ref block, _, Some(box ref catch_block), _) => {
+            let try_type = resolve_type(block);
+            let catch_type = resolve_type(catch_block);
+            if try_type == catch_type {
+                try_type
+            } else {
+                AnyOfType(vec!(try_type, catch_type))
+            }
+        },
+        TryExpr(box ref block, _, None, _) =>
+            resolve_type(block),
         SwitchExpr(_, ref matches, None) => {
             AnyOfType(matches.iter().map(|&(_, ref block)| resolve_type(&block[block.len() - 1])).collect())
         },
@@ -85,19 +119,31 @@ pub fn resolve_type(expr:&Expr) -> Type {
             types.push(resolve_type(def));
             AnyOfType(types)
         },
-        FunctionDeclExpr(_, _, _) | ArrowFunctionDeclExpr(_, _) =>
+        FunctionDeclExpr(_, _, _, _) | GeneratorDeclExpr(_, _, _, _) | ArrowFunctionDeclExpr(_, _, _) | ClassDeclExpr(_, _, _, _) =>
             FunctionType,
         ConstructExpr(_, _) =>
             ObjectType,
-        ReturnExpr(_) =>
+        ReturnExpr(_) | BreakExpr(_) | ContinueExpr(_) =>
             UndefinedType,
+        LabelledExpr(_, box ref expr) =>
+            resolve_type(expr),
         ThrowExpr(_) =>
             UndefinedType,
-        AssignExpr(_, box ref what) =>
+        AssignExpr(_, box ref what) | DestructureExpr(_, box ref what) =>
             resolve_type(what),
-        VarDeclExpr(_) =>
+        AssignOpExpr(op, box ref a, box ref b) =>
+            resolve_type(&Expr::new(BinOpExpr(op, box a.clone(), box b.clone()), a.start, b.end)),
+        VarDeclExpr(_) | LetDeclExpr(_) | ConstDeclExpr(_) =>
+            UndefinedType,
+        ScriptExpr(ref stmts, _) if stmts.len() > 0 =>
+            resolve_type(&stmts[stmts.len() - 1]),
+        ScriptExpr(_, _) | ModuleExpr(_) | ImportExpr(_, _) | ExportDeclExpr(_) | ExportDefaultExpr(_) | ExportNamedExpr(_, _) | ExportAllExpr(_) =>
             UndefinedType,
         TypeOfExpr(_) =>
-            StringType
+            StringType,
+        VoidExpr(_) =>
+            UndefinedType,
+        DeleteExpr(_) =>
+            BooleanType
     }
 }
\ No newline at end of file
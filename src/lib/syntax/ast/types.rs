@@ -13,6 +13,8 @@ pub enum Type {
     NumberType,
     /// `number`
     IntegerType,
+    /// `bigint`
+    BigIntType,
     /// `string`
     StringType,
     /// A native object
@@ -46,6 +48,8 @@ impl Show for Type {
                 write!(f, "boolean"),
             NumberType | IntegerType =>
                 write!(f, "number"),
+            BigIntType =>
+                write!(f, "bigint"),
             StringType =>
                 write!(f, "string"),
             NativeObjectType =>
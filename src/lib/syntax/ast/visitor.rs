@@ -0,0 +1,350 @@
+use syntax::ast::expr::*;
+use syntax::ast::op::{BinOp, UnaryOp, UpdateOp};
+use syntax::ast::constant::Const;
+use syntax::ast::pattern::{Pattern, RefPattern, ObjectPattern, ArrayPattern, DefaultPattern};
+use collections::treemap::TreeMap;
+/**
+ * A visitor over an expression and the expressions inside of it, which by
+ * default visits each of the expressions inside of the one it is visiting,
+ * so a visitor only has to override the methods for the expressions it is
+ * interested in.
+*/
+pub trait Visitor {
+    /// Visit an expression, which by default visits it with the method for its kind
+    fn visit(&mut self, expr:&Expr) {
+        walk(self, expr)
+    }
+    /// Visit an expression if there is one
+    fn visit_opt(&mut self, expr:&Option<Box<Expr>>) {
+        match *expr {
+            Some(box ref expr) => self.visit(expr),
+            None => ()
+        }
+    }
+    /// Visit several expressions in order
+    fn visit_all(&mut self, exprs:&Vec<Expr>) {
+        for expr in exprs.iter() {
+            self.visit(expr);
+        }
+    }
+    /// Visit a pattern, visiting the references and default values inside of it
+    fn visit_pattern(&mut self, pattern:&Pattern) {
+        match *pattern {
+            RefPattern(box ref target) => self.visit(target),
+            ObjectPattern(ref fields) => for &(_, ref field) in fields.iter() {
+                self.visit_pattern(field);
+            },
+            ArrayPattern(ref items) => for item in items.iter() {
+                match *item {
+                    Some(ref item) => self.visit_pattern(item),
+                    None => ()
+                }
+            },
+            DefaultPattern(box ref pattern, box ref default) => {
+                self.visit_pattern(pattern);
+                self.visit(default);
+            }
+        }
+    }
+    /// Visit the patterns and values of a declaration
+    fn visit_decls(&mut self, vars:&Vec<(Pattern, Option<Expr>)>) {
+        for &(ref pattern, ref value) in vars.iter() {
+            self.visit_pattern(pattern);
+            match *value {
+                Some(ref value) => self.visit(value),
+                None => ()
+            }
+        }
+    }
+    /// Visit a binary operation
+    fn visit_bin_op(&mut self, _:BinOp, left:&Expr, right:&Expr) {
+        self.visit(left);
+        self.visit(right);
+    }
+    /// Visit a unary operation
+    fn visit_unary_op(&mut self, _:UnaryOp, operand:&Expr) {
+        self.visit(operand);
+    }
+    /// Visit an increment or decrement
+    fn visit_update(&mut self, _:bool, _:UpdateOp, target:&Expr) {
+        self.visit(target);
+    }
+    /// Visit a constant
+    fn visit_const(&mut self, _:&Const) {
+    }
+    /// Visit a template literal
+    fn visit_template(&mut self, _:&Vec<String>, exprs:&Vec<Expr>) {
+        self.visit_all(exprs);
+    }
+    /// Visit a block
+    fn visit_block(&mut self, exprs:&Vec<Expr>) {
+        self.visit_all(exprs);
+    }
+    /// Visit a local variable
+    fn visit_local(&mut self, _:&String) {
+    }
+    /// Visit a `this`
+    fn visit_this(&mut self) {
+    }
+    /// Visit a field access with a constant name
+    fn visit_get_const_field(&mut self, obj:&Expr, _:&String) {
+        self.visit(obj);
+    }
+    /// Visit a field access
+    fn visit_get_field(&mut self, obj:&Expr, field:&Expr) {
+        self.visit(obj);
+        self.visit(field);
+    }
+    /// Visit an optional chain
+    fn visit_optional_chain(&mut self, chain:&Expr) {
+        self.visit(chain);
+    }
+    /// Visit the object of an optional access or call
+    fn visit_optional(&mut self, obj:&Expr) {
+        self.visit(obj);
+    }
+    /// Visit a function call
+    fn visit_call(&mut self, func:&Expr, args:&Vec<Expr>) {
+        self.visit(func);
+        self.visit_all(args);
+    }
+    /// Visit a while loop
+    fn visit_while_loop(&mut self, cond:&Expr, body:&Expr) {
+        self.visit(cond);
+        self.visit(body);
+    }
+    /// Visit a for loop
+    fn visit_for_loop(&mut self, init:&Option<Box<Expr>>, cond:&Option<Box<Expr>>, step:&Option<Box<Expr>>, body:&Expr) {
+        self.visit_opt(init);
+        self.visit_opt(cond);
+        self.visit_opt(step);
+        self.visit(body);
+    }
+    /// Visit a for-in loop
+    fn visit_for_in(&mut self, name:&Expr, obj:&Expr, body:&Expr) {
+        self.visit(name);
+        self.visit(obj);
+        self.visit(body);
+    }
+    /// Visit a for-of loop
+    fn visit_for_of(&mut self, name:&Expr, iterable:&Expr, body:&Expr) {
+        self.visit(name);
+        self.visit(iterable);
+        self.visit(body);
+    }
+    /// Visit an if statement or conditional expression
+    fn visit_if(&mut self, cond:&Expr, if_expr:&Expr, else_expr:&Option<Box<Expr>>) {
+        self.visit(cond);
+        self.visit(if_expr);
+        self.visit_opt(else_expr);
+    }
+    /// Visit a try statement
+    fn visit_try(&mut self, block:&Expr, _:&Option<String>, catch_block:&Option<Box<Expr>>, finally_block:&Option<Box<Expr>>) {
+        self.visit(block);
+        self.visit_opt(catch_block);
+        self.visit_opt(finally_block);
+    }
+    /// Visit a break statement
+    fn visit_break(&mut self, _:&Option<String>) {
+    }
+    /// Visit a continue statement
+    fn visit_continue(&mut self, _:&Option<String>) {
+    }
+    /// Visit a labelled statement
+    fn visit_labelled(&mut self, _:&String, expr:&Expr) {
+        self.visit(expr);
+    }
+    /// Visit a switch statement
+    fn visit_switch(&mut self, value:&Expr, cases:&Vec<(Expr, Vec<Expr>)>, default:&Option<Box<Expr>>) {
+        self.visit(value);
+        for &(ref cond, ref block) in cases.iter() {
+            self.visit(cond);
+            self.visit_all(block);
+        }
+        self.visit_opt(default);
+    }
+    /// Visit an object declaration
+    fn visit_object_decl(&mut self, fields:&TreeMap<String, Expr>) {
+        for (_, value) in fields.iter() {
+            self.visit(value);
+        }
+    }
+    /// Visit a property with a getter or setter
+    fn visit_accessor(&mut self, getter:&Option<Box<Expr>>, setter:&Option<Box<Expr>>) {
+        self.visit_opt(getter);
+        self.visit_opt(setter);
+    }
+    /// Visit an array declaration
+    fn visit_array_decl(&mut self, items:&Vec<Expr>) {
+        self.visit_all(items);
+    }
+    /// Visit a spread argument or item
+    fn visit_spread(&mut self, iterable:&Expr) {
+        self.visit(iterable);
+    }
+    /// Visit a function declaration or expression
+    fn visit_function_decl(&mut self, _:&Option<String>, _:&Vec<String>, body:&Expr, _:bool) {
+        self.visit(body);
+    }
+    /// Visit a generator function declaration or expression
+    fn visit_generator_decl(&mut self, _:&Option<String>, _:&Vec<String>, body:&Expr, _:bool) {
+        self.visit(body);
+    }
+    /// Visit a `yield` or `yield*`
+    fn visit_yield(&mut self, _:bool, value:&Option<Box<Expr>>) {
+        self.visit_opt(value);
+    }
+    /// Visit an arrow function declaration
+    fn visit_arrow_function_decl(&mut self, _:&Vec<String>, body:&Expr, _:bool) {
+        self.visit(body);
+    }
+    /// Visit a class declaration
+    fn visit_class_decl(&mut self, _:&Option<String>, parent:&Option<Box<Expr>>, constructor:&Expr, methods:&Vec<(bool, MethodKind, String, Expr)>) {
+        self.visit_opt(parent);
+        self.visit(constructor);
+        for &(_, _, _, ref method) in methods.iter() {
+            self.visit(method);
+        }
+    }
+    /// Visit a `super`
+    fn visit_super(&mut self) {
+    }
+    /// Visit a `new.target`
+    fn visit_new_target(&mut self) {
+    }
+    /// Visit a construction of an object
+    fn visit_construct(&mut self, func:&Expr, args:&Vec<Expr>) {
+        self.visit(func);
+        self.visit_all(args);
+    }
+    /// Visit a return statement
+    fn visit_return(&mut self, value:&Option<Box<Expr>>) {
+        self.visit_opt(value);
+    }
+    /// Visit a throw statement
+    fn visit_throw(&mut self, value:&Expr) {
+        self.visit(value);
+    }
+    /// Visit an assignment
+    fn visit_assign(&mut self, target:&Expr, value:&Expr) {
+        self.visit(target);
+        self.visit(value);
+    }
+    /// Visit a destructuring assignment
+    fn visit_destructure(&mut self, pattern:&Pattern, value:&Expr) {
+        self.visit_pattern(pattern);
+        self.visit(value);
+    }
+    /// Visit an assignment with an operator
+    fn visit_assign_op(&mut self, _:BinOp, target:&Expr, value:&Expr) {
+        self.visit(target);
+        self.visit(value);
+    }
+    /// Visit a variable declaration
+    fn visit_var_decl(&mut self, vars:&Vec<(Pattern, Option<Expr>)>) {
+        self.visit_decls(vars);
+    }
+    /// Visit a block-scoped variable declaration
+    fn visit_let_decl(&mut self, vars:&Vec<(Pattern, Option<Expr>)>) {
+        self.visit_decls(vars);
+    }
+    /// Visit a declaration of constants
+    fn visit_const_decl(&mut self, vars:&Vec<(Pattern, Option<Expr>)>) {
+        self.visit_decls(vars);
+    }
+    /// Visit a script
+    fn visit_script(&mut self, stmts:&Vec<Expr>, _:bool) {
+        self.visit_all(stmts);
+    }
+    /// Visit a module
+    fn visit_module(&mut self, stmts:&Vec<Expr>) {
+        self.visit_all(stmts);
+    }
+    /// Visit an import declaration
+    fn visit_import(&mut self, _:&Vec<(String, String)>, _:&String) {
+    }
+    /// Visit an exported declaration
+    fn visit_export_decl(&mut self, decl:&Expr) {
+        self.visit(decl);
+    }
+    /// Visit a default export
+    fn visit_export_default(&mut self, value:&Expr) {
+        self.visit(value);
+    }
+    /// Visit an export of names
+    fn visit_export_named(&mut self, _:&Vec<(String, String)>, _:&Option<String>) {
+    }
+    /// Visit an export of all the exports of another module
+    fn visit_export_all(&mut self, _:&String) {
+    }
+    /// Visit a typeof expression
+    fn visit_typeof(&mut self, expr:&Expr) {
+        self.visit(expr);
+    }
+    /// Visit a void expression
+    fn visit_void(&mut self, expr:&Expr) {
+        self.visit(expr);
+    }
+    /// Visit a delete expression
+    fn visit_delete(&mut self, expr:&Expr) {
+        self.visit(expr);
+    }
+}
+/// Visit an expression with the method of the visitor for its kind
+pub fn walk<V:Visitor>(visitor:&mut V, expr:&Expr) {
+    match expr.def {
+        BinOpExpr(op, box ref left, box ref right) => visitor.visit_bin_op(op, left, right),
+        UnaryOpExpr(op, box ref operand) => visitor.visit_unary_op(op, operand),
+        UpdateExpr(prefix, op, box ref target) => visitor.visit_update(prefix, op, target),
+        ConstExpr(ref c) => visitor.visit_const(c),
+        TemplateExpr(ref quasis, ref exprs) => visitor.visit_template(quasis, exprs),
+        BlockExpr(ref exprs) => visitor.visit_block(exprs),
+        LocalExpr(ref name) => visitor.visit_local(name),
+        ThisExpr => visitor.visit_this(),
+        GetConstFieldExpr(box ref obj, ref field) => visitor.visit_get_const_field(obj, field),
+        GetFieldExpr(box ref obj, box ref field) => visitor.visit_get_field(obj, field),
+        OptionalChainExpr(box ref chain) => visitor.visit_optional_chain(chain),
+        OptionalExpr(box ref obj) => visitor.visit_optional(obj),
+        CallExpr(box ref func, ref args) => visitor.visit_call(func, args),
+        WhileLoopExpr(box ref cond, box ref body) => visitor.visit_while_loop(cond, body),
+        ForLoopExpr(ref init, ref cond, ref step, box ref body) => visitor.visit_for_loop(init, cond, step, body),
+        ForInExpr(box ref name, box ref obj, box ref body) => visitor.visit_for_in(name, obj, body),
+        ForOfExpr(box ref name, box ref iterable, box ref body) => visitor.visit_for_of(name, iterable, body),
+        IfExpr(box ref cond, box ref if_expr, ref else_expr) => visitor.visit_if(cond, if_expr, else_expr),
+        TryExpr(box ref block, ref name, ref catch_block, ref finally_block) => visitor.visit_try(block, name, catch_block, finally_block),
+        BreakExpr(ref label) => visitor.visit_break(label),
+        ContinueExpr(ref label) => visitor.visit_continue(label),
+        LabelledExpr(ref label, box ref expr) => visitor.visit_labelled(label, expr),
+        SwitchExpr(box ref value, ref cases, ref default) => visitor.visit_switch(value, cases, default),
+        ObjectDeclExpr(box ref fields) => visitor.visit_object_decl(fields),
+        AccessorExpr(ref getter, ref setter) => visitor.visit_accessor(getter, setter),
+        ArrayDeclExpr(ref items) => visitor.visit_array_decl(items),
+        SpreadExpr(box ref iterable) => visitor.visit_spread(iterable),
+        FunctionDeclExpr(ref name, ref args, box ref body, strict) => visitor.visit_function_decl(name, args, body, strict),
+        GeneratorDeclExpr(ref name, ref args, box ref body, strict) => visitor.visit_generator_decl(name, args, body, strict),
+        YieldExpr(delegate, ref value) => visitor.visit_yield(delegate, value),
+        ArrowFunctionDeclExpr(ref args, box ref body, strict) => visitor.visit_arrow_function_decl(args, body, strict),
+        ClassDeclExpr(ref name, ref parent, box ref constructor, ref methods) => visitor.visit_class_decl(name, parent, constructor, methods),
+        SuperExpr => visitor.visit_super(),
+        NewTargetExpr => visitor.visit_new_target(),
+        ConstructExpr(box ref func, ref args) => visitor.visit_construct(func, args),
+        ReturnExpr(ref value) => visitor.visit_return(value),
+        ThrowExpr(box ref value) => visitor.visit_throw(value),
+        AssignExpr(box ref target, box ref value) => visitor.visit_assign(target, value),
+        DestructureExpr(ref pattern, box ref value) => visitor.visit_destructure(pattern, value),
+        AssignOpExpr(op, box ref target, box ref value) => visitor.visit_assign_op(op, target, value),
+        VarDeclExpr(ref vars) => visitor.visit_var_decl(vars),
+        LetDeclExpr(ref vars) => visitor.visit_let_decl(vars),
+        ConstDeclExpr(ref vars) => visitor.visit_const_decl(vars),
+        ScriptExpr(ref stmts, strict) => visitor.visit_script(stmts, strict),
+        ModuleExpr(ref stmts) => visitor.visit_module(stmts),
+        ImportExpr(ref names, ref specifier) => visitor.visit_import(names, specifier),
+        ExportDeclExpr(box ref decl) => visitor.visit_export_decl(decl),
+        ExportDefaultExpr(box ref value) => visitor.visit_export_default(value),
+        ExportNamedExpr(ref names, ref specifier) => visitor.visit_export_named(names, specifier),
+        ExportAllExpr(ref specifier) => visitor.visit_export_all(specifier),
+        TypeOfExpr(box ref expr) => visitor.visit_typeof(expr),
+        VoidExpr(box ref expr) => visitor.visit_void(expr),
+        DeleteExpr(box ref expr) => visitor.visit_delete(expr)
+    }
+}
@@ -0,0 +1,864 @@
+use syntax::ast::constant::*;
+use syntax::ast::expr::*;
+use syntax::ast::op::{Operator, BinOp, BinNum, OpPow, UnaryMinus, UnaryPlus, UpdateDecrement, UpdateIncrement};
+use syntax::ast::pattern::{Pattern, RefPattern, ObjectPattern, ArrayPattern, DefaultPattern};
+use std::f64;
+use collections::treemap::TreeMap;
+/// The precedence of an expression that can appear anywhere, including with commas
+static ANY_PRECEDENCE: uint = 20;
+/// The precedence of an expression that can be a function argument or an array item
+static ASSIGN_PRECEDENCE: uint = 18;
+/// The precedence of a conditional expression
+static IF_PRECEDENCE: uint = 16;
+/// The precedence of a function call, which is also the highest precedence a callee can have
+static CALL_PRECEDENCE: uint = 2;
+
+#[deriving(Clone, PartialEq, Show)]
+/// The quotes that string literals are written with
+pub enum QuoteStyle {
+    /// `"Hello"`
+    DoubleQuotes,
+    /// `'Hello'`
+    SingleQuotes
+}
+#[deriving(Clone, PartialEq, Show)]
+/// The options that change how source code is generated
+pub struct GenOptions {
+    /// The text written once for each level a line is indented by
+    pub indent: String,
+    /// The quotes that string literals are written with
+    pub quotes: QuoteStyle
+}
+impl GenOptions {
+    /// Make the default options, which indent by 4 spaces and use double quotes
+    pub fn new() -> GenOptions {
+        GenOptions {
+            indent: "    ".into_string(),
+            quotes: DoubleQuotes
+        }
+    }
+}
+/// Generates Javascript source code from expressions, which can be parsed
+/// again to give the same expressions
+pub struct CodeGenerator {
+    /// The options that change how the code is generated
+    pub options: GenOptions,
+    /// The code generated so far
+    out: String,
+    /// How many levels the current line is indented by
+    level: uint
+}
+/// Generate the source code of the expression given, which is written as a statement
+/// unless it is a script or module
+pub fn generate(expr:&Expr, options:GenOptions) -> String {
+    let mut gen = CodeGenerator::new(options);
+    gen.gen_stmt(expr);
+    gen.finish()
+}
+/// Check if the name given can be written as an identifier
+fn is_identifier(name:&str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(ch) if ch.is_alphabetic() || ch == '_' || ch == '$' =>
+            chars.all(|ch| ch.is_alphanumeric() || ch == '_' || ch == '$'),
+        _ => false
+    }
+}
+/// Check if the code of the expression given would start with something that makes a
+/// statement mean something else, so it needs to be in parentheses
+fn starts_ambiguously(expr:&Expr) -> bool {
+    match expr.def {
+        ObjectDeclExpr(_) | FunctionDeclExpr(None, _, _, _) | GeneratorDeclExpr(None, _, _, _) | ClassDeclExpr(None, _, _, _) => true,
+        DestructureExpr(ObjectPattern(_), _) => true,
+        BinOpExpr(_, box ref left, _) | AssignExpr(box ref left, _) | AssignOpExpr(_, box ref left, _)
+        | GetConstFieldExpr(box ref left, _) | GetFieldExpr(box ref left, _) | CallExpr(box ref left, _)
+        | OptionalChainExpr(box ref left) | OptionalExpr(box ref left) | UpdateExpr(false, _, box ref left) => starts_ambiguously(left),
+        IfExpr(box ref cond, _, Some(_)) => starts_ambiguously(cond),
+        _ => false
+    }
+}
+/// Get the precedence of the expression when it is written as code, where expressions
+/// that are written as a single term have the lowest precedence so they never need parentheses
+fn precedence(def:&ExprDef) -> uint {
+    match *def {
+        ConstExpr(CInt(num)) if num < 0 => 4,
+        ConstExpr(CNum(num)) if num < 0.0 || num == f64::NEG_INFINITY => 4,
+        ConstExpr(_) | TemplateExpr(_, _) | LocalExpr(_) | ThisExpr | SuperExpr | NewTargetExpr | BlockExpr(_)
+        | ObjectDeclExpr(_) | ArrayDeclExpr(_) | FunctionDeclExpr(_, _, _, _) | GeneratorDeclExpr(_, _, _, _) | ClassDeclExpr(_, _, _, _) => 0,
+        OptionalChainExpr(box ref chain) => precedence(&chain.def),
+        ArrowFunctionDeclExpr(_, _, _) | SpreadExpr(_) => ASSIGN_PRECEDENCE,
+        ref def => def.get_precedence()
+    }
+}
+impl CodeGenerator {
+    /// Make a code generator with the options given
+    pub fn new(options:GenOptions) -> CodeGenerator {
+        CodeGenerator {
+            options: options,
+            out: String::new(),
+            level: 0
+        }
+    }
+    /// Get the code that has been generated
+    pub fn finish(self) -> String {
+        self.out
+    }
+    #[inline(always)]
+    fn write(&mut self, text:&str) {
+        self.out.push_str(text);
+    }
+    /// Start a new line at the current level of indentation
+    fn newline(&mut self) {
+        self.out.push_char('\n');
+        for _ in range(0, self.level) {
+            self.out.push_str(self.options.indent.as_slice());
+        }
+    }
+    /// Write a string literal with the quotes given in the options
+    fn write_string(&mut self, text:&str) {
+        let quote = match self.options.quotes {
+            DoubleQuotes => '"',
+            SingleQuotes => '\''
+        };
+        self.out.push_char(quote);
+        for ch in text.chars() {
+            match ch {
+                '\\' => self.write("\\\\"),
+                '\n' => self.write("\\n"),
+                '\r' => self.write("\\r"),
+                '\t' => self.write("\\t"),
+                '\u2028' => self.write("\\u2028"),
+                '\u2029' => self.write("\\u2029"),
+                _ if ch == quote => {
+                    self.out.push_char('\\');
+                    self.out.push_char(ch);
+                },
+                _ if (ch as u32) < 0x20 => self.write(format!("\\u{:04x}", ch as u32).as_slice()),
+                _ => self.out.push_char(ch)
+            }
+        }
+        self.out.push_char(quote);
+    }
+    /// Write the text of a template literal between its backticks and substitutions
+    fn write_template_text(&mut self, text:&str) {
+        let mut chars = text.chars().peekable();
+        loop {
+            match chars.next() {
+                Some('\\') => self.write("\\\\"),
+                Some('`') => self.write("\\`"),
+                Some('$') if chars.peek() == Some(&'{') => self.write("\\$"),
+                Some(ch) => self.out.push_char(ch),
+                None => break
+            }
+        }
+    }
+    /// Write the name of a property, quoting it if it isn't an identifier
+    fn write_property_name(&mut self, name:&str) {
+        if is_identifier(name) {
+            self.write(name);
+        } else {
+            self.write_string(name);
+        }
+    }
+    /// Write a constant
+    fn gen_const(&mut self, c:&Const) {
+        match *c {
+            CString(ref text) => self.write_string(text.as_slice()),
+            CNum(num) if num.is_nan() => self.write("NaN"),
+            CNum(num) if num == f64::INFINITY => self.write("Infinity"),
+            CNum(num) if num == f64::NEG_INFINITY => self.write("-Infinity"),
+            _ => self.write(c.to_string().as_slice())
+        }
+    }
+    /// Write a block of statements between braces
+    fn gen_block(&mut self, stmts:&Vec<Expr>) {
+        if stmts.is_empty() {
+            self.write("{}");
+            return;
+        }
+        self.write("{");
+        self.level += 1;
+        for stmt in stmts.iter() {
+            self.newline();
+            self.gen_stmt(stmt);
+        }
+        self.level -= 1;
+        self.newline();
+        self.write("}");
+    }
+    /// Write a statement that is the body of a loop or `if`, which is on the
+    /// same line if it is a block
+    fn gen_body(&mut self, body:&Expr) {
+        match body.def {
+            BlockExpr(ref stmts) => {
+                self.write(" ");
+                self.gen_block(stmts);
+            },
+            _ => {
+                self.level += 1;
+                self.newline();
+                self.gen_stmt(body);
+                self.level -= 1;
+            }
+        }
+    }
+    /// Write the statements one after another on separate lines
+    fn gen_stmts(&mut self, stmts:&Vec<Expr>) {
+        for (i, stmt) in stmts.iter().enumerate() {
+            if i > 0 {
+                self.newline();
+            }
+            self.gen_stmt(stmt);
+        }
+    }
+    /// Write an expression as a statement
+    pub fn gen_stmt(&mut self, expr:&Expr) {
+        match expr.def {
+            BlockExpr(ref stmts) => self.gen_block(stmts),
+            ScriptExpr(ref stmts, _) | ModuleExpr(ref stmts) => self.gen_stmts(stmts),
+            IfExpr(box ref cond, box ref if_expr, ref else_expr) => {
+                self.write("if (");
+                self.gen_expr(cond, ANY_PRECEDENCE);
+                self.write(")");
+                self.gen_body(if_expr);
+                match *else_expr {
+                    Some(box ref else_expr) => {
+                        match if_expr.def {
+                            BlockExpr(_) => self.write(" "),
+                            _ => self.newline()
+                        }
+                        self.write("else");
+                        match else_expr.def {
+                            IfExpr(_, _, _) => {
+                                self.write(" ");
+                                self.gen_stmt(else_expr);
+                            },
+                            _ => self.gen_body(else_expr)
+                        }
+                    },
+                    None => ()
+                }
+            },
+            WhileLoopExpr(box ref cond, box ref body) => {
+                self.write("while (");
+                self.gen_expr(cond, ANY_PRECEDENCE);
+                self.write(")");
+                self.gen_body(body);
+            },
+            ForLoopExpr(ref init, ref cond, ref step, box ref body) => {
+                self.write("for (");
+                match *init {
+                    Some(box ref init) => self.gen_expr(init, ANY_PRECEDENCE),
+                    None => ()
+                }
+                for clause in [cond, step].iter() {
+                    self.write(";");
+                    match **clause {
+                        Some(box ref clause) => {
+                            self.write(" ");
+                            self.gen_expr(clause, ANY_PRECEDENCE);
+                        },
+                        None => ()
+                    }
+                }
+                self.write(")");
+                self.gen_body(body);
+            },
+            ForInExpr(box ref name, box ref obj, box ref body) | ForOfExpr(box ref name, box ref obj, box ref body) => {
+                self.write("for (");
+                self.gen_expr(name, ANY_PRECEDENCE);
+                self.write(match expr.def {
+                    ForInExpr(_, _, _) => " in ",
+                    _ => " of "
+                });
+                self.gen_expr(obj, ASSIGN_PRECEDENCE);
+                self.write(")");
+                self.gen_body(body);
+            },
+            TryExpr(box ref block, ref name, ref catch_block, ref finally_block) => {
+                self.write("try ");
+                self.gen_stmt(block);
+                match *catch_block {
+                    Some(box ref catch_block) => {
+                        self.write(" catch ");
+                        match *name {
+                            Some(ref name) => {
+                                self.write("(");
+                                self.write(name.as_slice());
+                                self.write(") ");
+                            },
+                            None => ()
+                        }
+                        self.gen_stmt(catch_block);
+                    },
+                    None => ()
+                }
+                match *finally_block {
+                    Some(box ref finally_block) => {
+                        self.write(" finally ");
+                        self.gen_stmt(finally_block);
+                    },
+                    None => ()
+                }
+            },
+            SwitchExpr(box ref value, ref cases, ref default) => {
+                self.write("switch (");
+                self.gen_expr(value, ANY_PRECEDENCE);
+                self.write(") {");
+                self.level += 1;
+                for &(ref cond, ref block) in cases.iter() {
+                    self.newline();
+                    self.write("case ");
+                    self.gen_expr(cond, ANY_PRECEDENCE);
+                    self.write(":");
+                    self.level += 1;
+                    for stmt in block.iter() {
+                        self.newline();
+                        self.gen_stmt(stmt);
+                    }
+                    self.level -= 1;
+                }
+                match *default {
+                    Some(box ref default) => {
+                        self.newline();
+                        self.write("default:");
+                        self.level += 1;
+                        match default.def {
+                            BlockExpr(ref stmts) => for stmt in stmts.iter() {
+                                self.newline();
+                                self.gen_stmt(stmt);
+                            },
+                            _ => {
+                                self.newline();
+                                self.gen_stmt(default);
+                            }
+                        }
+                        self.level -= 1;
+                    },
+                    None => ()
+                }
+                self.level -= 1;
+                self.newline();
+                self.write("}");
+            },
+            LabelledExpr(ref label, box ref body) => {
+                self.write(label.as_slice());
+                self.write(": ");
+                self.gen_stmt(body);
+            },
+            FunctionDeclExpr(Some(_), _, _, _) | GeneratorDeclExpr(Some(_), _, _, _) | ClassDeclExpr(Some(_), _, _, _) =>
+                self.gen_expr(expr, ANY_PRECEDENCE),
+            ExportDeclExpr(box ref decl) => {
+                self.write("export ");
+                self.gen_stmt(decl);
+            },
+            ExportDefaultExpr(box ref value) => {
+                self.write("export default ");
+                match value.def {
+                    FunctionDeclExpr(_, _, _, _) | GeneratorDeclExpr(_, _, _, _) | ClassDeclExpr(_, _, _, _) =>
+                        self.gen_expr(value, ANY_PRECEDENCE),
+                    _ => {
+                        self.gen_expr(value, ASSIGN_PRECEDENCE);
+                        self.write(";");
+                    }
+                }
+            },
+            _ if starts_ambiguously(expr) => {
+                self.write("(");
+                self.gen_expr(expr, ANY_PRECEDENCE);
+                self.write(");");
+            },
+            _ => {
+                self.gen_expr(expr, ANY_PRECEDENCE);
+                self.write(";");
+            }
+        }
+    }
+    /// Write the patterns and values of a declaration
+    fn gen_decls(&mut self, keyword:&str, vars:&Vec<(Pattern, Option<Expr>)>) {
+        self.write(keyword);
+        self.write(" ");
+        for (i, &(ref pattern, ref value)) in vars.iter().enumerate() {
+            if i > 0 {
+                self.write(", ");
+            }
+            self.gen_pattern(pattern);
+            match *value {
+                Some(ref value) => {
+                    self.write(" = ");
+                    self.gen_expr(value, ASSIGN_PRECEDENCE);
+                },
+                None => ()
+            }
+        }
+    }
+    /// Write a destructuring pattern
+    fn gen_pattern(&mut self, pattern:&Pattern) {
+        match *pattern {
+            RefPattern(box ref target) => self.gen_expr(target, CALL_PRECEDENCE),
+            ObjectPattern(ref fields) => {
+                self.write("{");
+                for (i, &(ref name, ref field)) in fields.iter().enumerate() {
+                    self.write(if i == 0 {""} else {", "});
+                    self.write_property_name(name.as_slice());
+                    self.write(": ");
+                    self.gen_pattern(field);
+                }
+                self.write("}");
+            },
+            ArrayPattern(ref items) => {
+                self.write("[");
+                for (i, item) in items.iter().enumerate() {
+                    self.write(if i == 0 {""} else {", "});
+                    match *item {
+                        Some(ref item) => self.gen_pattern(item),
+                        None => ()
+                    }
+                }
+                // a hole at the end needs a comma after it to be kept
+                if items.last().map_or(false, |item| item.is_none()) {
+                    self.write(",");
+                }
+                self.write("]");
+            },
+            DefaultPattern(box ref pattern, box ref default) => {
+                self.gen_pattern(pattern);
+                self.write(" = ");
+                self.gen_expr(default, ASSIGN_PRECEDENCE);
+            }
+        }
+    }
+    /// Write items separated by commas, such as the arguments of a call
+    fn gen_list(&mut self, items:&Vec<Expr>) {
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                self.write(", ");
+            }
+            self.gen_expr(item, ASSIGN_PRECEDENCE);
+        }
+    }
+    /// Write the arguments and body of a function
+    fn gen_function_rest(&mut self, args:&Vec<String>, body:&Expr) {
+        self.write("(");
+        self.write(args.connect(", ").as_slice());
+        self.write(") ");
+        match body.def {
+            BlockExpr(ref stmts) => self.gen_block(stmts),
+            _ => self.gen_block(&vec!(body.clone()))
+        }
+    }
+    /// Write a method of an object or class, which is a function after its name
+    fn gen_method(&mut self, name:&str, method:&Expr) {
+        match method.def {
+            FunctionDeclExpr(_, ref args, box ref body, _) => {
+                self.write_property_name(name);
+                self.gen_function_rest(args, body);
+            },
+            GeneratorDeclExpr(_, ref args, box ref body, _) => {
+                self.write("*");
+                self.write_property_name(name);
+                self.gen_function_rest(args, body);
+            },
+            _ => {
+                self.write_property_name(name);
+                self.write(": ");
+                self.gen_expr(method, ASSIGN_PRECEDENCE);
+            }
+        }
+    }
+    /// Write a binary operation, adding parentheses to the operands that need them
+    fn gen_bin_op(&mut self, op:BinOp, left:&Expr, right:&Expr) {
+        let precedence = op.get_precedence();
+        // exponentiation groups to the right and can't have a unary operation on its left,
+        // and every other operator groups to the left
+        let (left_limit, right_limit) = if op == BinNum(OpPow) {
+            (precedence - 2, precedence)
+        } else {
+            (precedence, precedence - 1)
+        };
+        self.gen_expr(left, left_limit);
+        self.write(" ");
+        self.write(op.to_string().as_slice());
+        self.write(" ");
+        self.gen_expr(right, right_limit);
+    }
+    /// Write an expression, in parentheses if its precedence is higher than `limit`
+    pub fn gen_expr(&mut self, expr:&Expr, limit:uint) {
+        let precedence = precedence(&expr.def);
+        if precedence > limit {
+            self.write("(");
+            self.gen_expr(expr, ANY_PRECEDENCE);
+            self.write(")");
+            return;
+        }
+        match expr.def {
+            ConstExpr(ref c) => self.gen_const(c),
+            TemplateExpr(ref quasis, ref exprs) => {
+                self.write("`");
+                for (i, quasi) in quasis.iter().enumerate() {
+                    self.write_template_text(quasi.as_slice());
+                    if i < exprs.len() {
+                        self.write("${");
+                        self.gen_expr(&exprs[i], ANY_PRECEDENCE);
+                        self.write("}");
+                    }
+                }
+                self.write("`");
+            },
+            BlockExpr(ref stmts) => self.gen_block(stmts),
+            LocalExpr(ref name) => self.write(name.as_slice()),
+            ThisExpr => self.write("this"),
+            SuperExpr => self.write("super"),
+            NewTargetExpr => self.write("new.target"),
+            GetConstFieldExpr(box Expr{def: OptionalExpr(box ref obj), ..}, ref field) => {
+                self.gen_expr(obj, CALL_PRECEDENCE);
+                self.write("?.");
+                self.write(field.as_slice());
+            },
+            GetConstFieldExpr(box ref obj, ref field) => {
+                match obj.def {
+                    // a dot straight after an integer would be read as a decimal point
+                    ConstExpr(CInt(_)) | ConstExpr(CNum(_)) => {
+                        self.write("(");
+                        self.gen_expr(obj, ANY_PRECEDENCE);
+                        self.write(")");
+                    },
+                    _ => self.gen_expr(obj, CALL_PRECEDENCE)
+                }
+                self.write(".");
+                self.write(field.as_slice());
+            },
+            GetFieldExpr(box ref obj, box ref field) => {
+                match obj.def {
+                    OptionalExpr(box ref obj) => {
+                        self.gen_expr(obj, CALL_PRECEDENCE);
+                        self.write("?.[");
+                    },
+                    _ => {
+                        self.gen_expr(obj, CALL_PRECEDENCE);
+                        self.write("[");
+                    }
+                }
+                self.gen_expr(field, ANY_PRECEDENCE);
+                self.write("]");
+            },
+            OptionalChainExpr(box ref chain) => self.gen_expr(chain, limit),
+            OptionalExpr(box ref obj) => self.gen_expr(obj, CALL_PRECEDENCE),
+            CallExpr(box ref func, ref args) => {
+                match func.def {
+                    OptionalExpr(box ref func) => {
+                        self.gen_expr(func, CALL_PRECEDENCE);
+                        self.write("?.");
+                    },
+                    FunctionDeclExpr(_, _, _, _) | GeneratorDeclExpr(_, _, _, _) | ArrowFunctionDeclExpr(_, _, _) => {
+                        self.write("(");
+                        self.gen_expr(func, ANY_PRECEDENCE);
+                        self.write(")");
+                    },
+                    _ => self.gen_expr(func, CALL_PRECEDENCE)
+                }
+                self.write("(");
+                self.gen_list(args);
+                self.write(")");
+            },
+            ConstructExpr(box ref func, ref args) => {
+                self.write("new ");
+                match func.def {
+                    // the arguments of a call in the constructor would be taken as the arguments of `new`
+                    CallExpr(_, _) | OptionalChainExpr(_) => {
+                        self.write("(");
+                        self.gen_expr(func, ANY_PRECEDENCE);
+                        self.write(")");
+                    },
+                    _ => self.gen_expr(func, CALL_PRECEDENCE)
+                }
+                self.write("(");
+                self.gen_list(args);
+                self.write(")");
+            },
+            IfExpr(box ref cond, box ref if_expr, ref else_expr) => {
+                self.gen_expr(cond, IF_PRECEDENCE - 1);
+                self.write(" ? ");
+                self.gen_expr(if_expr, ASSIGN_PRECEDENCE);
+                self.write(" : ");
+                match *else_expr {
+                    Some(box ref else_expr) => self.gen_expr(else_expr, ASSIGN_PRECEDENCE),
+                    None => self.write("undefined")
+                }
+            },
+            ObjectDeclExpr(box ref fields) => {
+                if fields.is_empty() {
+                    self.write("{}");
+                    return;
+                }
+                self.write("{");
+                self.level += 1;
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        self.write(",");
+                    }
+                    self.newline();
+                    match value.def {
+                        AccessorExpr(ref getter, ref setter) => {
+                            match *getter {
+                                Some(box ref getter) => {
+                                    self.write("get ");
+                                    self.gen_method(name.as_slice(), getter);
+                                },
+                                None => ()
+                            }
+                            match *setter {
+                                Some(box ref setter) => {
+                                    if getter.is_some() {
+                                        self.write(",");
+                                        self.newline();
+                                    }
+                                    self.write("set ");
+                                    self.gen_method(name.as_slice(), setter);
+                                },
+                                None => ()
+                            }
+                        },
+                        _ => {
+                            self.write_property_name(name.as_slice());
+                            self.write(": ");
+                            self.gen_expr(value, ASSIGN_PRECEDENCE);
+                        }
+                    }
+                }
+                self.level -= 1;
+                self.newline();
+                self.write("}");
+            },
+            AccessorExpr(ref getter, ref setter) => {
+                // an accessor outside of an object is written as an object with just it
+                let mut fields = TreeMap::new();
+                fields.insert("value".into_string(), Expr::new(AccessorExpr(getter.clone(), setter.clone()), expr.start, expr.end));
+                self.gen_expr(&Expr::new(ObjectDeclExpr(box fields), expr.start, expr.end), limit);
+            },
+            ArrayDeclExpr(ref items) => {
+                self.write("[");
+                self.gen_list(items);
+                self.write("]");
+            },
+            SpreadExpr(box ref iterable) => {
+                self.write("...");
+                self.gen_expr(iterable, ASSIGN_PRECEDENCE);
+            },
+            FunctionDeclExpr(ref name, ref args, box ref body, _) | GeneratorDeclExpr(ref name, ref args, box ref body, _) => {
+                self.write(match expr.def {
+                    FunctionDeclExpr(_, _, _, _) => "function",
+                    _ => "function*"
+                });
+                match *name {
+                    Some(ref name) => {
+                        self.write(" ");
+                        self.write(name.as_slice());
+                    },
+                    None => ()
+                }
+                self.gen_function_rest(args, body);
+            },
+            ArrowFunctionDeclExpr(ref args, box ref body, _) => {
+                self.write("(");
+                self.write(args.connect(", ").as_slice());
+                self.write(") => ");
+                match body.def {
+                    BlockExpr(ref stmts) => self.gen_block(stmts),
+                    ObjectDeclExpr(_) => {
+                        self.write("(");
+                        self.gen_expr(body, ANY_PRECEDENCE);
+                        self.write(")");
+                    },
+                    _ => self.gen_expr(body, ASSIGN_PRECEDENCE)
+                }
+            },
+            ClassDeclExpr(ref name, ref parent, box ref constructor, ref methods) => {
+                self.write("class");
+                match *name {
+                    Some(ref name) => {
+                        self.write(" ");
+                        self.write(name.as_slice());
+                    },
+                    None => ()
+                }
+                match *parent {
+                    Some(box ref parent) => {
+                        self.write(" extends ");
+                        self.gen_expr(parent, CALL_PRECEDENCE);
+                    },
+                    None => ()
+                }
+                self.write(" {");
+                self.level += 1;
+                self.newline();
+                self.gen_method("constructor", constructor);
+                for &(is_static, ref kind, ref name, ref method) in methods.iter() {
+                    self.newline();
+                    if is_static {
+                        self.write("static ");
+                    }
+                    self.write(match *kind {
+                        PlainMethod => "",
+                        GetterMethod => "get ",
+                        SetterMethod => "set "
+                    });
+                    self.gen_method(name.as_slice(), method);
+                }
+                self.level -= 1;
+                self.newline();
+                self.write("}");
+            },
+            YieldExpr(delegate, ref value) => {
+                self.write(if delegate {"yield*"} else {"yield"});
+                match *value {
+                    Some(box ref value) => {
+                        self.write(" ");
+                        self.gen_expr(value, ASSIGN_PRECEDENCE);
+                    },
+                    None => ()
+                }
+            },
+            BinOpExpr(op, box ref left, box ref right) => self.gen_bin_op(op, left, right),
+            UnaryOpExpr(op, box ref operand) => {
+                self.write(op.to_string().as_slice());
+                // `- -a` and `+ +a` would be read as decrements and increments without the space
+                match (op, &operand.def) {
+                    (UnaryMinus, &UnaryOpExpr(UnaryMinus, _)) | (UnaryMinus, &UpdateExpr(true, UpdateDecrement, _))
+                    | (UnaryPlus, &UnaryOpExpr(UnaryPlus, _)) | (UnaryPlus, &UpdateExpr(true, UpdateIncrement, _)) => self.write(" "),
+                    _ => ()
+                }
+                self.gen_expr(operand, 4);
+            },
+            UpdateExpr(true, op, box ref target) => {
+                self.write(op.to_string().as_slice());
+                self.gen_expr(target, CALL_PRECEDENCE);
+            },
+            UpdateExpr(false, op, box ref target) => {
+                self.gen_expr(target, CALL_PRECEDENCE);
+                self.write(op.to_string().as_slice());
+            },
+            TypeOfExpr(box ref operand) | VoidExpr(box ref operand) | DeleteExpr(box ref operand) => {
+                self.write(match expr.def {
+                    TypeOfExpr(_) => "typeof ",
+                    VoidExpr(_) => "void ",
+                    _ => "delete "
+                });
+                self.gen_expr(operand, 4);
+            },
+            AssignExpr(box ref target, box ref value) => {
+                self.gen_expr(target, CALL_PRECEDENCE);
+                self.write(" = ");
+                self.gen_expr(value, ASSIGN_PRECEDENCE);
+            },
+            DestructureExpr(ref pattern, box ref value) => {
+                self.gen_pattern(pattern);
+                self.write(" = ");
+                self.gen_expr(value, ASSIGN_PRECEDENCE);
+            },
+            AssignOpExpr(op, box ref target, box ref value) => {
+                self.gen_expr(target, CALL_PRECEDENCE);
+                self.write(" ");
+                self.write(op.to_string().as_slice());
+                self.write("= ");
+                self.gen_expr(value, ASSIGN_PRECEDENCE);
+            },
+            VarDeclExpr(ref vars) => self.gen_decls("var", vars),
+            LetDeclExpr(ref vars) => self.gen_decls("let", vars),
+            ConstDeclExpr(ref vars) => self.gen_decls("const", vars),
+            ReturnExpr(ref value) => {
+                self.write("return");
+                match *value {
+                    Some(box ref value) => {
+                        self.write(" ");
+                        self.gen_expr(value, ANY_PRECEDENCE);
+                    },
+                    None => ()
+                }
+            },
+            ThrowExpr(box ref value) => {
+                self.write("throw ");
+                self.gen_expr(value, ANY_PRECEDENCE);
+            },
+            BreakExpr(ref label) | ContinueExpr(ref label) => {
+                self.write(match expr.def {
+                    BreakExpr(_) => "break",
+                    _ => "continue"
+                });
+                match *label {
+                    Some(ref label) => {
+                        self.write(" ");
+                        self.write(label.as_slice());
+                    },
+                    None => ()
+                }
+            },
+            ImportExpr(ref names, ref specifier) => {
+                self.write("import ");
+                let mut named = Vec::new();
+                let mut wrote = false;
+                for &(ref imported, ref local) in names.iter() {
+                    match imported.as_slice() {
+                        "default" | "*" => {
+                            if wrote {
+                                self.write(", ");
+                            }
+                            if imported.as_slice() == "*" {
+                                self.write("* as ");
+                            }
+                            self.write(local.as_slice());
+                            wrote = true;
+                        },
+                        _ => named.push((imported, local))
+                    }
+                }
+                if !named.is_empty() {
+                    if wrote {
+                        self.write(", ");
+                    }
+                    self.write("{");
+                    for (i, &(imported, local)) in named.iter().enumerate() {
+                        self.write(if i == 0 {""} else {", "});
+                        self.write(imported.as_slice());
+                        if imported != local {
+                            self.write(" as ");
+                            self.write(local.as_slice());
+                        }
+                    }
+                    self.write("}");
+                    wrote = true;
+                }
+                if wrote {
+                    self.write(" from ");
+                }
+                self.write_string(specifier.as_slice());
+            },
+            ExportNamedExpr(ref names, ref specifier) => {
+                self.write("export {");
+                for (i, &(ref local, ref exported)) in names.iter().enumerate() {
+                    self.write(if i == 0 {""} else {", "});
+                    self.write(local.as_slice());
+                    if local != exported {
+                        self.write(" as ");
+                        self.write(exported.as_slice());
+                    }
+                }
+                self.write("}");
+                match *specifier {
+                    Some(ref specifier) => {
+                        self.write(" from ");
+                        self.write_string(specifier.as_slice());
+                    },
+                    None => ()
+                }
+            },
+            ExportAllExpr(ref specifier) => {
+                self.write("export * from ");
+                self.write_string(specifier.as_slice());
+            },
+            ScriptExpr(_, _) | ModuleExpr(_) | WhileLoopExpr(_, _) | ForLoopExpr(_, _, _, _) | ForInExpr(_, _, _)
+            | ForOfExpr(_, _, _) | TryExpr(_, _, _, _) | SwitchExpr(_, _, _) | LabelledExpr(_, _)
+            | ExportDeclExpr(_) | ExportDefaultExpr(_) => self.gen_stmt(expr)
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use syntax::ast::constant::*;
+use syntax::ast::expr::*;
+use syntax::ast::op::*;
+use syntax::ast::pattern::Pattern;
+use syntax::ast::visitor::Visitor;
+use syntax::transform::{Transform, map_children};
+use std::num::Zero;
+use std::i32;
+
+/// Fold the constant expressions in the expression given into the constants they give,
+/// take the branch of `if` statements with constant conditions that would always be run,
+/// and remove the statements after a `return`, `throw`, `break` or `continue` in a block
+pub fn fold(expr:Expr) -> Expr {
+    let expr = map_children(expr, |child| fold(child));
+    let (start, end) = (expr.start, expr.end);
+    let def = match expr.def {
+        BinOpExpr(op, box Expr{def: ConstExpr(ref a), ..}, box Expr{def: ConstExpr(ref b), ..}) => match fold_bin_op(op, a, b) {
+            Some(c) => ConstExpr(c),
+            None => expr.def.clone()
+        },
+        UnaryOpExpr(op, box Expr{def: ConstExpr(ref a), ..}) => match fold_unary_op(op, a) {
+            Some(c) => ConstExpr(c),
+            None => expr.def.clone()
+        },
+        IfExpr(box Expr{def: ConstExpr(ref cond), ..}, box ref if_expr, ref else_expr) => {
+            let (taken, skipped) = if is_truthy(cond) {
+                (Some(if_expr), else_expr.as_ref().map(|e| &**e))
+            } else {
+                (else_expr.as_ref().map(|e| &**e), Some(if_expr))
+            };
+            // variables and functions declared in the branch that isn't taken still exist
+            if skipped.map_or(false, |skipped| declares(skipped)) {
+                expr.def.clone()
+            } else {
+                match taken {
+                    Some(taken) => return taken.clone(),
+                    None => BlockExpr(Vec::new())
+                }
+            }
+        },
+        BlockExpr(ref stmts) => BlockExpr(remove_dead_tail(stmts)),
+        ref def => def.clone()
+    };
+    Expr::new(def, start, end)
+}
+/// Get the statements of a block up to the first one that always leaves it, keeping
+/// the declarations after it as they are hoisted to the start of the block
+fn remove_dead_tail(stmts:&Vec<Expr>) -> Vec<Expr> {
+    let mut kept = Vec::with_capacity(stmts.len());
+    let mut dead = false;
+    for stmt in stmts.iter() {
+        if !dead || declares(stmt) {
+            kept.push(stmt.clone());
+        }
+        dead = dead || match stmt.def {
+            ReturnExpr(_) | ThrowExpr(_) | BreakExpr(_) | ContinueExpr(_) => true,
+            _ => false
+        };
+    }
+    kept
+}
+/// Finds if an expression declares a variable or function that is hoisted out of it
+struct DeclFinder {
+    found: bool
+}
+impl Visitor for DeclFinder {
+    fn visit_var_decl(&mut self, _:&Vec<(Pattern, Option<Expr>)>) {
+        self.found = true;
+    }
+    fn visit_function_decl(&mut self, name:&Option<String>, _:&Vec<String>, _:&Expr, _:bool) {
+        self.found = self.found || name.is_some();
+    }
+    fn visit_generator_decl(&mut self, name:&Option<String>, _:&Vec<String>, _:&Expr, _:bool) {
+        self.found = self.found || name.is_some();
+    }
+    fn visit_arrow_function_decl(&mut self, _:&Vec<String>, _:&Expr, _:bool) {
+    }
+}
+/// Check if the expression declares a variable or function that is hoisted out of it
+fn declares(expr:&Expr) -> bool {
+    let mut finder = DeclFinder {found: false};
+    finder.visit(expr);
+    finder.found
+}
+/// Check if the constant is truthy
+fn is_truthy(c:&Const) -> bool {
+    match *c {
+        CString(ref text) => !text.is_empty(),
+        CNum(num) => num != 0.0 && !num.is_nan(),
+        CInt(num) => num != 0,
+        CBigInt(ref num) => !num.is_zero(),
+        CBool(value) => value,
+        CRegExp(_, _) => true,
+        CNull | CUndefined => false
+    }
+}
+/// Get the number that a constant converts to, if it is a number
+fn as_num(c:&Const) -> Option<f64> {
+    match *c {
+        CNum(num) => Some(num),
+        CInt(num) => Some(num as f64),
+        _ => None
+    }
+}
+/// Get the text that a constant converts to, if it is easy to tell what it is
+fn as_text(c:&Const) -> Option<String> {
+    match *c {
+        CString(ref text) => Some(text.clone()),
+        CInt(num) => Some(num.to_string()),
+        CBool(value) => Some(value.to_string()),
+        CNull => Some("null".into_string()),
+        CUndefined => Some("undefined".into_string()),
+        _ => None
+    }
+}
+/// Make a constant for a number, which is an integer if it fits in one
+fn num_const(num:f64) -> Const {
+    // negative zero isn't an integer
+    if num.fract() == 0.0 && num >= i32::MIN as f64 && num <= i32::MAX as f64 && !(num == 0.0 && 1.0 / num < 0.0) {
+        CInt(num as i32)
+    } else {
+        CNum(num)
+    }
+}
+/// Convert a number into a 32-bit integer, like bitwise operators do
+fn to_int32(num:f64) -> i32 {
+    if num.is_nan() || num.is_infinite() {
+        0
+    } else {
+        (num.trunc() % 4294967296.0) as i64 as i32
+    }
+}
+/// Fold a binary operation on two constants, if it can be folded
+fn fold_bin_op(op:BinOp, a:&Const, b:&Const) -> Option<Const> {
+    match op {
+        BinNum(OpAdd) => match (a, b) {
+            (&CString(_), _) | (_, &CString(_)) => match (as_text(a), as_text(b)) {
+                (Some(a), Some(b)) => Some(CString(a.append(b.as_slice()))),
+                _ => None
+            },
+            _ => as_num(a).and_then(|a| as_num(b).map(|b| num_const(a + b)))
+        },
+        BinNum(op) => match (as_num(a), as_num(b)) {
+            (Some(a), Some(b)) => Some(num_const(match op {
+                OpSub => a - b,
+                OpMul => a * b,
+                OpDiv => a / b,
+                OpMod => a % b,
+                OpPow => a.powf(b),
+                OpAdd => a + b
+            })),
+            _ => None
+        },
+        BinBit(op) => match (as_num(a), as_num(b)) {
+            (Some(a), Some(b)) => {
+                let (a, b) = (to_int32(a), to_int32(b));
+                let shift = (b & 31) as uint;
+                Some(match op {
+                    BitAnd => CInt(a & b),
+                    BitOr => CInt(a | b),
+                    BitXor => CInt(a ^ b),
+                    BitShl => CInt(a << shift),
+                    BitShr => CInt(a >> shift),
+                    BitUShr => num_const(((a as u32) >> shift) as f64)
+                })
+            },
+            _ => None
+        },
+        BinComp(_) | BinLog(_) => None
+    }
+}
+/// Fold a unary operation on a constant, if it can be folded
+fn fold_unary_op(op:UnaryOp, a:&Const) -> Option<Const> {
+    match op {
+        UnaryNot => Some(CBool(!is_truthy(a))),
+        UnaryMinus => as_num(a).map(|a| num_const(-a)),
+        UnaryPlus => as_num(a).map(|a| num_const(a)),
+        UnaryBitNot => as_num(a).map(|a| CInt(!to_int32(a)))
+    }
+}
+/// A transform that folds constant expressions and removes dead code, like `fold`
+pub struct ConstantFolding;
+impl Transform for ConstantFolding {
+    fn transform(&mut self, expr:Expr) -> Expr {
+        fold(expr)
+    }
+}
@@ -0,0 +1,101 @@
+use syntax::ast::expr::*;
+use syntax::ast::pattern::{Pattern, RefPattern};
+use syntax::ast::pos::Position;
+use syntax::ast::visitor::Visitor;
+use syntax::transform::Transform;
+/// The declarations in the body of a function, script or module that are made
+/// before the body is run
+pub struct Scope {
+    /// The names of the variables declared with `var` anywhere in the body,
+    /// outside of the functions inside of it
+    pub vars: Vec<String>,
+    /// The function declarations directly in the body
+    pub functions: Vec<Expr>
+}
+impl Scope {
+    /// Find the declarations in the statements of a body
+    pub fn of(stmts:&Vec<Expr>) -> Scope {
+        let mut finder = VarFinder {names: Vec::new()};
+        finder.visit_all(stmts);
+        let functions : Vec<Expr> = stmts.iter().filter(|stmt| is_function_decl(*stmt)).map(|stmt| stmt.clone()).collect();
+        let mut vars = Vec::new();
+        for name in finder.names.move_iter() {
+            if !vars.contains(&name) {
+                vars.push(name);
+            }
+        }
+        Scope {
+            vars: vars,
+            functions: functions
+        }
+    }
+}
+/// Finds the names of the variables declared with `var`, without looking in functions
+struct VarFinder {
+    names: Vec<String>
+}
+impl Visitor for VarFinder {
+    fn visit_var_decl(&mut self, vars:&Vec<(Pattern, Option<Expr>)>) {
+        for &(ref pattern, _) in vars.iter() {
+            pattern.add_names(&mut self.names);
+        }
+        self.visit_decls(vars);
+    }
+    fn visit_function_decl(&mut self, _:&Option<String>, _:&Vec<String>, _:&Expr, _:bool) {
+    }
+    fn visit_generator_decl(&mut self, _:&Option<String>, _:&Vec<String>, _:&Expr, _:bool) {
+    }
+    fn visit_arrow_function_decl(&mut self, _:&Vec<String>, _:&Expr, _:bool) {
+    }
+}
+/// Check if a statement is a function declaration, which is made before the body it is in is run
+fn is_function_decl(stmt:&Expr) -> bool {
+    match stmt.def {
+        FunctionDeclExpr(Some(_), _, _, _) | GeneratorDeclExpr(Some(_), _, _, _) => true,
+        ExportDeclExpr(box ref decl) => is_function_decl(decl),
+        _ => false
+    }
+}
+/// Move the declarations in the statements of a body to the start of it, with a `var`
+/// declaration of all of the variables declared in it without their values, followed
+/// by its function declarations, so functions can be called before they are declared
+pub fn hoist_body(stmts:Vec<Expr>, at:Position) -> Vec<Expr> {
+    let scope = Scope::of(&stmts);
+    let mut hoisted = Vec::with_capacity(stmts.len() + 1);
+    let vars : Vec<(Pattern, Option<Expr>)> = scope.vars.move_iter().map(|name|
+        (RefPattern(box Expr::new(LocalExpr(name), at, at)), None)
+    ).collect();
+    if !vars.is_empty() {
+        hoisted.push(Expr::new(VarDeclExpr(vars), at, at));
+    }
+    hoisted.push_all_move(scope.functions);
+    hoisted.extend(stmts.move_iter().filter(|stmt| !is_function_decl(stmt)));
+    hoisted
+}
+/// Hoist the declarations in the bodies of the functions, scripts and modules in the expression given
+pub fn hoist(expr:Expr) -> Expr {
+    Hoisting.transform(expr)
+}
+/// A transform that hoists the declarations in each function, script and module body, like `hoist`
+pub struct Hoisting;
+impl Transform for Hoisting {
+    fn transform_expr(&mut self, expr:Expr) -> Expr {
+        let (start, end) = (expr.start, expr.end);
+        let def = match expr.def {
+            ScriptExpr(stmts, strict) => ScriptExpr(hoist_body(stmts, start), strict),
+            ModuleExpr(stmts) => ModuleExpr(hoist_body(stmts, start)),
+            FunctionDeclExpr(name, args, box body, strict) => FunctionDeclExpr(name, args, box hoist_block(body), strict),
+            GeneratorDeclExpr(name, args, box body, strict) => GeneratorDeclExpr(name, args, box hoist_block(body), strict),
+            ArrowFunctionDeclExpr(args, box body, strict) => ArrowFunctionDeclExpr(args, box hoist_block(body), strict),
+            def => def
+        };
+        Expr::new(def, start, end)
+    }
+}
+/// Hoist the declarations in the body of a function, if it is a block
+fn hoist_block(body:Expr) -> Expr {
+    match body {
+        Expr {def: BlockExpr(stmts), start, end} => Expr::new(BlockExpr(hoist_body(stmts, start)), start, end),
+        body => body
+    }
+}
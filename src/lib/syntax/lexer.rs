@@ -1,15 +1,25 @@
 use syntax::ast::punc::*;
 use syntax::ast::token::*;
-use std::io::{BufReader, BufferedReader, Buffer, IoError, IoResult, EndOfFile};
+use syntax::ast::pos::Position;
+use syntax::parser::ParserOptions;
+use syntax::ast::keyword::{Keyword, KThis, KSuper, KVar, KLet, KConst, KFunction, KYield};
+use std::io::{BufReader, BufferedReader, Buffer, IoError, EndOfFile};
 use std::char::from_u32;
 use std::num::from_str_radix;
 use std::from_str::FromStr;
+use std::str::{from_utf8, utf8_char_width};
+use std::fmt;
+use std::mem::replace;
+use std::default::Default;
+use std::uint;
+use num::bigint::BigInt;
+use serialize::json::{ToJson, Json};
 macro_rules! vop(
     ($this:ident, $assign_op:expr, $op:expr) => ({
         let preview = try!($this.preview_next());
         match preview {
-            '=' => {
-                $this.buffer.consume(1);
+            Some('=') => {
+                $this.skip('=');
                 $assign_op
             },
             _ => $op
@@ -18,18 +28,24 @@ macro_rules! vop(
     ($this:ident, $assign_op:expr, $op:expr, {$($case:pat => $block:expr),+}) => ({
         let preview = try!($this.preview_next());
         match preview {
-            '=' => {
-                $this.buffer.consume(1);
+            Some('=') => {
+                $this.skip('=');
                 $assign_op
             },
-            $($case => $block)+,
+            $(Some(ch @ $case) => {
+                $this.skip(ch);
+                $block
+            })+
             _ => $op
         }
     });
     ($this:ident, $op:expr, {$($case:pat => $block:expr),+}) => ({
         let preview = try!($this.preview_next());
         match preview {
-            $($case => $block)+,
+            $(Some(ch @ $case) => {
+                $this.skip(ch);
+                $block
+            })+
             _ => $op
         }
     });
@@ -45,17 +61,220 @@ macro_rules! op(
     });
     ($this:ident, $op:expr, {$($case:pat => $block:expr),+}) => ({
         let punc = vop!($this, $op, {$($case => $block),+});
-        $this.push_punc();
+        $this.push_punc(punc);
     });
 )
+#[deriving(Clone, PartialEq)]
+/// The kind of error encountered while lexing
+pub enum LexErrorKind {
+    /// The input couldn't be read
+    ReadError(IoError),
+    /// The input ended in the middle of a token
+    UnexpectedEnd,
+    /// A character that can't start a token
+    UnexpectedChar(char),
+    /// An escape sequence that doesn't exist, such as `\q`
+    InvalidEscape(char),
+    /// An escape for a number that isn't a Unicode scalar value
+    InvalidUnicode(u32),
+    /// A numeric literal that isn't a valid number, such as `1.2.3`
+    InvalidNumber(String),
+    /// A numeric literal with a prefix or exponent but no digits after it
+    MissingDigits(String),
+    /// A string that isn't closed before the end of its line
+    UnterminatedString,
+    /// A regular expression that isn't closed before the end of its line
+    UnterminatedRegExp,
+    /// A reserved word used as the name of a variable or function
+    ReservedWord(Keyword),
+    /// A legacy octal number or escape in strict mode
+    LegacyOctal,
+    /// One of the lexer's limits was exceeded, which is described
+    LimitExceeded(&'static str)
+}
+#[deriving(Clone, PartialEq)]
+/// An error encountered while lexing, including its position
+pub struct LexError {
+    /// The line number the error was found on
+    pub line : uint,
+    /// The column number the error was found at
+    pub column : uint,
+    /// The kind of error
+    pub kind : LexErrorKind
+}
+impl LexError {
+    /// Create a new error from its kind, line number and column number
+    pub fn new(kind: LexErrorKind, line: uint, column: uint) -> LexError {
+        LexError {
+            line: line,
+            column: column,
+            kind: kind
+        }
+    }
+}
+impl fmt::Show for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "{}:{}: ", self.line, self.column));
+        match self.kind {
+            ReadError(ref err) => write!(f, "Could not read the script: {}", err),
+            UnexpectedEnd => write!(f, "Unexpected end of script"),
+            UnexpectedChar(ch) => write!(f, "Unexpected '{}'", ch),
+            InvalidEscape(ch) => write!(f, "Invalid escape `{}`", ch),
+            InvalidUnicode(num) => write!(f, "{} is not a valid unicode scalar value", num),
+            InvalidNumber(ref num) => write!(f, "{} is not a valid number", num),
+            MissingDigits(ref num) => write!(f, "Missing digits after {}", num),
+            UnterminatedString => write!(f, "Unterminated string"),
+            UnterminatedRegExp => write!(f, "Unterminated regular expression"),
+            ReservedWord(ref word) => write!(f, "Unexpected reserved word `{}` used as a name", word),
+            LegacyOctal => write!(f, "Legacy octal numbers and escapes aren't allowed in strict mode"),
+            LimitExceeded(limit) => write!(f, "Exceeded the maximum {}", limit)
+        }
+    }
+}
+#[deriving(Clone, PartialEq, Show)]
+/// A kind of token that can span several lines, which the input can end in
+/// the middle of when it is being entered a line at a time
+pub enum Unfinished {
+    /// A string literal continued onto the next line with a `\`
+    UnfinishedString,
+    /// A `/* */` comment
+    UnfinishedComment,
+    /// A template literal, or a substitution inside one
+    UnfinishedTemplate
+}
+#[deriving(Clone, PartialEq)]
+/// Limits on how much the lexer will read, so untrusted scripts can't make it
+/// use an unbounded amount of memory
+pub struct LexerLimits {
+    /// The maximum number of tokens in the script
+    pub max_tokens : uint,
+    /// The maximum length in bytes of a single token, such as a string,
+    /// identifier or comment
+    pub max_token_length : uint,
+    /// The maximum number of template literals nested inside each other's
+    /// substitutions
+    pub max_template_depth : uint
+}
+impl Default for LexerLimits {
+    fn default() -> LexerLimits {
+        LexerLimits {
+            max_tokens: uint::MAX,
+            max_token_length: uint::MAX,
+            max_template_depth: uint::MAX
+        }
+    }
+}
+#[deriving(PartialEq)]
+/// What part of a JSX element is being lexed
+enum JsxContext {
+    /// The inside of a tag, which is a closing tag if this is true
+    JsxTag(bool),
+    /// The children between an opening tag and its closing tag
+    JsxChildren,
+    /// A Javascript expression in braces, with the number of unclosed blocks inside it
+    JsxExpression(uint)
+}
+/// The result of lexing, which is either successful or a `LexError`
+pub type LexResult<T> = Result<T, LexError>;
+#[deriving(PartialEq)]
+/// The base that a numeric literal is written in
+enum NumberBase {
+    /// A decimal number, such as `42` or `0.5`
+    DecimalNumber,
+    /// A hexadecimal number, such as `0x2A`
+    HexNumber,
+    /// An octal number, such as `0o52`
+    OctalNumber,
+    /// A legacy octal number, such as `052`
+    LegacyOctalNumber,
+    /// A binary number, such as `0b101010`
+    BinaryNumber
+}
+impl NumberBase {
+    /// Get the radix of numbers written in this base
+    fn radix(&self) -> uint {
+        match *self {
+            DecimalNumber => 10,
+            HexNumber => 16,
+            OctalNumber | LegacyOctalNumber => 8,
+            BinaryNumber => 2
+        }
+    }
+}
+impl fmt::Show for NumberBase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self {
+            DecimalNumber | LegacyOctalNumber => "0",
+            HexNumber => "0x",
+            OctalNumber => "0o",
+            BinaryNumber => "0b"
+        })
+    }
+}
+/// Checks if a character ends a line, which is a line feed, a carriage
+/// return, a line separator or a paragraph separator
+pub fn is_line_terminator(ch:char) -> bool {
+    ch == '\n' || ch == '\r' || ch == '\u2028' || ch == '\u2029'
+}
+/// Adds a whitespace character to a list of trivia, extending the run of
+/// whitespace at the end if there is one
+fn push_whitespace(trivia:&mut Vec<Trivia>, ch:char) {
+    match trivia.mut_last() {
+        Some(&WhitespaceTrivia(ref mut run)) => {
+            run.push_char(ch);
+            return;
+        },
+        _ => ()
+    }
+    trivia.push(WhitespaceTrivia(ch.to_string()));
+}
 /// A Javascript lexer
 pub struct Lexer<B> {
     /// The list of tokens generated so far
     pub tokens : Vec<Token>,
+    /// The last token that was lexed, not including comments
+    last : Option<TokenData>,
     /// The current line number in the script
     line_number : uint,
     /// The current column number in the script
     column_number : uint,
+    /// The number of bytes read from the script so far
+    offset : uint,
+    /// Whether the last character read was a carriage return, so a line feed
+    /// after it doesn't start another line
+    after_cr : bool,
+    /// The position that the token being lexed started at
+    start : Position,
+    /// The source text of the token being lexed
+    raw : String,
+    /// Whether the token being lexed uses a legacy octal number or escape
+    legacy_octal : bool,
+    /// Whether a line terminator has been read since the last token
+    newline_before : bool,
+    /// Whether the script is in strict mode, so legacy octal numbers and
+    /// escapes are errors
+    strict : bool,
+    /// Whether the HTML-like comments from Annex B of the spec are allowed
+    web_compat : bool,
+    /// Whether JSX elements are lexed into JSX tokens
+    jsx : bool,
+    /// The parts of the JSX elements being lexed, from outermost to innermost
+    jsx_stack : Vec<JsxContext>,
+    /// The limits on how much will be read
+    limits : LexerLimits,
+    /// The number of tokens lexed so far
+    tokens_lexed : uint,
+    /// The number of unclosed blocks inside each template substitution being lexed
+    template_depth : Vec<uint>,
+    /// The kind of multi-line token being lexed, if it is one
+    state : Option<Unfinished>,
+    /// The kind of token that the input ended in the middle of
+    unfinished : Option<Unfinished>,
+    /// Whether whitespace and comments are attached to tokens as trivia
+    /// instead of comments being pushed as tokens
+    keep_trivia : bool,
+    /// The trivia read since the last token, which will lead the next one
+    trivia : Vec<Trivia>,
     /// The reader
     buffer: B
 }
@@ -64,277 +283,981 @@ impl<B:Buffer> Lexer<B> {
     pub fn new(buffer: B) -> Lexer<B> {
         Lexer {
             tokens: Vec::new(),
+            last: None,
             line_number: 1,
             column_number: 0,
+            offset: 0,
+            after_cr: false,
+            start: Position::new(1, 1, 0),
+            raw: String::new(),
+            legacy_octal: false,
+            newline_before: false,
+            strict: false,
+            web_compat: false,
+            jsx: false,
+            jsx_stack: Vec::new(),
+            limits: Default::default(),
+            tokens_lexed: 0,
+            template_depth: Vec::new(),
+            state: None,
+            unfinished: None,
+            keep_trivia: false,
+            trivia: Vec::new(),
             buffer: buffer
         }
     }
     #[inline(always)]
     fn push_token(&mut self, tk:TokenData) {
-        self.tokens.push(Token::new(tk, self.line_number, self.column_number))
+        let end = Position::new(self.line_number, self.column_number + 1, self.offset);
+        self.tokens_lexed += 1;
+        let mut token = Token::new(tk.clone(), self.start, end);
+        token.raw = self.raw.clone();
+        token.legacy_octal = self.legacy_octal;
+        token.newline_before = self.newline_before;
+        match tk {
+            TComment(_) => (),
+            _ => {
+                self.last = Some(tk);
+                self.newline_before = false;
+            }
+        }
+        if self.keep_trivia {
+            token.leading = replace(&mut self.trivia, Vec::new());
+        }
+        self.tokens.push(token)
     }
     #[inline(always)]
     fn push_punc(&mut self, punc:Punctuator) {
         self.push_token(TPunctuator(punc));
     }
-    /// Processes an input stream from a string into an array of tokens
-    pub fn lex_str(script:&str) -> Vec<Token> {
+    /// Sets whether whitespace and comments are kept as trivia on the tokens
+    /// around them, so that the exact source can be rebuilt from the tokens
+    pub fn set_keep_trivia(&mut self, keep:bool) {
+        self.keep_trivia = keep;
+    }
+    /// Sets whether the script is in strict mode, which makes legacy octal
+    /// numbers and escapes errors instead of marking their tokens
+    pub fn set_strict(&mut self, strict:bool) {
+        self.strict = strict;
+    }
+    /// Sets whether `<!--` and `-->` at the start of a line begin comments, as
+    /// they do for scripts on web pages
+    pub fn set_web_compat(&mut self, web_compat:bool) {
+        self.web_compat = web_compat;
+    }
+    /// Sets whether JSX elements such as `<a href="/">Home</a>` are lexed into
+    /// JSX tokens, where they can start an expression
+    pub fn set_jsx(&mut self, jsx:bool) {
+        self.jsx = jsx;
+    }
+    /// Sets whether the script is in strict mode, whether HTML-like comments are
+    /// allowed and whether JSX elements are lexed from the parser options given
+    pub fn set_options(&mut self, options:&ParserOptions) {
+        self.strict = options.strict;
+        self.web_compat = options.web_compat;
+        self.jsx = options.allow_jsx;
+    }
+    /// Sets the limits on how much will be read, which are exceeded with a
+    /// `LimitExceeded` error
+    pub fn set_limits(&mut self, limits:LexerLimits) {
+        self.limits = limits;
+    }
+    /// Processes an input stream from a string into an array of tokens, giving the
+    /// error instead if the string can't be lexed
+    pub fn lex_str(script:&str) -> LexResult<Vec<Token>> {
         let script_bytes:&[u8] = script.as_bytes();
         let reader = BufReader::new(script_bytes);
         let buf_reader = BufferedReader::new(reader);
         let mut lexer = Lexer::new(buf_reader);
-        lexer.lex().unwrap();
-        lexer.tokens
+        try!(lexer.lex());
+        Ok(lexer.tokens)
+    }
+    #[inline(always)]
+    fn error<T>(&self, kind: LexErrorKind) -> LexResult<T> {
+        Err(LexError::new(kind, self.line_number, self.column_number))
+    }
+    /// Moves the position past a character that has been read
+    fn advance(&mut self, ch:char) {
+        self.offset += ch.len_utf8_bytes();
+        self.raw.push_char(ch);
+        if ch == '\n' && self.after_cr {
+            // a `\r\n` pair is a single line terminator
+        } else if is_line_terminator(ch) {
+            self.line_number += 1;
+            self.column_number = 0;
+        } else {
+            self.column_number += 1;
+        }
+        self.after_cr = ch == '\r';
+    }
+    /// Consumes a character that was looked at with `preview_next`
+    #[inline(always)]
+    fn skip(&mut self, ch:char) {
+        self.buffer.consume(ch.len_utf8_bytes());
+        self.advance(ch);
     }
+    /// Checks that the token being lexed hasn't grown past the length limit
     #[inline(always)]
-    fn next(&mut self) -> IoResult<char> {
-        self.buffer.read_char()
+    fn check_length(&self) -> LexResult<()> {
+        if self.raw.len() > self.limits.max_token_length {
+            self.error(LimitExceeded("token length"))
+        } else {
+            Ok(())
+        }
+    }
+    fn next(&mut self) -> LexResult<char> {
+        try!(self.check_length());
+        match self.buffer.read_char() {
+            Ok(ch) => {
+                self.advance(ch);
+                Ok(ch)
+            },
+            Err(IoError {kind: EndOfFile, ..}) => {
+                self.unfinished = self.state;
+                self.error(UnexpectedEnd)
+            },
+            Err(err) => self.error(ReadError(err))
+        }
+    }
+    /// Looks at the next character without consuming it, or gives `None` at
+    /// the end of the input
+    fn preview_next(&mut self) -> LexResult<Option<char>> {
+        try!(self.check_length());
+        let buf = match self.buffer.fill_buf() {
+            Ok(buf) => buf,
+            Err(IoError {kind: EndOfFile, ..}) => return Ok(None),
+            Err(err) => return Err(LexError::new(ReadError(err), self.line_number, self.column_number))
+        };
+        let width = utf8_char_width(buf[0]);
+        Ok(Some(if width > 1 && buf.len() >= width {
+            match from_utf8(buf.slice_to(width)) {
+                Some(text) => text.char_at(0),
+                None => buf[0] as char
+            }
+        } else {
+            buf[0] as char
+        }))
     }
-    fn preview_next(&mut self) -> IoResult<char> {
-        let buf = try!(self.buffer.fill_buf());
-        Ok(buf[0] as char)
+    /// Looks at the character after the next one without consuming anything,
+    /// if it has been buffered
+    fn preview_second(&mut self) -> LexResult<Option<char>> {
+        let buf = match self.buffer.fill_buf() {
+            Ok(buf) => buf,
+            Err(IoError {kind: EndOfFile, ..}) => return Ok(None),
+            Err(err) => return Err(LexError::new(ReadError(err), self.line_number, self.column_number))
+        };
+        Ok(if buf.len() > 1 {
+            Some(buf[1] as char)
+        } else {
+            None
+        })
+    }
+    /// Checks if a `/` at this point would begin a regular expression, rather
+    /// than being a division, by looking at the last significant token
+    fn regex_allowed(&self) -> bool {
+        match self.last {
+            Some(ref tk) => match *tk {
+                TIdentifier(_) | TNumericLiteral(_) | TBigInt(_) | TStringLiteral(_) | TBooleanLiteral(_)
+                | TNullLiteral | TRegularExpression(_, _) | TTemplate(_) | TTemplateTail(_)
+                | TKeyword(KThis) | TKeyword(KSuper)
+                | TPunctuator(PCloseParen) | TPunctuator(PCloseBracket) | TPunctuator(PCloseBlock)
+                | TPunctuator(PInc) | TPunctuator(PDec)
+                | TJSXTagEnd | TJSXSelfClosingTagEnd => false,
+                _ => true
+            },
+            None => true
+        }
     }
-    fn next_is(&mut self, peek:char) -> IoResult<bool> {
-        let result = try!(self.preview_next()) == peek;
+    fn next_is(&mut self, peek:char) -> LexResult<bool> {
+        let result = try!(self.preview_next()) == Some(peek);
         if result {
-            self.buffer.consume(1);
+            self.skip(peek);
         }
         Ok(result)
     }
-    /// Processes an input stream from the `buffer` into a vector of tokens
-    pub fn lex(&mut self) -> IoResult<()> {
+    /// Reads the rest of an escape sequence after a `\`, returning `None` if
+    /// it was a line continuation
+    fn read_escape(&mut self) -> LexResult<Option<char>> {
+        let escape = try!(self.next());
+        if is_line_terminator(escape) {
+            if escape == '\r' {
+                try!(self.next_is('\n'));
+            }
+            return Ok(None);
+        }
+        Ok(Some(match escape {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'b' => '\x08',
+            'f' => '\x0c',
+            'x' => {
+                let mut nums = String::with_capacity(2);
+                for _ in range(0u8, 2) {
+                    nums.push_char(try!(self.next()));
+                }
+                let as_num = match from_str_radix(nums.as_slice(), 16) {
+                    Some(v) => v,
+                    None => 0
+                };
+                match from_u32(as_num) {
+                    Some(v) => v,
+                    None => return self.error(InvalidUnicode(as_num))
+                }
+            },
+            'u' => {
+                let mut nums = String::new();
+                for _ in range(0u8, 4) {
+                    nums.push_char(try!(self.next()));
+                }
+                let as_num = match from_str_radix(nums.as_slice(), 16) {
+                    Some(v) => v,
+                    None => 0
+                };
+                match from_u32(as_num) {
+                    Some(v) => v,
+                    None => return self.error(InvalidUnicode(as_num))
+                }
+            },
+            '\'' | '"' | '`' | '$' | '{' | '}' | '\\' => escape,
+            '8' | '9' => {
+                self.legacy_octal = true;
+                escape
+            },
+            _ if escape.is_digit_radix(8) => {
+                let mut value = escape.to_digit(8).unwrap();
+                // octal escapes go up to `\377`
+                let max_digits = if escape <= '3' {3} else {2};
+                let mut digits = 1;
+                while digits < max_digits {
+                    match try!(self.preview_next()) {
+                        Some(ch) if ch.is_digit_radix(8) => {
+                            self.skip(ch);
+                            value = value * 8 + ch.to_digit(8).unwrap();
+                            digits += 1;
+                        },
+                        _ => break
+                    }
+                }
+                // `\0` on its own is a null character rather than an octal escape
+                if escape != '0' || digits > 1 {
+                    self.legacy_octal = true;
+                }
+                from_u32(value as u32).unwrap()
+            },
+            _ => return self.error(InvalidEscape(escape))
+        }))
+    }
+    /// Reads the characters of a template literal up to the closing backtick,
+    /// returning the characters and `true`, or up to the start of a `${}`
+    /// substitution, returning the characters and `false`
+    fn read_template(&mut self) -> LexResult<(String, bool)> {
+        let mut buf = String::new();
         loop {
-            let ch = match self.next() {
-                Ok(ch) => ch,
-                Err(IoError {kind: EndOfFile, ..}) => break,
-                Err(err) => return Err(err)
-            };
-            self.column_number += 1;
-            match ch {
+            match try!(self.next()) {
+                '`' => return Ok((buf, true)),
+                '$' => {
+                    if try!(self.next_is('{')) {
+                        return Ok((buf, false));
+                    }
+                    buf.push_char('$');
+                },
+                '\\' => match try!(self.read_escape()) {
+                    Some(escaped_ch) => buf.push_char(escaped_ch),
+                    None => ()
+                },
+                ch => buf.push_char(ch)
+            }
+        }
+    }
+    /// Reads the rest of a comment after a `//`, up to the end of the line
+    fn read_line_comment(&mut self) -> LexResult<String> {
+        let mut buf = String::new();
+        loop {
+            match try!(self.preview_next()) {
+                Some(ch) if !is_line_terminator(ch) => {
+                    self.skip(ch);
+                    buf.push_char(ch);
+                },
+                _ => return Ok(buf)
+            }
+        }
+    }
+    /// Checks if the next characters are `text`, consuming them if they are
+    fn next_is_str(&mut self, text:&str) -> LexResult<bool> {
+        let result = match self.buffer.fill_buf() {
+            Ok(buf) => buf.starts_with(text.as_bytes()),
+            Err(IoError {kind: EndOfFile, ..}) => false,
+            Err(err) => return Err(LexError::new(ReadError(err), self.line_number, self.column_number))
+        };
+        if result {
+            for ch in text.chars() {
+                self.skip(ch);
+            }
+        }
+        Ok(result)
+    }
+    /// Reads the rest of an HTML-like comment after the `start` that began it,
+    /// up to the end of the line
+    fn read_html_comment(&mut self, start:&str) -> LexResult<()> {
+        let comment = try!(self.read_line_comment());
+        if self.keep_trivia {
+            self.trivia.push(HtmlCommentTrivia(start.to_string().append(comment.as_slice())));
+        } else {
+            self.push_token(TComment(comment));
+        }
+        Ok(())
+    }
+    /// Reads the rest of a comment after a `/*`, up to and including the `*/`
+    fn read_block_comment(&mut self) -> LexResult<String> {
+        let mut buf = String::new();
+        loop {
+            match try!(self.next()) {
+                '*' =>
+                    if try!(self.next_is('/')) {
+                        return Ok(buf);
+                    } else {
+                        buf.push_char('*');
+                    },
+                ch =>
+                    buf.push_char(ch)
+            }
+        }
+    }
+    /// Reads the whitespace and comments after the last token up to the end
+    /// of its line, and attaches them to it
+    fn read_trailing_trivia(&mut self) -> LexResult<()> {
+        let mut trailing = Vec::new();
+        loop {
+            match try!(self.preview_next()) {
+                Some(ch) if ch == '\ufeff' || (ch.is_whitespace() && !is_line_terminator(ch)) => {
+                    self.skip(ch);
+                    push_whitespace(&mut trailing, ch);
+                },
+                Some('/') => match try!(self.preview_second()) {
+                    Some('/') => {
+                        self.skip('/');
+                        self.skip('/');
+                        trailing.push(LineCommentTrivia(try!(self.read_line_comment())));
+                    },
+                    Some('*') => {
+                        self.skip('/');
+                        self.skip('*');
+                        trailing.push(BlockCommentTrivia(try!(self.read_block_comment())));
+                    },
+                    _ => break
+                },
+                _ => break
+            }
+        }
+        match self.tokens.mut_last() {
+            Some(token) => token.trailing = trailing,
+            None => ()
+        }
+        Ok(())
+    }
+    /// Checks if `ch` is part of JSX markup, rather than Javascript or
+    /// whitespace between the parts of a tag
+    fn in_jsx_markup(&self, ch:char) -> bool {
+        match self.jsx_stack.last() {
+            Some(&JsxTag(_)) => !ch.is_whitespace() && !is_line_terminator(ch),
+            Some(&JsxChildren) => true,
+            _ => false
+        }
+    }
+    /// Lexes the part of a JSX tag or the text between tags starting with `ch`
+    fn lex_jsx_token(&mut self, ch:char) -> LexResult<()> {
+        let context = match self.jsx_stack.last() {
+            Some(&JsxTag(closing)) => JsxTag(closing),
+            _ => JsxChildren
+        };
+        match context {
+            JsxTag(closing) => match ch {
+                '=' => self.push_punc(PAssign),
+                '{' => {
+                    self.jsx_stack.push(JsxExpression(0));
+                    self.push_punc(POpenBlock)
+                },
+                '/' => {
+                    if !try!(self.next_is('>')) {
+                        return self.error(UnexpectedChar(ch));
+                    }
+                    self.jsx_stack.pop();
+                    self.push_token(TJSXSelfClosingTagEnd)
+                },
+                '>' => {
+                    self.jsx_stack.pop();
+                    if closing {
+                        // the closing tag also ends the children it closes
+                        self.jsx_stack.pop();
+                    } else {
+                        self.jsx_stack.push(JsxChildren);
+                    }
+                    self.push_token(TJSXTagEnd)
+                },
                 '"' | '\'' => {
+                    self.state = Some(UnfinishedString);
                     let mut buf = String::new();
                     loop {
                         match try!(self.next()) {
-                            '\'' if ch == '\'' => {
-                                break;
-                            },
-                            '"' if ch == '"' => {
-                                break;
-                            },
-                            '\\' => {
-                                let escape = try!(self.next());
-                                if escape != '\n' {
-                                    let escaped_ch = match escape {
-                                        'n' => '\n',
-                                        'r' => '\r',
-                                        't' => '\t',
-                                        'b' => '\x08',
-                                        'f' => '\x0c',
-                                        '0' => '\0',
-                                        'x' => {
-                                            let mut nums = String::with_capacity(2);
-                                            for _ in range(0u8, 2) {
-                                                nums.push_char(try!(self.next()));
-                                            }
-                                            self.column_number += 2;
-                                            let as_num = match from_str_radix(nums.as_slice(), 16) {
-                                                Some(v) => v,
-                                                None => 0
-                                            };
-                                            match from_u32(as_num) {
-                                                Some(v) => v,
-                                                None => fail!("{}:{}: {} is not a valid unicode scalar value", self.line_number, self.column_number, as_num)
-                                            }
-                                        },
-                                        'u' => {
-                                            let mut nums = String::new();
-                                            for _ in range(0u8, 4) {
-                                                nums.push_char(try!(self.next()));
-                                            }
-                                            self.column_number += 4;
-                                            let as_num = match from_str_radix(nums.as_slice(), 16) {
-                                                Some(v) => v,
-                                                None => 0
-                                            };
-                                            match from_u32(as_num) {
-                                                Some(v) => v,
-                                                None => fail!("{}:{}: {} is not a valid unicode scalar value", self.line_number, self.column_number, as_num)
-                                            }
-                                        },
-                                        '\'' | '"' => escape,
-                                        _ => fail!("{}:{}: Invalid escape `{}`", self.line_number, self.column_number, ch)
-                                    };
-                                    buf.push_char(escaped_ch);
-                                }
-                            },
-                            ch => buf.push_char(ch)
+                            next if next == ch => break,
+                            next => buf.push_char(next)
                         }
                     }
-                    self.push_token(TStringLiteral(buf))
+                    self.push_token(TJSXString(buf))
                 },
-                '0' => {
-                    let mut buf = String::new();
-                    let num = if try!(self.next_is('x')) {
-                        loop {
-                            let ch = try!(self.preview_next());
-                            match ch {
-                                ch if ch.is_digit_radix(16) => {
-                                    self.buffer.consume(1);
-                                    buf.push_char(ch)
-                                },
-                                _ => break
-                            }
+                _ if ch.is_alphabetic() || ch == '_' || ch == '$' => {
+                    let mut buf = ch.to_string();
+                    loop {
+                        match try!(self.preview_next()) {
+                            Some(next) if next.is_alphanumeric() || next == '-' || next == ':' || next == '.' || next == '_' || next == '$' => {
+                                self.skip(next);
+                                buf.push_char(next);
+                            },
+                            _ => break
                         }
-                        from_str_radix(buf.as_slice(), 16).unwrap()                  
+                    }
+                    self.push_token(TJSXIdentifier(buf))
+                },
+                _ => return self.error(UnexpectedChar(ch))
+            },
+            _ => match ch {
+                '<' => {
+                    let closing = try!(self.next_is('/'));
+                    self.jsx_stack.push(JsxTag(closing));
+                    self.push_token(if closing {
+                        TJSXClosingTagStart
                     } else {
-                        let mut gone_decimal = false;
+                        TJSXTagStart
+                    })
+                },
+                '{' => {
+                    self.jsx_stack.push(JsxExpression(0));
+                    self.push_punc(POpenBlock)
+                },
+                _ => {
+                    let mut buf = ch.to_string();
+                    loop {
+                        match try!(self.preview_next()) {
+                            Some(next) if next != '<' && next != '{' => {
+                                self.skip(next);
+                                buf.push_char(next);
+                            },
+                            _ => break
+                        }
+                    }
+                    self.push_token(TJSXText(buf))
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Reads the exponent of a decimal number, such as the `e-3` in `2.5e-3`,
+    /// into `buf` if there is one, returning if there was
+    fn read_exponent(&mut self, buf: &mut String) -> LexResult<bool> {
+        let e = match try!(self.preview_next()) {
+            Some(e) if e == 'e' || e == 'E' => e,
+            _ => return Ok(false)
+        };
+        self.skip(e);
+        buf.push_char(e);
+        match try!(self.preview_next()) {
+            Some(sign) if sign == '+' || sign == '-' => {
+                self.skip(sign);
+                buf.push_char(sign);
+            },
+            _ => ()
+        }
+        let mut has_digits = false;
+        loop {
+            match try!(self.preview_next()) {
+                Some(ch) if ch.is_digit() => {
+                    self.skip(ch);
+                    buf.push_char(ch);
+                    has_digits = true;
+                },
+                _ => break
+            }
+        }
+        if !has_digits {
+            return self.error(MissingDigits(buf.clone()));
+        }
+        Ok(true)
+    }
+    /// Processes an input stream from the `buffer` into a vector of tokens
+    pub fn lex(&mut self) -> LexResult<()> {
+        while try!(self.lex_token()) {}
+        Ok(())
+    }
+    /// Serializes the tokens lexed so far into a JSON list, with the kind,
+    /// value, source text and position of each one
+    pub fn to_json(&self) -> Json {
+        self.tokens.to_json()
+    }
+    /// Gives the kind of token that the input ended in the middle of, if more
+    /// input could finish it, such as when a REPL needs another line
+    pub fn unfinished(&self) -> Option<Unfinished> {
+        match self.unfinished {
+            Some(state) => Some(state),
+            None if !self.template_depth.is_empty() => Some(UnfinishedTemplate),
+            None => None
+        }
+    }
+    /// Gives the source text of the token that the input ended in the middle of
+    pub fn pending<'a>(&'a self) -> &'a str {
+        match self.unfinished {
+            Some(_) => self.raw.as_slice(),
+            None => ""
+        }
+    }
+    /// Continues lexing from `buffer` after the input ended in the middle of a
+    /// token, where `buffer` starts with the `pending` text of that token
+    pub fn resume(&mut self, buffer: B) -> LexResult<()> {
+        if self.unfinished.is_some() {
+            self.line_number = self.start.line_number;
+            self.column_number = self.start.column_number - 1;
+            self.offset = self.start.offset;
+            self.after_cr = false;
+            self.unfinished = None;
+        }
+        self.buffer = buffer;
+        self.lex()
+    }
+    /// Returns an iterator that lexes the tokens from the `buffer` as they
+    /// are needed, rather than collecting them all into `tokens` at once
+    pub fn tokens_iter<'a>(&'a mut self) -> Tokens<'a, B> {
+        Tokens {
+            lexer: self,
+            failed: false
+        }
+    }
+    /// Processes the next character from the `buffer`, which may push a token,
+    /// returning false when the end of the input has been reached
+    fn lex_token(&mut self) -> LexResult<bool> {
+        self.start = Position::new(self.line_number, self.column_number + 1, self.offset);
+        self.raw.truncate(0);
+        self.legacy_octal = false;
+        self.state = None;
+        let ch = match self.buffer.read_char() {
+            Ok(ch) => ch,
+            Err(IoError {kind: EndOfFile, ..}) => {
+                // the trivia at the end of the script needs a token to lead
+                if self.keep_trivia && !self.trivia.is_empty() {
+                    self.push_token(TEOF);
+                }
+                return Ok(false);
+            },
+            Err(err) => return self.error(ReadError(err))
+        };
+        self.advance(ch);
+        let token_count = self.tokens.len();
+        match ch {
+            _ if self.in_jsx_markup(ch) => try!(self.lex_jsx_token(ch)),
+            '"' | '\'' => {
+                self.state = Some(UnfinishedString);
+                let mut buf = String::new();
+                loop {
+                    match try!(self.next()) {
+                        '\'' if ch == '\'' => {
+                            break;
+                        },
+                        '"' if ch == '"' => {
+                            break;
+                        },
+                        '\\' => match try!(self.read_escape()) {
+                            Some(escaped_ch) => buf.push_char(escaped_ch),
+                            None => ()
+                        },
+                        // line and paragraph separators are allowed in strings
+                        '\n' | '\r' => return self.error(UnterminatedString),
+                        ch => buf.push_char(ch)
+                    }
+                }
+                self.push_token(TStringLiteral(buf))
+            },
+            '0' => {
+                let base = match try!(self.preview_next()) {
+                    Some('x') | Some('X') => HexNumber,
+                    Some('o') | Some('O') => OctalNumber,
+                    Some('b') | Some('B') => BinaryNumber,
+                    Some(ch) if ch.is_digit() => LegacyOctalNumber,
+                    _ => DecimalNumber
+                };
+                if base == LegacyOctalNumber {
+                    self.legacy_octal = true;
+                }
+                let token = match base {
+                    // `0n` is the only BigInt that can start with a zero
+                    DecimalNumber if try!(self.next_is('n')) => {
+                        let zero : BigInt = from_str("0").unwrap();
+                        TBigInt(zero)
+                    },
+                    DecimalNumber | LegacyOctalNumber => {
+                        let mut buf = "0".into_string();
+                        let mut gone_decimal = base == DecimalNumber;
                         loop {
-                            let ch = try!(self.preview_next());
-                            match ch {
-                                ch if ch.is_digit_radix(8) => {
+                            match try!(self.preview_next()) {
+                                Some(ch) if ch.is_digit_radix(8) => {
                                     buf.push_char(ch);
-                                    self.buffer.consume(1);
+                                    self.skip(ch);
                                 },
-                                '8' | '9' | '.' => {
+                                Some(ch) if ch == '8' || ch == '9' || ch == '.' => {
                                     gone_decimal = true;
                                     buf.push_char(ch);
-                                    self.buffer.consume(1);
+                                    self.skip(ch);
                                 },
                                 _ =>
                                     break
                             }
                         }
-                        if gone_decimal {
+                        if try!(self.read_exponent(&mut buf)) {
+                            gone_decimal = true;
+                        }
+                        match if gone_decimal {
                             from_str(buf.as_slice())
                         } else {
                             from_str_radix(buf.as_slice(), 8)
-                        }.unwrap()
-                    };
-                    self.push_token(TNumericLiteral(num))
-                },
-                _ if ch.is_digit() => {
-                    let mut buf = ch.to_string();
-                    loop {
-                        let ch = try!(self.preview_next());
-                        match ch {
-                            '.' => {
-                                buf.push_char(ch);
-                                self.buffer.consume(1);
-                            },
-                            _ if ch.is_digit() => {
-                                buf.push_char(ch);
-                                self.buffer.consume(1);
-                            },
-                            _ => break
+                        } {
+                            Some(num) => TNumericLiteral(num),
+                            None => return self.error(InvalidNumber(buf))
                         }
-                    }
-                    self.push_token(TNumericLiteral(from_str(buf.as_slice()).unwrap()));
-                },
-                _ if ch.is_alphabetic() || ch == '$' || ch == '_' => {
-                    let mut buf = ch.to_string();
-                    loop {
-                        let ch = try!(self.preview_next());
-                        match ch {
-                            _ if ch.is_alphabetic() || ch.is_digit() || ch == '_' => {
-                                buf.push_char(ch);
-                                self.buffer.consume(1);
-                            },
-                            _ => {
-                                break;
+                    },
+                    _ => {
+                        try!(self.next());
+                        let radix = base.radix();
+                        let mut buf = String::new();
+                        loop {
+                            match try!(self.preview_next()) {
+                                Some(ch) if ch.is_digit_radix(radix) => {
+                                    self.skip(ch);
+                                    buf.push_char(ch)
+                                },
+                                _ => break
                             }
                         }
-                    }
-                    self.push_token(match buf.as_slice() {
-                        "true" => TBooleanLiteral(true),
-                        "false" => TBooleanLiteral(false),
-                        "null" => TNullLiteral,
-                        slice => match FromStr::from_str(slice) {
-                            Some(keyword) => TKeyword(keyword),
-                            None => TIdentifier(buf.clone())
+                        if buf.len() == 0 {
+                            return self.error(MissingDigits(base.to_string()));
                         }
-                    });
-                },
-                ';' => self.push_punc(PSemicolon),
-                ':' => self.push_punc(PColon),
-                '.' => self.push_punc(PDot),
-                '(' => self.push_punc(POpenParen),
-                ')' => self.push_punc(PCloseParen),
-                ',' => self.push_punc(PComma),
-                '{' => self.push_punc(POpenBlock),
-                '}' => self.push_punc(PCloseBlock),
-                '[' => self.push_punc(POpenBracket),
-                ']' => self.push_punc(PCloseBracket),
-                '?' => self.push_punc(PQuestion),
-                '/' => {
-                    let token = match try!(self.preview_next()) {
-                        '/' => {
-                            let comment = try!(self.buffer.read_line());
-                            TComment(comment.as_slice().slice_to(comment.len() - 1).into_string())
+                        if try!(self.next_is('n')) {
+                            TBigInt(from_str_radix(buf.as_slice(), radix).unwrap())
+                        } else {
+                            TNumericLiteral(from_str_radix(buf.as_slice(), radix).unwrap())
+                        }
+                    }
+                };
+                self.push_token(token)
+            },
+            _ if ch.is_digit() => {
+                let mut buf = ch.to_string();
+                loop {
+                    match try!(self.preview_next()) {
+                        Some(ch) if ch == '.' || ch.is_digit() => {
+                            buf.push_char(ch);
+                            self.skip(ch);
                         },
-                        '*' => {
-                            let mut buf = String::new();
-                            loop {
-                                match try!(self.next()) {
-                                    '*' =>
-                                        if try!(self.next_is('/')) {
-                                            break;
-                                        } else {
-                                            buf.push_char('*');
-                                        },
-                                    ch =>
-                                        buf.push_char(ch)
-                                }
-                            }
-                            TComment(buf)
+                        _ => break
+                    }
+                }
+                if !buf.as_slice().contains_char('.') && try!(self.next_is('n')) {
+                    self.push_token(TBigInt(from_str(buf.as_slice()).unwrap()));
+                } else {
+                    try!(self.read_exponent(&mut buf));
+                    match from_str(buf.as_slice()) {
+                        Some(num) => self.push_token(TNumericLiteral(num)),
+                        None => return self.error(InvalidNumber(buf))
+                    }
+                }
+            },
+            _ if ch.is_alphabetic() || ch == '$' || ch == '_' => {
+                let mut buf = ch.to_string();
+                loop {
+                    match try!(self.preview_next()) {
+                        Some(ch) if ch.is_alphabetic() || ch.is_digit() || ch == '_' || ch == '$' => {
+                            buf.push_char(ch);
+                            self.skip(ch);
                         },
-                        '=' => TPunctuator(PAssignDiv),
-                        _ => TPunctuator(PDiv)
-                    };
-                    self.push_token(token)
-                },
-                '*' => op!(self, PAssignMul, PMul),
-                '+' => op!(self, PAssignAdd, PAdd, {
-                    '+' => PInc
-                }),
-                '-' => op!(self, PAssignSub, PSub, {
-                    '+' => PDec
-                }),
-                '%' => op!(self, PAssignMod, PMod),
-                '|' => op!(self, PAssignOr, POr, {
-                    '|' => PBoolOr
-                }),
-                '&' => op!(self, PAssignAnd, PAnd, {
-                    '&' => PBoolAnd
-                }),
-                '^' => op!(self, PAssignXor, PXor),
-                '=' => op!(self, if try!(self.next_is('=')) {
-                    PStrictEq
+                        _ => {
+                            break;
+                        }
+                    }
+                }
+                let last = self.last.clone();
+                let data = match (last, buf.as_slice()) {
+                    // reserved words can be used as property names
+                    (Some(TPunctuator(PDot)), _) | (Some(TPunctuator(POptionalChain)), _) => TIdentifier(buf.clone()),
+                    (_, "true") => TBooleanLiteral(true),
+                    (_, "false") => TBooleanLiteral(false),
+                    (_, "null") => TNullLiteral,
+                    (last, slice) => match FromStr::from_str(slice) {
+                        Some(keyword) => match last {
+                            // `let` and `yield` are only reserved in strict mode, except that `let` can't
+                            // declare `let`, and the parser reserves `yield` in generators
+                            Some(TKeyword(KVar)) | Some(TKeyword(KFunction)) if !self.strict && (keyword == KLet || keyword == KYield) =>
+                                TIdentifier(buf.clone()),
+                            Some(TKeyword(KLet)) | Some(TKeyword(KConst)) if !self.strict && keyword == KYield =>
+                                TIdentifier(buf.clone()),
+                            Some(TKeyword(KVar)) | Some(TKeyword(KLet)) | Some(TKeyword(KConst)) | Some(TKeyword(KFunction)) =>
+                                return self.error(ReservedWord(keyword)),
+                            _ => TKeyword(keyword)
+                        },
+                        None => TIdentifier(buf.clone())
+                    }
+                };
+                self.push_token(data);
+            },
+            ';' => self.push_punc(PSemicolon),
+            ':' => self.push_punc(PColon),
+            '.' => {
+                let punc = if try!(self.preview_next()) == Some('.') && try!(self.preview_second()) == Some('.') {
+                    self.skip('.');
+                    self.skip('.');
+                    PSpread
+                } else {
+                    PDot
+                };
+                self.push_punc(punc)
+            },
+            '(' => self.push_punc(POpenParen),
+            ')' => self.push_punc(PCloseParen),
+            ',' => self.push_punc(PComma),
+            '`' => {
+                self.state = Some(UnfinishedTemplate);
+                let (buf, closed) = try!(self.read_template());
+                let token = if closed {
+                    TTemplate(buf)
+                } else {
+                    if self.template_depth.len() >= self.limits.max_template_depth {
+                        return self.error(LimitExceeded("template depth"));
+                    }
+                    self.template_depth.push(0);
+                    TTemplateHead(buf)
+                };
+                self.push_token(token)
+            },
+            '{' => {
+                match self.template_depth.mut_last() {
+                    Some(depth) => *depth += 1,
+                    None => ()
+                }
+                match self.jsx_stack.mut_last() {
+                    Some(&JsxExpression(ref mut depth)) => *depth += 1,
+                    _ => ()
+                }
+                self.push_punc(POpenBlock)
+            },
+            '}' if self.jsx_stack.last() == Some(&JsxExpression(0)) => {
+                self.jsx_stack.pop();
+                self.push_punc(PCloseBlock)
+            },
+            '}' if self.template_depth.last() == Some(&0) => {
+                self.state = Some(UnfinishedTemplate);
+                let (buf, closed) = try!(self.read_template());
+                let token = if closed {
+                    self.template_depth.pop();
+                    TTemplateTail(buf)
                 } else {
-                    PEq
-                }, PAssign, {
-                    '>' => PArrow
-                }),
-                '<' => op!(self, PLessThanOrEq, PLessThan, {
+                    TTemplateMiddle(buf)
+                };
+                self.push_token(token)
+            },
+            '}' => {
+                match self.template_depth.mut_last() {
+                    Some(depth) => *depth -= 1,
+                    None => ()
+                }
+                match self.jsx_stack.mut_last() {
+                    Some(&JsxExpression(ref mut depth)) => *depth -= 1,
+                    _ => ()
+                }
+                self.push_punc(PCloseBlock)
+            },
+            '[' => self.push_punc(POpenBracket),
+            ']' => self.push_punc(PCloseBracket),
+            '?' => {
+                let punc = match try!(self.preview_next()) {
+                    Some('?') => {
+                        self.skip('?');
+                        vop!(self, PAssignCoalesce, PCoalesce)
+                    },
+                    // `a?.5:b` is a conditional with a number, not an optional chain
+                    Some('.') if !try!(self.preview_second()).map_or(false, |ch| ch.is_digit()) => {
+                        self.skip('.');
+                        POptionalChain
+                    },
+                    _ => PQuestion
+                };
+                self.push_punc(punc)
+            },
+            '/' => {
+                let token = match try!(self.preview_next()) {
+                    Some('/') => {
+                        self.skip('/');
+                        let comment = try!(self.read_line_comment());
+                        if self.keep_trivia {
+                            self.trivia.push(LineCommentTrivia(comment));
+                            return Ok(true);
+                        }
+                        TComment(comment)
+                    },
+                    Some('*') => {
+                        self.skip('*');
+                        self.state = Some(UnfinishedComment);
+                        let comment = try!(self.read_block_comment());
+                        // a comment over several lines separates tokens like a line terminator does
+                        if comment.as_slice().chars().any(is_line_terminator) {
+                            self.newline_before = true;
+                        }
+                        if self.keep_trivia {
+                            self.trivia.push(BlockCommentTrivia(comment));
+                            return Ok(true);
+                        }
+                        TComment(comment)
+                    },
+                    _ if self.regex_allowed() => {
+                        let mut body = String::new();
+                        let mut in_class = false;
+                        loop {
+                            match try!(self.next()) {
+                                '/' if !in_class => break,
+                                '[' => {
+                                    in_class = true;
+                                    body.push_char('[');
+                                },
+                                ']' => {
+                                    in_class = false;
+                                    body.push_char(']');
+                                },
+                                '\\' => {
+                                    body.push_char('\\');
+                                    match try!(self.next()) {
+                                        ch if is_line_terminator(ch) => return self.error(UnterminatedRegExp),
+                                        ch => body.push_char(ch)
+                                    }
+                                },
+                                ch if is_line_terminator(ch) => return self.error(UnterminatedRegExp),
+                                ch => body.push_char(ch)
+                            }
+                        }
+                        let mut flags = String::new();
+                        loop {
+                            match try!(self.preview_next()) {
+                                Some(ch) if ch.is_alphabetic() => {
+                                    self.skip(ch);
+                                    flags.push_char(ch);
+                                },
+                                _ => break
+                            }
+                        }
+                        TRegularExpression(body, flags)
+                    },
+                    Some('=') => {
+                        self.skip('=');
+                        TPunctuator(PAssignDiv)
+                    },
+                    _ => TPunctuator(PDiv)
+                };
+                self.push_token(token)
+            },
+            '*' => op!(self, PAssignMul, PMul, {
+                '*' => vop!(self, PAssignPow, PPow)
+            }),
+            '+' => op!(self, PAssignAdd, PAdd, {
+                '+' => PInc
+            }),
+            // `-->` is only a comment at the start of a line
+            '-' => if self.web_compat && (self.newline_before || self.last.is_none()) && try!(self.next_is_str("->")) {
+                try!(self.read_html_comment("-->"))
+            } else {
+                op!(self, PAssignSub, PSub, {
+                    '-' => PDec
+                })
+            },
+            '%' => op!(self, PAssignMod, PMod),
+            '|' => op!(self, PAssignOr, POr, {
+                '|' => vop!(self, PAssignBoolOr, PBoolOr)
+            }),
+            '&' => op!(self, PAssignAnd, PAnd, {
+                '&' => vop!(self, PAssignBoolAnd, PBoolAnd)
+            }),
+            '^' => op!(self, PAssignXor, PXor),
+            '=' => op!(self, if try!(self.next_is('=')) {
+                PStrictEq
+            } else {
+                PEq
+            }, PAssign, {
+                '>' => PArrow
+            }),
+            '<' => if self.jsx && self.regex_allowed() && try!(self.preview_next()).map_or(false, |next| next.is_alphabetic() || next == '>') {
+                self.jsx_stack.push(JsxTag(false));
+                self.push_token(TJSXTagStart)
+            } else if self.web_compat && try!(self.next_is_str("!--")) {
+                try!(self.read_html_comment("<!--"))
+            } else {
+                op!(self, PLessThanOrEq, PLessThan, {
                     '<' => vop!(self, PAssignLeftSh, PLeftSh)
-                }),
-                '>' => op!(self, PGreaterThanOrEq, PGreaterThan, {
-                    '>' => vop!(self, PAssignRightSh, PRightSh, {
-                        '>' => vop!(self, PAssignURightSh, PURightSh)
-                    })
-                }),
-                '!' => op!(self, vop!(self, PStrictNotEq, PNotEq), PNot),
-                '~' => self.push_punc(PNeg),
-                '\n' | '\u2028'|'\u2029' => {
-                    self.line_number += 1;
-                    self.column_number = 0;
-                },
-                '\r' => {
-                    self.column_number = 0;
+                })
+            },
+            '>' => op!(self, PGreaterThanOrEq, PGreaterThan, {
+                '>' => vop!(self, PAssignRightSh, PRightSh, {
+                    '>' => vop!(self, PAssignURightSh, PURightSh)
+                })
+            }),
+            '!' => op!(self, vop!(self, PStrictNotEq, PNotEq), PNot),
+            '~' => self.push_punc(PNeg),
+            // a byte order mark at the start of the script isn't part of it
+            '\ufeff' if self.start.offset == 0 => {
+                self.column_number = 0;
+            },
+            ' ' | '\t' | '\x0b' | '\x0c' | '\u00a0' | '\ufeff' =>
+                if self.keep_trivia {
+                    push_whitespace(&mut self.trivia, ch)
                 },
-                ' ' => (),
-                ch => fail!("{}:{}: Unexpected '{}'", self.line_number, self.column_number, ch)
-            };
+            _ if ch.is_whitespace() || is_line_terminator(ch) => {
+                if is_line_terminator(ch) {
+                    self.newline_before = true;
+                }
+                if self.keep_trivia {
+                    push_whitespace(&mut self.trivia, ch)
+                }
+            },
+            ch => return self.error(UnexpectedChar(ch))
         };
-        Ok(())
+        if self.strict && self.legacy_octal {
+            return self.error(LegacyOctal);
+        }
+        if self.tokens_lexed > self.limits.max_tokens {
+            return self.error(LimitExceeded("number of tokens"));
+        }
+        if self.keep_trivia && self.tokens.len() > token_count {
+            try!(self.read_trailing_trivia());
+        }
+        Ok(true)
     }
-}
\ No newline at end of file
+}
+/// An iterator over the tokens of a lexer, which lexes them one at a time, giving
+/// the error that stopped it as its last item if the input can't be lexed
+pub struct Tokens<'a, B> {
+    /// The lexer the tokens come from
+    lexer: &'a mut Lexer<B>,
+    /// Whether an error has been given, after which nothing more is lexed
+    failed: bool
+}
+impl<'a, B:Buffer> Iterator<LexResult<Token>> for Tokens<'a, B> {
+    fn next(&mut self) -> Option<LexResult<Token>> {
+        if self.failed {
+            return None;
+        }
+        while self.lexer.tokens.is_empty() {
+            match self.lexer.lex_token() {
+                Ok(true) => (),
+                Ok(false) if !self.lexer.tokens.is_empty() => break,
+                Ok(false) => return None,
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+        self.lexer.tokens.remove(0).map(|token| Ok(token))
+    }
+}
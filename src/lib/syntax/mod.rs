@@ -3,4 +3,12 @@ pub mod ast;
 /// Parses a string stream into a sequence of tokens
 pub mod lexer;
 /// Parses a sequence of tokens into expressions
-pub mod parser;
\ No newline at end of file
+pub mod parser;
+/// Generates Javascript source code from expressions
+pub mod codegen;
+/// Folds constant expressions
+pub mod fold;
+/// Hoists declarations to the start of the bodies they are in
+pub mod hoist;
+/// Rewrites expressions before they are run
+pub mod transform;
\ No newline at end of file
@@ -4,125 +4,662 @@ use syntax::ast::constant::*;
 use syntax::ast::op::*;
 use syntax::ast::punc::*;
 use syntax::ast::keyword::*;
+use syntax::ast::pattern::*;
+use syntax::ast::pos::Position;
 use collections::treemap::TreeMap;
 use std::fmt;
 use std::vec::Vec;
+use std::mem::replace;
+use std::default::Default;
+/// The precedence limit of an expression that can contain any operator
+static ANY_PRECEDENCE: uint = 20;
+/// The precedence of an assignment
+static ASSIGN_PRECEDENCE: uint = 18;
+/// The precedence of a conditional expression
+static IF_PRECEDENCE: uint = 16;
+/// The precedence of a postfix increment or decrement
+static POSTFIX_PRECEDENCE: uint = 3;
 macro_rules! mk (
     ($this:expr, $def:expr) => (
-        Expr::new($def, try!($this.get_token($this.pos - 1)).pos, try!($this.get_token($this.pos - 1)).pos)
+        Expr::new($def, try!($this.get_token($this.pos - 1)).pos, try!($this.get_token($this.pos - 1)).end)
+    );
+    ($this:expr, $def:expr, after $first:expr) => (
+        Expr::new($def, $first.start, try!($this.get_token($this.pos - 1)).end)
     );
     ($this:expr, $def:expr, $first:expr) => (
-        Expr::new($def, $first.pos, try!($this.get_token($this.pos - 1)).pos)
+        Expr::new($def, $first.pos, try!($this.get_token($this.pos - 1)).end)
     );
 )
+/// Wraps an expression in an optional chain if it contains optional accesses
+/// that aren't part of one yet
+fn close_chain(expr:Expr) -> Expr {
+    fn has_optional(expr:&Expr) -> bool {
+        match expr.def {
+            OptionalExpr(_) => true,
+            GetConstFieldExpr(box ref obj, _) | GetFieldExpr(box ref obj, _) | CallExpr(box ref obj, _) =>
+                has_optional(obj),
+            _ => false
+        }
+    }
+    if has_optional(&expr) {
+        Expr::new(OptionalChainExpr(box expr.clone()), expr.start, expr.end)
+    } else {
+        expr
+    }
+}
+/// Returns an error if an increment or decrement can't store its result in `target`
+fn check_update_target(target:&Expr) -> Result<(), ParseError> {
+    match target.def {
+        LocalExpr(_) | GetConstFieldExpr(_, _) | GetFieldExpr(_, _) => Ok(()),
+        _ => Err(ParseError::new(ExpectedExpr("variable or field to update", target.clone())))
+    }
+}
+/// Converts the left side of a destructuring assignment, which is parsed as an array or object
+/// declaration, into the pattern it stands for
+fn to_pattern(expr:&Expr) -> Result<Pattern, ParseError> {
+    Ok(match expr.def {
+        LocalExpr(_) | GetConstFieldExpr(_, _) | GetFieldExpr(_, _) =>
+            RefPattern(box expr.clone()),
+        AssignExpr(box ref target, ref default) =>
+            DefaultPattern(box try!(to_pattern(target)), default.clone()),
+        ArrayDeclExpr(ref items) => {
+            let mut patterns = Vec::with_capacity(items.len());
+            for item in items.iter() {
+                patterns.push(match item.def {
+                    // holes are parsed as `null`, which can't be assigned to anyway
+                    ConstExpr(CNull) => None,
+                    _ => Some(try!(to_pattern(item)))
+                });
+            }
+            ArrayPattern(patterns)
+        },
+        ObjectDeclExpr(box ref fields) => {
+            let mut patterns = Vec::with_capacity(fields.len());
+            for (name, value) in fields.iter() {
+                patterns.push((name.clone(), try!(to_pattern(value))));
+            }
+            ObjectPattern(patterns)
+        },
+        _ => return Err(ParseError::new(ExpectedExpr("destructuring pattern", expr.clone())))
+    })
+}
 #[deriving(Clone, PartialEq)]
-/// An error encountered during parsing an expression
-pub enum ParseError {
+/// The kind of an error encountered during parsing an expression
+pub enum ParseErrorKind {
     /// When it expected a certain kind of token, but got another as part of something
     Expected(Vec<TokenData>, Token, &'static str),
     /// When it expected a certain expression, but got another
     ExpectedExpr(&'static str, Expr),
     /// When it didn't expect this keyword
-    UnexpectedKeyword(Keyword),
+    UnexpectedKeyword(Token),
     /// When there is an abrupt end to the parsing
-    AbruptEnd
+    AbruptEnd,
+    /// When a legacy octal number or escape is used in strict mode
+    StrictLegacyOctal(Token),
+    /// When a `break` or `continue` targets a label that doesn't surround it
+    UndefinedLabel(Token, String),
+    /// When `new.target` is used outside of a function
+    NewTargetOutsideFunction(Token),
+    /// When a variable is deleted in strict mode, where only properties can be
+    StrictDelete(Token),
+    /// When `let` or `yield` is declared in strict mode, or `yield` in a generator, where they are reserved
+    ReservedName(Token),
+    /// When a regular expression has flags that are unknown or repeated
+    InvalidRegExpFlags(Token, String),
+    /// When syntax is used that was added in a later version of the standard than the one being parsed,
+    /// with the name of the syntax and the version it was added in
+    UnsupportedSyntax(Token, &'static str, EsVersion)
+}
+#[deriving(Clone, PartialEq)]
+/// An error encountered during parsing an expression, with where it happened and
+/// the tokens that were expected there
+pub struct ParseError {
+    /// The line number the error was found on
+    pub line : uint,
+    /// The column number the error was found at
+    pub column : uint,
+    /// The token that was found where the error happened, which is `None` at the end of the script
+    pub found : Option<TokenData>,
+    /// The tokens that could have been there instead, which is empty if an expression was expected
+    pub expected : Vec<TokenData>,
+    /// The kind of error
+    pub kind : ParseErrorKind
+}
+impl ParseError {
+    /// Create a new error from its kind, taking where it happened from the token or expression it has
+    pub fn new(kind: ParseErrorKind) -> ParseError {
+        let (found, expected, pos) = match kind {
+            Expected(ref wanted, ref got, _) => (Some(got.data.clone()), wanted.clone(), Some(got.pos)),
+            ExpectedExpr(_, ref got) => (None, Vec::new(), Some(got.start)),
+            UnexpectedKeyword(ref tk) | StrictLegacyOctal(ref tk) | UndefinedLabel(ref tk, _)
+                | NewTargetOutsideFunction(ref tk) | StrictDelete(ref tk) | ReservedName(ref tk) | InvalidRegExpFlags(ref tk, _)
+                | UnsupportedSyntax(ref tk, _, _) => (Some(tk.data.clone()), Vec::new(), Some(tk.pos)),
+            AbruptEnd => (None, Vec::new(), None)
+        };
+        ParseError {
+            line: match pos {Some(pos) => pos.line_number, None => 0},
+            column: match pos {Some(pos) => pos.column_number, None => 0},
+            found: found,
+            expected: expected,
+            kind: kind
+        }
+    }
 }
 impl fmt::Show for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
-            Expected(ref wanted, ref got, ref routine) if wanted.len() == 0 => write!(f, "{}:{}: Expected expression for {}, got {}", got.pos.line_number, got.pos.column_number, routine, got.data),
+        try!(write!(f, "{}:{}: ", self.line, self.column));
+        match self.kind {
+            Expected(ref wanted, ref got, ref routine) if wanted.len() == 0 => write!(f, "Expected expression for {} but found {}", routine, got.data),
             Expected(ref wanted, ref got, ref routine) => {
-                try!(write!(f, "{}:{}: ", got.pos.line_number, got.pos.column_number));
                 try!(write!(f, "Expected "));
                 let last = wanted.last().unwrap();
                 for wanted_token in wanted.iter() {
                     try!(write!(f, "'{}'{}", wanted_token, if wanted_token == last {""} else {", "}));
                 }
-                try!(write!(f, " for {}", routine));
-                write!(f, " but got {}", got.data)
-            },
-            UnexpectedKeyword(ref key) => {
-                write!(f, "Unexpected {}", key)
-            }
-            ExpectedExpr(ref wanted, ref got) => {
-                write!(f, "Expected {}, but got {}", wanted, got)
+                write!(f, " for {} but found '{}'", routine, got.data)
             },
-            AbruptEnd => {
-                write!(f, "Abrupt end")
-            }
+            UnexpectedKeyword(ref tk) => write!(f, "Unexpected {}", tk.data),
+            ExpectedExpr(ref wanted, ref got) => write!(f, "Expected {} but found {}", wanted, got),
+            AbruptEnd => write!(f, "Unexpected end of script"),
+            StrictLegacyOctal(_) => write!(f, "Legacy octal numbers and escapes aren't allowed in strict mode"),
+            UndefinedLabel(_, ref label) => write!(f, "Undefined label '{}'", label),
+            NewTargetOutsideFunction(_) => write!(f, "new.target can only be used in functions"),
+            StrictDelete(_) => write!(f, "Delete of an unqualified identifier in strict mode"),
+            ReservedName(ref tk) => write!(f, "Unexpected reserved word '{}' used as a name", tk.data),
+            InvalidRegExpFlags(_, ref flags) => write!(f, "Invalid regular expression flags '{}'", flags),
+            UnsupportedSyntax(_, ref syntax, ref version) => write!(f, "{} need {} or later", syntax, version)
         }
     }
 }
 pub type ParseResult = Result<Expr, ParseError>;
+#[deriving(Clone, PartialEq, PartialOrd)]
+/// A version of the ECMAScript standard, which decides the syntax that is accepted
+pub enum EsVersion {
+    /// ECMAScript 5, from 2009
+    Es5,
+    /// ECMAScript 2015, which added classes, modules, arrow functions and much more
+    Es2015,
+    /// ECMAScript 2016, which added the exponent operator
+    Es2016,
+    /// ECMAScript 2017
+    Es2017,
+    /// ECMAScript 2018
+    Es2018,
+    /// ECMAScript 2019, which made the binding of a catch block optional
+    Es2019,
+    /// ECMAScript 2020, which added optional chaining, nullish coalescing and BigInts
+    Es2020,
+    /// ECMAScript 2021, which added logical assignment
+    Es2021
+}
+impl fmt::Show for EsVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self {
+            Es5 => "ES5",
+            Es2015 => "ES2015",
+            Es2016 => "ES2016",
+            Es2017 => "ES2017",
+            Es2018 => "ES2018",
+            Es2019 => "ES2019",
+            Es2020 => "ES2020",
+            Es2021 => "ES2021"
+        })
+    }
+}
+#[deriving(Clone, PartialEq)]
+/// The options that decide which syntax is accepted, so that an embedder can lock
+/// the grammar down, such as to ES5 for a configuration language
+pub struct ParserOptions {
+    /// The latest version of the standard whose syntax is accepted
+    pub es_version : EsVersion,
+    /// Whether JSX elements are lexed
+    pub allow_jsx : bool,
+    /// Whether the HTML-like comments from Annex B of the spec are allowed
+    pub web_compat : bool,
+    /// Whether the code starts in strict mode
+    pub strict : bool
+}
+impl Default for ParserOptions {
+    fn default() -> ParserOptions {
+        ParserOptions {
+            es_version: Es2021,
+            allow_jsx: false,
+            web_compat: false,
+            strict: false
+        }
+    }
+}
+/// Get the first version of the standard with the syntax that starts at a token,
+/// and the name of the syntax, if it was added after ES5
+fn version_needed(tk:&Token, next:Option<&Token>) -> Option<(EsVersion, &'static str)> {
+    let next = next.map(|next| &next.data);
+    Some(match tk.data {
+        TKeyword(KLet) | TKeyword(KConst) => (Es2015, "let and const declarations"),
+        TKeyword(KClass) | TKeyword(KSuper) => (Es2015, "classes"),
+        TKeyword(KImport) | TKeyword(KExport) => (Es2015, "modules"),
+        TKeyword(KYield) => (Es2015, "generators"),
+        TKeyword(KFunction) if next == Some(&TPunctuator(PMul)) => (Es2015, "generators"),
+        TPunctuator(PArrow) => (Es2015, "arrow functions"),
+        TPunctuator(PSpread) => (Es2015, "spread and rest elements"),
+        TTemplate(_) | TTemplateHead(_) => (Es2015, "template literals"),
+        TNumericLiteral(_) if tk.raw.len() > 1 && match tk.raw.as_slice().char_at(1) {
+            'b' | 'B' | 'o' | 'O' => tk.raw.as_slice().char_at(0) == '0',
+            _ => false
+        } => (Es2015, "binary and octal literals"),
+        TPunctuator(PPow) | TPunctuator(PAssignPow) => (Es2016, "exponent operators"),
+        TKeyword(KCatch) if next == Some(&TPunctuator(POpenBlock)) => (Es2019, "catch blocks without a binding"),
+        TPunctuator(POptionalChain) => (Es2020, "optional chains"),
+        TPunctuator(PCoalesce) => (Es2020, "nullish coalescing operators"),
+        TBigInt(_) => (Es2020, "BigInt literals"),
+        TPunctuator(PAssignBoolAnd) | TPunctuator(PAssignBoolOr) | TPunctuator(PAssignCoalesce) => (Es2021, "logical assignments"),
+        _ => return None
+    })
+}
 /// A Javascript parser
 pub struct Parser {
     /// The tokens being input
     tokens: Vec<Token>,
     /// The current position within the tokens
-    pos: uint
+    pos: uint,
+    /// Whether the code being parsed is in strict mode
+    strict: bool,
+    /// The labels of the statements surrounding the code being parsed
+    labels: Vec<String>,
+    /// Whether the expression being parsed is followed by a colon, so an identifier
+    /// followed by a colon isn't a label
+    expect_colon: bool,
+    /// Whether the code being parsed is in the body of a generator function, where `yield` starts an expression
+    in_generator: bool,
+    /// Whether the code being parsed is in the body of a function, including arrow functions inside of one
+    in_function: bool,
+    /// The loosest precedence that the operators of the next expression parsed can have
+    limit: uint,
+    /// The options that decide which syntax is accepted
+    options: ParserOptions
 }
 impl Parser {
     #[inline(always)]
     /// Creates a new parser, using `tokens` as input
     pub fn new(tokens: Vec<Token>) -> Parser {
-        Parser {tokens: tokens, pos: 0}
+        Parser::with_options(tokens, Default::default())
+    }
+    /// Creates a new parser, using `tokens` as input and only accepting the syntax that the options allow
+    pub fn with_options(tokens: Vec<Token>, options: ParserOptions) -> Parser {
+        Parser {tokens: tokens, pos: 0, strict: options.strict, labels: Vec::new(), expect_colon: false, in_generator: false, in_function: false, limit: ANY_PRECEDENCE, options: options}
+    }
+    /// Returns an error for the first token from `pos` that starts syntax which
+    /// the version of the standard being parsed doesn't have
+    fn check_version(&self, pos:uint) -> Result<(), ParseError> {
+        for i in range(pos, self.tokens.len()) {
+            let tk = &self.tokens[i];
+            match version_needed(tk, self.tokens.slice_from(i + 1).head()) {
+                Some((version, syntax)) if version > self.options.es_version =>
+                    return Err(ParseError::new(UnsupportedSyntax(tk.clone(), syntax, version))),
+                _ => ()
+            }
+        }
+        Ok(())
     }
-    /// Parse all expressions in the token array
+    /// Checks if the code starting at `pos` begins with a `"use strict"` directive
+    fn has_use_strict(&self, pos:uint) -> bool {
+        if pos >= self.tokens.len() {
+            return false;
+        }
+        for tk in self.tokens.slice_from(pos).iter() {
+            match tk.data {
+                TComment(_) => (),
+                // the directive can't contain escapes, so check how it was written
+                TStringLiteral(_) => return tk.raw.as_slice() == "'use strict'" || tk.raw.as_slice() == "\"use strict\"",
+                _ => return false
+            }
+        }
+        false
+    }
+    /// Parse all expressions in the token array as a script, like `parse_script`
     pub fn parse_all(&mut self) -> ParseResult {
+        self.parse_script()
+    }
+    /// Parse a single expression that makes up all of the tokens, which can't be a statement,
+    /// such as a value in a configuration file or the condition of a template
+    pub fn parse_expr(&mut self) -> ParseResult {
+        try!(self.check_version(self.pos));
+        let first = try!(self.get_token(self.pos));
+        match first.data {
+            TKeyword(KIf) | TKeyword(KVar) | TKeyword(KLet) | TKeyword(KConst) | TKeyword(KReturn) | TKeyword(KThrow)
+            | TKeyword(KBreak) | TKeyword(KContinue) | TKeyword(KWhile) | TKeyword(KDo) | TKeyword(KFor)
+            | TKeyword(KTry) | TKeyword(KSwitch) | TKeyword(KImport) | TKeyword(KExport) =>
+                return Err(ParseError::new(UnexpectedKeyword(first))),
+            _ => ()
+        }
+        let expr = try!(self.parse());
+        match expr.def {
+            BlockExpr(_) | LabelledExpr(_, _) => return Err(ParseError::new(ExpectedExpr("expression", expr))),
+            _ => ()
+        }
+        while self.pos < self.tokens.len() {
+            let tk = self.tokens[self.pos].clone();
+            match tk.data {
+                TComment(_) => self.pos += 1,
+                _ => return Err(ParseError::new(Expected(vec!(TEOF), tk, "expression")))
+            }
+        }
+        Ok(expr)
+    }
+    /// Parse all expressions in the token array as a script
+    pub fn parse_script(&mut self) -> ParseResult {
+        try!(self.check_version(self.pos));
+        let first = try!(self.get_token(self.pos));
         let mut exprs = Vec::new();
+        if self.has_use_strict(self.pos) {
+            self.strict = true;
+        }
         while self.pos < self.tokens.len() {
             let result = try!(self.parse());
             exprs.push(result);
         }
-        Ok(mk!(self, BlockExpr(exprs)))
+        Ok(mk!(self, ScriptExpr(exprs, self.strict), first))
+    }
+    /// Parse all expressions in the token array, skipping past the end of the statement that
+    /// each error is in and carrying on, giving the expressions that could be parsed and the errors
+    pub fn parse_lenient(&mut self) -> (Expr, Vec<ParseError>) {
+        let mut exprs = Vec::new();
+        let mut errors = Vec::new();
+        match self.check_version(self.pos) {
+            Ok(()) => (),
+            Err(err) => errors.push(err)
+        }
+        if self.has_use_strict(self.pos) {
+            self.strict = true;
+        }
+        let strict = self.strict;
+        while self.pos < self.tokens.len() {
+            let start = self.pos;
+            match self.parse() {
+                Ok(expr) => exprs.push(expr),
+                Err(err) => {
+                    errors.push(err);
+                    // the error could have happened anywhere inside of the statement
+                    self.strict = strict;
+                    self.labels = Vec::new();
+                    self.expect_colon = false;
+                    self.in_generator = false;
+                    self.in_function = false;
+                    self.limit = ANY_PRECEDENCE;
+                    self.skip_statement(start);
+                }
+            }
+        }
+        let start = match self.tokens.head() {
+            Some(tk) => tk.pos,
+            None => Position::new(1, 1, 0)
+        };
+        let end = match self.tokens.last() {
+            Some(tk) => tk.end,
+            None => start
+        };
+        (Expr::new(ScriptExpr(exprs, strict), start, end), errors)
+    }
+    /// Skip to just after the next semicolon or closing brace, which is where the statement
+    /// starting at `start` that an error was found in most likely ends
+    fn skip_statement(&mut self, start:uint) {
+        fn ends_statement(tk:&Token) -> bool {
+            tk.data == TPunctuator(PSemicolon) || tk.data == TPunctuator(PCloseBlock)
+        }
+        if self.pos > self.tokens.len() {
+            self.pos = self.tokens.len();
+        }
+        // the token the error was found at could have ended the statement already
+        if self.pos > start + 1 && ends_statement(&self.tokens[self.pos - 1]) {
+            return;
+        }
+        // always move on, so the same error isn't found again
+        if self.pos <= start {
+            self.pos = start + 1;
+        }
+        while self.pos < self.tokens.len() {
+            self.pos += 1;
+            if ends_statement(&self.tokens[self.pos - 1]) {
+                return;
+            }
+        }
+    }
+    /// Parse all expressions in the token array as a module, which is always in strict mode
+    /// and can import and export bindings in its top level
+    pub fn parse_module(&mut self) -> ParseResult {
+        try!(self.check_version(self.pos));
+        let first = try!(self.get_token(self.pos));
+        let mut stmts = Vec::new();
+        self.strict = true;
+        while self.pos < self.tokens.len() {
+            let stmt = match try!(self.get_token(self.pos)).data {
+                TPunctuator(PSemicolon) | TComment(_) => {
+                    self.pos += 1;
+                    continue;
+                },
+                TKeyword(KImport) => {
+                    self.pos += 1;
+                    try!(self.parse_import())
+                },
+                TKeyword(KExport) => {
+                    self.pos += 1;
+                    try!(self.parse_export())
+                },
+                _ => try!(self.parse())
+            };
+            stmts.push(stmt);
+        }
+        Ok(mk!(self, ModuleExpr(stmts), first))
+    }
+    /// Parse an import declaration after the `import` keyword
+    fn parse_import(&mut self) -> ParseResult {
+        let start = try!(self.get_token(self.pos - 1));
+        let mut names = Vec::new();
+        let tk = try!(self.get_token(self.pos));
+        match tk.data {
+            // a module can be imported just to run it
+            TStringLiteral(_) => (),
+            TIdentifier(ref local) => {
+                self.pos += 1;
+                names.push(("default".into_string(), local.clone()));
+                if try!(self.get_token(self.pos)).data == TPunctuator(PComma) {
+                    self.pos += 1;
+                    names.push_all(try!(self.parse_import_bindings()).as_slice());
+                }
+                try!(self.expect_contextual("from", "import declaration"));
+            },
+            _ => {
+                names = try!(self.parse_import_bindings());
+                try!(self.expect_contextual("from", "import declaration"));
+            }
+        }
+        let specifier = try!(self.parse_module_specifier("import declaration"));
+        Ok(mk!(self, ImportExpr(names, specifier), start))
+    }
+    /// Parse either the name that an object holding all the exports of a module is bound to,
+    /// or the braces around the exports bound by name
+    fn parse_import_bindings(&mut self) -> Result<Vec<(String, String)>, ParseError> {
+        if try!(self.get_token(self.pos)).data != TPunctuator(PMul) {
+            return self.parse_module_names("import declaration");
+        }
+        self.pos += 1;
+        try!(self.expect_contextual("as", "namespace import"));
+        let tk = try!(self.get_token(self.pos));
+        self.pos += 1;
+        match tk.data {
+            TIdentifier(ref local) => Ok(vec!(("*".into_string(), local.clone()))),
+            _ => Err(ParseError::new(Expected(vec!(TIdentifier("identifier".into_string())), tk.clone(), "namespace import")))
+        }
+    }
+    /// Parse an export declaration after the `export` keyword
+    fn parse_export(&mut self) -> ParseResult {
+        let start = try!(self.get_token(self.pos - 1));
+        let tk = try!(self.get_token(self.pos));
+        let def = match tk.data {
+            TKeyword(KDefault) => {
+                self.pos += 1;
+                ExportDefaultExpr(box try!(self.parse_limited(ASSIGN_PRECEDENCE)))
+            },
+            TKeyword(KVar) | TKeyword(KLet) | TKeyword(KConst) | TKeyword(KFunction) | TKeyword(KClass) =>
+                ExportDeclExpr(box try!(self.parse())),
+            TPunctuator(PMul) => {
+                self.pos += 1;
+                let name = if self.is_contextual("as") {
+                    self.pos += 1;
+                    Some(try!(self.parse_module_name("export declaration")))
+                } else {
+                    None
+                };
+                try!(self.expect_contextual("from", "export declaration"));
+                let specifier = try!(self.parse_module_specifier("export declaration"));
+                match name {
+                    Some(name) => ExportNamedExpr(vec!(("*".into_string(), name)), Some(specifier)),
+                    None => ExportAllExpr(specifier)
+                }
+            },
+            TPunctuator(POpenBlock) => {
+                let names = try!(self.parse_module_names("export declaration"));
+                let specifier = if self.is_contextual("from") {
+                    self.pos += 1;
+                    Some(try!(self.parse_module_specifier("export declaration")))
+                } else {
+                    None
+                };
+                ExportNamedExpr(names, specifier)
+            },
+            _ => return Err(ParseError::new(Expected(vec!(TKeyword(KDefault), TKeyword(KVar), TKeyword(KLet), TKeyword(KConst), TKeyword(KFunction), TKeyword(KClass), TPunctuator(PMul), TPunctuator(POpenBlock)), tk, "export declaration")))
+        };
+        Ok(mk!(self, def, start))
+    }
+    /// Parse the names in the braces of an import or export declaration, as pairs of
+    /// each name and the name it is bound or exported as
+    fn parse_module_names(&mut self, routine:&'static str) -> Result<Vec<(String, String)>, ParseError> {
+        try!(self.expect_punc(POpenBlock, routine));
+        let mut names = Vec::new();
+        while try!(self.get_token(self.pos)).data != TPunctuator(PCloseBlock) {
+            let name = try!(self.parse_module_name(routine));
+            let alias = if self.is_contextual("as") {
+                self.pos += 1;
+                try!(self.parse_module_name(routine))
+            } else {
+                name.clone()
+            };
+            names.push((name, alias));
+            if try!(self.get_token(self.pos)).data != TPunctuator(PComma) {
+                break;
+            }
+            self.pos += 1;
+        }
+        try!(self.expect_punc(PCloseBlock, routine));
+        Ok(names)
+    }
+    /// Parse a name that is imported or exported, which can be a keyword such as `default`
+    fn parse_module_name(&mut self, routine:&'static str) -> Result<String, ParseError> {
+        let tk = try!(self.get_token(self.pos));
+        self.pos += 1;
+        match tk.data {
+            TIdentifier(ref name) => Ok(name.clone()),
+            TKeyword(ref keyword) => Ok(keyword.to_string()),
+            _ => Err(ParseError::new(Expected(vec!(TIdentifier("identifier".into_string())), tk.clone(), routine)))
+        }
+    }
+    /// Parse the string that specifies the module being imported from
+    fn parse_module_specifier(&mut self, routine:&'static str) -> Result<String, ParseError> {
+        let tk = try!(self.get_token(self.pos));
+        self.pos += 1;
+        match tk.data {
+            TStringLiteral(ref specifier) => Ok(specifier.clone()),
+            _ => Err(ParseError::new(Expected(vec!(TStringLiteral("module specifier".into_string())), tk.clone(), routine)))
+        }
+    }
+    /// Checks if the next token is the identifier given, which is a keyword in some places such as `from`
+    fn is_contextual(&self, word:&'static str) -> bool {
+        self.pos < self.tokens.len() && match self.tokens[self.pos].data {
+            TIdentifier(ref id) => id.as_slice() == word,
+            _ => false
+        }
+    }
+    /// Returns an error if the next token is not the identifier given
+    fn expect_contextual(&mut self, word:&'static str, routine:&'static str) -> Result<(), ParseError> {
+        self.expect(TIdentifier(word.into_string()), routine)
     }
     fn parse_struct(&mut self, keyword:Keyword) -> ParseResult {
+        let start = try!(self.get_token(self.pos - 1));
         match keyword {
             KThrow => {
+                // a semicolon would be inserted before a value on the next line, leaving nothing to throw
+                let tk = try!(self.get_token(self.pos));
+                if tk.newline_before {
+                    return Err(ParseError::new(Expected(Vec::new(), tk, "throw statement")));
+                }
                 let thrown = try!(self.parse());
-                Ok(mk!(self, ThrowExpr(box thrown)))
+                Ok(mk!(self, ThrowExpr(box thrown), start))
             },
             KVar => {
-                let mut vars = Vec::new();
-                loop {
-                    let name = match self.get_token(self.pos) {
-                        Ok(Token { data: TIdentifier(ref name), ..}) => name.clone(),
-                        Ok(tok) => return Err(Expected(vec!(TIdentifier("identifier".into_string())), tok, "var statement")),
-                        Err(AbruptEnd) => break,
-                        Err(e) => return Err(e)
-                    };
-                    self.pos += 1;
-                    match self.get_token(self.pos) {
-                        Ok(Token {data: TPunctuator(PAssign), ..}) => {
-                            self.pos += 1;
-                            let val = try!(self.parse());
-                            vars.push((name, Some(val)));
-                            match self.get_token(self.pos) {
-                                Ok(Token {data: TPunctuator(PComma), ..}) => self.pos += 1,
-                                _ => break
-                            }
-                        },
-                        Ok(Token {data: TPunctuator(PComma), ..}) => {
-                            self.pos += 1;
-                            vars.push((name, None));
-                        },
-                        _ => {
-                            vars.push((name, None));
-                            break;
-                        }
+                let vars = try!(self.parse_decls("var statement", false));
+                Ok(mk!(self, VarDeclExpr(vars), start))
+            },
+            // outside of strict mode, `let` is a name unless it is followed by something it can declare
+            KLet if !self.strict && !self.starts_pattern() => Ok(mk!(self, LocalExpr("let".into_string()), start)),
+            KLet => {
+                let vars = try!(self.parse_decls("let declaration", false));
+                Ok(mk!(self, LetDeclExpr(vars), start))
+            },
+            KConst => {
+                let vars = try!(self.parse_decls("const declaration", true));
+                Ok(mk!(self, ConstDeclExpr(vars), start))
+            },
+            KBreak => {
+                let label = try!(self.parse_jump_label());
+                Ok(mk!(self, BreakExpr(label), start))
+            },
+            KContinue => {
+                let label = try!(self.parse_jump_label());
+                Ok(mk!(self, ContinueExpr(label), start))
+            },
+            KReturn => {
+                // a semicolon is inserted before a value on the next line, so it isn't returned
+                let has_value = match self.get_token(self.pos) {
+                    Ok(tk) => !tk.newline_before && tk.data != TPunctuator(PSemicolon) && tk.data != TPunctuator(PCloseBlock),
+                    Err(_) => false
+                };
+                if has_value {
+                    Ok(mk!(self, ReturnExpr(Some(box try!(self.parse()))), start))
+                } else {
+                    if self.pos < self.tokens.len() && self.tokens[self.pos].data == TPunctuator(PSemicolon) {
+                        self.pos += 1;
                     }
+                    Ok(mk!(self, ReturnExpr(None), start))
                 }
-                Ok(mk!(self, VarDeclExpr(vars)))
             },
-            KReturn => Ok(mk!(self, ReturnExpr(Some(box try!(self.parse()).clone())))),
             KNew => {
+                if try!(self.get_token(self.pos)).data == TPunctuator(PDot) {
+                    let tk = try!(self.get_token(self.pos + 1));
+                    match tk.data {
+                        TIdentifier(ref name) if name.as_slice() == "target" => (),
+                        _ => return Err(ParseError::new(Expected(vec!(TIdentifier("target".into_string())), tk.clone(), "new.target")))
+                    }
+                    if !self.in_function {
+                        return Err(ParseError::new(NewTargetOutsideFunction(start)));
+                    }
+                    self.pos += 2;
+                    return Ok(mk!(self, NewTargetExpr, start));
+                }
                 let call = try!(self.parse());
                 match call.def {
-                    CallExpr(ref func, ref args) => Ok(mk!(self, ConstructExpr(func.clone(), args.clone()))),
-                    _ => Err(ExpectedExpr("constructor", call))
+                    CallExpr(ref func, ref args) => Ok(mk!(self, ConstructExpr(func.clone(), args.clone()), start)),
+                    _ => Err(ParseError::new(ExpectedExpr("constructor", call)))
+                }
+            },
+            KThis => Ok(mk!(self, ThisExpr)),
+            KTypeOf => Ok(mk!(self, TypeOfExpr(box try!(self.parse_unary_operand())), start)),
+            KVoid => Ok(mk!(self, VoidExpr(box try!(self.parse_unary_operand())), start)),
+            KDelete => {
+                let target = try!(self.parse_unary_operand());
+                match target.def {
+                    LocalExpr(_) if self.strict => Err(ParseError::new(StrictDelete(start))),
+                    _ => Ok(mk!(self, DeleteExpr(box target), start))
                 }
             },
-            KTypeOf => Ok(mk!(self, TypeOfExpr(box try!(self.parse())))),
             KIf => {
                 try!(self.expect_punc(POpenParen, "if block"));
                 let cond = try!(self.parse());
@@ -134,14 +671,100 @@ impl Parser {
                     Some(box try!(self.parse()))
                 } else {
                     None
-                })))
+                }), start))
             },
             KWhile => {
                 try!(self.expect_punc(POpenParen, "while condition"));
                 let cond = try!(self.parse());
                 try!(self.expect_punc(PCloseParen, "while condition"));
                 let expr = try!(self.parse());
-                Ok(mk!(self, WhileLoopExpr(box cond, box expr)))
+                Ok(mk!(self, WhileLoopExpr(box cond, box expr), start))
+            },
+            KFor => {
+                try!(self.expect_punc(POpenParen, "for loop"));
+                let init = if try!(self.get_token(self.pos)).data == TPunctuator(PSemicolon) {
+                    self.pos += 1;
+                    None
+                } else {
+                    // constants in the head of a for-in or for-of loop don't have values
+                    let init = if try!(self.get_token(self.pos)).data == TKeyword(KConst) {
+                        self.pos += 1;
+                        let vars = try!(self.parse_decls("const declaration", false));
+                        mk!(self, ConstDeclExpr(vars))
+                    } else {
+                        try!(self.parse())
+                    };
+                    let (is_in, is_of) = match try!(self.get_token(self.pos)).data {
+                        TKeyword(KIn) => (true, false),
+                        TIdentifier(ref s) if s.as_slice() == "of" => (false, true),
+                        _ => (false, false)
+                    };
+                    if is_in || is_of {
+                        match init.def {
+                            VarDeclExpr(ref vars) | LetDeclExpr(ref vars) | ConstDeclExpr(ref vars)
+                                if vars.len() == 1 && match vars[0] {(_, None) => true, _ => false} => (),
+                            LocalExpr(_) | GetConstFieldExpr(_, _) | GetFieldExpr(_, _) => (),
+                            _ => return Err(ParseError::new(ExpectedExpr("for loop variable", init.clone())))
+                        }
+                        self.pos += 1;
+                        let value = try!(self.parse());
+                        try!(self.expect_punc(PCloseParen, "for loop"));
+                        let expr = try!(self.parse());
+                        return Ok(mk!(self, if is_in {
+                            ForInExpr(box init, box value, box expr)
+                        } else {
+                            ForOfExpr(box init, box value, box expr)
+                        }, start));
+                    }
+                    match init.def {
+                        ConstDeclExpr(ref vars) if vars.iter().any(|&(_, ref val)| val.is_none()) =>
+                            return Err(ParseError::new(ExpectedExpr("const declaration with values", init.clone()))),
+                        _ => ()
+                    }
+                    try!(self.end_for_clause(PSemicolon));
+                    Some(box init)
+                };
+                let cond = try!(self.parse_for_clause(PSemicolon));
+                let step = try!(self.parse_for_clause(PCloseParen));
+                let expr = try!(self.parse());
+                Ok(mk!(self, ForLoopExpr(init, cond, step, box expr), start))
+            },
+            KTry => {
+                let block = try!(self.parse_block("try block"));
+                let mut name = None;
+                let mut catch_block = None;
+                let mut finally_block = None;
+                match self.get_token(self.pos) {
+                    Ok(Token {data: TKeyword(KCatch), ..}) => {
+                        self.pos += 1;
+                        // the binding of the thrown value is optional
+                        if try!(self.get_token(self.pos)).data == TPunctuator(POpenParen) {
+                            self.pos += 1;
+                            let tk = try!(self.get_token(self.pos));
+                            name = match tk.data {
+                                TIdentifier(ref id) => Some(id.clone()),
+                                _ => return Err(ParseError::new(Expected(vec!(TIdentifier("identifier".into_string())), tk.clone(), "catch binding")))
+                            };
+                            self.pos += 1;
+                            try!(self.expect_punc(PCloseParen, "catch binding"));
+                        }
+                        catch_block = Some(box try!(self.parse_block("catch block")));
+                    },
+                    _ => ()
+                }
+                match self.get_token(self.pos) {
+                    Ok(Token {data: TKeyword(KFinally), ..}) => {
+                        self.pos += 1;
+                        finally_block = Some(box try!(self.parse_block("finally block")));
+                    },
+                    Ok(tk) => if catch_block.is_none() {
+                        return Err(ParseError::new(Expected(vec!(TKeyword(KCatch), TKeyword(KFinally)), tk, "try statement")));
+                    },
+                    Err(e) => if catch_block.is_none() {
+                        return Err(e);
+                    }
+                }
+                Ok(mk!(self, TryExpr(box block, name, catch_block, finally_block), start))
             },
             KSwitch => {
                 try!(self.expect_punc(POpenParen, "switch value"));
@@ -155,7 +778,7 @@ impl Parser {
                     self.pos += 1;
                     match tok.data {
                         TKeyword(KCase) => {
-                            let cond = self.parse();
+                            let cond = self.parse_before_colon();
                             let mut block = Vec::new();
                             try!(self.expect_punc(PColon, "switch case"));
                             loop {
@@ -180,66 +803,168 @@ impl Parser {
                             default = Some(mk!(self, BlockExpr(block)));
                         },
                         TPunctuator(PCloseBlock) => break,
-                        _ => return Err(Expected(vec!(TKeyword(KCase), TKeyword(KDefault), TPunctuator(PCloseBlock)), tok, "switch block"))
+                        _ => return Err(ParseError::new(Expected(vec!(TKeyword(KCase), TKeyword(KDefault), TPunctuator(PCloseBlock)), tok, "switch block")))
                     }
                 }
                 try!(self.expect_punc(PCloseBlock, "switch block"));
                 Ok(mk!(self, SwitchExpr(box value.unwrap(), cases, match default {
                     Some(v) => Some(box v),
                     None => None
-                })))
+                }), start))
             },
             KFunction => {
+                let generator = try!(self.get_token(self.pos)).data == TPunctuator(PMul);
+                if generator {
+                    self.pos += 1;
+                }
                 let tk = try!(self.get_token(self.pos));
                 let name = match tk.data {
                     TIdentifier(ref name) => {
+                        try!(self.check_name(&tk, name.as_slice()));
                         self.pos += 1;
                         Some(name.clone())
                     },
                     TPunctuator(POpenParen) => None,
-                    _ => return Err(Expected(vec!(TIdentifier("identifier".into_string())), tk.clone(), "function name"))
+                    _ => return Err(ParseError::new(Expected(vec!(TIdentifier("identifier".into_string())), tk.clone(), "function name")))
                 };
-                try!(self.expect_punc(POpenParen, "function"));
-                let mut args:Vec<String> = Vec::new();
-                let mut tk = try!(self.get_token(self.pos));
-                while tk.data != TPunctuator(PCloseParen) {
-                    match tk.data {
-                        TIdentifier(ref id) => args.push(id.clone()),
-                        _ => return Err(Expected(vec!(TIdentifier("identifier".into_string())), tk.clone(), "function arguments"))
-                    }
-                    self.pos += 1;
-                    if try!(self.get_token(self.pos)).data == TPunctuator(PComma) {
+                // the function starts at the `function` keyword rather than its arguments
+                let function = try!(self.parse_function(name, generator));
+                Ok(Expr {start: start.pos, ..function})
+            },
+            KClass => {
+                let name = match try!(self.get_token(self.pos)).data {
+                    TIdentifier(ref name) => {
                         self.pos += 1;
+                        Some(name.clone())
+                    },
+                    _ => None
+                };
+                let parent = if try!(self.get_token(self.pos)).data == TKeyword(KExtends) {
+                    self.pos += 1;
+                    Some(box try!(self.parse_limited(POSTFIX_PRECEDENCE)))
+                } else {
+                    None
+                };
+                try!(self.expect_punc(POpenBlock, "class body"));
+                // the body of a class is always in strict mode
+                let was_strict = self.strict;
+                self.strict = true;
+                let members = self.parse_class_members();
+                self.strict = was_strict;
+                let (constructor, methods) = try!(members);
+                let constructor = match constructor {
+                    Some(constructor) => constructor,
+                    None => {
+                        // the default constructor of a class that extends another passes its arguments on
+                        let body = match parent {
+                            Some(_) => {
+                                let args = Expr::new(LocalExpr("arguments".into_string()), start.pos, start.end);
+                                let super_call = CallExpr(box Expr::new(SuperExpr, start.pos, start.end), vec!(Expr::new(SpreadExpr(box args), start.pos, start.end)));
+                                vec!(Expr::new(super_call, start.pos, start.end))
+                            },
+                            None => Vec::new()
+                        };
+                        let body = Expr::new(BlockExpr(body), start.pos, start.end);
+                        box Expr::new(FunctionDeclExpr(None, Vec::new(), box body, true), start.pos, start.end)
                     }
-                    tk = try!(self.get_token(self.pos));
+                };
+                Ok(mk!(self, ClassDeclExpr(name, parent, constructor, methods), start))
+            },
+            KSuper => Ok(mk!(self, SuperExpr)),
+            KThis => Ok(mk!(self, ThisExpr)),
+            KYield if self.in_generator => {
+                let start = try!(self.get_token(self.pos - 1));
+                let delegate = try!(self.get_token(self.pos)).data == TPunctuator(PMul);
+                if delegate {
+                    self.pos += 1;
                 }
-                self.pos += 1;
-                let block = try!(self.parse());
-                Ok(mk!(self, FunctionDeclExpr(name, args, box block)))
+                let value = if delegate || self.has_yield_value() {
+                    Some(box try!(self.parse_limited(ASSIGN_PRECEDENCE)))
+                } else {
+                    None
+                };
+                Ok(mk!(self, YieldExpr(delegate, value), start))
             },
-            _ => Err(UnexpectedKeyword(keyword))
+            KYield if !self.strict => Ok(mk!(self, LocalExpr("yield".into_string()))),
+            _ => Err(ParseError::new(UnexpectedKeyword(try!(self.get_token(self.pos - 1)))))
+        }
+    }
+    /// Checks if the token at `pos` starts a pattern that a declaration can bind a value to
+    fn starts_pattern(&self) -> bool {
+        match self.tokens.get(self.pos).map(|tk| tk.data.clone()) {
+            Some(TIdentifier(_)) | Some(TPunctuator(POpenBracket)) | Some(TPunctuator(POpenBlock)) => true,
+            _ => false
+        }
+    }
+    /// Check that a name can be declared, which `let` and `yield` can't be in strict
+    /// mode, and `yield` can't be in the body of a generator
+    fn check_name(&self, tk:&Token, name:&str) -> Result<(), ParseError> {
+        if (self.strict && (name == "let" || name == "yield")) || (self.in_generator && name == "yield") {
+            Err(ParseError::new(ReservedName(tk.clone())))
+        } else {
+            Ok(())
         }
     }
     /// Parse a single expression
     pub fn parse(&mut self) -> ParseResult {
         if self.pos > self.tokens.len() {
-            return Err(AbruptEnd);
+            return Err(self.abrupt_end());
         }
+        let limit = replace(&mut self.limit, ANY_PRECEDENCE);
         let token = try!(self.get_token(self.pos));
         self.pos += 1;
+        if token.legacy_octal && self.strict {
+            return Err(ParseError::new(StrictLegacyOctal(token)));
+        }
         let expr : Expr = match token.data {
-            TPunctuator(PSemicolon) | TComment(_) if self.pos < self.tokens.len() => try!(self.parse()),
+            TPunctuator(PSemicolon) | TComment(_) if self.pos < self.tokens.len() => {
+                self.limit = limit;
+                try!(self.parse())
+            },
             TPunctuator(PSemicolon) | TComment(_) => mk!(self, ConstExpr(CUndefined)),
             TNumericLiteral(num) =>
                 mk!(self, ConstExpr(CNum(num))),
+            TBigInt(num) =>
+                mk!(self, ConstExpr(CBigInt(num))),
             TNullLiteral =>
                 mk!(self, ConstExpr(CNull)),
             TStringLiteral(text) =>
                 mk!(self, ConstExpr(CString(text))),
+            TRegularExpression(ref body, ref flags) => match from_str::<RegExpFlags>(flags.as_slice()) {
+                Some(parsed) => mk!(self, ConstExpr(CRegExp(body.clone(), parsed))),
+                None => return Err(ParseError::new(InvalidRegExpFlags(token.clone(), flags.clone())))
+            },
+            TTemplate(text) =>
+                mk!(self, TemplateExpr(vec!(text), Vec::new())),
+            TTemplateHead(ref text) => {
+                let mut quasis = vec!(text.clone());
+                let mut exprs = Vec::new();
+                loop {
+                    exprs.push(try!(self.parse()));
+                    let tk = try!(self.get_token(self.pos));
+                    self.pos += 1;
+                    match tk.data {
+                        TTemplateMiddle(ref text) => quasis.push(text.clone()),
+                        TTemplateTail(ref text) => {
+                            quasis.push(text.clone());
+                            break;
+                        },
+                        _ => return Err(ParseError::new(Expected(vec!(TTemplateMiddle("template".into_string()), TTemplateTail("template".into_string())), tk, "template literal")))
+                    }
+                }
+                mk!(self, TemplateExpr(quasis, exprs), token)
+            },
             TBooleanLiteral(val) =>
                 mk!(self, ConstExpr(CBool(val))),
             TIdentifier(ref s) if s.as_slice() == "undefined" =>
                 mk!(self, ConstExpr(CUndefined)),
+            TIdentifier(ref s) if !self.expect_colon && self.pos < self.tokens.len() && self.tokens[self.pos].data == TPunctuator(PColon) => {
+                self.pos += 1;
+                self.labels.push(s.clone());
+                let expr = self.parse();
+                self.labels.pop();
+                mk!(self, LabelledExpr(s.clone(), box try!(expr)), token)
+            },
             TIdentifier(s) =>
                 mk!(self, LocalExpr(s)),
             TKeyword(keyword) =>
@@ -248,8 +973,8 @@ impl Parser {
                 match try!(self.get_token(self.pos)).data {
                     TPunctuator(PCloseParen) if try!(self.get_token(self.pos + 1)).data == TPunctuator(PArrow) => {
                         self.pos += 2;
-                        let expr = try!(self.parse());
-                        mk!(self, ArrowFunctionDeclExpr(Vec::new(), box expr), token)
+                        let (expr, strict) = try!(self.parse_arrow_body());
+                        mk!(self, ArrowFunctionDeclExpr(Vec::new(), box expr, strict), token)
                     },
                     _ => {
                         let next = try!(self.parse());
@@ -259,37 +984,32 @@ impl Parser {
                             TPunctuator(PCloseParen) => next,
                             TPunctuator(PComma) => { // at this point it's probably gonna be an arrow function
                                 let mut args = vec!(match next.def {
-                                    LocalExpr(name) => name,
-                                    _ => "".into_string()
-                                }, match try!(self.get_token(self.pos)).data {
-                                    TIdentifier(ref id) => id.clone(),
-                                    _ => "".into_string()
+                                    LocalExpr(ref name) => name.clone(),
+                                    _ => return Err(ParseError::new(ExpectedExpr("arrow function argument", next.clone())))
                                 });
                                 let mut expect_ident = true;
                                 loop {
-                                    self.pos += 1;
                                     let curr_tk = try!(self.get_token(self.pos));
+                                    self.pos += 1;
                                     match curr_tk.data {
                                         TIdentifier(ref id) if expect_ident => {
                                             args.push(id.clone());
                                             expect_ident = false;
                                         },
-                                        TPunctuator(PComma) => {
+                                        TPunctuator(PComma) if !expect_ident => {
                                             expect_ident = true;
                                         },
-                                        TPunctuator(PCloseParen) => {
-                                            self.pos += 1;
-                                            break;
-                                        },
-                                        _ if expect_ident => return Err(Expected(vec!(TIdentifier("identifier".into_string())), curr_tk, "arrow function")),
-                                        _ => return Err(Expected(vec!(TPunctuator(PComma), TPunctuator(PCloseParen)), curr_tk, "arrow function"))
+                                        TPunctuator(PCloseParen) if !expect_ident =>
+                                            break,
+                                        _ if expect_ident => return Err(ParseError::new(Expected(vec!(TIdentifier("identifier".into_string())), curr_tk, "arrow function"))),
+                                        _ => return Err(ParseError::new(Expected(vec!(TPunctuator(PComma), TPunctuator(PCloseParen)), curr_tk, "arrow function")))
                                     }
                                 }
                                 try!(self.expect(TPunctuator(PArrow), "arrow function"));
-                                let expr = try!(self.parse());
-                                mk!(self, ArrowFunctionDeclExpr(args, box expr), token)
+                                let (expr, strict) = try!(self.parse_arrow_body());
+                                mk!(self, ArrowFunctionDeclExpr(args, box expr, strict), token)
                             }
-                            _ => return Err(Expected(vec!(TPunctuator(PCloseParen)), next_tok, "brackets"))
+                            _ => return Err(ParseError::new(Expected(vec!(TPunctuator(PCloseParen)), next_tok, "brackets")))
                         }
                     }
                 }
@@ -308,9 +1028,9 @@ impl Parser {
                         array.push(mk!(self, ConstExpr(CNull)));
                         expect_comma_or_end = false;
                     } else if expect_comma_or_end {
-                        return Err(Expected(vec!(TPunctuator(PComma), TPunctuator(PCloseBracket)), token.clone(), "array declaration"));
+                        return Err(ParseError::new(Expected(vec!(TPunctuator(PComma), TPunctuator(PCloseBracket)), token.clone(), "array declaration")));
                     } else {
-                        let parsed = try!(self.parse());
+                        let parsed = try!(self.parse_spreadable());
                         self.pos -= 1;
                         array.push(parsed);
                         expect_comma_or_end = true;
@@ -323,22 +1043,9 @@ impl Parser {
                 self.pos += 1;
                 mk!(self, ObjectDeclExpr(box TreeMap::new()), token)
             },
-            TPunctuator(POpenBlock) if try!(self.get_token(self.pos + 1)).data == TPunctuator(PColon) => {
-                let mut map = box TreeMap::new();
-                while try!(self.get_token(self.pos - 1)).data == TPunctuator(PComma) || map.len() == 0 {
-                    let tk = try!(self.get_token(self.pos));
-                    let name = match tk.data {
-                        TIdentifier(ref id) => id.clone(),
-                        TStringLiteral(ref str) => str.clone(),
-                        _ => return Err(Expected(vec!(TIdentifier("identifier".into_string()), TStringLiteral("string".into_string())), tk, "object declaration"))
-                    };
-                    self.pos += 1;
-                    try!(self.expect(TPunctuator(PColon), "object declaration"));
-                    let value = try!(self.parse());
-                    map.insert(name, value);
-                    self.pos += 1;
-                }
-                mk!(self, ObjectDeclExpr(map), token)
+            TPunctuator(POpenBlock) if self.is_object_decl() => {
+                let map = try!(self.parse_object_decl());
+                mk!(self, ObjectDeclExpr(box map), token)
             },
             TPunctuator(POpenBlock) => {
                 let mut exprs = Vec::new();
@@ -353,41 +1060,354 @@ impl Parser {
                 mk!(self, BlockExpr(exprs), token)
             },
             TPunctuator(PSub) =>
-                mk!(self, UnaryOpExpr(UnaryMinus, box try!(self.parse()))),
+                mk!(self, UnaryOpExpr(UnaryMinus, box try!(self.parse_unary_operand())), token),
             TPunctuator(PAdd) =>
-                mk!(self, UnaryOpExpr(UnaryPlus, box try!(self.parse()))),
+                mk!(self, UnaryOpExpr(UnaryPlus, box try!(self.parse_unary_operand())), token),
             TPunctuator(PNot) =>
-                mk!(self, UnaryOpExpr(UnaryNot, box try!(self.parse()))),
-            TPunctuator(PInc) =>
-                mk!(self, UnaryOpExpr(UnaryIncrementPre, box try!(self.parse()))),
-            TPunctuator(PDec) =>
-                mk!(self, UnaryOpExpr(UnaryDecrementPre, box try!(self.parse()))),
-            _ => return Err(Expected(Vec::new(), token.clone(), "script"))
+                mk!(self, UnaryOpExpr(UnaryNot, box try!(self.parse_unary_operand())), token),
+            TPunctuator(PNeg) =>
+                mk!(self, UnaryOpExpr(UnaryBitNot, box try!(self.parse_unary_operand())), token),
+            TPunctuator(PInc) => {
+                let target = try!(self.parse_update_target());
+                mk!(self, UpdateExpr(true, UpdateIncrement, box target), token)
+            },
+            TPunctuator(PDec) => {
+                let target = try!(self.parse_update_target());
+                mk!(self, UpdateExpr(true, UpdateDecrement, box target), token)
+            },
+            _ => return Err(ParseError::new(Expected(Vec::new(), token.clone(), "script")))
         };
         if self.pos >= self.tokens.len() {
             Ok(expr)
         } else {
-            self.parse_next(expr)
+            self.parse_next(expr, limit)
+        }
+    }
+    /// Parse the arguments and body of a function or generator function, starting at the opening parenthesis
+    fn parse_function(&mut self, name:Option<String>, generator:bool) -> ParseResult {
+        let first = try!(self.get_token(self.pos));
+        try!(self.expect_punc(POpenParen, "function"));
+        let mut args:Vec<String> = Vec::new();
+        let mut tk = try!(self.get_token(self.pos));
+        while tk.data != TPunctuator(PCloseParen) {
+            match tk.data {
+                TIdentifier(ref id) if generator && id.as_slice() == "yield" =>
+                    return Err(ParseError::new(ReservedName(tk.clone()))),
+                TIdentifier(ref id) => {
+                    try!(self.check_name(&tk, id.as_slice()));
+                    args.push(id.clone())
+                },
+                _ => return Err(ParseError::new(Expected(vec!(TIdentifier("identifier".into_string())), tk.clone(), "function arguments")))
+            }
+            self.pos += 1;
+            if try!(self.get_token(self.pos)).data == TPunctuator(PComma) {
+                self.pos += 1;
+            }
+            tk = try!(self.get_token(self.pos));
+        }
+        self.pos += 1;
+        let was_strict = self.strict;
+        if try!(self.get_token(self.pos)).data == TPunctuator(POpenBlock) && self.has_use_strict(self.pos + 1) {
+            self.strict = true;
+        }
+        let strict = self.strict;
+        // labels outside of the function can't be targeted from inside it
+        let labels = replace(&mut self.labels, Vec::new());
+        let was_expect_colon = self.expect_colon;
+        self.expect_colon = false;
+        let was_generator = replace(&mut self.in_generator, generator);
+        let was_function = replace(&mut self.in_function, true);
+        let block = self.parse_block("function body");
+        self.strict = was_strict;
+        self.labels = labels;
+        self.expect_colon = was_expect_colon;
+        self.in_generator = was_generator;
+        self.in_function = was_function;
+        let block = try!(block);
+        Ok(mk!(self, if generator {
+            GeneratorDeclExpr(name, args, box block, strict)
+        } else {
+            FunctionDeclExpr(name, args, box block, strict)
+        }, first))
+    }
+    /// Checks if the `yield` just parsed is followed by the value it gives, which has to be on the same line
+    fn has_yield_value(&self) -> bool {
+        if self.pos >= self.tokens.len() {
+            return false;
+        }
+        let tk = &self.tokens[self.pos];
+        !tk.newline_before && match tk.data {
+            TPunctuator(PCloseParen) | TPunctuator(PCloseBracket) | TPunctuator(PCloseBlock) |
+            TPunctuator(PComma) | TPunctuator(PSemicolon) | TPunctuator(PColon) |
+            TTemplateMiddle(_) | TTemplateTail(_) => false,
+            _ => true
+        }
+    }
+    /// Checks if the braces just opened start an object declaration rather than a block
+    fn is_object_decl(&self) -> bool {
+        if self.pos + 1 >= self.tokens.len() {
+            return false;
+        }
+        match (&self.tokens[self.pos].data, &self.tokens[self.pos + 1].data) {
+            (_, &TPunctuator(PColon)) => true,
+            (&TIdentifier(ref s), &TIdentifier(_)) | (&TIdentifier(ref s), &TStringLiteral(_)) | (&TIdentifier(ref s), &TKeyword(_)) =>
+                s.as_slice() == "get" || s.as_slice() == "set",
+            (&TIdentifier(_), &TPunctuator(PComma)) | (&TIdentifier(_), &TPunctuator(PCloseBlock)) => true,
+            // a method is told apart from a call in a block by the braces after the arguments
+            (&TIdentifier(_), &TPunctuator(POpenParen)) | (&TStringLiteral(_), &TPunctuator(POpenParen)) => {
+                let mut depth = 0u;
+                for (i, tk) in self.tokens.slice_from(self.pos + 1).iter().enumerate() {
+                    match tk.data {
+                        TPunctuator(POpenParen) => depth += 1,
+                        TPunctuator(PCloseParen) if depth == 1 => {
+                            let after = self.pos + i + 2;
+                            return after < self.tokens.len() && self.tokens[after].data == TPunctuator(POpenBlock);
+                        },
+                        TPunctuator(PCloseParen) => depth -= 1,
+                        _ => ()
+                    }
+                }
+                false
+            },
+            _ => false
+        }
+    }
+    /// Parse the properties of an object declaration up to the closing brace
+    fn parse_object_decl(&mut self) -> Result<TreeMap<String, Expr>, ParseError> {
+        let mut map = TreeMap::new();
+        loop {
+            let tk = try!(self.get_token(self.pos));
+            if tk.data == TPunctuator(PCloseBlock) {
+                self.pos += 1;
+                break;
+            }
+            let kind = if try!(self.parse_modifier("get")) {
+                GetterMethod
+            } else if try!(self.parse_modifier("set")) {
+                SetterMethod
+            } else {
+                PlainMethod
+            };
+            if kind == PlainMethod && try!(self.get_token(self.pos + 1)).data == TPunctuator(POpenParen) {
+                let (name, method) = try!(self.parse_method("object declaration"));
+                map.insert(name, method);
+            } else if kind == PlainMethod {
+                let name = match tk.data {
+                    TIdentifier(ref id) | TStringLiteral(ref id) => id.clone(),
+                    _ => return Err(ParseError::new(Expected(vec!(TIdentifier("identifier".into_string()), TStringLiteral("string".into_string())), tk, "object declaration")))
+                };
+                self.pos += 1;
+                let value = match (try!(self.get_token(self.pos)).data, tk.data.clone()) {
+                    (TPunctuator(PColon), _) => {
+                        self.pos += 1;
+                        try!(self.parse_limited(ASSIGN_PRECEDENCE))
+                    },
+                    // a name on its own gives the value of the variable with that name
+                    (TPunctuator(PComma), TIdentifier(_)) | (TPunctuator(PCloseBlock), TIdentifier(_)) =>
+                        Expr::new(LocalExpr(name.clone()), tk.pos, tk.end),
+                    (_, _) => return Err(ParseError::new(Expected(vec!(TPunctuator(PColon)), try!(self.get_token(self.pos)), "object declaration")))
+                };
+                map.insert(name, value);
+            } else {
+                let (name, method) = try!(self.parse_method("object declaration"));
+                // a getter and a setter with the same name make up a single property
+                let (getter, setter) = match map.pop(&name) {
+                    Some(Expr {def: AccessorExpr(getter, setter), ..}) => (getter, setter),
+                    _ => (None, None)
+                };
+                let accessor = match kind {
+                    GetterMethod => AccessorExpr(Some(box method.clone()), setter),
+                    _ => AccessorExpr(getter, Some(box method.clone()))
+                };
+                map.insert(name, Expr::new(accessor, tk.pos, method.end));
+            }
+            let tk = try!(self.get_token(self.pos));
+            self.pos += 1;
+            match tk.data {
+                TPunctuator(PComma) => (),
+                TPunctuator(PCloseBlock) => break,
+                _ => return Err(ParseError::new(Expected(vec!(TPunctuator(PComma), TPunctuator(PCloseBlock)), tk, "object declaration")))
+            }
+        }
+        Ok(map)
+    }
+    /// Parse the members of a class up to the closing brace, giving the constructor if there
+    /// is one and the other methods
+    fn parse_class_members(&mut self) -> Result<(Option<Box<Expr>>, Vec<(bool, MethodKind, String, Expr)>), ParseError> {
+        let mut constructor = None;
+        let mut methods = Vec::new();
+        loop {
+            match try!(self.get_token(self.pos)).data {
+                TPunctuator(PCloseBlock) => {
+                    self.pos += 1;
+                    break;
+                },
+                TPunctuator(PSemicolon) => {
+                    self.pos += 1;
+                    continue;
+                },
+                _ => ()
+            }
+            let is_static = try!(self.parse_modifier("static"));
+            let kind = if try!(self.parse_modifier("get")) {
+                GetterMethod
+            } else if try!(self.parse_modifier("set")) {
+                SetterMethod
+            } else {
+                PlainMethod
+            };
+            let (name, method) = try!(self.parse_method("class method"));
+            if !is_static && kind == PlainMethod && name.as_slice() == "constructor" {
+                constructor = Some(box method);
+            } else {
+                methods.push((is_static, kind, name, method));
+            }
         }
+        Ok((constructor, methods))
+    }
+    /// Skip over the modifier of a method such as `static` or `get` if it is next, returning
+    /// false if there isn't one or it is actually the name of the method
+    fn parse_modifier(&mut self, modifier:&'static str) -> Result<bool, ParseError> {
+        match try!(self.get_token(self.pos)).data {
+            TIdentifier(ref s) if s.as_slice() == modifier => (),
+            _ => return Ok(false)
+        }
+        if try!(self.get_token(self.pos + 1)).data == TPunctuator(POpenParen) {
+            return Ok(false);
+        }
+        self.pos += 1;
+        Ok(true)
+    }
+    /// Parse the name, arguments and body of a method
+    fn parse_method(&mut self, routine:&'static str) -> Result<(String, Expr), ParseError> {
+        let tk = try!(self.get_token(self.pos));
+        let name = match tk.data {
+            TIdentifier(ref id) | TStringLiteral(ref id) => id.clone(),
+            // methods can be named after keywords, such as `delete`
+            TKeyword(ref keyword) => keyword.to_string(),
+            _ => return Err(ParseError::new(Expected(vec!(TIdentifier("identifier".into_string()), TStringLiteral("string".into_string())), tk, routine)))
+        };
+        self.pos += 1;
+        let method = try!(self.parse_function(None, false));
+        Ok((name, method))
+    }
+    /// Parse an expression that can only contain operators with a precedence of `limit` or tighter
+    fn parse_limited(&mut self, limit:uint) -> ParseResult {
+        self.limit = limit;
+        self.parse()
+    }
+    /// Parse the operand of a prefix operator, which binds tighter than any binary operator
+    fn parse_unary_operand(&mut self) -> ParseResult {
+        self.parse_limited(POSTFIX_PRECEDENCE)
+    }
+    /// Parse an argument of a call or an item of an array, which can spread the items of an iterable value
+    fn parse_spreadable(&mut self) -> ParseResult {
+        let tk = try!(self.get_token(self.pos));
+        if tk.data != TPunctuator(PSpread) {
+            return self.parse();
+        }
+        self.pos += 1;
+        let iterable = try!(self.parse_limited(ASSIGN_PRECEDENCE));
+        Ok(Expr::new(SpreadExpr(box iterable.clone()), tk.pos, iterable.end))
+    }
+    /// Parse the body of an arrow function, which is either a block or a single expression
+    /// giving the value to return, along with whether it is in strict mode
+    fn parse_arrow_body(&mut self) -> Result<(Expr, bool), ParseError> {
+        // arrow functions can't be generators, even inside of one
+        let was_generator = replace(&mut self.in_generator, false);
+        let was_strict = self.strict;
+        let is_block = try!(self.get_token(self.pos)).data == TPunctuator(POpenBlock);
+        if is_block && self.has_use_strict(self.pos + 1) {
+            self.strict = true;
+        }
+        let strict = self.strict;
+        let body = if is_block {
+            // labels outside of the function can't be targeted from inside it
+            let labels = replace(&mut self.labels, Vec::new());
+            let was_expect_colon = self.expect_colon;
+            self.expect_colon = false;
+            let block = self.parse_block("arrow function body");
+            self.labels = labels;
+            self.expect_colon = was_expect_colon;
+            block
+        } else {
+            self.parse_limited(ASSIGN_PRECEDENCE)
+        };
+        self.in_generator = was_generator;
+        self.strict = was_strict;
+        Ok((try!(body), strict))
+    }
+    /// Parse the variable or field updated by a prefix increment or decrement
+    fn parse_update_target(&mut self) -> ParseResult {
+        let target = try!(self.parse_unary_operand());
+        try!(check_update_target(&target));
+        Ok(target)
     }
     fn get_token(&self, pos:uint) -> Result<Token, ParseError> {
         if pos < self.tokens.len() {
             Ok(self.tokens[pos].clone())
         } else {
-            Err(AbruptEnd)
+            Err(self.abrupt_end())
+        }
+    }
+    /// Make the error for the script ending early, which happens just after the last token
+    fn abrupt_end(&self) -> ParseError {
+        let mut err = ParseError::new(AbruptEnd);
+        match self.tokens.last() {
+            Some(tk) => {
+                err.line = tk.end.line_number;
+                err.column = tk.end.column_number;
+            },
+            None => ()
         }
+        err
     }
-    fn parse_next(&mut self, expr:Expr) -> ParseResult {
+    /// Parse the operators after `expr` whose precedence is `limit` or tighter
+    fn parse_next(&mut self, expr:Expr, limit:uint) -> ParseResult {
+        // a semicolon ends the statement, even if an expression inside of it was what found it
+        if self.pos > 0 && self.tokens[self.pos - 1].data == TPunctuator(PSemicolon) {
+            return Ok(close_chain(expr));
+        }
         let next = try!(self.get_token(self.pos));
+        let expr = match next.data {
+            TPunctuator(PDot) | TPunctuator(POptionalChain) | TPunctuator(POpenParen) | TPunctuator(POpenBracket) => expr,
+            _ => close_chain(expr)
+        };
+        // operators that bind looser than the limit belong to an enclosing expression
+        let precedence = match next.data {
+            TPunctuator(PAssign) | TPunctuator(PArrow) => ASSIGN_PRECEDENCE,
+            TPunctuator(PQuestion) => IF_PRECEDENCE,
+            TPunctuator(ref p) if p.as_assign_op().is_some() => ASSIGN_PRECEDENCE,
+            TPunctuator(ref p) => p.as_binop().map_or(0, |op| op.get_precedence()),
+            _ => 0
+        };
+        if precedence > limit {
+            return Ok(expr);
+        }
         let mut carry_on = true;
         let mut result = expr.clone();
         match next.data {
+            TPunctuator(POptionalChain) => {
+                self.pos += 1;
+                let obj = Expr::new(OptionalExpr(box expr.clone()), expr.start, expr.end);
+                let tk = try!(self.get_token(self.pos));
+                match tk.data {
+                    TIdentifier(ref s) => {
+                        self.pos += 1;
+                        result = mk!(self, GetConstFieldExpr(box obj, s.to_string()), after expr);
+                    },
+                    // the call or index is parsed next, on the optional object
+                    TPunctuator(POpenParen) | TPunctuator(POpenBracket) =>
+                        result = obj,
+                    _ => return Err(ParseError::new(Expected(vec!(TIdentifier("identifier".into_string()), TPunctuator(POpenParen), TPunctuator(POpenBracket)), tk, "optional chain")))
+                }
+            },
             TPunctuator(PDot) => {
                 self.pos += 1;
                 let tk = try!(self.get_token(self.pos));
                 match tk.data {
-                    TIdentifier(ref s) => result = mk!(self, GetConstFieldExpr(box expr, s.to_string())),
-                    _ => return Err(Expected(vec!(TIdentifier("identifier".into_string())), tk, "field access"))
+                    TIdentifier(ref s) => result = mk!(self, GetConstFieldExpr(box expr.clone(), s.to_string()), after expr),
+                    _ => return Err(ParseError::new(Expected(vec!(TIdentifier("identifier".into_string())), tk, "field access")))
                 }
                 self.pos += 1;
             },
@@ -403,121 +1423,269 @@ impl Parser {
                     } else if token.data == TPunctuator(PComma) && expect_comma_or_end {
                         expect_comma_or_end = false;
                     } else if expect_comma_or_end {
-                        return Err(Expected(vec!(TPunctuator(PComma), TPunctuator(PCloseParen)), token, "function call arguments"));
+                        return Err(ParseError::new(Expected(vec!(TPunctuator(PComma), TPunctuator(PCloseParen)), token, "function call arguments")));
                     } else {
-                        let parsed = try!(self.parse());
+                        let parsed = try!(self.parse_spreadable());
                         self.pos -= 1;
                         args.push(parsed);
                         expect_comma_or_end = true;
                     }
                 }
-                result = mk!(self, CallExpr(box expr, args));
+                result = mk!(self, CallExpr(box expr.clone(), args), after expr);
             },
             TPunctuator(PQuestion) => {
                 self.pos += 1;
-                let if_e = try!(self.parse());
+                let if_e = try!(self.parse_before_colon());
                 try!(self.expect(TPunctuator(PColon), "if expression"));
-                let else_e = try!(self.parse());
-                result = mk!(self, IfExpr(box expr, box if_e, Some(box else_e)));
+                let else_e = try!(self.parse_limited(ASSIGN_PRECEDENCE));
+                result = mk!(self, IfExpr(box expr.clone(), box if_e, Some(box else_e)), after expr);
             },
             TPunctuator(POpenBracket) => {
                 self.pos += 1;
                 let index = try!(self.parse());
                 try!(self.expect(TPunctuator(PCloseBracket), "array index"));
-                result = mk!(self, GetFieldExpr(box expr, box index));
+                result = mk!(self, GetFieldExpr(box expr.clone(), box index), after expr);
             },
-            TPunctuator(PSemicolon) | TComment(_) => {
+            TPunctuator(PSemicolon) => {
+                self.pos += 1;
+                carry_on = false;
+            },
+            TComment(_) => {
                 self.pos += 1;
             },
             TPunctuator(PAssign) => {
                 self.pos += 1;
-                let next = try!(self.parse());
-                result = mk!(self, AssignExpr(box expr, box next));
+                let next = try!(self.parse_limited(ASSIGN_PRECEDENCE));
+                result = match expr.def {
+                    ArrayDeclExpr(_) | ObjectDeclExpr(_) => mk!(self, DestructureExpr(try!(to_pattern(&expr)), box next), after expr),
+                    _ => mk!(self, AssignExpr(box expr.clone(), box next), after expr)
+                };
             },
+            TPunctuator(ref p) if p.as_assign_op().is_some() =>
+                result = try!(self.assign_op(p.as_assign_op().unwrap(), expr)),
             TPunctuator(PArrow) => {
                 self.pos += 1;
                 let mut args = Vec::with_capacity(1);
                 match result.def {
                     LocalExpr(name) => args.push(name),
-                    _ => return Err(ExpectedExpr("identifier", result))
+                    _ => return Err(ParseError::new(ExpectedExpr("identifier", result)))
                 }
-                let next = try!(self.parse());
-                result = mk!(self, ArrowFunctionDeclExpr(args, box next));
+                let (next, strict) = try!(self.parse_arrow_body());
+                result = Expr::new(ArrowFunctionDeclExpr(args, box next.clone(), strict), expr.start, next.end);
+            },
+            TPunctuator(ref p) if p.as_binop().is_some() =>
+                result = try!(self.binop(p.as_binop().unwrap(), expr)),
+            // a postfix operator can't be on the next line, as a semicolon is inserted before it
+            TPunctuator(PInc) if !next.newline_before => {
+                self.pos += 1;
+                try!(check_update_target(&expr));
+                result = Expr::new(UpdateExpr(false, UpdateIncrement, box expr.clone()), expr.start, next.end);
+            },
+            TPunctuator(PDec) if !next.newline_before => {
+                self.pos += 1;
+                try!(check_update_target(&expr));
+                result = Expr::new(UpdateExpr(false, UpdateDecrement, box expr.clone()), expr.start, next.end);
             },
-            TPunctuator(PAdd) =>
-                result = try!(self.binop(BinNum(OpAdd), expr)),
-            TPunctuator(PSub) =>
-                result = try!(self.binop(BinNum(OpSub), expr)),
-            TPunctuator(PMul) =>
-                result = try!(self.binop(BinNum(OpMul), expr)),
-            TPunctuator(PDiv) =>
-                result = try!(self.binop(BinNum(OpDiv), expr)),
-            TPunctuator(PMod) =>
-                result = try!(self.binop(BinNum(OpMod), expr)),
-            TPunctuator(PBoolAnd) =>
-                result = try!(self.binop(BinLog(LogAnd), expr)),
-            TPunctuator(PBoolOr) =>
-                result = try!(self.binop(BinLog(LogOr), expr)),
-            TPunctuator(PAnd) =>
-                result = try!(self.binop(BinBit(BitAnd), expr)),
-            TPunctuator(POr) =>
-                result = try!(self.binop(BinBit(BitOr), expr)),
-            TPunctuator(PXor) =>
-                result = try!(self.binop(BinBit(BitXor), expr)),
-            TPunctuator(PLeftSh) =>
-                result = try!(self.binop(BinBit(BitShl), expr)),
-            TPunctuator(PRightSh) =>
-                result = try!(self.binop(BinBit(BitShr), expr)),
-            TPunctuator(PEq) =>
-                result = try!(self.binop(BinComp(CompEqual), expr)),
-            TPunctuator(PNotEq) =>
-                result = try!(self.binop(BinComp(CompNotEqual), expr)),
-            TPunctuator(PStrictEq) =>
-                result = try!(self.binop(BinComp(CompStrictEqual), expr)),
-            TPunctuator(PStrictNotEq) =>
-                result = try!(self.binop(BinComp(CompStrictNotEqual), expr)),
-            TPunctuator(PLessThan) =>
-                result = try!(self.binop(BinComp(CompLessThan), expr)),
-            TPunctuator(PLessThanOrEq) =>
-                result = try!(self.binop(BinComp(CompLessThanOrEqual), expr)),
-            TPunctuator(PGreaterThan) =>
-                result = try!(self.binop(BinComp(CompGreaterThan), expr)),
-            TPunctuator(PGreaterThanOrEq) =>
-                result = try!(self.binop(BinComp(CompGreaterThanOrEqual), expr)),
-            TPunctuator(PInc) =>
-                result = mk!(self, UnaryOpExpr(UnaryIncrementPost, box try!(self.parse()))),
-            TPunctuator(PDec) =>
-                result = mk!(self, UnaryOpExpr(UnaryDecrementPost, box try!(self.parse()))),
             _ => carry_on = false
         };
         if carry_on && self.pos < self.tokens.len() {
-            self.parse_next(result)
+            self.parse_next(result, limit)
         } else {
-            Ok(result)
+            Ok(close_chain(result))
         }
     }
+    /// Parse the right side of the binary operator `op`
     fn binop(&mut self, op:BinOp, orig:Expr) -> Result<Expr, ParseError> {
         let (precedence, assoc) = op.get_precedence_and_assoc();
         self.pos += 1;
-        let next = try!(self.parse());
-        Ok(match next.def {
-            BinOpExpr(ref op2, ref a, ref b) => {
-                let other_precedence = op2.get_precedence();
-                if precedence < other_precedence || (precedence == other_precedence && !assoc) {
-                    mk!(self, BinOpExpr(*op2, b.clone(), box mk!(self, BinOpExpr(op.clone(), box orig, a.clone()))))
-                } else {
-                    mk!(self, BinOpExpr(op, box orig, box next.clone()))
+        // operators of the same precedence on the right only belong to it if they go rightwards
+        let next = try!(self.parse_limited(if assoc {precedence} else {precedence - 1}));
+        Ok(mk!(self, BinOpExpr(op, box orig.clone(), box next), after orig))
+    }
+    /// Parse the patterns and optional values of a variable declaration, which
+    /// are required if `needs_value` is true
+    fn parse_decls(&mut self, routine:&'static str, needs_value:bool) -> Result<Vec<(Pattern, Option<Expr>)>, ParseError> {
+        let mut vars = Vec::new();
+        loop {
+            let pattern = match self.get_token(self.pos) {
+                Ok(_) => try!(self.parse_pattern(routine)),
+                Err(ParseError {kind: AbruptEnd, ..}) => break,
+                Err(e) => return Err(e)
+            };
+            match self.get_token(self.pos) {
+                Ok(Token {data: TPunctuator(PAssign), ..}) => {
+                    self.pos += 1;
+                    let val = try!(self.parse());
+                    vars.push((pattern, Some(val)));
+                    match self.get_token(self.pos) {
+                        Ok(Token {data: TPunctuator(PComma), ..}) => self.pos += 1,
+                        _ => break
+                    }
+                },
+                Ok(ref tok) if needs_value => return Err(ParseError::new(Expected(vec!(TPunctuator(PAssign)), tok.clone(), routine))),
+                Err(ref err) if needs_value && err.kind == AbruptEnd => return Err(err.clone()),
+                Ok(Token {data: TPunctuator(PComma), ..}) => {
+                    self.pos += 1;
+                    vars.push((pattern, None));
+                },
+                _ => {
+                    vars.push((pattern, None));
+                    break;
                 }
+            }
+        }
+        Ok(vars)
+    }
+    /// Parse a pattern that a declaration binds a value to
+    fn parse_pattern(&mut self, routine:&'static str) -> Result<Pattern, ParseError> {
+        let tk = try!(self.get_token(self.pos));
+        self.pos += 1;
+        Ok(match tk.data {
+            TIdentifier(ref name) => {
+                try!(self.check_name(&tk, name.as_slice()));
+                RefPattern(box Expr::new(LocalExpr(name.clone()), tk.pos, tk.end))
             },
-            _ => mk!(self, BinOpExpr(op, box orig, box next))
+            TPunctuator(POpenBracket) => {
+                let mut items = Vec::new();
+                loop {
+                    match try!(self.get_token(self.pos)).data {
+                        TPunctuator(PCloseBracket) => {
+                            self.pos += 1;
+                            break;
+                        },
+                        TPunctuator(PComma) => {
+                            self.pos += 1;
+                            items.push(None);
+                            continue;
+                        },
+                        _ => items.push(Some(try!(self.parse_pattern_item(routine))))
+                    }
+                    let tk = try!(self.get_token(self.pos));
+                    self.pos += 1;
+                    match tk.data {
+                        TPunctuator(PComma) => (),
+                        TPunctuator(PCloseBracket) => break,
+                        _ => return Err(ParseError::new(Expected(vec!(TPunctuator(PComma), TPunctuator(PCloseBracket)), tk, routine)))
+                    }
+                }
+                ArrayPattern(items)
+            },
+            TPunctuator(POpenBlock) => {
+                let mut fields = Vec::new();
+                loop {
+                    let tk = try!(self.get_token(self.pos));
+                    self.pos += 1;
+                    let name = match tk.data {
+                        TPunctuator(PCloseBlock) => break,
+                        TIdentifier(ref id) | TStringLiteral(ref id) => id.clone(),
+                        _ => return Err(ParseError::new(Expected(vec!(TIdentifier("identifier".into_string()), TStringLiteral("string".into_string()), TPunctuator(PCloseBlock)), tk, routine)))
+                    };
+                    let pattern = match (try!(self.get_token(self.pos)).data, tk.data.clone()) {
+                        (TPunctuator(PColon), _) => {
+                            self.pos += 1;
+                            try!(self.parse_pattern_item(routine))
+                        },
+                        // a field without a pattern is bound to a variable with the same name
+                        (_, TIdentifier(_)) => {
+                            let target = RefPattern(box Expr::new(LocalExpr(name.clone()), tk.pos, tk.end));
+                            try!(self.parse_pattern_default(target))
+                        },
+                        (_, _) => return Err(ParseError::new(Expected(vec!(TPunctuator(PColon)), try!(self.get_token(self.pos)), routine)))
+                    };
+                    fields.push((name, pattern));
+                    let tk = try!(self.get_token(self.pos));
+                    self.pos += 1;
+                    match tk.data {
+                        TPunctuator(PComma) => (),
+                        TPunctuator(PCloseBlock) => break,
+                        _ => return Err(ParseError::new(Expected(vec!(TPunctuator(PComma), TPunctuator(PCloseBlock)), tk, routine)))
+                    }
+                }
+                ObjectPattern(fields)
+            },
+            _ => return Err(ParseError::new(Expected(vec!(TIdentifier("identifier".into_string()), TPunctuator(POpenBracket), TPunctuator(POpenBlock)), tk, routine)))
         })
     }
+    /// Parse a pattern inside of an array or object pattern, which can have a default value
+    fn parse_pattern_item(&mut self, routine:&'static str) -> Result<Pattern, ParseError> {
+        let pattern = try!(self.parse_pattern(routine));
+        self.parse_pattern_default(pattern)
+    }
+    /// Parse the default value of `pattern` if it has one
+    fn parse_pattern_default(&mut self, pattern:Pattern) -> Result<Pattern, ParseError> {
+        if try!(self.get_token(self.pos)).data != TPunctuator(PAssign) {
+            return Ok(pattern);
+        }
+        self.pos += 1;
+        let default = try!(self.parse_limited(ASSIGN_PRECEDENCE));
+        Ok(DefaultPattern(box pattern, box default))
+    }
+    /// Parse an expression that is followed by a colon, such as the value of a switch case
+    fn parse_before_colon(&mut self) -> ParseResult {
+        let was_expect_colon = self.expect_colon;
+        self.expect_colon = true;
+        let result = self.parse();
+        self.expect_colon = was_expect_colon;
+        result
+    }
+    /// Parse the optional label targeted by a `break` or `continue`, which has to be on the same line
+    fn parse_jump_label(&mut self) -> Result<Option<String>, ParseError> {
+        let tk = match self.get_token(self.pos) {
+            Ok(tk) => tk,
+            Err(_) => return Ok(None)
+        };
+        match tk.data {
+            TIdentifier(ref label) if !tk.newline_before => {
+                if !self.labels.contains(label) {
+                    return Err(ParseError::new(UndefinedLabel(tk.clone(), label.clone())));
+                }
+                self.pos += 1;
+                Ok(Some(label.clone()))
+            },
+            _ => Ok(None)
+        }
+    }
+    /// Parse a block of expressions surrounded by braces
+    fn parse_block(&mut self, routine:&'static str) -> ParseResult {
+        let start = try!(self.get_token(self.pos));
+        try!(self.expect_punc(POpenBlock, routine));
+        let mut exprs = Vec::new();
+        while try!(self.get_token(self.pos)).data != TPunctuator(PCloseBlock) {
+            exprs.push(try!(self.parse()));
+        }
+        self.pos += 1;
+        Ok(mk!(self, BlockExpr(exprs), start))
+    }
+    /// Parse a clause of a for loop that ends with the punctuator `end`, which can be empty
+    fn parse_for_clause(&mut self, end:Punctuator) -> Result<Option<Box<Expr>>, ParseError> {
+        if try!(self.get_token(self.pos)).data == TPunctuator(end) {
+            self.pos += 1;
+            return Ok(None);
+        }
+        let clause = try!(self.parse());
+        try!(self.end_for_clause(end));
+        Ok(Some(box clause))
+    }
+    /// Returns an error if a clause of a for loop isn't followed by the punctuator `end`
+    fn end_for_clause(&mut self, end:Punctuator) -> Result<(), ParseError> {
+        // a semicolon after the clause has already been consumed by it
+        if end == PSemicolon && try!(self.get_token(self.pos - 1)).data == TPunctuator(PSemicolon) {
+            Ok(())
+        } else {
+            self.expect_punc(end, "for loop")
+        }
+    }
+    fn assign_op(&mut self, op:BinOp, orig:Expr) -> Result<Expr, ParseError> {
+        self.pos += 1;
+        let next = try!(self.parse_limited(ASSIGN_PRECEDENCE));
+        Ok(mk!(self, AssignOpExpr(op, box orig.clone(), box next), after orig))
+    }
     /// Returns an error if the next symbol is not `tk`
     fn expect(&mut self, tk:TokenData, routine:&'static str) -> Result<(), ParseError> {
         self.pos += 1;
         let curr_tk = try!(self.get_token(self.pos - 1));
         if curr_tk.data != tk {
-            Err(Expected(vec!(tk), curr_tk, routine))
+            Err(ParseError::new(Expected(vec!(tk), curr_tk, routine)))
         } else {
             Ok(())
         }
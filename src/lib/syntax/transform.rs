@@ -0,0 +1,178 @@
+use syntax::ast::expr::*;
+use syntax::ast::pattern::{Pattern, RefPattern, ObjectPattern, ArrayPattern, DefaultPattern};
+/**
+ * A pass that rewrites expressions, such as one that desugars them into simpler
+ * expressions, which is run on the expressions parsed before they are run. By
+ * default it transforms the expressions inside of each expression before the
+ * expression itself, so a transform only has to override `transform_expr`.
+*/
+pub trait Transform {
+    /// Transform an expression whose children have already been transformed, which
+    /// by default leaves it as it is
+    fn transform_expr(&mut self, expr:Expr) -> Expr {
+        expr
+    }
+    /// Transform an expression and all of the expressions inside of it
+    fn transform(&mut self, expr:Expr) -> Expr {
+        let expr = map_children(expr, |child| self.transform(child));
+        self.transform_expr(expr)
+    }
+}
+/// A list of transforms that are run one after another on the expressions parsed
+pub struct Pipeline {
+    /// The transforms, in the order they are run
+    pub transforms: Vec<Box<Transform>>
+}
+impl Pipeline {
+    /// Make a pipeline without any transforms
+    pub fn new() -> Pipeline {
+        Pipeline {
+            transforms: Vec::new()
+        }
+    }
+    /// Add a transform to the end of the pipeline
+    pub fn add(&mut self, transform:Box<Transform>) -> &mut Pipeline {
+        self.transforms.push(transform);
+        self
+    }
+    /// Run each of the transforms on the expression in order
+    pub fn run(&mut self, expr:Expr) -> Expr {
+        let mut expr = expr;
+        for transform in self.transforms.mut_iter() {
+            debug!("Transforming {}", expr);
+            expr = transform.transform(expr);
+        }
+        expr
+    }
+}
+/// Apply `f` to an expression if there is one
+fn map_opt(expr:Option<Box<Expr>>, f:|Expr| -> Expr) -> Option<Box<Expr>> {
+    match expr {
+        Some(box expr) => Some(box f(expr)),
+        None => None
+    }
+}
+/// Apply `f` to the expressions in a pattern
+fn map_pattern(pattern:Pattern, f:|Expr| -> Expr) -> Pattern {
+    match pattern {
+        RefPattern(box target) => RefPattern(box f(target)),
+        ObjectPattern(fields) => ObjectPattern(fields.move_iter().map(|(name, field)| (name, map_pattern(field, |e| f(e)))).collect()),
+        ArrayPattern(items) => ArrayPattern(items.move_iter().map(|item| item.map(|item| map_pattern(item, |e| f(e)))).collect()),
+        DefaultPattern(box pattern, box default) => {
+            let pattern = map_pattern(pattern, |e| f(e));
+            DefaultPattern(box pattern, box f(default))
+        }
+    }
+}
+/// Apply `f` to the patterns and values of a declaration
+fn map_decls(vars:Vec<(Pattern, Option<Expr>)>, f:|Expr| -> Expr) -> Vec<(Pattern, Option<Expr>)> {
+    vars.move_iter().map(|(pattern, value)| (map_pattern(pattern, |e| f(e)), value.map(|value| f(value)))).collect()
+}
+/// Rebuild an expression with `f` applied to each of the expressions directly inside of it
+pub fn map_children(expr:Expr, f:|Expr| -> Expr) -> Expr {
+    let (start, end) = (expr.start, expr.end);
+    let def = match expr.def {
+        BinOpExpr(op, box left, box right) => {
+            let left = f(left);
+            BinOpExpr(op, box left, box f(right))
+        },
+        UnaryOpExpr(op, box operand) => UnaryOpExpr(op, box f(operand)),
+        UpdateExpr(prefix, op, box target) => UpdateExpr(prefix, op, box f(target)),
+        TemplateExpr(quasis, exprs) => TemplateExpr(quasis, exprs.move_iter().map(|e| f(e)).collect()),
+        BlockExpr(stmts) => BlockExpr(stmts.move_iter().map(|e| f(e)).collect()),
+        GetConstFieldExpr(box obj, field) => GetConstFieldExpr(box f(obj), field),
+        GetFieldExpr(box obj, box field) => {
+            let obj = f(obj);
+            GetFieldExpr(box obj, box f(field))
+        },
+        OptionalChainExpr(box chain) => OptionalChainExpr(box f(chain)),
+        OptionalExpr(box obj) => OptionalExpr(box f(obj)),
+        CallExpr(box func, args) => {
+            let func = f(func);
+            CallExpr(box func, args.move_iter().map(|e| f(e)).collect())
+        },
+        WhileLoopExpr(box cond, box body) => {
+            let cond = f(cond);
+            WhileLoopExpr(box cond, box f(body))
+        },
+        ForLoopExpr(init, cond, step, box body) => {
+            let init = map_opt(init, |e| f(e));
+            let cond = map_opt(cond, |e| f(e));
+            let step = map_opt(step, |e| f(e));
+            ForLoopExpr(init, cond, step, box f(body))
+        },
+        ForInExpr(box name, box obj, box body) => {
+            let (name, obj) = (f(name), f(obj));
+            ForInExpr(box name, box obj, box f(body))
+        },
+        ForOfExpr(box name, box iterable, box body) => {
+            let (name, iterable) = (f(name), f(iterable));
+            ForOfExpr(box name, box iterable, box f(body))
+        },
+        IfExpr(box cond, box if_expr, else_expr) => {
+            let (cond, if_expr) = (f(cond), f(if_expr));
+            IfExpr(box cond, box if_expr, map_opt(else_expr, |e| f(e)))
+        },
+        TryExpr(box block, name, catch_block, finally_block) => {
+            let block = f(block);
+            let catch_block = map_opt(catch_block, |e| f(e));
+            TryExpr(box block, name, catch_block, map_opt(finally_block, |e| f(e)))
+        },
+        LabelledExpr(label, box body) => LabelledExpr(label, box f(body)),
+        SwitchExpr(box value, cases, default) => {
+            let value = f(value);
+            let cases = cases.move_iter().map(|(cond, block)| {
+                let cond = f(cond);
+                (cond, block.move_iter().map(|e| f(e)).collect())
+            }).collect();
+            SwitchExpr(box value, cases, map_opt(default, |e| f(e)))
+        },
+        ObjectDeclExpr(box fields) => ObjectDeclExpr(box fields.move_iter().map(|(name, value)| (name, f(value))).collect()),
+        AccessorExpr(getter, setter) => {
+            let getter = map_opt(getter, |e| f(e));
+            AccessorExpr(getter, map_opt(setter, |e| f(e)))
+        },
+        ArrayDeclExpr(items) => ArrayDeclExpr(items.move_iter().map(|e| f(e)).collect()),
+        SpreadExpr(box iterable) => SpreadExpr(box f(iterable)),
+        FunctionDeclExpr(name, args, box body, strict) => FunctionDeclExpr(name, args, box f(body), strict),
+        GeneratorDeclExpr(name, args, box body, strict) => GeneratorDeclExpr(name, args, box f(body), strict),
+        YieldExpr(delegate, value) => YieldExpr(delegate, map_opt(value, |e| f(e))),
+        ArrowFunctionDeclExpr(args, box body, strict) => ArrowFunctionDeclExpr(args, box f(body), strict),
+        ClassDeclExpr(name, parent, box constructor, methods) => {
+            let parent = map_opt(parent, |e| f(e));
+            let constructor = f(constructor);
+            ClassDeclExpr(name, parent, box constructor, methods.move_iter().map(|(is_static, kind, name, method)| (is_static, kind, name, f(method))).collect())
+        },
+        ConstructExpr(box func, args) => {
+            let func = f(func);
+            ConstructExpr(box func, args.move_iter().map(|e| f(e)).collect())
+        },
+        ReturnExpr(value) => ReturnExpr(map_opt(value, |e| f(e))),
+        ThrowExpr(box value) => ThrowExpr(box f(value)),
+        AssignExpr(box target, box value) => {
+            let target = f(target);
+            AssignExpr(box target, box f(value))
+        },
+        DestructureExpr(pattern, box value) => {
+            let pattern = map_pattern(pattern, |e| f(e));
+            DestructureExpr(pattern, box f(value))
+        },
+        AssignOpExpr(op, box target, box value) => {
+            let target = f(target);
+            AssignOpExpr(op, box target, box f(value))
+        },
+        VarDeclExpr(vars) => VarDeclExpr(map_decls(vars, |e| f(e))),
+        LetDeclExpr(vars) => LetDeclExpr(map_decls(vars, |e| f(e))),
+        ConstDeclExpr(vars) => ConstDeclExpr(map_decls(vars, |e| f(e))),
+        ScriptExpr(stmts, strict) => ScriptExpr(stmts.move_iter().map(|e| f(e)).collect(), strict),
+        ModuleExpr(stmts) => ModuleExpr(stmts.move_iter().map(|e| f(e)).collect()),
+        ExportDeclExpr(box decl) => ExportDeclExpr(box f(decl)),
+        ExportDefaultExpr(box value) => ExportDefaultExpr(box f(value)),
+        TypeOfExpr(box operand) => TypeOfExpr(box f(operand)),
+        VoidExpr(box operand) => VoidExpr(box f(operand)),
+        DeleteExpr(box operand) => DeleteExpr(box f(operand)),
+        // constants, names, jumps and imports don't have any expressions inside of them
+        def => def
+    };
+    Expr::new(def, start, end)
+}
@@ -0,0 +1,15 @@
+// @description Array unit tests
+var items = [1, 2, 3];
+items[5] = 6;
+assert(items.length == 6 && !items.hasOwnProperty(4) && items[4] === undefined, "Setting an index past the end grows the length, leaving holes");
+items.length = 2;
+assert(items.length == 2 && items[2] === undefined && !items.hasOwnProperty(2), "Shrinking the length deletes items");
+var holey = [];
+holey["01"] = "not an index";
+holey["1"] = "index";
+assert(holey.length == 2, "Only canonical index keys change the length");
+assert(new Array(3).length == 3 && new Array(1, 2).length == 2 && Array(4).length == 4, "Making arrays with the Array constructor");
+var invalid = false;
+try { items.length = -1; } catch (e) { invalid = true; }
+assert(invalid, "Lengths must be whole numbers below 2^32");
+assert(Array.isArray(items) && !Array.isArray({length: 0}) && !Array.isArray({__array__: true}), "Array.isArray tells arrays from array-like objects");
@@ -0,0 +1,42 @@
+// @description Keyed collection unit tests
+var key = {}, other = {};
+var map = new Map([["a", 1], [key, 2]]);
+map.set(NaN, 3).set("a", 4);
+assert(map.size == 3 && map.get(key) == 2 && map.get(other) === undefined, "Maps are keyed by object identity");
+assert(map.get(NaN) == 3 && map.get("a") == 4, "NaN is a map key and setting an existing key replaces its value");
+var order = [];
+map.forEach(function(value, k) { order.push(value); });
+assert(order.join() == "4,2,3", "Maps keep the order entries were added in");
+map.delete(key);
+var entries = [...map];
+assert(entries.length == 2 && entries[1][0] !== entries[1][0] && entries[1][1] == 3, "Maps iterate over their entries");
+var set = new Set([1, 2, 2, 3]);
+set.add(-0);
+assert(set.size == 4 && set.has(0) && !set.has("1"), "Sets hold each value once");
+var seen = [];
+for (var value of set) {
+    if (value == 1) set.delete(2);
+    if (value == 3) set.add(4);
+    seen.push(value);
+}
+assert(seen.join() == "1,3,0,4", "Set iteration sees deletions and additions made while iterating");
+set.clear();
+assert(set.size == 0 && [...set.keys()].length == 0, "Clearing a set removes every value");
+var weak = new WeakMap([[key, "private"]]);
+var weakSet = new WeakSet([key]);
+assert(weak.get(key) == "private" && weakSet.has(key) && !weakSet.has(other), "Weak collections hold objects");
+var rejected = false;
+try { weak.set("string", 1); } catch (e) { rejected = true; }
+assert(rejected, "Weak collections only hold objects");
+var spoofed = false;
+try { Map.prototype.get.call({__keys__: [1], __values__: [2], __weak__: false}, 1); } catch (e) { spoofed = true; }
+assert(spoofed, "Collection methods need a real collection");
+var cleared = new Map([[1, 1], [2, 2]]);
+var clearedIterator = cleared.keys();
+clearedIterator.next();
+cleared.clear();
+cleared.set(3, 3);
+assert(clearedIterator.next().value == 3, "Iterators over a cleared collection see entries added after");
+var shared = new WeakMap();
+shared.set(key, 1);
+assert(!(new WeakMap()).has(key) && shared.delete(key) && !shared.has(key), "Each weak collection has its own entries");
@@ -0,0 +1,53 @@
+// @description eval unit tests
+assert(eval("1 + 2") == 3 && eval(5) === 5 && eval("") === undefined, "eval gives the value of the last statement");
+function readLocal() {
+    var secret = "local";
+    return eval("secret");
+}
+assert(readLocal() == "local", "Direct eval sees the variables of the calling scope");
+function declareLocal() {
+    eval("var declared = 1");
+    return declared;
+}
+assert(declareLocal() == 1 && typeof declared == "undefined", "Direct eval declares variables in the calling function");
+function strictDeclare() {
+    "use strict";
+    eval("var hidden = 1");
+    return typeof hidden;
+}
+assert(strictDeclare() == "undefined", "Strict eval keeps its variables to itself");
+function declareInBlock() {
+    { let inner = 1; eval("var lifted = inner + 1"); }
+    return lifted;
+}
+assert(declareInBlock() == 2, "Direct eval in a block declares variables in the calling function");
+var where = "global";
+function indirect() {
+    var where = "local";
+    var run = eval;
+    return run("where");
+}
+assert(indirect() == "global", "Indirect eval runs in the global scope");
+var failed = false;
+try { eval("var = ;"); } catch (e) { failed = e; }
+assert(failed instanceof SyntaxError && failed.name == "SyntaxError", "eval throws a SyntaxError when the source can't be parsed");
+var add = Function("a", "b", "return a + b");
+var outer = "global";
+function makeInside() {
+    var outer = "local";
+    return Function("return outer");
+}
+assert(add(2, 3) == 5 && Function("a, b", "return a * b")(2, 3) == 6, "Making a function from the source of its parameters and body");
+assert(makeInside()() == "global", "Functions made from source close over the global scope");
+var rejected = false;
+try { Function("}, function() {"); } catch (e) { rejected = e; }
+assert(rejected instanceof SyntaxError, "The body of a function made from source can't end the function early");
+assert(Function("return typeof anonymous")() == "undefined" && Function("").name == "anonymous", "Functions made from source are named anonymous without their bodies seeing the name");
+var unnamed = null;
+try { new Function("return anonymous")(); } catch (e) { unnamed = e; }
+assert(unnamed instanceof ReferenceError, "The body of a function made from source can't refer to it as anonymous");
+assert(eval("1; var unused = 2;") == 1 && eval("3; function f() {}") == 3, "Declarations don't change the value of a script");
+assert(eval("4; { 5; }") == 5 && eval("6; if (true) 7;") == 7 && eval("8; if (false) 9;") === undefined, "Blocks and ifs give the value of what they ran");
+assert(eval("var i = 0; while (i < 3) { i++; i * 10; }") == 30, "Loops give the value of their last iteration");
+assert(eval("while (true) { 11; break; }") == 11 && eval("12; for (;false;);") === undefined, "Breaks keep the value of the loop");
+assert(eval("switch (1) { case 1: 13; case 2: 14; break; default: 15; }") == 14, "Switches give the value of the clauses they ran");
@@ -0,0 +1,17 @@
+// @description Logical operator unit tests
+assert((null ?? 5) == 5, "Nullish coalescing of null");
+assert((undefined ?? 5) == 5, "Nullish coalescing of undefined");
+assert((0 ?? 5) == 0, "Nullish coalescing of zero");
+assert((false ?? true) == false, "Nullish coalescing of false");
+assert((true || undefined.field) == true, "Logical or short-circuits");
+assert((false && undefined.field) == false, "Logical and short-circuits");
+assert(null == undefined && !(null == 0) && !(undefined == false), "null and undefined only equal each other");
+assert("1" == 1 && true == 1 && "" == 0 && !(NaN == NaN), "Loose equality converts strings and booleans to numbers");
+var same = {};
+assert(same == same && !({} == {}), "Objects are only equal to themselves");
+assert({valueOf: function() { return 2; }} == 2, "Objects are converted to primitives to compare with them");
+assert(!(1 === "1") && 1 === 1.0 && !(null === undefined), "Strict equality doesn't convert values");
+assert("a" < "b" && "B" < "a" && "10" < "9" && !("a" < "a"), "Strings are compared by their characters");
+assert("10" > 9 && 2 < "10", "Strings are compared with numbers as numbers");
+assert(!(NaN < 1) && !(NaN >= 1) && !(undefined <= 0), "Nothing is less or greater than NaN");
+assert(null >= 0 && null <= 0, "null is compared as zero");
@@ -9,3 +9,53 @@ assert((5 - 2) == 3, "Number subtraction");
 assert((5 / 2) == 2.5, "Number division");
 assert((5 * 2) == 10, "Number multiplication");
 assert((3 + 4 * 5) == 23, "Operator precedence");
+assert(1e3 == 1000 && 1E3 == 1000 && 2.5E-1 == 0.25, "Exponents");
+assert(2.5E-3 == 0.0025, "Negative exponents");
+assert(0x1F == 31, "Hexadecimal literals");
+assert(0o777 == 511, "Octal literals");
+assert(017 == 15, "Legacy octal literals");
+assert(0b1010 == 10, "Binary literals");
+assert((0b11 + 0o7 + 0xF + 10) == 35, "Mixed radix arithmetic");
+assert((2 ** 10) == 1024, "Exponentiation");
+assert((2 ** 3 ** 2) == 512, "Exponentiation is right-associative");
+assert(09 == 9, "Decimal literal with a leading zero");
+assert(typeof 10n == "bigint", "BigInt type");
+assert(9007199254740993n + 1n == 9007199254740994n, "BigInt addition beyond the safe integer range");
+assert(0xFFn == 255n, "Hexadecimal BigInt");
+assert(7n / 2n == 3n, "BigInt division truncates");
+var mixed = false, divided = false, negative = false;
+try { 1n + 1; } catch (e) { mixed = true; }
+try { 1n / 0n; } catch (e) { divided = true; }
+try { 2n ** -1n; } catch (e) { negative = true; }
+assert(mixed && divided && negative, "Mixing BigInts with numbers, dividing a BigInt by zero and negative BigInt exponents throw");
+assert((10 - 4 - 3) == 3, "Subtraction is left-associative");
+assert((2 * 3 + 4 * 5) == 26, "Mixed precedence on both sides");
+assert((1 + 2 << 1) == 6, "Shifts bind looser than addition");
+assert((-1 >>> 0) == 4294967295 && (-8 >>> 1) == 2147483644, "Unsigned right shift gives unsigned results");
+assert((4294967296 | 0) == 0 && (2147483648 | 0) == -2147483648 && ~4294967295 == 0, "Bitwise operators wrap numbers to 32-bit integers");
+assert((6 & 3 | 8) == 10, "Bitwise and binds tighter than or");
+assert(-2 + 5 == 3, "Negation binds tighter than addition");
+var first, second;
+first = second = 4;
+assert(first == 4 && second == 4, "Assignment is right-associative");
+assert(+"" == 0 && +" 12 " == 12 && +"0x1F" == 31 && isNaN(+"12px"), "Strings are converted to numbers like the spec says");
+assert(+{valueOf: function() { return 3; }} == 3, "Objects are converted to numbers with valueOf");
+assert(+{toString: function() { return "4"; }} == 4, "Objects are converted to numbers with toString if they don't have valueOf");
+assert(!!{} && !"" && !NaN && !!"0", "Values are converted to booleans");
+assert(String(0.1 + 0.2) == "0.30000000000000004" && String(1 / 3) == "0.3333333333333333", "Numbers are written with the fewest digits that give them back");
+assert(String(100) == "100" && String(1.5) == "1.5" && String(-0) == "0", "Whole numbers have no fraction and -0 is written as 0");
+assert(String(1e21) == "1e+21" && String(123e-20) == "1.23e-18" && String(0.000001) == "0.000001", "Large and small numbers are written with exponents");
+assert(String(1 / 0) == "Infinity" && String(-1 / 0) == "-Infinity" && String(0 / 0) == "NaN", "Numbers that aren't finite");
+assert(JSON.stringify([0.1, 1e21, 0 / 0]) == "[0.1,1e+21,null]", "JSON writes numbers like strings do");
+assert(isNaN(NaN) && Infinity > 1e308 && undefined === void 0, "Global values");
+assert(isNaN("abc") && !isNaN("12") && isNaN() && !isNaN(null), "isNaN converts its argument to a number");
+assert(isFinite("12") && !isFinite("Infinity") && !isFinite() && isFinite({valueOf: function() { return 1; }}), "isFinite converts its argument to a number");
+assert(!Number.isNaN("abc") && !Number.isFinite("12"), "Number.isNaN and Number.isFinite don't convert their argument");
+var threw = false;
+try { isNaN(Symbol()); } catch (e) { threw = true; }
+assert(threw, "isNaN throws for symbols");
+assert(parseInt("  42px") == 42 && parseInt("-0x1F") == -31 && parseInt("11", 2) == 3 && parseInt("z", 36) == 35, "parseInt parses the start of a string in a radix");
+assert(isNaN(parseInt("px")) && isNaN(parseInt("1", 37)) && parseInt("08") == 8 && parseInt("0x10", 10) == 0, "parseInt without an integer or radix");
+assert(parseFloat("3.14abc") == 3.14 && parseFloat(" -.5") == -0.5 && parseFloat("1e3e") == 1000 && parseFloat("2e") == 2, "parseFloat parses the start of a string");
+assert(parseFloat("-Infinityx") == -Infinity && isNaN(parseFloat(".")) && isNaN(parseFloat("abc")), "parseFloat of Infinity and non-numbers");
+assert(Number.parseInt === parseInt && Number.parseFloat === parseFloat, "Number.parseInt and Number.parseFloat are the global functions");
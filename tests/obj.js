@@ -1,2 +1,71 @@
 // @description Object unit tests
-assert(Object.prototype == {}.__proto__, "Object prototype");
\ No newline at end of file
+assert(Object.prototype == {}.__proto__, "Object prototype");
+var keys = 0;
+for(var key in Object.prototype) keys++;
+assert(keys == 0, "Built-in properties aren't enumerable");
+var names = "";
+var point = {x: 1};
+point.y = 2;
+for (var key in point) names += key;
+assert(names == "xy", "Properties that are declared or assigned are enumerable");
+var temperature = {
+    celsius: 20,
+    get fahrenheit() { return this.celsius * 9 / 5 + 32; },
+    set fahrenheit(value) { this.celsius = (value - 32) * 5 / 9; }
+};
+assert(temperature.fahrenheit == 68, "Getter in an object declaration");
+temperature.fahrenheit = 212;
+assert(temperature.celsius == 100, "Setter in an object declaration");
+var width = 2, height = 3;
+var rect = {width, height, area() { return this.width * this.height; }};
+assert(rect.width == 2 && rect.area() == 6, "Shorthand properties and methods");
+var grid = {rows: [{cells: {}}]}, row = 0;
+grid.rows[row].cells.first = 1;
+grid["rows"][row]["cells"]["second"] = 2;
+assert(grid.rows[0].cells.first == 1 && grid.rows[0].cells.second == 2, "Assigning to computed and nested fields");
+var base = {greet: function() { return "hi " + this.name; }, kind: "base"};
+var middle = Object.create ? Object.create(base) : {__proto__: base};
+var derived = {__proto__: middle, name: "derived"};
+assert(derived.kind == "base", "Fields are looked up along the prototype chain");
+assert(derived.greet() == "hi derived", "Methods found on prototypes are called on the original object");
+assert(!derived.hasOwnProperty("kind") && derived.hasOwnProperty("name"), "Inherited fields aren't own properties");
+var counted = {reads: 0};
+Object.defineProperty(counted, "next", {get: function() { return ++this.reads; }, enumerable: false, configurable: true});
+assert(counted.next == 1 && counted.next == 2, "Getters defined with defineProperty run on each read");
+var inheritsAccessor = {__proto__: temperature};
+inheritsAccessor.fahrenheit = 32;
+assert(inheritsAccessor.celsius == 0, "Inherited setters run with this being the object assigned to");
+var made = {
+    get pair() { return [this.first, {second: 2}]; },
+    set pair(value) { this.first = new Array(value, value); }
+};
+made.pair = 1;
+assert(made.pair.length == 2 && made.pair[0].length == 2 && made.pair[1].second == 2, "Getters and setters run with the global object, so they can make objects and use globals");
+var frozen = Object.freeze({a: 1});
+frozen.a = 2;
+var thrown = false;
+try { (function() { "use strict"; frozen.a = 3; })(); } catch (e) { thrown = true; }
+assert(frozen.a == 1 && thrown, "Frozen objects can't be assigned to, which only throws in strict code");
+assert(!(delete frozen.a) && frozen.a == 1, "Frozen objects can't have properties removed");
+var undeletable = null;
+try { (function() { "use strict"; delete frozen.a; })(); } catch (e) { undeletable = e; }
+assert(undeletable instanceof TypeError && frozen.a == 1, "Deleting a property that can't be configured throws a TypeError in strict code");
+var unqualified = null;
+try { eval("'use strict'; var gone = 1; delete gone;"); } catch (e) { unqualified = e; }
+assert(unqualified instanceof SyntaxError, "Deleting a variable is a SyntaxError in strict code");
+var sealed = Object.seal({b: 1});
+sealed.b = 2;
+try { sealed.c = 3; } catch (e) {}
+assert(sealed.b == 2 && sealed.c === undefined && !(delete sealed.b), "Sealed objects can be assigned to but not added to or removed from");
+var fixed = Object.preventExtensions({d: 1});
+try { fixed.e = 2; } catch (e) {}
+assert(fixed.e === undefined && delete fixed.d && fixed.d === undefined, "Objects that can't be extended can still have properties removed");
+assert(Object.isFrozen(frozen) && Object.isSealed(frozen) && !Object.isExtensible(frozen), "Frozen objects are sealed and can't be extended");
+assert(Object.isSealed(sealed) && !Object.isFrozen(sealed) && Object.isExtensible({}), "Checking the integrity of objects");
+assert(Object.isFrozen(Object.preventExtensions({})) && Object.isFrozen(1), "Empty objects that can't be extended are frozen, as are primitives");
+assert(JSON.stringify({a: 1, b: [true, "x"]}) == '{"a":1,"b":[true,"x"]}', "JSON writes the enumerable properties of objects");
+var cycle = [];
+cycle[0] = cycle;
+var circular = false;
+try { JSON.stringify(cycle); } catch (e) { circular = true; }
+assert(circular, "JSON throws for circular structures");
@@ -0,0 +1,29 @@
+// @description Promise unit tests
+var order = [];
+Promise.resolve(1).then(function(value) { order.push("then " + value); });
+order.push("sync");
+new Promise(function(resolve, reject) { reject("no"); })
+    .catch(function(reason) { return reason + " caught"; })
+    .finally(function() { order.push("finally"); })
+    .then(function(value) {
+        assert(value == "no caught", "Catching a rejection and passing its value through finally");
+        assert(order[0] == "sync" && order[1] == "then 1", "Reactions run after the code that queued them");
+    });
+var thenable = {then: function(resolve) { resolve(42); }};
+Promise.resolve(thenable).then(function(value) { assert(value == 42, "Resolving with a thenable follows it"); });
+Promise.all([1, Promise.resolve(2), thenable]).then(function(values) {
+    assert(values.length == 3 && values[1] == 2 && values[2] == 42, "Promise.all gives every value in order");
+});
+Promise.all([Promise.reject("first"), Promise.reject("second")]).catch(function(reason) {
+    assert(reason == "first", "Promise.all is rejected by the first rejection");
+});
+Promise.allSettled([1, Promise.reject("bad")]).then(function(results) {
+    assert(results[0].status == "fulfilled" && results[1].reason == "bad", "Promise.allSettled describes each outcome");
+});
+Promise.race([new Promise(function() {}), Promise.resolve("fast")]).then(function(value) {
+    assert(value == "fast", "Promise.race settles like the first item to settle");
+});
+var cycle = Promise.resolve().then(function() { return cycle; }), cycleRejected = false;
+cycle.catch(function() { cycleRejected = true; }).then(function() {
+    assert(cycleRejected, "Resolving a promise with itself rejects it");
+});
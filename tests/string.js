@@ -4,4 +4,27 @@ assert(hello.length == 13, "String length");
 assert(hello[2] == 'l', "String index");
 assert(hello.charAt(1) == 'e', "String charAt");
 assert(("Hello, "+'world!') == hello, "String concatenation and equality");
-assert(String.fromCharCode(65,66,67) == "ABC", "String.fromCharCode");
\ No newline at end of file
+assert(String.fromCharCode(65,66,67) == "ABC", "String.fromCharCode");
+assert("\101" == "A", "Legacy octal escape");
+assert("\8" == "8", "Non-octal digit escape");
+assert("Hello, \
+world!" == hello, "Line continuation in a string");
+var chars = "";
+for(var ch of "abc") chars = ch + chars;
+assert(chars == "cba", "String iteration");
+var name = "world";
+assert(`Hello, ${name}!` == hello, "Template literal substitution");
+assert(`${1 + 2}${"a"}` == "3a", "Template literal adjacent substitutions");
+assert(`plain` == "plain", "Template literal without substitutions");
+var pattern = /ab+c/gi;
+assert(pattern.source == "ab+c" && pattern.flags == "gi", "Regular expression literal source and flags");
+assert(pattern.global && pattern.ignoreCase && !pattern.multiline, "Regular expression literal flag fields");
+assert(pattern.toString() == "/ab+c/gi", "Regular expression string representation");
+assert("a" + 1 === "a1" && 1 + "a" === "1a" && 1 + 2 + "3" === "33", "Adding a string concatenates");
+assert(1 + true === 2 && 1 + null === 1 && isNaN(1 + undefined), "Adding other primitives adds them as numbers");
+assert([] + {} === "[object Object]", "Objects are converted to primitives before adding");
+assert({valueOf: function() { return 2; }} + 3 === 5, "Objects that give numbers are added as numbers");
+assert("abc".length == 3 && "abc"[1] == "b" && "abc"[3] === undefined, "Primitive strings have their length and characters");
+assert("abc".toUpperCase() == "ABC" && "ABC".toLowerCase() == "abc", "Primitive strings inherit from String.prototype");
+assert(typeof new String("abc") == "object" && new String("abc").length == 3, "String objects wrap primitive strings");
+assert(String(12) === "12" && new String("abc").valueOf() === "abc", "Converting values to strings");
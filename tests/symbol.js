@@ -0,0 +1,28 @@
+// @description Symbol unit tests
+var tag = Symbol("tag");
+assert(typeof tag == "symbol" && tag !== Symbol("tag"), "Every symbol is unique");
+assert(Symbol.for("shared") === Symbol.for("shared") && Symbol.keyFor(Symbol.for("shared")) == "shared", "Registered symbols are shared");
+assert(Symbol.keyFor(tag) === undefined, "Symbols that aren't registered have no key");
+assert(tag.toString() == "Symbol(tag)" && tag.description == "tag", "Symbols are described");
+var tagged = {};
+tagged[tag] = 1;
+tagged.tag = 2;
+var keys = 0;
+for (var key in tagged) keys++;
+assert(tagged[tag] == 1 && tagged.tag == 2 && keys == 1, "Symbol keys are separate from string keys and aren't enumerated");
+var spoofed = {"@@iterator": 1};
+assert(spoofed[Symbol.iterator] === undefined && []["@@iterator"] === undefined, "Strings never key the properties of symbols");
+var range = {};
+range[Symbol.iterator] = function() {
+    var current = 0;
+    return {next: function() { current++; return {value: current, done: current > 3}; }};
+};
+var sum = 0;
+for (var n of range) sum += n;
+assert(sum == 6 && [...range].length == 3, "Objects with a Symbol.iterator method are iterable");
+var money = {};
+money[Symbol.toPrimitive] = function(hint) { return hint == "number" ? 5 : "five"; };
+assert(+money == 5 && `${money}` == "five", "Symbol.toPrimitive converts objects with the hint");
+var custom = {};
+custom[Symbol.toStringTag] = "Custom";
+assert(Object.prototype.toString.call(custom) == "[object Custom]", "Symbol.toStringTag names the kind of object");
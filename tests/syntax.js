@@ -0,0 +1,269 @@
+// @description Syntax unit tests
+var separated = false;
+// a line separator ends a comment separated = true;
+assert(separated, "Line separator ends a line comment");
+var paragraphs = false;
+// so does a paragraph separator paragraphs = true;
+assert(paragraphs, "Paragraph separator ends a line comment");
+var total = 0;
+for(var i = 0; i < 5; i++) total += i;
+assert(total == 10, "For loop");
+var runs = 0;
+for(; runs < 3;) runs++;
+assert(runs == 3, "For loop with empty clauses");
+var keyRuns = 0, counts = {a: 1};
+function countedKey() { keyRuns++; return "a"; }
+counts[countedKey()] += 2;
+counts[countedKey()] ||= 5;
+assert(counts.a == 3 && keyRuns == 2, "Compound assignments run their target once");
+var updated = "5", before = updated++, after = ++counts.a, items = [1];
+items[countedKey() && 0]--;
+assert(before === 5 && updated === 6 && after == 4 && counts.a == 4 && items[0] == 0 && keyRuns == 3, "Increments and decrements store the new value");
+var caught = null, cleaned = false;
+try {
+    throw 5;
+} catch(e) {
+    caught = e;
+} finally {
+    cleaned = true;
+}
+assert(caught == 5, "Catching a thrown value");
+assert(cleaned, "Finally block runs");
+var caughtWithoutBinding = false;
+try {
+    throw 5;
+} catch {
+    caughtWithoutBinding = true;
+}
+assert(caughtWithoutBinding, "Catching without binding the thrown value");
+var order = "", declA = (order += "a", 1), declB, declC = (order += "c", declA + 1);
+assert(declA == 1 && declB === undefined && declC == 2, "Declaring several variables at once");
+assert(order == "ac", "Initial values of declarations are run from left to right");
+var loops = 0, odd = 0;
+for(;;) {
+    loops++;
+    if(loops % 2 == 0) continue;
+    if(loops > 5) break;
+    odd++;
+}
+assert(loops == 7 && odd == 3, "Break and continue in a loop");
+var stepped = 0, skipped = 0;
+for(var s = 0; s < 4; stepped = ++s) {
+    if(s % 2 == 0) continue;
+    skipped++;
+}
+assert(stepped == 4 && skipped == 2, "Continue in a for loop still runs the step");
+var pairs = 0;
+outer: for(var a = 0; a < 3; a++) {
+    for(var b = 0; b < 3; b++) {
+        if(b == 1) continue outer;
+        if(a == 2) break outer;
+        pairs++;
+    }
+}
+assert(pairs == 2, "Labelled break and continue");
+let counted = 0;
+for(const step of "xyz") counted++;
+const expected = 3;
+assert(counted == expected, "Let and const declarations");
+var reassigned = null;
+try { expected = 4; } catch (e) { reassigned = e; }
+assert(reassigned instanceof TypeError && expected == 3, "Assigning to a constant throws a TypeError");
+NaN = 1;
+undefined = 1;
+assert(NaN !== NaN && undefined === void 0, "Assigning to read only globals is ignored in sloppy code");
+var readOnly = null;
+try { (function() { "use strict"; NaN = 1; })(); } catch (e) { readOnly = e; }
+assert(readOnly instanceof TypeError, "Assigning to read only globals throws a TypeError in strict code");
+var parity = 1 + 1 == 2 ? "even" : "odd";
+assert(parity == "even", "Conditional binds looser than binary operators");
+var skipped = true;
+var picked = false ? (skipped = false) : 0 ? 1 : 2;
+assert(picked == 2 && skipped, "Conditional only runs the branch taken");
+assert(typeof undefined == "undefined" && typeof null == "object", "Typeof undefined and null");
+assert(typeof function() {} == "function" && typeof "a" + "b" == "stringb", "Typeof binds tighter than binary operators");
+assert(void 5 === undefined, "Void gives undefined");
+var deletable = {kept: 1, removed: 2};
+assert(delete deletable.removed && deletable.removed === undefined && deletable.kept == 1, "Delete removes a property");
+var tally = {count: "5"}, index = "count";
+assert(tally.count++ === 5 && tally.count === 6, "Postfix increment gives the old value as a number");
+assert(--tally[index] === 5 && tally.count === 5, "Prefix decrement of a computed field gives the new value");
+var add = (a, b) => a + b, twice = x => x * 2, nothing = () => {};
+assert(add(1, 2) == 3 && twice(4) == 8 && nothing() === undefined, "Arrow functions with expression and block bodies");
+var owner = {name: "owner", get: function() { return (() => this.name)(); }};
+assert(owner.get() == "owner", "Arrow functions capture this");
+var immediate = (function() { return 7; })();
+assert(immediate == 7, "Immediately invoked function expression");
+var factorial = function fact(n) { return n <= 1 ? 1 : n * fact(n - 1); };
+assert(factorial(5) == 120 && typeof fact == "undefined", "Named function expression is only bound inside itself");
+var rest = [2, 3], spread = [1, ...rest, 4];
+assert(spread.length == 4 && spread[2] == 3, "Spread in an array literal");
+assert(add(...rest) == 5 && add(..."ab") == "ab", "Spread in call arguments");
+var {a: first, b: {c}} = {a: 1, b: {c: 2}}, [x, , y = 3] = [4, 5];
+assert(first == 1 && c == 2 && x == 4 && y == 3, "Destructuring declarations with nesting, holes and defaults");
+var swapped = [x, y] = [y, x];
+assert(x == 3 && y == 4 && swapped.length == 2, "Destructuring assignment");
+class Shape {
+    constructor(sides) { this.sides = sides; }
+    describe() { return this.sides + " sides"; }
+    static create() { return new Shape(0); }
+    get doubled() { return this.sides * 2; }
+}
+class Square extends Shape {
+    constructor() { super(4); }
+    describe() { return "square with " + super.describe(); }
+}
+var square = new Square();
+assert(square.describe() == "square with 4 sides" && square.doubled == 8, "Class inheritance with super calls and getters");
+assert(Shape.create().sides == 0, "Static class methods");
+class Dial {
+    get level() { return this.stored; }
+    set level(value) { this.stored = value * 2; }
+    turn() { return this.level; }
+}
+var dial = new Dial(), listed = "";
+dial.level = 5;
+for (var key in dial) listed += key;
+assert(dial.turn() == 10 && listed == "stored", "Class methods and accessors aren't enumerable, and a getter and setter share a property");
+function* counter(limit) {
+    for(var i = 0; i < limit; i++) {
+        var skip = yield i;
+        if(skip) i++;
+    }
+    return "done";
+}
+var count = counter(4);
+assert(count.next().value == 0, "Generator yields the first value");
+assert(count.next(true).value == 2, "Generator receives the value passed to next");
+var last = count.next();
+assert(last.value == 3 && !last.done, "Generator pauses at each yield");
+var end = count.next();
+assert(end.value == "done" && end.done, "Generator finishes with its return value");
+function* delegating() {
+    yield 1;
+    yield* [2, 3];
+}
+var items = [];
+for(var item of delegating()) items.push(item);
+assert(items.length == 3 && items[2] == 3, "Generator delegates to an iterable with yield*");
+var closing = counter(10);
+closing.next();
+assert(closing.return(5).value == 5 && closing.next().done, "Generator return finishes it");
+function Guarded() {
+    if(!new.target) throw "Guarded must be called with new";
+    this.made = true;
+}
+var threw = false;
+try { Guarded(); } catch(e) { threw = true; }
+assert(threw && new Guarded().made, "new.target tells if a function was called with new");
+function sloppyThis() { return this; }
+function strictThis() { "use strict"; return this; }
+assert(sloppyThis() != undefined, "Functions that aren't strict are called on the global object");
+assert(strictThis() === undefined, "Strict functions are called on undefined");
+var first = 1
+var second = first
+second = 2
+assert(first == 1 && second == 2, "Semicolons are inserted at the ends of lines")
+function returnsNothing() {
+    return
+    42
+}
+assert(returnsNothing() === undefined, "A semicolon is inserted after return at the end of a line")
+var afterSemicolon = 3; +4;
+assert(afterSemicolon == 3, "A semicolon ends the statement")
+assert(isEven(10) && !isOdd(10), "Functions can be called before they are declared");
+function isEven(n) { return n == 0 || isOdd(n - 1); }
+function isOdd(n) { return n != 0 && isEven(n - 1); }
+assert(hoistedVar === undefined, "Variables are declared before the statement declaring them is run");
+var hoistedVar = 1;
+function makeCounter() {
+    var n = 0;
+    return function() { return ++n; };
+}
+var count = makeCounter(), otherCount = makeCounter();
+count();
+assert(count() == 2 && otherCount() == 1, "Functions keep the variables of the scope they were made in");
+var shadowed = 1;
+function assignsOuter() {
+    shadowed = 2;
+    var inner = 1;
+    (function() { inner = 3; })();
+    return inner;
+}
+assert(assignsOuter() == 3 && shadowed == 2, "Assignments go to the scope that declared the variable");
+var afterReturn = false;
+function returnsEarly() {
+    if (true) {
+        return 1;
+    }
+    afterReturn = true;
+    return 2;
+}
+assert(returnsEarly() == 1 && !afterReturn, "Nothing after a return is run");
+var receiver = {name: "receiver", getName: function() { return this.name; }};
+var detached = receiver.getName;
+assert(receiver.getName() == "receiver", "Method calls run with this being the object");
+assert(detached() === undefined || detached() == this.name, "Plain calls run on the global object");
+function Made() { this.made = this instanceof Made; }
+assert(new Made().made, "Constructors run with this being the new object");
+function describe(greeting, punctuation) { return greeting + " " + this.name + punctuation; }
+var named = {name: "named"};
+assert(describe.call(named, "Hello", "!") == "Hello named!", "Calling a function with call");
+assert(describe.apply(named, ["Hi", "?"]) == "Hi named?", "Calling a function with apply");
+var greet = describe.bind(named, "Hey");
+assert(greet(".") == "Hey named.", "Binding this and leading arguments");
+function Point(x, y) { this.x = x; this.y = y; }
+var AtOrigin = Point.bind(null, 0);
+var bound = new AtOrigin(5);
+assert(bound.x == 0 && bound.y == 5 && bound instanceof Point, "Constructing a bound function constructs its target");
+function countArguments() { return arguments.length; }
+function secondArgument() { return arguments[1]; }
+function selfReference() { return arguments.callee; }
+assert(countArguments(1, 2, 3) == 3 && secondArgument("a", "b") == "b", "Functions have an arguments object");
+assert(selfReference() === selfReference, "The arguments object refers to the function called");
+var letters = [];
+for (var letter of "ab") letters.push(letter);
+var total = 0;
+for (var n of [1, 2, 3]) total += n;
+assert(letters.join("") == "ab" && total == 6, "Iterating over strings and arrays with for-of");
+function* countTo(limit) { for (var i = 1; i <= limit; i++) yield i; }
+assert(Math.max(...countTo(3)) == 3 && [..."hi"].length == 2, "Spreading iterables");
+var closed = false;
+function* tracked() { try { yield 1; yield 2; } finally { closed = true; } }
+var [first] = tracked();
+assert(first == 1 && closed, "Destructuring closes iterators it doesn't finish");
+var replacement = {replaced: true};
+function Replacing() { this.replaced = false; return replacement; }
+function ReturningPrimitive() { this.kept = true; return 5; }
+assert(new Replacing() === replacement && new ReturningPrimitive().kept, "Constructors can return an object instead of this");
+function Failing() { throw "construction failed"; }
+var caught;
+try { new Failing(); } catch (e) { caught = e; }
+assert(caught == "construction failed", "Errors thrown by constructors are thrown by new");
+assert(new Function("return 1")() == 1, "Constructing a function from source gives the function");
+function recurse(n) { return recurse(n + 1); }
+var overflow = null;
+try { recurse(0); } catch (e) { overflow = e; }
+assert(overflow instanceof RangeError && overflow.message == "Maximum call stack size exceeded", "Deep recursion throws a RangeError instead of overflowing the stack");
+assert(overflow instanceof Error && String(overflow) == "RangeError: Maximum call stack size exceeded", "RangeErrors are errors");
+function countdown(n) { return n == 0 ? 0 : countdown(n - 1); }
+assert(countdown(500) == 0, "Calls run again after the call stack overflows");
+function depth(n) { return n == 0 ? 0 : 1 + depth(n - 1); }
+assert(depth(990) == 990, "Calls nested almost as deeply as the call depth limit allows run without overflowing the stack");
+var looping = {get self() { return this.self; }, valueOf() { return this + 1; }};
+var gotten = null, converted = null;
+try { looping.self; } catch (e) { gotten = e; }
+try { looping * 2; } catch (e) { converted = e; }
+assert(gotten instanceof RangeError && converted instanceof RangeError, "Getters and conversions that run themselves throw a RangeError");
+var let = 1;
+var yield = 2;
+assert(let + yield == 3, "let and yield are names outside of strict mode and generators");
+var options = {"default": 1, "class": 2};
+var missing = null;
+assert(options?.default == 1 && options?.class == 2 && missing?.default === undefined, "Reserved words are field names after an optional chain");
+var unknown = null, uncallable = null, unwritable = null;
+try { notDeclaredAnywhere; } catch (e) { unknown = e; }
+try { (1)(); } catch (e) { uncallable = e; }
+try { (function() { "use strict"; Object.freeze(options).default = 2; })(); } catch (e) { unwritable = e; }
+assert(unknown instanceof ReferenceError && unknown.message == "notDeclaredAnywhere is not defined", "Reading a variable that isn't declared throws a ReferenceError");
+assert(uncallable instanceof TypeError && unwritable instanceof TypeError, "Calling a value that isn't a function or assigning to a read only property in strict code throws a TypeError");
@@ -0,0 +1,25 @@
+// @description Timer unit tests
+var events = [];
+setTimeout(function(label) { events.push(label); }, 20, "late");
+setTimeout(function() { events.push("early"); }, 10);
+Promise.resolve().then(function() { events.push("job"); });
+var ticks = 0;
+var interval = setInterval(function() {
+    ticks++;
+    if (ticks == 3) clearInterval(interval);
+}, 5);
+var cancelled = setTimeout(function() { events.push("cancelled"); }, 1);
+clearTimeout(cancelled);
+setTimeout(function() {
+    Promise.resolve().then(function() { events.push("job after timer"); });
+    setTimeout(function() { events.push("next timer"); }, 0);
+}, 30);
+setTimeout(function() {
+    assert(events.join() == "job,early,late,job after timer,next timer", "Timers run in order, with their jobs before the next timer");
+    assert(ticks == 3, "Intervals repeat until they are cleared");
+}, 100);
+var first = setTimeout(function() {}, 0);
+var second = setTimeout(function() {}, 0);
+clearTimeout(12345);
+clearTimeout("nonsense");
+assert(first > 0 && second == first + 1, "Timers are given increasing IDs");